@@ -15,6 +15,8 @@ class InstrumentConfig:
     timeframe: str = "1m"
     tick_size: float = 0.1
     rolling_window_minutes: int = 240
+    min_qty: float = 0.0  # Minimum order quantity (0 = no minimum)
+    qty_step: float = 0.0  # Order sizes are rounded down to this step (0 = continuous)
 
 
 @dataclass
@@ -29,11 +31,16 @@ class ValueAreaConfig:
     min_va_bins: int = 20
 
 
+AMBIGUOUS_GATE_MODES = ("confirm", "suppress")
+
+
 @dataclass
 class OrderFlowConfig:
     """Order flow configuration."""
     max_quote_staleness_ms: int = 250
     ambiguous_trade_frac_max: float = 0.35
+    # Above ambiguous_trade_frac_max: "confirm" requires OF + qimb, "suppress" blocks entries
+    ambiguous_gate_mode: str = "confirm"
     use_tick_rule_fallback: bool = True
     use_qimb: bool = True
     qimb_entry_min: float = 0.10
@@ -41,6 +48,23 @@ class OrderFlowConfig:
     qimb_fail_max: float = -0.10
     spread_lookback_minutes: int = 60
 
+    def __post_init__(self):
+        self.validate()
+
+    def validate(self) -> None:
+        """Raise ValueError on an unknown mode string."""
+        if self.ambiguous_gate_mode not in AMBIGUOUS_GATE_MODES:
+            raise ValueError(
+                f"ambiguous_gate_mode must be one of {AMBIGUOUS_GATE_MODES}, "
+                f"got {self.ambiguous_gate_mode!r}"
+            )
+
+
+TARGET_MODES = ("structure", "va_width")
+EDGE_CLOSE_POLICIES = ("inside", "fractional")
+EXIT_MODES = ("fixed_targets", "value_area_target")
+VA_TARGET_LEVELS = ("poc", "opposite_edge")
+
 
 @dataclass
 class SignalConfig:
@@ -54,6 +78,38 @@ class SignalConfig:
     accept_outside_k: int = 3
     enable_retest_mode: bool = True
     enable_flip_on_signal: bool = True
+    target_mode: str = "structure"  # "structure" (POC/VA edge, R multiples) | "va_width"
+    tp1_va_width_mult: float = 0.5  # va_width mode: TP1 distance from entry in VA widths
+    tp2_va_width_mult: float = 1.0  # va_width mode: TP2 distance from entry in VA widths
+    edge_close_policy: str = "inside"  # Close at VAH/VAL: "inside" (resets acceptance) | "fractional"
+    edge_close_weight: float = 0.5  # fractional mode: acceptance credit for an at-edge close
+    edge_tolerance_ticks: float = 0.0  # Closes within this many ticks of an edge count as at it
+    exit_mode: str = "fixed_targets"  # Backtest exits: "fixed_targets" | "value_area_target"
+    va_target_level: str = "poc"  # value_area_target mode: "poc" | "opposite_edge"
+
+    def __post_init__(self):
+        self.validate()
+
+    def validate(self) -> None:
+        """Raise ValueError on an unknown mode string."""
+        if self.target_mode not in TARGET_MODES:
+            raise ValueError(
+                f"target_mode must be one of {TARGET_MODES}, got {self.target_mode!r}"
+            )
+        if self.edge_close_policy not in EDGE_CLOSE_POLICIES:
+            raise ValueError(
+                f"edge_close_policy must be one of {EDGE_CLOSE_POLICIES}, "
+                f"got {self.edge_close_policy!r}"
+            )
+        if self.exit_mode not in EXIT_MODES:
+            raise ValueError(
+                f"exit_mode must be one of {EXIT_MODES}, got {self.exit_mode!r}"
+            )
+        if self.va_target_level not in VA_TARGET_LEVELS:
+            raise ValueError(
+                f"va_target_level must be one of {VA_TARGET_LEVELS}, "
+                f"got {self.va_target_level!r}"
+            )
 
 
 @dataclass
@@ -66,14 +122,42 @@ class SizingConfig:
     move_stop_to_breakeven_after_tp1: bool = True
 
 
+COOLDOWN_SCOPES = ("global", "per_direction")
+MIN_HOLD_SCOPES = ("all", "targets_only")
+
+
 @dataclass
 class RiskConfig:
     """Risk management configuration."""
     max_hold_minutes: int = 60
     extend_if_profitable: bool = True
+    extension_minutes: int = 30  # Added per extension while profitable at the time stop
+    max_extensions: int = 2  # Time stop is enforced after this many extensions
     cooldown_minutes: int = 3
+    cooldown_scope: str = "global"  # Backtest cooldown blocks: "global" | "per_direction"
     stop_buffer_ticks: int = 2
     max_daily_loss: Optional[float] = None
+    # Backtest trailing stop: {"type": "r_multiple", "r": 1.0} | {"type": "ticks", "ticks": 20} | None
+    trail_trigger: Optional[dict] = None
+    trail_offset_ticks: Optional[int] = None  # None = the initial risk distance
+    max_reentries: int = 0  # Backtest re-entries per setup after a stop-out (0 = disabled)
+    min_hold_bars: int = 0  # Backtest: bars after entry during which exits are suppressed (0 = disabled)
+    min_hold_scope: str = "all"  # Exits the minimum hold suppresses: "all" | "targets_only"
+    hard_stop_ticks: Optional[int] = None  # Catastrophic stop honored even during the minimum hold
+
+    def __post_init__(self):
+        self.validate()
+
+    def validate(self) -> None:
+        """Raise ValueError on an unknown scope string."""
+        if self.cooldown_scope not in COOLDOWN_SCOPES:
+            raise ValueError(
+                f"cooldown_scope must be one of {COOLDOWN_SCOPES}, got {self.cooldown_scope!r}"
+            )
+        if self.min_hold_scope not in MIN_HOLD_SCOPES:
+            raise ValueError(
+                f"min_hold_scope must be one of {MIN_HOLD_SCOPES}, got {self.min_hold_scope!r}"
+            )
 
 
 @dataclass
@@ -85,6 +169,9 @@ class ExecutionConfig:
     slippage_ticks_exit: int = 1
     taker_fee_bps: float = 5.0
     maker_fee_bps: float = -1.0
+    slippage_seed: Optional[int] = None  # Backtest: seed for randomized slippage (None = full slippage)
+    spread_multiple: float = 0.0  # Market slippage of at least this many quoted spreads (0 = fixed ticks)
+    max_slippage_ticks: Optional[int] = None  # Cap on market slippage in ticks (None = uncapped)
 
 
 @dataclass
@@ -93,6 +180,7 @@ class BacktestConfig:
     funding_rate_8h_bps: float = 1.0
     initial_capital: float = 10000.0
     workers: int = 0  # 0 = auto (CPU/2)
+    warmup_bars: int = 0  # Bars fed to features before signals are acted on
 
 
 @dataclass
@@ -138,6 +226,9 @@ class Config:
                 for key, value in data[section_name].items():
                     if hasattr(section_obj, key):
                         setattr(section_obj, key, value)
+        config.order_flow.validate()
+        config.signal.validate()
+        config.risk.validate()
         return config
 
     def to_dict(self) -> dict:
@@ -191,6 +191,11 @@ class OrderFlowMetrics:
     ambiguous_volume: float
     ambiguous_frac: float
 
+    @property
+    def has_volume(self) -> bool:
+        """Whether any volume traded (zero normalized flow otherwise means no data)."""
+        return self.total_volume > 0
+
     def is_high_ambiguous(self, threshold: float = 0.35) -> bool:
         """Check if ambiguous fraction is above threshold."""
         return self.ambiguous_frac > threshold
@@ -241,6 +246,7 @@ class Position:
     strategy_tag: str = ""
     fees_paid: float = 0.0
     funding_paid: float = 0.0
+    hold_extensions: int = 0
 
     def unrealized_pnl(self, current_price: float) -> float:
         """Calculate unrealized P&L at current price."""
@@ -257,9 +263,9 @@ class Position:
 @dataclass
 class AcceptanceState:
     """State for tracking acceptance sequences."""
-    # Breakout acceptance tracking
-    consecutive_above_vah: int = 0
-    consecutive_below_val: int = 0
+    # Breakout acceptance tracking (fractional with edge_close_policy "fractional")
+    consecutive_above_vah: float = 0
+    consecutive_below_val: float = 0
     # Locked VA boundaries for sequences
     locked_vah: Optional[float] = None
     locked_val: Optional[float] = None
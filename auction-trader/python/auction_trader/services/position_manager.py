@@ -309,21 +309,31 @@ class PositionManager:
         return self._price_crossed(high, low, self.position.tp2_price, above_for_long=True)
 
     def _check_time_stop(self, current_price: float, current_ts: int) -> bool:
-        """Check time-based exit condition."""
+        """Check time-based exit condition.
+
+        The hold limit is max_hold_minutes plus extension_minutes for each
+        extension granted. When the limit is reached and the position is
+        profitable, one more extension is granted (up to max_extensions);
+        otherwise the time stop fires.
+        """
         if not self.position:
             return False
 
         hold_ms = current_ts - self.position.entry_ts
-        max_hold_ms = self.risk.max_hold_minutes * 60_000
+        max_hold_minutes = (
+            self.risk.max_hold_minutes
+            + self.position.hold_extensions * self.risk.extension_minutes
+        )
 
-        if hold_ms < max_hold_ms:
+        if hold_ms < max_hold_minutes * 60_000:
             return False
 
         # Time limit reached - check if we should extend
-        if self.risk.extend_if_profitable:
-            if self.position.is_profitable(current_price):
-                # Extend - don't exit yet
-                return False
+        if (self.risk.extend_if_profitable
+                and self.position.hold_extensions < self.risk.max_extensions
+                and self.position.is_profitable(current_price)):
+            self.position.hold_extensions += 1
+            return False
 
         return True
 
@@ -6,8 +6,9 @@ Three core setups:
 3. Failed Breakout: Fakeout reversal back into value
 """
 
+import copy
 from dataclasses import dataclass, field
-from typing import Optional, List
+from typing import Any, Dict, Optional, List
 from enum import Enum
 
 from ..config import Config, SignalConfig, OrderFlowConfig
@@ -27,6 +28,9 @@ class PriceZone(Enum):
     INSIDE_VA = "inside"
     ABOVE_VAH = "above"
     BELOW_VAL = "below"
+    # Close at an edge (only with edge_close_policy "fractional")
+    AT_VAH = "at_vah"
+    AT_VAL = "at_val"
 
 
 @dataclass
@@ -40,6 +44,33 @@ class SignalCandidate:
     confidence: float = 1.0
 
 
+@dataclass
+class GateResult:
+    """Outcome of one signal gate and the values it compared."""
+    name: str
+    passed: bool
+    value: Any = None
+    threshold: Any = None
+    detail: str = ""
+
+
+@dataclass
+class SignalEvaluation:
+    """Dry-run evaluation of one minute: the signal plus every gate checked."""
+    signal: Signal
+    setup: Optional[str] = None
+    gates: List[GateResult] = field(default_factory=list)
+
+    def gate(self, name: str) -> Optional[GateResult]:
+        """Look up a gate by name."""
+        return next((g for g in self.gates if g.name == name), None)
+
+    @property
+    def blocked_by(self) -> Optional[str]:
+        """Name of the first failed gate (None if all passed)."""
+        return next((g.name for g in self.gates if not g.passed), None)
+
+
 class SignalEngine:
     """Signal engine for generating trading signals based on AMT.
 
@@ -52,6 +83,9 @@ class SignalEngine:
     """
 
     def __init__(self, config: Config):
+        config.order_flow.validate()
+        config.signal.validate()
+        config.risk.validate()
         self.config = config
         self.signal_config = config.signal
         self.of_config = config.order_flow
@@ -67,6 +101,9 @@ class SignalEngine:
         # Cooldown tracking
         self.last_signal_ts: Optional[int] = None
 
+        # Gates checked per setup, recorded while evaluate_verbose runs
+        self._trace: Optional[Dict[SignalType, List[GateResult]]] = None
+
     def process(self, features: Features1m) -> Signal:
         """Process features and generate signal.
 
@@ -91,26 +128,12 @@ class SignalEngine:
         self._update_acceptance(zone, features)
 
         # Collect candidate signals
-        candidates: List[SignalCandidate] = []
-
-        # Check for Break-in signals
-        breakin = self._check_breakin(features, zone)
-        if breakin:
-            candidates.append(breakin)
-
-        # Check for Failed Breakout signals
-        failed = self._check_failed_breakout(features, zone)
-        if failed:
-            candidates.append(failed)
+        candidates = self._check_setups(features, zone)
 
-        # Check for Breakout signals
-        breakout = self._check_breakout(features, zone)
-        if breakout:
-            candidates.append(breakout)
-
-        # Store for next iteration
+        # Store for next iteration (an at-edge close keeps the last clear zone)
         self.prev_features = features
-        self.prev_zone = zone
+        if zone not in (PriceZone.AT_VAH, PriceZone.AT_VAL):
+            self.prev_zone = zone
 
         # Resolve priority and return signal
         if not candidates:
@@ -123,21 +146,101 @@ class SignalEngine:
         # Record signal time for cooldown
         self.last_signal_ts = features.ts_min
 
+        tp1_price, tp2_price = winner.tp1_price, winner.tp2_price
+        if self.signal_config.target_mode == "va_width":
+            tp1_price, tp2_price = self._va_width_targets(winner.signal_type, features)
+
         return Signal(
             ts_min=features.ts_min,
             signal_type=winner.signal_type,
             action=self._signal_to_action(winner.signal_type),
             stop_price=winner.stop_price,
-            tp1_price=winner.tp1_price,
-            tp2_price=winner.tp2_price,
+            tp1_price=tp1_price,
+            tp2_price=tp2_price,
             strategy_tag=self._get_strategy_tag(winner.signal_type),
             confidence=winner.confidence,
             reason=winner.reason,
             features_snapshot=features,
         )
 
+    def evaluate_verbose(self, features: Features1m) -> SignalEvaluation:
+        """Evaluate features without changing engine state, reporting each gate.
+
+        Gates are listed in the order they apply: va_valid, cooldown,
+        va_break, acceptance (failed breakouts and breakouts), has_volume,
+        ambiguous_frac, of_threshold and qimb. The setup gates are recorded
+        from the same checks process() runs; the setup reported is the
+        highest-priority one that passed, else the highest-priority one the
+        price path reached.
+        """
+        saved = (copy.deepcopy(self.acceptance), self.prev_features, self.prev_zone, self.last_signal_ts)
+        try:
+            gates = [GateResult("va_valid", features.va.is_valid, features.va.is_valid, True)]
+            elapsed = None
+            if self.last_signal_ts is not None:
+                elapsed = (features.ts_min - self.last_signal_ts) / 60_000
+            gates.append(GateResult(
+                "cooldown",
+                not self._in_cooldown(features.ts_min),
+                elapsed,
+                self.risk_config.cooldown_minutes,
+                "minutes since the last signal",
+            ))
+
+            setup = None
+            if features.va.is_valid:
+                zone = self._get_price_zone(features.mid_close, features.va)
+                zones = (self.prev_zone.value if self.prev_zone else None, zone.value)
+                self._update_acceptance(zone, features)
+                self._trace = {}
+                self._check_setups(features, zone)
+                traced = self._trace
+                self._trace = None
+
+                passed = [t for t, setup_gates in traced.items() if all(g.passed for g in setup_gates)]
+                signal_type = next(iter(passed or traced), None)
+                gates.append(GateResult("va_break", signal_type is not None, zones, None, "(previous zone, zone)"))
+                if signal_type is not None:
+                    setup = signal_type.name.lower()
+                    gates.extend(traced[signal_type])
+
+            self.acceptance, self.prev_features, self.prev_zone, self.last_signal_ts = copy.deepcopy(saved)
+            signal = self.process(features)
+        finally:
+            self.acceptance, self.prev_features, self.prev_zone, self.last_signal_ts = saved
+            self._trace = None
+
+        return SignalEvaluation(signal=signal, setup=setup, gates=gates)
+
+    def _check_setups(self, features: Features1m, zone: PriceZone) -> List[SignalCandidate]:
+        """Candidate signals of every setup, in priority order."""
+        checks = (self._check_breakin, self._check_failed_breakout, self._check_breakout)
+        return [c for c in (check(features, zone) for check in checks) if c]
+
+    def _record_gates(self, signal_type: SignalType, gates: List[GateResult]) -> bool:
+        """Record a setup's gates for evaluate_verbose; True if all passed."""
+        if self._trace is not None:
+            self._trace.setdefault(signal_type, []).extend(gates)
+        return all(g.passed for g in gates)
+
+    def _acceptance_gate(self, signal_type: SignalType, passed: bool, count: float, detail: str) -> bool:
+        """Record the acceptance gate of a (failed) breakout."""
+        k = self.signal_config.accept_outside_k
+        return self._record_gates(signal_type, [GateResult("acceptance", passed, count, k, detail)])
+
     def _get_price_zone(self, price: float, va: ValueArea) -> PriceZone:
-        """Determine which zone the price is in."""
+        """Determine which zone the price is in.
+
+        A close within edge_tolerance_ticks of VAH/VAL (or equal up to float
+        rounding) is at the edge: inside the VA under edge_close_policy
+        "inside", AT_VAH/AT_VAL under "fractional".
+        """
+        tolerance = self.signal_config.edge_tolerance_ticks * self.config.instrument.tick_size
+        fractional = self.signal_config.edge_close_policy == "fractional"
+        for edge, at_zone in ((va.vah, PriceZone.AT_VAH), (va.val, PriceZone.AT_VAL)):
+            if abs(price - edge) <= max(tolerance, 1e-9 * abs(edge)):
+                return at_zone if fractional else PriceZone.INSIDE_VA
+
         if price > va.vah:
             return PriceZone.ABOVE_VAH
         elif price < va.val:
@@ -169,6 +272,22 @@ class SignalEngine:
             # Reset above sequence
             self.acceptance.reset_above()
 
+        elif zone == PriceZone.AT_VAH:
+            # Partial acceptance above VAH
+            if self.acceptance.consecutive_above_vah == 0:
+                self.acceptance.locked_vah = va.vah
+                self.acceptance.sequence_start_ts = features.ts_min
+            self.acceptance.consecutive_above_vah += self.signal_config.edge_close_weight
+            self.acceptance.reset_below()
+
+        elif zone == PriceZone.AT_VAL:
+            # Partial acceptance below VAL
+            if self.acceptance.consecutive_below_val == 0:
+                self.acceptance.locked_val = va.val
+                self.acceptance.sequence_start_ts = features.ts_min
+            self.acceptance.consecutive_below_val += self.signal_config.edge_close_weight
+            self.acceptance.reset_above()
+
         else:
             # Inside VA - reset both sequences
             self.acceptance.reset_above()
@@ -225,9 +344,10 @@ class SignalEngine:
         k = self.signal_config.accept_outside_k
 
         # Long Failed Breakout: Was below VAL (1 to k-1 bars), now inside
+        below = self.acceptance.consecutive_below_val
         if (zone == PriceZone.INSIDE_VA and
             self.prev_zone == PriceZone.BELOW_VAL and
-            1 <= self.acceptance.consecutive_below_val < k):
+            self._acceptance_gate(SignalType.FAILED_BREAKOUT_LONG, 1 <= below < k, below, "closes outside, 1 to k-1")):
 
             if self._check_of_fail_long(of, features):
                 return SignalCandidate(
@@ -239,9 +359,10 @@ class SignalEngine:
                 )
 
         # Short Failed Breakout: Was above VAH (1 to k-1 bars), now inside
+        above = self.acceptance.consecutive_above_vah
         if (zone == PriceZone.INSIDE_VA and
             self.prev_zone == PriceZone.ABOVE_VAH and
-            1 <= self.acceptance.consecutive_above_vah < k):
+            self._acceptance_gate(SignalType.FAILED_BREAKOUT_SHORT, 1 <= above < k, above, "closes outside, 1 to k-1")):
 
             if self._check_of_fail_short(of, features):
                 return SignalCandidate(
@@ -267,8 +388,9 @@ class SignalEngine:
         k = self.signal_config.accept_outside_k
 
         # Long Breakout: k consecutive closes above VAH
+        above = self.acceptance.consecutive_above_vah
         if (zone == PriceZone.ABOVE_VAH and
-            self.acceptance.consecutive_above_vah >= k):
+            self._acceptance_gate(SignalType.BREAKOUT_LONG, above >= k, above, "consecutive closes outside the VA")):
 
             if self._check_of_breakout_long(of, features):
                 # Use locked VAH as stop reference
@@ -283,8 +405,9 @@ class SignalEngine:
                 )
 
         # Short Breakout: k consecutive closes below VAL
+        below = self.acceptance.consecutive_below_val
         if (zone == PriceZone.BELOW_VAL and
-            self.acceptance.consecutive_below_val >= k):
+            self._acceptance_gate(SignalType.BREAKOUT_SHORT, below >= k, below, "consecutive closes outside the VA")):
 
             if self._check_of_breakout_short(of, features):
                 # Use locked VAL as stop reference
@@ -308,6 +431,7 @@ class SignalEngine:
         of_norm_threshold: float,
         qimb_threshold: float,
         is_long: bool,
+        signal_type: Optional[SignalType] = None,
     ) -> bool:
         """Check order flow conditions for a signal.
 
@@ -318,14 +442,60 @@ class SignalEngine:
             of_norm_threshold: Normalized OF threshold (absolute value)
             qimb_threshold: QIMB threshold (absolute value)
             is_long: True for long signals, False for short signals
+            signal_type: Setup whose gates evaluate_verbose records
+
+        When the minute's ambiguous fraction exceeds ambiguous_trade_frac_max
+        the OF reading is unreliable: the signal is either suppressed or must
+        be confirmed by qimb even if use_qimb is off (ambiguous_gate_mode).
+        A minute with no volume never confirms (zero flow there means no data).
         """
-        if is_long:
-            of_ok = of.of_1m >= of_threshold or of.of_norm_1m >= of_norm_threshold
-            qimb_ok = not self.of_config.use_qimb or features.qimb_ema >= qimb_threshold
-        else:
-            of_ok = of.of_1m <= -of_threshold or of.of_norm_1m <= -of_norm_threshold
-            qimb_ok = not self.of_config.use_qimb or features.qimb_ema <= -qimb_threshold
-        return of_ok and qimb_ok
+        gates = self._of_gates(of, features, of_threshold, of_norm_threshold, qimb_threshold, is_long)
+        if signal_type is None:
+            return all(g.passed for g in gates)
+        return self._record_gates(signal_type, gates)
+
+    def _of_gates(
+        self,
+        of: OrderFlowMetrics,
+        features: Features1m,
+        of_threshold: float,
+        of_norm_threshold: float,
+        qimb_threshold: float,
+        is_long: bool,
+    ) -> List[GateResult]:
+        """The order flow gates of `_check_of_condition` with their values."""
+        frac_max = self.of_config.ambiguous_trade_frac_max
+        high_ambiguous = of.is_high_ambiguous(frac_max)
+        suppress = self.of_config.ambiguous_gate_mode == "suppress"
+        require_qimb = self.of_config.use_qimb or high_ambiguous
+        sign = 1.0 if is_long else -1.0
+
+        of_ok = sign * of.of_1m >= of_threshold or sign * of.of_norm_1m >= of_norm_threshold
+        qimb_ok = not require_qimb or sign * features.qimb_ema >= qimb_threshold
+        return [
+            GateResult("has_volume", of.has_volume, of.total_volume, 0.0),
+            GateResult(
+                "ambiguous_frac",
+                not (high_ambiguous and suppress),
+                of.ambiguous_frac,
+                frac_max,
+                "above the max requires qimb" if high_ambiguous and not suppress else "",
+            ),
+            GateResult(
+                "of_threshold",
+                of_ok,
+                (of.of_1m, of.of_norm_1m),
+                (sign * of_threshold, sign * of_norm_threshold),
+                "(of_1m, of_norm_1m); either may confirm",
+            ),
+            GateResult(
+                "qimb",
+                qimb_ok,
+                features.qimb_ema,
+                sign * qimb_threshold if require_qimb else None,
+                "" if require_qimb else "not required",
+            ),
+        ]
 
     def _check_of_entry_long(self, of: OrderFlowMetrics, features: Features1m) -> bool:
         """Check order flow conditions for long entry (break-in)."""
@@ -335,6 +505,7 @@ class SignalEngine:
             self.signal_config.of_entry_min_norm,
             self.of_config.qimb_entry_min,
             is_long=True,
+            signal_type=SignalType.BREAKIN_LONG,
         )
 
     def _check_of_entry_short(self, of: OrderFlowMetrics, features: Features1m) -> bool:
@@ -345,6 +516,7 @@ class SignalEngine:
             self.signal_config.of_entry_min_norm,
             self.of_config.qimb_entry_min,
             is_long=False,
+            signal_type=SignalType.BREAKIN_SHORT,
         )
 
     def _check_of_breakout_long(self, of: OrderFlowMetrics, features: Features1m) -> bool:
@@ -355,6 +527,7 @@ class SignalEngine:
             self.signal_config.of_breakout_min_norm,
             self.of_config.qimb_breakout_min,
             is_long=True,
+            signal_type=SignalType.BREAKOUT_LONG,
         )
 
     def _check_of_breakout_short(self, of: OrderFlowMetrics, features: Features1m) -> bool:
@@ -365,6 +538,7 @@ class SignalEngine:
             self.signal_config.of_breakout_min_norm,
             self.of_config.qimb_breakout_min,
             is_long=False,
+            signal_type=SignalType.BREAKOUT_SHORT,
         )
 
     def _check_of_fail_long(self, of: OrderFlowMetrics, features: Features1m) -> bool:
@@ -375,6 +549,7 @@ class SignalEngine:
             self.signal_config.of_fail_max_norm,
             self.of_config.qimb_fail_max,
             is_long=True,
+            signal_type=SignalType.FAILED_BREAKOUT_LONG,
         )
 
     def _check_of_fail_short(self, of: OrderFlowMetrics, features: Features1m) -> bool:
@@ -385,6 +560,19 @@ class SignalEngine:
             self.signal_config.of_fail_max_norm,
             self.of_config.qimb_fail_max,
             is_long=False,
+            signal_type=SignalType.FAILED_BREAKOUT_SHORT,
+        )
+
+    def _va_width_targets(
+        self, signal_type: SignalType, features: Features1m
+    ) -> tuple[float, float]:
+        """TP1/TP2 at configured multiples of the VA width beyond entry."""
+        width = features.va.vah - features.va.val
+        direction = 1.0 if signal_type.is_long() else -1.0
+        entry = features.mid_close
+        return (
+            entry + direction * self.signal_config.tp1_va_width_mult * width,
+            entry + direction * self.signal_config.tp2_va_width_mult * width,
         )
 
     def _stop_buffer(self, features: Features1m) -> float:
@@ -1,8 +1,14 @@
 //! Fill model for backtesting.
 //!
-//! Models realistic fills using bid/ask prices and slippage.
+//! Models realistic fills using bid/ask prices and slippage. With a
+//! slippage seed, market fills draw a whole number of ticks in
+//! `[0, slippage_ticks]` instead of always taking the full slippage.
+//! With a spread multiple, market fills slip at least that multiple of the
+//! quoted spread, so wide-spread moments cost more.
 
-use auction_core::{Fill, PositionSide, Quote, TimestampMs};
+use crate::position::ContractSpec;
+use auction_core::{market_slippage_ticks, Config, Fill, PositionSide, Quote, TimestampMs};
+use std::cell::Cell;
 
 /// Configuration for the fill model.
 #[derive(Debug, Clone)]
@@ -17,6 +23,16 @@ pub struct FillModelConfig {
     pub taker_fee_bps: f64,
     /// Maker fee in basis points (negative = rebate).
     pub maker_fee_bps: f64,
+    /// Minimum order quantity (0 = no minimum).
+    pub min_qty: f64,
+    /// Order quantity step (0 = continuous sizes).
+    pub qty_step: f64,
+    /// Seed for randomized slippage (None = always the full slippage).
+    pub slippage_seed: Option<u64>,
+    /// Market fills slip at least this multiple of the quoted spread (0 = fixed ticks only).
+    pub spread_multiple: f64,
+    /// Cap on market fill slippage in ticks (None = uncapped).
+    pub max_slippage_ticks: Option<u32>,
 }
 
 impl Default for FillModelConfig {
@@ -27,6 +43,30 @@ impl Default for FillModelConfig {
             tick_size: 0.1,
             taker_fee_bps: 5.0,
             maker_fee_bps: -1.0,
+            min_qty: 0.0,
+            qty_step: 0.0,
+            slippage_seed: None,
+            spread_multiple: 0.0,
+            max_slippage_ticks: None,
+        }
+    }
+}
+
+impl From<&Config> for FillModelConfig {
+    /// Fill model for the instrument and execution settings of `config`.
+    fn from(config: &Config) -> Self {
+        let execution = &config.execution;
+        Self {
+            slippage_ticks_entry: execution.slippage_ticks_entry,
+            slippage_ticks_exit: execution.slippage_ticks_exit,
+            tick_size: config.instrument.tick_size,
+            taker_fee_bps: execution.taker_fee_bps,
+            maker_fee_bps: execution.maker_fee_bps,
+            min_qty: config.instrument.min_qty,
+            qty_step: config.instrument.qty_step,
+            slippage_seed: execution.slippage_seed,
+            spread_multiple: execution.spread_multiple,
+            max_slippage_ticks: execution.max_slippage_ticks,
         }
     }
 }
@@ -34,19 +74,72 @@ impl Default for FillModelConfig {
 /// Fill model for simulating order execution.
 pub struct FillModel {
     config: FillModelConfig,
+    /// Contract whose notional fees are charged on.
+    contract: ContractSpec,
+    /// Slippage RNG state (splitmix64), if seeded.
+    rng_state: Option<Cell<u64>>,
 }
 
 impl FillModel {
     /// Create a new fill model.
     pub fn new(config: FillModelConfig) -> Self {
-        Self { config }
+        let rng_state = config.slippage_seed.map(Cell::new);
+        Self {
+            config,
+            contract: ContractSpec::linear(),
+            rng_state,
+        }
+    }
+
+    /// Charge fees on the notional of `contract` instead of `price * size`.
+    pub fn with_contract(mut self, contract: ContractSpec) -> Self {
+        self.contract = contract;
+        self
+    }
+
+    /// Slippage for an entry market order (fixed ticks, capped).
+    pub fn entry_slippage(&self) -> f64 {
+        self.slippage(self.config.slippage_ticks_entry, None)
+    }
+
+    /// Slippage for an exit market order (fixed ticks, capped).
+    pub fn exit_slippage(&self) -> f64 {
+        self.slippage(self.config.slippage_ticks_exit, None)
+    }
+
+    /// Slippage for an entry market order against a quote's spread.
+    pub fn entry_slippage_at(&self, quote: &Quote) -> f64 {
+        self.slippage(self.config.slippage_ticks_entry, Some(quote))
+    }
+
+    /// Slippage for an exit market order against a quote's spread.
+    pub fn exit_slippage_at(&self, quote: &Quote) -> f64 {
+        self.slippage(self.config.slippage_ticks_exit, Some(quote))
+    }
+
+    /// Slippage of up to [`market_slippage_ticks`] whole ticks: the full
+    /// amount unless seeded.
+    fn slippage(&self, ticks: u32, quote: Option<&Quote>) -> f64 {
+        let tick_size = self.config.tick_size;
+        let spread_ticks = quote.map_or(0.0, |q| (q.ask_px - q.bid_px) / tick_size);
+        let max_ticks = market_slippage_ticks(
+            ticks,
+            spread_ticks,
+            self.config.spread_multiple,
+            self.config.max_slippage_ticks,
+        );
+        let ticks = match &self.rng_state {
+            Some(state) => next_random(state) % (max_ticks as u64 + 1),
+            None => max_ticks as u64,
+        };
+        ticks as f64 * tick_size
     }
 
     /// Simulate a market buy fill.
     pub fn market_buy(&self, ts_ms: TimestampMs, quote: &Quote, size: f64) -> Fill {
-        let slippage = self.config.slippage_ticks_entry as f64 * self.config.tick_size;
+        let slippage = self.entry_slippage_at(quote);
         let fill_price = quote.ask_px + slippage;
-        let notional = fill_price * size;
+        let notional = self.contract.notional(size, fill_price);
         let fee = notional * self.config.taker_fee_bps / 10000.0;
 
         Fill {
@@ -61,9 +154,9 @@ impl FillModel {
 
     /// Simulate a market sell fill.
     pub fn market_sell(&self, ts_ms: TimestampMs, quote: &Quote, size: f64) -> Fill {
-        let slippage = self.config.slippage_ticks_exit as f64 * self.config.tick_size;
+        let slippage = self.exit_slippage_at(quote);
         let fill_price = quote.bid_px - slippage;
-        let notional = fill_price * size;
+        let notional = self.contract.notional(size, fill_price);
         let fee = notional * self.config.taker_fee_bps / 10000.0;
 
         Fill {
@@ -89,7 +182,7 @@ impl FillModel {
         // Fill if ask <= limit price
         if quote.ask_px <= limit_price {
             let fill_price = limit_price.min(quote.ask_px);
-            let notional = fill_price * size;
+            let notional = self.contract.notional(size, fill_price);
             let fee = notional * self.config.maker_fee_bps / 10000.0;
 
             Some(Fill {
@@ -118,7 +211,7 @@ impl FillModel {
         // Fill if bid >= limit price
         if quote.bid_px >= limit_price {
             let fill_price = limit_price.max(quote.bid_px);
-            let notional = fill_price * size;
+            let notional = self.contract.notional(size, fill_price);
             let fee = notional * self.config.maker_fee_bps / 10000.0;
 
             Some(Fill {
@@ -134,6 +227,24 @@ impl FillModel {
         }
     }
 
+    /// Round a computed size down to a valid lot.
+    ///
+    /// Returns 0.0 if the rounded size is below `min_qty` (order rejected).
+    pub fn round_qty(&self, size: f64) -> f64 {
+        let step = self.config.qty_step;
+        let rounded = if step > 0.0 {
+            // Tolerance keeps exact multiples (e.g. 0.3 / 0.1) from flooring down
+            (size / step + 1e-9).floor() * step
+        } else {
+            size
+        };
+        if rounded <= 0.0 || rounded < self.config.min_qty {
+            0.0
+        } else {
+            rounded
+        }
+    }
+
     /// Calculate fee for a given notional and order type.
     pub fn calculate_fee(&self, notional: f64, is_maker: bool) -> f64 {
         let bps = if is_maker {
@@ -145,9 +256,20 @@ impl FillModel {
     }
 }
 
+/// Advance a splitmix64 state and return the next output.
+fn next_random(state: &Cell<u64>) -> u64 {
+    let s = state.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+    state.set(s);
+    let mut z = s;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use auction_core::SizeUnit;
 
     fn make_quote(bid: f64, ask: f64) -> Quote {
         Quote {
@@ -175,8 +297,43 @@ mod tests {
         assert!((fill.price - 50001.1).abs() < 1e-10);
         assert!((fill.slippage - 0.1).abs() < 1e-10);
 
-        // Fee: 50001.1 * 0.1 * 5 / 10000 = 0.25
-        assert!((fill.fee - 0.250).abs() < 0.01);
+        // Fee: 50001.1 * 0.1 * 5 / 10000 = 2.5
+        assert!((fill.fee - 2.500055).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_inverse_contract_fee() {
+        let model = FillModel::new(FillModelConfig {
+            slippage_ticks_entry: 0,
+            taker_fee_bps: 5.0,
+            ..Default::default()
+        })
+        .with_contract(ContractSpec {
+            multiplier: 100.0,
+            inverse: true,
+            ..ContractSpec::linear()
+        });
+
+        // 10 contracts of 100 USD: 1000 USD of face value at any price
+        let fill = model.market_buy(1000, &make_quote(50000.0, 50001.0), 10.0);
+        assert!((fill.fee - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_quote_sized_fee() {
+        let model = FillModel::new(FillModelConfig {
+            slippage_ticks_entry: 0,
+            taker_fee_bps: 5.0,
+            ..Default::default()
+        })
+        .with_contract(ContractSpec {
+            size_unit: SizeUnit::Quote,
+            ..ContractSpec::linear()
+        });
+
+        // 5000 USDT of notional is charged on 5000, not 5000 * price
+        let fill = model.market_buy(1000, &make_quote(50000.0, 50001.0), 5000.0);
+        assert!((fill.fee - 2.5).abs() < 1e-9);
     }
 
     #[test]
@@ -216,6 +373,29 @@ mod tests {
         assert!(fill.is_none());
     }
 
+    #[test]
+    fn test_round_qty() {
+        let stepped = FillModel::new(FillModelConfig {
+            qty_step: 0.01,
+            ..Default::default()
+        });
+        assert!((stepped.round_qty(0.137) - 0.13).abs() < 1e-12);
+        assert!((stepped.round_qty(0.3) - 0.3).abs() < 1e-12);
+        assert_eq!(stepped.round_qty(0.004), 0.0);
+
+        let min_lot = FillModel::new(FillModelConfig {
+            min_qty: 0.5,
+            qty_step: 0.01,
+            ..Default::default()
+        });
+        assert_eq!(min_lot.round_qty(0.137), 0.0);
+        assert!((min_lot.round_qty(0.5) - 0.5).abs() < 1e-12);
+
+        // Disabled by default
+        let plain = FillModel::new(FillModelConfig::default());
+        assert_eq!(plain.round_qty(0.137), 0.137);
+    }
+
     #[test]
     fn test_maker_rebate() {
         let model = FillModel::new(FillModelConfig {
@@ -226,4 +406,84 @@ mod tests {
         let fee = model.calculate_fee(10000.0, true);
         assert!((fee - (-1.0)).abs() < 1e-10); // -1.0 = 10000 * -1 / 10000
     }
+
+    #[test]
+    fn test_seeded_slippage() {
+        let seeded = |seed| {
+            FillModel::new(FillModelConfig {
+                slippage_ticks_entry: 5,
+                tick_size: 0.1,
+                slippage_seed: Some(seed),
+                ..Default::default()
+            })
+        };
+        let quote = make_quote(50000.0, 50001.0);
+        let prices = |model: FillModel| -> Vec<f64> {
+            (0..50).map(|i| model.market_buy(i, &quote, 0.1).price).collect()
+        };
+
+        let a = prices(seeded(7));
+        assert_eq!(a, prices(seeded(7)));
+        let b = prices(seeded(8));
+        assert_ne!(a, b);
+
+        for price in a.iter().chain(&b) {
+            assert!(*price >= 50001.0 - 1e-9 && *price <= 50001.5 + 1e-9);
+        }
+        // Jitter actually spans the band
+        assert!(a.iter().any(|p| (p - 50001.0).abs() < 1e-9));
+        assert!(a.iter().any(|p| (p - 50001.5).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_spread_relative_slippage() {
+        let model = FillModel::new(FillModelConfig {
+            slippage_ticks_entry: 1,
+            tick_size: 0.1,
+            spread_multiple: 0.5,
+            max_slippage_ticks: Some(8),
+            ..Default::default()
+        });
+
+        // 1-tick spread: the fixed tick dominates
+        let tight = model.market_buy(1000, &make_quote(50000.0, 50000.1), 0.1);
+        assert!((tight.slippage - 0.1).abs() < 1e-9);
+
+        // 6-tick spread: half of it, 3 ticks
+        let wide = model.market_buy(1000, &make_quote(50000.0, 50000.6), 0.1);
+        assert!((wide.slippage - 0.3).abs() < 1e-9);
+        assert!((wide.price - 50000.9).abs() < 1e-9);
+
+        // 5-tick spread: 2.5 ticks round up to whole ticks, as in the seeded path
+        let odd = model.market_buy(1000, &make_quote(50000.0, 50000.5), 0.1);
+        assert!((odd.slippage - 0.3).abs() < 1e-9);
+
+        // 40-tick spread: capped at 8 ticks
+        let blown = model.market_buy(1000, &make_quote(50000.0, 50004.0), 0.1);
+        assert!((blown.slippage - 0.8).abs() < 1e-9);
+
+        // Without a quote only the fixed ticks apply
+        assert!((model.entry_slippage() - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_config_from_system_config() {
+        let mut system = Config::default();
+        system.instrument.tick_size = 0.5;
+        system.instrument.min_qty = 0.001;
+        system.instrument.qty_step = 0.001;
+        system.execution.slippage_ticks_exit = 3;
+        system.execution.slippage_seed = Some(7);
+        system.execution.spread_multiple = 0.5;
+        system.execution.max_slippage_ticks = Some(8);
+
+        let config = FillModelConfig::from(&system);
+        assert_eq!(config.tick_size, 0.5);
+        assert_eq!(config.min_qty, 0.001);
+        assert_eq!(config.qty_step, 0.001);
+        assert_eq!(config.slippage_ticks_exit, 3);
+        assert_eq!(config.slippage_seed, Some(7));
+        assert_eq!(config.spread_multiple, 0.5);
+        assert_eq!(config.max_slippage_ticks, Some(8));
+    }
 }
@@ -5,13 +5,17 @@
 //! - Bid/ask fill modeling
 //! - Fee and slippage accounting
 //! - Position tracking and P&L calculation
+//! - Trade log export (JSON/CSV) with entry context
 
 pub mod fill_model;
 pub mod simulator;
 pub mod position;
 pub mod metrics;
+pub mod trade_log;
 
 pub use fill_model::FillModel;
-pub use simulator::BacktestSimulator;
-pub use position::PositionTracker;
-pub use metrics::BacktestMetrics;
+pub use simulator::{
+    BacktestSimulator, FillTiming, FundingAccrual, IntrabarAssumption, RunStatus, TargetOrderPolicy, Tp1DustPolicy,
+};
+pub use position::{ClosedTrade, ContractSpec, ExitReason, PositionMode, PositionTracker};
+pub use metrics::{BacktestMetrics, EquityPoint, RelativeMetrics, SessionStats};
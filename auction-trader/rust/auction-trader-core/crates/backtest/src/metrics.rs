@@ -3,6 +3,9 @@
 //! Calculates various performance metrics from backtest results.
 
 use crate::position::ClosedTrade;
+use auction_core::TimestampMs;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
 
 /// Backtest performance metrics.
 #[derive(Debug, Clone, Default)]
@@ -21,6 +24,8 @@ pub struct BacktestMetrics {
     pub net_pnl: f64,
     /// Total fees paid.
     pub total_fees: f64,
+    /// Total slippage cost versus the touch price (already in the fill prices).
+    pub total_slippage: f64,
     /// Total funding paid.
     pub total_funding: f64,
     /// Average winning trade P&L.
@@ -60,15 +65,122 @@ pub struct EquityPoint {
     pub drawdown_pct: f64,
 }
 
+/// Strategy performance relative to a benchmark series.
+#[derive(Debug, Clone, Default)]
+pub struct RelativeMetrics {
+    /// Sensitivity of strategy returns to benchmark returns.
+    pub beta: f64,
+    /// Annualized return not explained by benchmark exposure (CAPM alpha).
+    pub alpha_annual: f64,
+    /// Correlation of strategy and benchmark returns.
+    pub correlation: f64,
+    /// Annualized standard deviation of active returns.
+    pub tracking_error: f64,
+    /// Number of aligned return periods used.
+    pub periods: usize,
+}
+
+/// Per-day summary produced at each UTC midnight rollover.
+#[derive(Debug, Clone)]
+pub struct DayStats {
+    /// UTC calendar date.
+    pub date: NaiveDate,
+    /// Number of closed trades.
+    pub trades: u32,
+    /// Net P&L (after fees and funding).
+    pub pnl: f64,
+    /// Fees paid.
+    pub fees: f64,
+    /// Maximum intraday drawdown of cumulative P&L (absolute).
+    pub max_drawdown: f64,
+}
+
+impl DayStats {
+    /// Summarize the trades closed during a single day.
+    pub fn from_trades(date: NaiveDate, trades: &[ClosedTrade]) -> Self {
+        let mut stats = DayStats {
+            date,
+            trades: trades.len() as u32,
+            pnl: 0.0,
+            fees: 0.0,
+            max_drawdown: 0.0,
+        };
+
+        let mut peak = 0.0f64;
+        for trade in trades {
+            stats.pnl += trade.pnl;
+            stats.fees += trade.fees;
+            peak = peak.max(stats.pnl);
+            stats.max_drawdown = stats.max_drawdown.max(peak - stats.pnl);
+        }
+
+        stats
+    }
+}
+
+/// Per-session summary produced at each configured session end.
+#[derive(Debug, Clone)]
+pub struct SessionStats {
+    /// UTC date on which the session ended.
+    pub date: NaiveDate,
+    /// Session end boundary.
+    pub end_ts: TimestampMs,
+    /// Number of closed trades (including the forced flat).
+    pub trades: u32,
+    /// Net P&L (after fees and funding).
+    pub pnl: f64,
+    /// Fees paid.
+    pub fees: f64,
+    /// Maximum intra-session drawdown of cumulative P&L (absolute).
+    pub max_drawdown: f64,
+}
+
+impl SessionStats {
+    /// Summarize the trades closed during a single session.
+    pub fn from_trades(end_ts: TimestampMs, trades: &[ClosedTrade]) -> Self {
+        let date = auction_core::utc_date(end_ts);
+        let day = DayStats::from_trades(date, trades);
+        SessionStats {
+            date,
+            end_ts,
+            trades: day.trades,
+            pnl: day.pnl,
+            fees: day.fees,
+            max_drawdown: day.max_drawdown,
+        }
+    }
+}
+
 /// Metrics calculator.
 pub struct MetricsCalculator {
     initial_capital: f64,
+    /// Annual risk-free rate subtracted from returns in Sharpe/Sortino.
+    risk_free_rate_annual: f64,
 }
 
 impl MetricsCalculator {
     /// Create a new metrics calculator.
     pub fn new(initial_capital: f64) -> Self {
-        Self { initial_capital }
+        Self {
+            initial_capital,
+            risk_free_rate_annual: 0.0,
+        }
+    }
+
+    /// Set the annual risk-free rate (e.g. 0.05 for 5%).
+    pub fn with_risk_free_rate(mut self, risk_free_rate_annual: f64) -> Self {
+        self.risk_free_rate_annual = risk_free_rate_annual;
+        self
+    }
+
+    /// Estimated number of return periods per year for `n` returns.
+    fn periods_per_year(n: f64) -> f64 {
+        252.0 * 24.0 * 60.0 / n.max(1.0)
+    }
+
+    /// Per-period equivalent of the annual risk-free rate.
+    fn risk_free_per_period(&self, n: f64) -> f64 {
+        self.risk_free_rate_annual / Self::periods_per_year(n)
     }
 
     /// Calculate metrics from closed trades.
@@ -77,10 +189,11 @@ impl MetricsCalculator {
             return BacktestMetrics::default();
         }
 
-        let mut metrics = BacktestMetrics::default();
-
         // Basic counts
-        metrics.total_trades = trades.len() as u32;
+        let mut metrics = BacktestMetrics {
+            total_trades: trades.len() as u32,
+            ..Default::default()
+        };
 
         let mut gross_wins = 0.0;
         let mut gross_losses = 0.0;
@@ -95,6 +208,7 @@ impl MetricsCalculator {
         for trade in trades {
             metrics.net_pnl += trade.pnl;
             metrics.total_fees += trade.fees;
+            metrics.total_slippage += trade.entry_slippage_cost + trade.exit_slippage_cost;
             metrics.total_funding += trade.funding;
 
             let gross = trade.pnl + trade.fees + trade.funding;
@@ -179,6 +293,22 @@ impl MetricsCalculator {
         metrics
     }
 
+    /// Calculate metrics separately for each strategy tag.
+    ///
+    /// Each tag's drawdown and ratios are computed over its own trades as if
+    /// it had traded the full initial capital alone.
+    pub fn calculate_by_tag(&self, trades: &[ClosedTrade]) -> BTreeMap<String, BacktestMetrics> {
+        let mut by_tag: BTreeMap<&str, Vec<ClosedTrade>> = BTreeMap::new();
+        for trade in trades {
+            by_tag.entry(&trade.strategy_tag).or_default().push(trade.clone());
+        }
+
+        by_tag
+            .into_iter()
+            .map(|(tag, tag_trades)| (tag.to_string(), self.calculate(&tag_trades)))
+            .collect()
+    }
+
     /// Build equity curve from trades.
     pub fn build_equity_curve(&self, trades: &[ClosedTrade]) -> Vec<EquityPoint> {
         let mut curve = Vec::with_capacity(trades.len() + 1);
@@ -216,6 +346,92 @@ impl MetricsCalculator {
         curve
     }
 
+    /// Calculate beta, alpha, correlation and tracking error against a benchmark.
+    ///
+    /// Each equity point is paired with the latest benchmark price at or before
+    /// its timestamp (points before the first benchmark price are dropped), and
+    /// returns are taken between consecutive pairs. Annualization uses the
+    /// average spacing of the aligned points. Returns defaults with fewer than
+    /// two return periods or a flat benchmark.
+    pub fn relative_metrics(
+        &self,
+        strategy_equity: &[EquityPoint],
+        benchmark: &[(TimestampMs, f64)],
+    ) -> RelativeMetrics {
+        let mut aligned: Vec<(TimestampMs, f64, f64)> = Vec::with_capacity(strategy_equity.len());
+        let mut bench_idx = 0;
+        let mut bench_price = None;
+
+        for point in strategy_equity {
+            while bench_idx < benchmark.len() && benchmark[bench_idx].0 <= point.ts_ms {
+                bench_price = Some(benchmark[bench_idx].1);
+                bench_idx += 1;
+            }
+            if let Some(price) = bench_price {
+                aligned.push((point.ts_ms, point.equity, price));
+            }
+        }
+
+        let (strat_returns, bench_returns): (Vec<f64>, Vec<f64>) = aligned
+            .windows(2)
+            .filter(|w| w[0].1 > 0.0 && w[0].2 > 0.0)
+            .map(|w| (w[1].1 / w[0].1 - 1.0, w[1].2 / w[0].2 - 1.0))
+            .unzip();
+
+        let n = strat_returns.len();
+        if n < 2 {
+            return RelativeMetrics::default();
+        }
+
+        let nf = n as f64;
+        let mean_s = strat_returns.iter().sum::<f64>() / nf;
+        let mean_b = bench_returns.iter().sum::<f64>() / nf;
+
+        let mut cov = 0.0;
+        let mut var_s = 0.0;
+        let mut var_b = 0.0;
+        let mut active_sq = 0.0;
+        let mean_active = mean_s - mean_b;
+        for (s, b) in strat_returns.iter().zip(&bench_returns) {
+            cov += (s - mean_s) * (b - mean_b);
+            var_s += (s - mean_s).powi(2);
+            var_b += (b - mean_b).powi(2);
+            active_sq += (s - b - mean_active).powi(2);
+        }
+        cov /= nf;
+        var_s /= nf;
+        var_b /= nf;
+
+        if var_b <= 0.0 {
+            return RelativeMetrics::default();
+        }
+
+        // Average spacing of aligned points, in minutes
+        let span_min = (aligned[aligned.len() - 1].0 - aligned[0].0) as f64 / 60_000.0;
+        let periods_per_year = if span_min > 0.0 {
+            252.0 * 24.0 * 60.0 / (span_min / nf)
+        } else {
+            Self::periods_per_year(nf)
+        };
+        let rf = self.risk_free_rate_annual / periods_per_year;
+
+        let beta = cov / var_b;
+        let alpha = (mean_s - rf) - beta * (mean_b - rf);
+        let correlation = if var_s > 0.0 {
+            cov / (var_s.sqrt() * var_b.sqrt())
+        } else {
+            0.0
+        };
+
+        RelativeMetrics {
+            beta,
+            alpha_annual: alpha * periods_per_year,
+            correlation,
+            tracking_error: (active_sq / nf).sqrt() * periods_per_year.sqrt(),
+            periods: n,
+        }
+    }
+
     /// Calculate Sharpe ratio from returns.
     fn calculate_sharpe(&self, returns: &[f64]) -> f64 {
         if returns.len() < 2 {
@@ -226,12 +442,13 @@ impl MetricsCalculator {
         let mean = returns.iter().sum::<f64>() / n;
         let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n;
         let std_dev = variance.sqrt();
+        let excess_mean = mean - self.risk_free_per_period(n);
 
         if std_dev > 0.0 {
             // Annualize: assume 525600 minutes per year, each trade is roughly independent
             // Simplified: just scale by sqrt of trades per year estimate
-            let annualization = (252.0 * 24.0 * 60.0 / n.max(1.0)).sqrt();
-            (mean / std_dev) * annualization
+            let annualization = Self::periods_per_year(n).sqrt();
+            (excess_mean / std_dev) * annualization
         } else {
             0.0
         }
@@ -244,19 +461,21 @@ impl MetricsCalculator {
         }
 
         let n = returns.len() as f64;
-        let mean = returns.iter().sum::<f64>() / n;
+        let rf = self.risk_free_per_period(n);
+        let mean = returns.iter().sum::<f64>() / n - rf;
 
-        // Downside deviation (only negative returns)
+        // Downside deviation (only returns below the risk-free rate)
         let downside_variance = returns
             .iter()
-            .filter(|&&r| r < 0.0)
+            .map(|r| r - rf)
+            .filter(|&r| r < 0.0)
             .map(|r| r.powi(2))
             .sum::<f64>()
             / n;
         let downside_dev = downside_variance.sqrt();
 
         if downside_dev > 0.0 {
-            let annualization = (252.0 * 24.0 * 60.0 / n.max(1.0)).sqrt();
+            let annualization = Self::periods_per_year(n).sqrt();
             (mean / downside_dev) * annualization
         } else if mean > 0.0 {
             f64::INFINITY
@@ -282,9 +501,14 @@ mod tests {
             size: 0.1,
             pnl,
             fees,
+            entry_fee: fees / 2.0,
+            exit_fee: fees / 2.0,
+            entry_slippage_cost: 0.0,
+            exit_slippage_cost: 0.0,
             funding: 0.0,
             exit_reason: ExitReason::TakeProfit1,
             strategy_tag: "test".to_string(),
+            entry_context: None,
         }
     }
 
@@ -307,6 +531,38 @@ mod tests {
         assert!((metrics.net_pnl - 125.0).abs() < 1e-10); // 100 - 50 + 75
     }
 
+    #[test]
+    fn test_calculate_by_tag() {
+        let calculator = MetricsCalculator::new(10000.0);
+        let tagged = |pnl: f64, tag: &str| ClosedTrade { strategy_tag: tag.to_string(), ..make_trade(pnl, 1.0, 60_000) };
+
+        let trades = vec![
+            tagged(100.0, "breakin"),
+            tagged(-40.0, "breakout"),
+            tagged(-20.0, "breakin"),
+            tagged(60.0, "breakout"),
+            tagged(30.0, "breakin"),
+        ];
+
+        let by_tag = calculator.calculate_by_tag(&trades);
+        assert_eq!(by_tag.len(), 2);
+
+        let breakin = &by_tag["breakin"];
+        assert_eq!(breakin.total_trades, 3);
+        assert_eq!(breakin.winning_trades, 2);
+        assert!((breakin.net_pnl - 110.0).abs() < 1e-10);
+        assert!((breakin.max_drawdown - 20.0).abs() < 1e-10);
+
+        let breakout = &by_tag["breakout"];
+        assert_eq!(breakout.total_trades, 2);
+        assert!((breakout.net_pnl - 20.0).abs() < 1e-10);
+
+        // Partitions sum to the whole
+        let total = calculator.calculate(&trades);
+        assert_eq!(breakin.total_trades + breakout.total_trades, total.total_trades);
+        assert!((breakin.net_pnl + breakout.net_pnl - total.net_pnl).abs() < 1e-10);
+    }
+
     #[test]
     fn test_empty_trades() {
         let calculator = MetricsCalculator::new(10000.0);
@@ -335,6 +591,111 @@ mod tests {
         assert!(curve[2].drawdown > 0.0); // Should have drawdown
     }
 
+    #[test]
+    fn test_sharpe_with_risk_free_rate() {
+        // Near-constant 1% return per period with a small alternating wobble
+        let returns: Vec<f64> = (0..100)
+            .map(|i| if i % 2 == 0 { 0.011 } else { 0.009 })
+            .collect();
+
+        let base = MetricsCalculator::new(10000.0);
+        let with_rf = MetricsCalculator::new(10000.0).with_risk_free_rate(0.05);
+
+        let n = returns.len() as f64;
+        let periods = MetricsCalculator::periods_per_year(n);
+        let rf_per_period = 0.05 / periods;
+
+        // Mean 0.01, std 0.001
+        let expected_base = (0.01 / 0.001) * periods.sqrt();
+        let expected_rf = ((0.01 - rf_per_period) / 0.001) * periods.sqrt();
+
+        let sharpe_base = base.calculate_sharpe(&returns);
+        let sharpe_rf = with_rf.calculate_sharpe(&returns);
+
+        assert!((sharpe_base - expected_base).abs() / expected_base < 1e-6);
+        assert!((sharpe_rf - expected_rf).abs() / expected_rf < 1e-6);
+        assert!(sharpe_rf < sharpe_base);
+    }
+
+    #[test]
+    fn test_relative_metrics_half_beta() {
+        let calculator = MetricsCalculator::new(10000.0);
+        let bench_returns = [0.01, -0.02, 0.015, 0.005, -0.01, 0.02, -0.005];
+
+        let mut benchmark = vec![(0i64, 100.0)];
+        let mut curve = vec![EquityPoint {
+            ts_ms: 0,
+            equity: 10000.0,
+            drawdown: 0.0,
+            drawdown_pct: 0.0,
+        }];
+        for (i, r) in bench_returns.iter().enumerate() {
+            let ts_ms = (i as i64 + 1) * 60_000;
+            let last_bench = benchmark.last().unwrap().1;
+            let last_equity = curve.last().unwrap().equity;
+            benchmark.push((ts_ms, last_bench * (1.0 + r)));
+            curve.push(EquityPoint {
+                ts_ms,
+                equity: last_equity * (1.0 + 0.5 * r),
+                drawdown: 0.0,
+                drawdown_pct: 0.0,
+            });
+        }
+
+        let rel = calculator.relative_metrics(&curve, &benchmark);
+        assert_eq!(rel.periods, bench_returns.len());
+        assert!((rel.beta - 0.5).abs() < 1e-9);
+        assert!((rel.correlation - 1.0).abs() < 1e-9);
+        assert!(rel.alpha_annual.abs() < 1e-6);
+        assert!(rel.tracking_error > 0.0);
+    }
+
+    #[test]
+    fn test_relative_metrics_alignment() {
+        let calculator = MetricsCalculator::new(10000.0);
+        let point = |ts_ms, equity| EquityPoint {
+            ts_ms,
+            equity,
+            drawdown: 0.0,
+            drawdown_pct: 0.0,
+        };
+
+        // Strategy points between benchmark ticks use the latest prior price;
+        // the point before any benchmark price is dropped
+        let curve = vec![
+            point(0, 10000.0),
+            point(90_000, 10100.0),
+            point(150_000, 10000.0),
+            point(210_000, 10200.0),
+        ];
+        let benchmark = vec![(60_000, 100.0), (120_000, 102.0), (180_000, 100.0)];
+
+        let rel = calculator.relative_metrics(&curve, &benchmark);
+        assert_eq!(rel.periods, 2);
+
+        // Too few periods
+        let rel = calculator.relative_metrics(&curve[..2], &benchmark);
+        assert_eq!(rel.periods, 0);
+        assert!(rel.beta.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_day_stats() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let trades = vec![
+            make_trade(100.0, 2.0, 60_000),
+            make_trade(-150.0, 2.0, 120_000),
+            make_trade(20.0, 2.0, 180_000),
+        ];
+
+        let stats = DayStats::from_trades(date, &trades);
+
+        assert_eq!(stats.trades, 3);
+        assert!((stats.pnl - (-30.0)).abs() < 1e-10);
+        assert!((stats.fees - 6.0).abs() < 1e-10);
+        assert!((stats.max_drawdown - 150.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_consecutive_wins_losses() {
         let calculator = MetricsCalculator::new(10000.0);
@@ -1,8 +1,87 @@
 //! Position tracking for backtesting.
 //!
-//! Tracks open positions, P&L, and generates fills.
+//! Tracks open positions, P&L, and generates fills. The same accounting can
+//! reconcile live trading: [`PositionTracker::apply_external_fill`] books
+//! exchange fills instead of simulated ones.
+
+use auction_core::{
+    config::InstrumentConfig, is_stop_triggered, is_target_triggered, Features1m, Fill, PositionSide, SizeConversion,
+    SizeUnit, TimestampMs,
+};
+use serde::Serialize;
+
+/// How price moves translate into P&L for an instrument.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContractSpec {
+    /// Contract multiplier applied to P&L.
+    pub multiplier: f64,
+    /// Inverse contract (P&L settled in the base asset).
+    pub inverse: bool,
+    /// Unit of position sizes.
+    pub size_unit: SizeUnit,
+}
+
+impl ContractSpec {
+    /// Linear contract with a multiplier of 1.
+    pub fn linear() -> Self {
+        Self {
+            multiplier: 1.0,
+            inverse: false,
+            size_unit: SizeUnit::Contracts,
+        }
+    }
+
+    /// Base asset quantity of `size` entered at `entry`.
+    pub fn base_qty(&self, size: f64, entry: f64) -> f64 {
+        SizeConversion {
+            unit: self.size_unit,
+            contract_multiplier: self.multiplier,
+            inverse: self.inverse,
+        }
+        .to_base(size, entry)
+    }
+
+    /// Quote currency notional of `size` at `price`, the basis for fees
+    /// and funding. For inverse contracts this is `size * multiplier`.
+    pub fn notional(&self, size: f64, price: f64) -> f64 {
+        self.base_qty(size, price) * price
+    }
+
+    /// Gross P&L of a `size` position on `side` from `entry` to `exit`, in
+    /// the quote currency like fees, funding and equity.
+    ///
+    /// Linear: `(exit - entry) * base_qty`. Inverse: the base asset P&L,
+    /// the entry notional `base_qty * entry` times `(1/entry - 1/exit)`,
+    /// valued at `exit`. Shorts are negated.
+    pub fn pnl(&self, side: PositionSide, entry: f64, exit: f64, size: f64) -> f64 {
+        let base_qty = self.base_qty(size, entry);
+        let long_pnl = if self.inverse {
+            base_qty * entry * (1.0 / entry - 1.0 / exit) * exit
+        } else {
+            base_qty * (exit - entry)
+        };
+        match side {
+            PositionSide::Long => long_pnl,
+            PositionSide::Short => -long_pnl,
+        }
+    }
+}
 
-use auction_core::{Fill, PositionSide, TimestampMs};
+impl Default for ContractSpec {
+    fn default() -> Self {
+        Self::linear()
+    }
+}
+
+impl From<&InstrumentConfig> for ContractSpec {
+    fn from(config: &InstrumentConfig) -> Self {
+        Self {
+            multiplier: config.contract_multiplier,
+            inverse: config.inverse,
+            size_unit: config.size_unit,
+        }
+    }
+}
 
 /// An open position.
 #[derive(Debug, Clone)]
@@ -19,6 +98,10 @@ pub struct Position {
     pub original_size: f64,
     /// Stop price.
     pub stop_price: f64,
+    /// Stop price at entry (defines 1R).
+    pub initial_stop: f64,
+    /// Whether the stop has converted to trailing.
+    pub trailing: bool,
     /// TP1 price.
     pub tp1_price: Option<f64>,
     /// TP2 price.
@@ -29,26 +112,62 @@ pub struct Position {
     pub strategy_tag: String,
     /// Total fees paid.
     pub fees_paid: f64,
+    /// Entry slippage per unit versus the touch price.
+    pub entry_slippage: f64,
     /// Total funding paid.
     pub funding_paid: f64,
+    /// Features at entry (for post-trade analysis).
+    pub entry_context: Option<Features1m>,
+    /// P&L convention of the traded contract.
+    pub contract: ContractSpec,
 }
 
 impl Position {
     /// Calculate unrealized P&L at current price.
+    ///
+    /// Only the open share of fees and funding is charged; partial closes
+    /// have already realized their pro-rated share.
     pub fn unrealized_pnl(&self, current_price: f64) -> f64 {
-        let price_diff = match self.side {
-            PositionSide::Long => current_price - self.entry_price,
-            PositionSide::Short => self.entry_price - current_price,
+        let open_share = if self.original_size > 0.0 { self.size / self.original_size } else { 1.0 };
+        self.contract.pnl(self.side, self.entry_price, current_price, self.size)
+            - (self.fees_paid + self.funding_paid) * open_share
+    }
+
+    /// Initial risk per unit (distance from entry to the initial stop).
+    pub fn initial_risk(&self) -> f64 {
+        (self.entry_price - self.initial_stop).abs()
+    }
+
+    /// Move the stop toward price; a stop is never loosened.
+    pub fn tighten_stop(&mut self, stop: f64) {
+        self.stop_price = match self.side {
+            PositionSide::Long => self.stop_price.max(stop),
+            PositionSide::Short => self.stop_price.min(stop),
         };
-        price_diff * self.size - self.fees_paid - self.funding_paid
+    }
+
+    /// Convert to trailing once the bar reaches `trigger_distance` of open
+    /// profit, then trail the bar's favorable extreme by `offset`.
+    pub fn update_trailing_stop(&mut self, low: f64, high: f64, trigger_distance: f64, offset: f64) {
+        let (extreme, profit) = match self.side {
+            PositionSide::Long => (high, high - self.entry_price),
+            PositionSide::Short => (low, self.entry_price - low),
+        };
+        if !self.trailing && profit >= trigger_distance {
+            self.trailing = true;
+        }
+        if self.trailing {
+            let stop = match self.side {
+                PositionSide::Long => extreme - offset,
+                PositionSide::Short => extreme + offset,
+            };
+            self.tighten_stop(stop);
+        }
     }
 
     /// Check if stop is triggered.
     pub fn is_stopped(&self, low: f64, high: f64) -> bool {
-        match self.side {
-            PositionSide::Long => low <= self.stop_price,
-            PositionSide::Short => high >= self.stop_price,
-        }
+        is_stop_triggered(self.side, self.stop_price, low, high)
     }
 
     /// Check if TP1 is triggered.
@@ -56,25 +175,19 @@ impl Position {
         if self.tp1_hit {
             return false; // Already hit
         }
-        match (self.side, self.tp1_price) {
-            (PositionSide::Long, Some(tp)) => high >= tp,
-            (PositionSide::Short, Some(tp)) => low <= tp,
-            _ => false,
-        }
+        self.tp1_price
+            .is_some_and(|tp| is_target_triggered(self.side, tp, low, high))
     }
 
     /// Check if TP2 is triggered.
     pub fn is_tp2_triggered(&self, low: f64, high: f64) -> bool {
-        match (self.side, self.tp2_price) {
-            (PositionSide::Long, Some(tp)) => high >= tp,
-            (PositionSide::Short, Some(tp)) => low <= tp,
-            _ => false,
-        }
+        self.tp2_price
+            .is_some_and(|tp| is_target_triggered(self.side, tp, low, high))
     }
 }
 
 /// Closed trade record.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ClosedTrade {
     /// Entry timestamp.
     pub entry_ts: TimestampMs,
@@ -90,18 +203,28 @@ pub struct ClosedTrade {
     pub size: f64,
     /// Realized P&L.
     pub pnl: f64,
-    /// Fees paid.
+    /// Fees paid (`entry_fee + exit_fee`).
     pub fees: f64,
+    /// Entry fee pro-rated to this size.
+    pub entry_fee: f64,
+    /// Exit fee.
+    pub exit_fee: f64,
+    /// Entry slippage versus the touch price, times size.
+    pub entry_slippage_cost: f64,
+    /// Exit slippage versus the touch price, times size.
+    pub exit_slippage_cost: f64,
     /// Funding paid.
     pub funding: f64,
     /// Exit reason.
     pub exit_reason: ExitReason,
     /// Strategy tag.
     pub strategy_tag: String,
+    /// Features at entry, if the simulator had them.
+    pub entry_context: Option<Features1m>,
 }
 
 /// Reason for exiting a position.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum ExitReason {
     /// Stop loss hit.
     StopLoss,
@@ -113,14 +236,38 @@ pub enum ExitReason {
     TimeStop,
     /// Signal flip.
     SignalFlip,
+    /// Forced flat at the configured session end.
+    SessionEnd,
+    /// Marked equity fell to the liquidation level.
+    Liquidation,
+    /// Backtest aborted on the drawdown limit.
+    Aborted,
+    /// Price reached the live VA target (`ExitMode::ValueAreaTarget`).
+    ValueAreaTarget,
     /// Manual/other.
     Manual,
 }
 
+/// Position accounting mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PositionMode {
+    /// At most one position; opening replaces it.
+    #[default]
+    OneWay,
+    /// Independent long and short positions may be open at once.
+    Hedge,
+}
+
 /// Position tracker for backtesting.
 pub struct PositionTracker {
-    /// Current open position.
+    /// Current open position (in hedge mode, the earlier-opened leg).
     pub position: Option<Position>,
+    /// Hedge mode only: the open position on the side opposite `position`.
+    pub hedge_position: Option<Position>,
+    /// Accounting mode.
+    mode: PositionMode,
+    /// P&L convention for new positions.
+    contract: ContractSpec,
     /// Closed trades.
     pub trades: Vec<ClosedTrade>,
     /// Total realized P&L.
@@ -140,6 +287,9 @@ impl PositionTracker {
     pub fn new() -> Self {
         Self {
             position: None,
+            hedge_position: None,
+            mode: PositionMode::OneWay,
+            contract: ContractSpec::linear(),
             trades: Vec::new(),
             total_pnl: 0.0,
             total_fees: 0.0,
@@ -149,40 +299,109 @@ impl PositionTracker {
         }
     }
 
+    /// Set the accounting mode.
+    pub fn with_mode(mut self, mode: PositionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Get the accounting mode.
+    pub fn mode(&self) -> PositionMode {
+        self.mode
+    }
+
+    /// Set the contract P&L convention for positions opened afterwards.
+    pub fn with_contract(mut self, contract: ContractSpec) -> Self {
+        self.contract = contract;
+        self
+    }
+
+    /// Get the contract P&L convention.
+    pub fn contract(&self) -> ContractSpec {
+        self.contract
+    }
+
     /// Check if there's an open position.
     pub fn has_position(&self) -> bool {
         self.position.is_some()
     }
 
-    /// Check if position is long.
+    /// Check if a long position is open.
     pub fn is_long(&self) -> bool {
-        self.position.as_ref().map(|p| p.side == PositionSide::Long).unwrap_or(false)
+        self.position_for(PositionSide::Long).is_some()
     }
 
-    /// Check if position is short.
+    /// Check if a short position is open.
     pub fn is_short(&self) -> bool {
-        self.position.as_ref().map(|p| p.side == PositionSide::Short).unwrap_or(false)
+        self.position_for(PositionSide::Short).is_some()
+    }
+
+    /// Get the open position on a side.
+    pub fn position_for(&self, side: PositionSide) -> Option<&Position> {
+        [&self.position, &self.hedge_position]
+            .into_iter()
+            .flatten()
+            .find(|p| p.side == side)
+    }
+
+    /// Net signed size across open positions (long positive).
+    pub fn net_size(&self) -> f64 {
+        [&self.position, &self.hedge_position]
+            .into_iter()
+            .flatten()
+            .map(|p| match p.side {
+                PositionSide::Long => p.size,
+                PositionSide::Short => -p.size,
+            })
+            .sum()
+    }
+
+    /// Unrealized P&L across open positions at a price.
+    pub fn unrealized_pnl(&self, current_price: f64) -> f64 {
+        [&self.position, &self.hedge_position]
+            .into_iter()
+            .flatten()
+            .map(|p| p.unrealized_pnl(current_price))
+            .sum()
     }
 
-    /// Open a new position.
+    /// Open a new position on the fill's side.
+    ///
+    /// In one-way mode this replaces any open position. In hedge mode it
+    /// replaces only an open position on the same side.
     pub fn open_position(&mut self, fill: Fill, stop_price: f64, tp1: Option<f64>, tp2: Option<f64>, strategy_tag: String) {
-        self.position = Some(Position {
+        let position = Some(Position {
             entry_ts: fill.ts_ms,
             side: fill.side,
             entry_price: fill.price,
             size: fill.size,
             original_size: fill.size,
             stop_price,
+            initial_stop: stop_price,
+            trailing: false,
             tp1_price: tp1,
             tp2_price: tp2,
             tp1_hit: false,
             strategy_tag,
             fees_paid: fill.fee,
+            entry_slippage: fill.slippage,
             funding_paid: 0.0,
+            entry_context: None,
+            contract: self.contract,
         });
+
+        let opposite = self.position.as_ref().is_some_and(|p| p.side != fill.side);
+        if self.mode == PositionMode::Hedge && opposite {
+            self.hedge_position = position;
+        } else {
+            self.position = position;
+        }
     }
 
     /// Close position (full or partial).
+    ///
+    /// Closes `position`; use [`close_side`](Self::close_side) to address a
+    /// specific leg in hedge mode.
     pub fn close_position(
         &mut self,
         ts_ms: TimestampMs,
@@ -191,18 +410,50 @@ impl PositionTracker {
         exit_fee: f64,
         reason: ExitReason,
     ) -> Option<ClosedTrade> {
-        let position = self.position.as_mut()?;
+        let side = self.position.as_ref()?.side;
+        self.close_side(side, ts_ms, exit_price, size, exit_fee, reason)
+    }
 
-        // Calculate P&L for this portion
-        let price_diff = match position.side {
-            PositionSide::Long => exit_price - position.entry_price,
-            PositionSide::Short => position.entry_price - exit_price,
+    /// Close the position on `side` (full or partial) without exit slippage.
+    pub fn close_side(
+        &mut self,
+        side: PositionSide,
+        ts_ms: TimestampMs,
+        exit_price: f64,
+        size: f64,
+        exit_fee: f64,
+        reason: ExitReason,
+    ) -> Option<ClosedTrade> {
+        let fill = Fill {
+            ts_ms,
+            price: exit_price,
+            size,
+            side: side.opposite(),
+            fee: exit_fee,
+            slippage: 0.0,
+        };
+        self.close_with_fill(side, &fill, reason)
+    }
+
+    /// Close the position on `side` with an exit fill, recording its fee
+    /// and slippage separately on the closed trade.
+    pub fn close_with_fill(&mut self, side: PositionSide, fill: &Fill, reason: ExitReason) -> Option<ClosedTrade> {
+        let (ts_ms, exit_price, size, exit_fee) = (fill.ts_ms, fill.price, fill.size, fill.fee);
+        let is_hedge_leg = self.hedge_position.as_ref().is_some_and(|p| p.side == side);
+        let slot = if is_hedge_leg {
+            &mut self.hedge_position
+        } else {
+            &mut self.position
         };
+        let position = slot.as_mut().filter(|p| p.side == side)?;
+
+        // Calculate P&L for this portion
+        let gross = position.contract.pnl(position.side, position.entry_price, exit_price, size);
 
         // Pro-rate fees and funding
         let fee_portion = position.fees_paid * (size / position.original_size);
         let funding_portion = position.funding_paid * (size / position.original_size);
-        let pnl = price_diff * size - fee_portion - funding_portion - exit_fee;
+        let pnl = gross - fee_portion - funding_portion - exit_fee;
 
         let trade = ClosedTrade {
             entry_ts: position.entry_ts,
@@ -213,9 +464,14 @@ impl PositionTracker {
             size,
             pnl,
             fees: fee_portion + exit_fee,
+            entry_fee: fee_portion,
+            exit_fee,
+            entry_slippage_cost: position.entry_slippage * size,
+            exit_slippage_cost: fill.slippage * size,
             funding: funding_portion,
             exit_reason: reason,
             strategy_tag: position.strategy_tag.clone(),
+            entry_context: position.entry_context.clone(),
         };
 
         // Update totals
@@ -234,23 +490,101 @@ impl PositionTracker {
         // Update position size
         position.size -= size;
 
-        // If fully closed, remove position
+        // If fully closed, remove position (the hedge leg becomes primary)
         if position.size <= 1e-10 {
-            self.position = None;
+            *slot = None;
+            if self.position.is_none() {
+                self.position = self.hedge_position.take();
+            }
         }
 
         Some(trade)
     }
 
-    /// Move stop to breakeven.
+    /// Book a fill produced outside the simulator (e.g. an exchange fill).
+    ///
+    /// An opposite-side fill in one-way mode reduces the open position,
+    /// closing it with `reason`; any excess opens a position on the fill's
+    /// side, with fees pro-rated by size. A same-side fill adds to the open
+    /// position at the size-weighted entry price. A position opened here has
+    /// no stop (NaN) or targets until set, so `tighten_stop` sets the first
+    /// stop. In hedge mode fills only open or add to the leg on their side;
+    /// close a leg with [`close_with_fill`](Self::close_with_fill).
+    ///
+    /// Returns the closed trade if the fill reduced a position.
+    pub fn apply_external_fill(&mut self, fill: Fill, reason: ExitReason) -> Option<ClosedTrade> {
+        if !fill.size.is_finite() || fill.size <= 0.0 {
+            return None;
+        }
+
+        let mut closed = None;
+        let mut remaining = fill.clone();
+        if self.mode == PositionMode::OneWay {
+            if let Some(open_size) = self.position.as_ref().filter(|p| p.side != fill.side).map(|p| p.size) {
+                let close_size = fill.size.min(open_size);
+                let close_fill = Fill {
+                    size: close_size,
+                    fee: fill.fee * close_size / fill.size,
+                    ..fill.clone()
+                };
+                closed = self.close_with_fill(fill.side.opposite(), &close_fill, reason);
+                remaining.size -= close_size;
+                remaining.fee -= close_fill.fee;
+                if remaining.size <= 1e-10 {
+                    return closed;
+                }
+            }
+        }
+
+        let is_hedge_leg = self.hedge_position.as_ref().is_some_and(|p| p.side == fill.side);
+        let slot = if is_hedge_leg { &mut self.hedge_position } else { &mut self.position };
+        match slot.as_mut().filter(|p| p.side == fill.side) {
+            Some(position) => {
+                let new_size = position.size + remaining.size;
+                position.entry_price = (position.entry_price * position.size + remaining.price * remaining.size) / new_size;
+                position.entry_slippage =
+                    (position.entry_slippage * position.size + remaining.slippage * remaining.size) / new_size;
+                position.size = new_size;
+                position.original_size += remaining.size;
+                position.fees_paid += remaining.fee;
+            }
+            None => self.open_position(remaining, f64::NAN, None, None, String::new()),
+        }
+        closed
+    }
+
+    /// Get the open position on a side, mutably.
+    fn position_for_mut(&mut self, side: PositionSide) -> Option<&mut Position> {
+        [&mut self.position, &mut self.hedge_position]
+            .into_iter()
+            .flatten()
+            .find(|p| p.side == side)
+    }
+
+    /// Move stop to breakeven (unless already tighter).
+    ///
+    /// Applies to every open position; use
+    /// [`move_stop_to_breakeven_for`](Self::move_stop_to_breakeven_for) to
+    /// address one leg in hedge mode.
     pub fn move_stop_to_breakeven(&mut self) {
-        if let Some(pos) = &mut self.position {
-            pos.stop_price = pos.entry_price;
+        for pos in [&mut self.position, &mut self.hedge_position].into_iter().flatten() {
+            pos.tighten_stop(pos.entry_price);
+            pos.tp1_hit = true;
+        }
+    }
+
+    /// Move the stop of the position on a side to breakeven.
+    pub fn move_stop_to_breakeven_for(&mut self, side: PositionSide) {
+        if let Some(pos) = self.position_for_mut(side) {
+            pos.tighten_stop(pos.entry_price);
             pos.tp1_hit = true;
         }
     }
 
     /// Add funding cost to current position.
+    ///
+    /// In hedge mode the legs pay funding of opposite sign, so charge each
+    /// with [`add_funding_for`](Self::add_funding_for) instead.
     pub fn add_funding(&mut self, funding: f64) {
         if let Some(pos) = &mut self.position {
             pos.funding_paid += funding;
@@ -258,6 +592,14 @@ impl PositionTracker {
         self.total_funding += funding;
     }
 
+    /// Add funding cost to the position on a side.
+    pub fn add_funding_for(&mut self, side: PositionSide, funding: f64) {
+        if let Some(pos) = self.position_for_mut(side) {
+            pos.funding_paid += funding;
+        }
+        self.total_funding += funding;
+    }
+
     /// Get current equity (starting capital + realized P&L).
     pub fn equity(&self, starting_capital: f64) -> f64 {
         starting_capital + self.total_pnl
@@ -295,6 +637,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cost_decomposition() {
+        use crate::fill_model::{FillModel, FillModelConfig};
+        use auction_core::Quote;
+
+        let model = FillModel::new(FillModelConfig::default());
+        let entry_quote = Quote { ts_ms: 1000, bid_px: 49999.9, bid_sz: 1.0, ask_px: 50000.0, ask_sz: 1.0 };
+        let exit_quote = Quote { ts_ms: 2000, bid_px: 50100.0, bid_sz: 1.0, ask_px: 50100.1, ask_sz: 1.0 };
+
+        let mut tracker = PositionTracker::new();
+        tracker.open_position(model.market_buy(1000, &entry_quote, 0.5), 49500.0, None, None, "test".to_string());
+        let exit = model.market_sell(2000, &exit_quote, 0.5);
+        let trade = tracker.close_with_fill(PositionSide::Long, &exit, ExitReason::Manual).unwrap();
+
+        assert!((trade.entry_fee + trade.exit_fee - trade.fees).abs() < 1e-10);
+        assert!((trade.entry_slippage_cost - 0.05).abs() < 1e-10);
+        assert!((trade.exit_slippage_cost - 0.05).abs() < 1e-10);
+
+        // Touch-to-touch P&L minus all costs is the realized P&L
+        let touch_pnl = (exit_quote.bid_px - entry_quote.ask_px) * 0.5;
+        let total_cost = trade.entry_fee + trade.exit_fee + trade.entry_slippage_cost + trade.exit_slippage_cost;
+        assert!((touch_pnl - total_cost - trade.pnl).abs() < 1e-9);
+    }
+
     #[test]
     fn test_open_close_long() {
         let mut tracker = PositionTracker::new();
@@ -321,6 +687,88 @@ mod tests {
         assert!(!tracker.has_position());
     }
 
+    #[test]
+    fn test_hedge_mode_independent_legs() {
+        let mut tracker = PositionTracker::new().with_mode(PositionMode::Hedge);
+
+        tracker.open_position(
+            make_fill(50000.0, 0.2, PositionSide::Long),
+            49500.0,
+            None,
+            Some(51000.0),
+            "long".to_string(),
+        );
+        tracker.open_position(
+            make_fill(50000.0, 0.1, PositionSide::Short),
+            50800.0,
+            None,
+            Some(49000.0),
+            "short".to_string(),
+        );
+
+        assert!(tracker.is_long() && tracker.is_short());
+        assert!((tracker.net_size() - 0.1).abs() < 1e-10);
+        assert_eq!(tracker.position_for(PositionSide::Short).unwrap().stop_price, 50800.0);
+
+        // Short stopped out, long left untouched
+        let short = tracker
+            .close_side(PositionSide::Short, 2000, 50800.0, 0.1, 1.0, ExitReason::StopLoss)
+            .unwrap();
+        assert_eq!(short.side, PositionSide::Short);
+        assert!((short.pnl - (-82.0)).abs() < 1e-9); // -80 - 2 fees
+        assert!(tracker.is_long() && !tracker.is_short());
+
+        let long = tracker
+            .close_side(PositionSide::Long, 3000, 51000.0, 0.2, 1.0, ExitReason::TakeProfit2)
+            .unwrap();
+        assert_eq!(long.side, PositionSide::Long);
+        assert!((long.pnl - 198.0).abs() < 1e-9); // 200 - 2 fees
+        assert_eq!(long.strategy_tag, "long");
+
+        assert!(!tracker.has_position());
+        assert_eq!(tracker.trades.len(), 2);
+    }
+
+    #[test]
+    fn test_hedge_mode_breakeven_and_funding() {
+        let mut tracker = PositionTracker::new().with_mode(PositionMode::Hedge);
+        let long = make_fill(50000.0, 0.3, PositionSide::Long);
+        tracker.open_position(long, 49500.0, None, None, "long".to_string());
+        let short = make_fill(50200.0, 0.1, PositionSide::Short);
+        tracker.open_position(short, 50800.0, None, None, "short".to_string());
+        let leg = |tracker: &PositionTracker, side| tracker.position_for(side).unwrap().clone();
+
+        // One leg at a time
+        tracker.move_stop_to_breakeven_for(PositionSide::Short);
+        assert_eq!(leg(&tracker, PositionSide::Short).stop_price, 50200.0);
+        assert_eq!(leg(&tracker, PositionSide::Long).stop_price, 49500.0);
+        tracker.add_funding_for(PositionSide::Long, 2.0);
+        assert_eq!(leg(&tracker, PositionSide::Long).funding_paid, 2.0);
+        assert_eq!(leg(&tracker, PositionSide::Short).funding_paid, 0.0);
+
+        // Both legs
+        tracker.move_stop_to_breakeven();
+        assert_eq!(leg(&tracker, PositionSide::Long).stop_price, 50000.0);
+        assert!(leg(&tracker, PositionSide::Long).tp1_hit);
+        tracker.add_funding_for(PositionSide::Short, -1.0);
+        assert_eq!(leg(&tracker, PositionSide::Long).funding_paid, 2.0);
+        assert_eq!(leg(&tracker, PositionSide::Short).funding_paid, -1.0);
+        assert!((tracker.total_funding - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_one_way_replaces_position() {
+        let mut tracker = PositionTracker::new();
+        tracker.open_position(make_fill(50000.0, 0.1, PositionSide::Long), 49500.0, None, None, "a".to_string());
+        tracker.open_position(make_fill(50000.0, 0.1, PositionSide::Short), 50500.0, None, None, "b".to_string());
+
+        assert!(tracker.is_short() && !tracker.is_long());
+        assert!(tracker.hedge_position.is_none());
+        assert!(tracker
+            .close_side(PositionSide::Long, 2000, 50000.0, 0.1, 0.0, ExitReason::Manual)
+            .is_none());
+    }
+
     #[test]
     fn test_partial_exit() {
         let mut tracker = PositionTracker::new();
@@ -356,12 +804,17 @@ mod tests {
             size: 0.1,
             original_size: 0.1,
             stop_price: 49500.0,
+            initial_stop: 49500.0,
+            trailing: false,
             tp1_price: Some(50500.0),
             tp2_price: Some(51000.0),
             tp1_hit: false,
             strategy_tag: "test".to_string(),
             fees_paid: 1.0,
+            entry_slippage: 0.0,
             funding_paid: 0.0,
+            entry_context: None,
+            contract: ContractSpec::linear(),
         };
 
         // Low touches stop
@@ -370,4 +823,103 @@ mod tests {
         // Low doesn't touch stop
         assert!(!position.is_stopped(49600.0, 50200.0));
     }
+
+    #[test]
+    fn test_apply_external_fill_matches_simulated_path() {
+        let fill = |ts_ms, price, size, side, fee| Fill { ts_ms, price, size, side, fee, slippage: 0.0 };
+
+        // Simulated path: open 0.2, close 0.2
+        let mut simulated = PositionTracker::new();
+        simulated.open_position(fill(1000, 50000.0, 0.2, PositionSide::Long, 2.0), 49500.0, None, None, String::new());
+        simulated.add_funding(0.5);
+        simulated.close_position(3000, 50200.0, 0.2, 2.0, ExitReason::Manual);
+
+        // Live path: the entry arrives as two partial fills, the exit as one
+        let mut live = PositionTracker::new();
+        assert!(live.apply_external_fill(fill(1000, 49990.0, 0.1, PositionSide::Long, 1.0), ExitReason::Manual).is_none());
+        live.apply_external_fill(fill(1500, 50010.0, 0.1, PositionSide::Long, 1.0), ExitReason::Manual);
+        let pos = live.position.as_ref().unwrap();
+        assert!((pos.entry_price - 50000.0).abs() < 1e-9);
+        assert!((pos.size - 0.2).abs() < 1e-12);
+        assert!(pos.stop_price.is_nan());
+        live.add_funding(0.5);
+        let trade = live
+            .apply_external_fill(fill(3000, 50200.0, 0.2, PositionSide::Short, 2.0), ExitReason::Manual)
+            .unwrap();
+
+        assert!(!live.has_position());
+        assert!((trade.pnl - simulated.trades[0].pnl).abs() < 1e-9);
+        assert!((live.total_pnl - simulated.total_pnl).abs() < 1e-9);
+        assert!((live.total_fees - simulated.total_fees).abs() < 1e-9);
+
+        // An oversized exit flips the position, splitting the fee by size
+        live.apply_external_fill(fill(4000, 50000.0, 0.1, PositionSide::Long, 1.0), ExitReason::Manual);
+        let flip = live
+            .apply_external_fill(fill(5000, 50100.0, 0.3, PositionSide::Short, 3.0), ExitReason::SignalFlip)
+            .unwrap();
+        assert!((flip.size - 0.1).abs() < 1e-12);
+        assert!((flip.exit_fee - 1.0).abs() < 1e-9);
+        let short = live.position.as_ref().unwrap();
+        assert_eq!(short.side, PositionSide::Short);
+        assert!((short.size - 0.2).abs() < 1e-12);
+        assert!((short.fees_paid - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_contract_multiplier_and_inverse() {
+        // Linear, multiplier 10: 100 * 2 * 10 = 2000, less 2 in fees
+        let mut linear = PositionTracker::new().with_contract(ContractSpec { multiplier: 10.0, ..ContractSpec::linear() });
+        linear.open_position(make_fill(50000.0, 2.0, PositionSide::Long), 49000.0, None, None, "t".into());
+        assert!((linear.unrealized_pnl(50100.0) - 1999.0).abs() < 1e-9);
+        let trade = linear.close_position(2000, 50100.0, 2.0, 1.0, ExitReason::Manual).unwrap();
+        assert!((trade.pnl - 1998.0).abs() < 1e-9);
+
+        // Inverse, 1000 contracts of 1 USD: long 1000 * (1/40000 - 1/50000) = 0.005 BTC,
+        // worth 250 USD at the exit
+        let inverse = ContractSpec { inverse: true, ..ContractSpec::linear() };
+        let mut tracker = PositionTracker::new().with_contract(inverse);
+        let mut fill = make_fill(40000.0, 1000.0, PositionSide::Long);
+        fill.fee = 0.0;
+        tracker.open_position(fill, 35000.0, None, None, "t".into());
+        assert!((tracker.unrealized_pnl(50000.0) - 250.0).abs() < 1e-9);
+        let trade = tracker.close_position(2000, 50000.0, 1000.0, 0.0, ExitReason::Manual).unwrap();
+        assert!((trade.pnl - 250.0).abs() < 1e-9);
+
+        // Short inverse from 50000 to 40000 gains 0.005 BTC, worth 200 USD at 40000
+        assert!((inverse.pnl(PositionSide::Short, 50000.0, 40000.0, 1000.0) - 200.0).abs() < 1e-9);
+        assert!((inverse.pnl(PositionSide::Short, 40000.0, 50000.0, 1000.0) + 250.0).abs() < 1e-9);
+
+        // Fees and funding are charged on the 1000 USD face value at any price
+        assert!((inverse.notional(1000.0, 40000.0) - 1000.0).abs() < 1e-9);
+        assert!((inverse.notional(1000.0, 50000.0) - 1000.0).abs() < 1e-9);
+
+        let config = InstrumentConfig { contract_multiplier: 100.0, inverse: true, ..Default::default() };
+        assert_eq!(ContractSpec::from(&config), ContractSpec { multiplier: 100.0, inverse: true, ..ContractSpec::linear() });
+        assert_eq!(ContractSpec::from(&InstrumentConfig::default()), ContractSpec::linear());
+    }
+
+    #[test]
+    fn test_size_unit_pnl() {
+        // 5000 USDT of notional at 50000 is 0.1 BTC: +100 per BTC = +10
+        let quote = ContractSpec { size_unit: SizeUnit::Quote, ..ContractSpec::linear() };
+        assert!((quote.base_qty(5000.0, 50000.0) - 0.1).abs() < 1e-12);
+        assert!((quote.pnl(PositionSide::Long, 50000.0, 50100.0, 5000.0) - 10.0).abs() < 1e-9);
+        assert!((quote.pnl(PositionSide::Short, 50000.0, 50100.0, 5000.0) + 10.0).abs() < 1e-9);
+
+        // Base sizes ignore the contract multiplier
+        let base = ContractSpec { multiplier: 100.0, size_unit: SizeUnit::Base, ..ContractSpec::linear() };
+        assert!((base.pnl(PositionSide::Long, 50000.0, 50100.0, 0.1) - 10.0).abs() < 1e-9);
+
+        // Inverse sized in base: 0.02 BTC at 40000 is 800 USD of contracts
+        let inverse_base = ContractSpec { inverse: true, size_unit: SizeUnit::Base, ..ContractSpec::linear() };
+        let expected = 800.0 * (1.0 / 40000.0 - 1.0 / 50000.0) * 50000.0;
+        assert!((inverse_base.pnl(PositionSide::Long, 40000.0, 50000.0, 0.02) - expected).abs() < 1e-9);
+        assert!((inverse_base.notional(0.02, 40000.0) - 800.0).abs() < 1e-9);
+
+        let mut tracker = PositionTracker::new().with_contract(quote);
+        let mut fill = make_fill(50000.0, 5000.0, PositionSide::Long);
+        fill.fee = 0.0;
+        tracker.open_position(fill, 49000.0, None, None, "t".into());
+        assert!((tracker.unrealized_pnl(49900.0) + 10.0).abs() < 1e-9);
+    }
 }
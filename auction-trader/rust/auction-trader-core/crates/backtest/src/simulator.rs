@@ -2,10 +2,84 @@
 //!
 //! Replays historical data and simulates trading based on signals.
 
-use auction_core::{Action, Bar1m, Features1m, Quote, TimestampMs};
+use auction_core::{
+    is_stop_triggered, ts_to_minute, utc_date, Action, Bar1m, Config, CooldownScope, DailyRollover, ExitMode, Features1m,
+    Fill, MinHoldScope, PositionSide, Quote, TimestampMs, TrailTrigger, VaTargetLevel,
+};
 use crate::fill_model::{FillModel, FillModelConfig};
-use crate::metrics::{BacktestMetrics, MetricsCalculator};
-use crate::position::{ClosedTrade, ExitReason, PositionTracker};
+use crate::metrics::{BacktestMetrics, DayStats, EquityPoint, MetricsCalculator, SessionStats};
+use chrono::NaiveTime;
+use crate::position::{ClosedTrade, ContractSpec, ExitReason, PositionTracker};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// When market orders from signals are filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillTiming {
+    /// Fill immediately against the quote passed with the signal.
+    #[default]
+    SignalQuote,
+    /// Defer the fill to the open of the next bar (avoids same-bar lookahead).
+    NextBarOpen,
+}
+
+/// Which level is assumed hit first when a bar's range spans both the stop
+/// and a target (TP1, or TP2 once TP1 is hit).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntrabarAssumption {
+    /// Worst case: the stop is hit first.
+    #[default]
+    StopFirst,
+    /// Targets are hit first; the remainder is then stopped at its
+    /// (possibly breakeven) stop.
+    TargetFirst,
+    /// Whichever of the stop and the next target is closer to the bar open.
+    NearestFirst,
+}
+
+/// What to do at TP1 when the partial (rounded to `qty_step`) or the
+/// remainder would be below `min_qty`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tp1DustPolicy {
+    /// Close the whole position at TP1.
+    #[default]
+    CloseFull,
+    /// Keep the whole position; TP1 still counts as hit (stop to breakeven).
+    SkipPartial,
+}
+
+/// Handling of entry signals whose TP2 is not beyond TP1 in the position's
+/// favorable direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TargetOrderPolicy {
+    /// Ignore the signal (counted in `rejected_signals` and logged).
+    Reject,
+    /// Move TP1 to TP2, so both targets fill together at the nearer price.
+    #[default]
+    Clamp,
+}
+
+/// How funding is charged to an open position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FundingAccrual {
+    /// The full interval's funding is charged in one payment per interval,
+    /// as exchanges do.
+    #[default]
+    Discrete,
+    /// Interval funding is pro-rated over the time between calls, giving a
+    /// smooth equity curve; the first call charges one bar.
+    Continuous,
+}
+
+/// Whether a backtest ran to the end of its data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RunStatus {
+    /// Still running, or ran to the end.
+    #[default]
+    Completed,
+    /// Halted early by `max_drawdown_abort_pct`; later bars and signals are ignored.
+    Aborted,
+}
 
 /// Backtest configuration.
 #[derive(Debug, Clone)]
@@ -16,10 +90,61 @@ pub struct BacktestConfig {
     pub fill_model: FillModelConfig,
     /// Funding rate per 8h in basis points.
     pub funding_rate_8h_bps: f64,
+    /// Lump-sum or pro-rated funding charges.
+    pub funding_accrual: FundingAccrual,
     /// TP1 allocation (fraction of position).
     pub tp1_pct: f64,
+    /// Handling of a TP1 partial or remainder that would be below `min_qty`.
+    pub tp1_dust_policy: Tp1DustPolicy,
+    /// Handling of entries with equal or inverted TP1/TP2.
+    pub target_order_policy: TargetOrderPolicy,
     /// Move stop to breakeven after TP1.
     pub move_stop_to_breakeven: bool,
+    /// Ordering of the stop and targets within a bar that spans both.
+    pub intrabar_assumption: IntrabarAssumption,
+    /// Number of leading bars used only to seed features; signals are
+    /// ignored until this many bars have been replayed.
+    pub warmup_bars: usize,
+    /// Fill timing for market orders.
+    pub fill_timing: FillTiming,
+    /// Minutes after a position is fully closed during which entries are
+    /// ignored (0 = disabled).
+    pub cooldown_minutes: u32,
+    /// Which entries the cooldown blocks.
+    pub cooldown_scope: CooldownScope,
+    /// UTC time at which open positions are forced flat (None = no sessions).
+    pub session_end_utc: Option<NaiveTime>,
+    /// UTC time before which new entries are ignored (requires `session_end_utc`).
+    pub session_start_utc: Option<NaiveTime>,
+    /// Open profit at which the fixed stop converts to trailing (None = never).
+    pub trail_trigger: Option<TrailTrigger>,
+    /// Trailing distance in ticks (None = the initial risk distance).
+    pub trail_offset_ticks: Option<u32>,
+    /// Marked equity at or below which an open position is force-closed
+    /// (None = no liquidation).
+    pub liquidation_equity: Option<f64>,
+    /// Maximum fractional deviation of a mark from the last bar close before
+    /// it is rejected as a bad print (None = accept all marks).
+    pub max_mark_deviation_pct: Option<f64>,
+    /// Re-entries allowed per setup after a stop-out (0 = disabled).
+    pub max_reentries: u32,
+    /// Bars after the entry bar's open during which exits are suppressed
+    /// (0 = disabled; 1 = no exits on the entry bar).
+    pub min_hold_bars: u32,
+    /// Which exits the minimum hold suppresses.
+    pub min_hold_scope: MinHoldScope,
+    /// Catastrophic stop distance from entry in ticks, honored even during
+    /// the minimum hold (None = no hard stop).
+    pub hard_stop_ticks: Option<u32>,
+    /// Drawdown of bar-close marked equity from its peak, as a fraction of
+    /// initial capital, at which the run is aborted (None = never).
+    pub max_drawdown_abort_pct: Option<f64>,
+    /// Fixed TP1/TP2 or a target read from the latest features' VA.
+    pub exit_mode: ExitMode,
+    /// VA level targeted under `ExitMode::ValueAreaTarget`.
+    pub va_target_level: VaTargetLevel,
+    /// Contract multiplier and linear/inverse P&L convention.
+    pub contract: ContractSpec,
 }
 
 impl Default for BacktestConfig {
@@ -28,8 +153,57 @@ impl Default for BacktestConfig {
             initial_capital: 10000.0,
             fill_model: FillModelConfig::default(),
             funding_rate_8h_bps: 1.0,
+            funding_accrual: FundingAccrual::Discrete,
             tp1_pct: 0.30,
+            tp1_dust_policy: Tp1DustPolicy::CloseFull,
+            target_order_policy: TargetOrderPolicy::Clamp,
             move_stop_to_breakeven: true,
+            intrabar_assumption: IntrabarAssumption::StopFirst,
+            warmup_bars: 0,
+            fill_timing: FillTiming::SignalQuote,
+            cooldown_minutes: 0,
+            cooldown_scope: CooldownScope::Global,
+            session_end_utc: None,
+            session_start_utc: None,
+            trail_trigger: None,
+            trail_offset_ticks: None,
+            liquidation_equity: None,
+            max_mark_deviation_pct: None,
+            max_reentries: 0,
+            min_hold_bars: 0,
+            min_hold_scope: MinHoldScope::All,
+            hard_stop_ticks: None,
+            max_drawdown_abort_pct: None,
+            exit_mode: ExitMode::FixedTargets,
+            va_target_level: VaTargetLevel::Poc,
+            contract: ContractSpec::linear(),
+        }
+    }
+}
+
+impl From<&Config> for BacktestConfig {
+    /// Backtest settings from the system config; fields without a
+    /// counterpart there keep their defaults.
+    fn from(config: &Config) -> Self {
+        Self {
+            initial_capital: config.backtest.initial_capital,
+            fill_model: FillModelConfig::from(config),
+            funding_rate_8h_bps: config.backtest.funding_rate_8h_bps,
+            tp1_pct: config.sizing.tp1_pct,
+            move_stop_to_breakeven: config.sizing.move_stop_to_breakeven_after_tp1,
+            warmup_bars: config.backtest.warmup_bars,
+            cooldown_minutes: config.risk.cooldown_minutes,
+            cooldown_scope: config.risk.cooldown_scope,
+            trail_trigger: config.risk.trail_trigger,
+            trail_offset_ticks: config.risk.trail_offset_ticks,
+            max_reentries: config.risk.max_reentries,
+            min_hold_bars: config.risk.min_hold_bars,
+            min_hold_scope: config.risk.min_hold_scope,
+            hard_stop_ticks: config.risk.hard_stop_ticks,
+            contract: ContractSpec::from(&config.instrument),
+            exit_mode: config.signal.exit_mode,
+            va_target_level: config.signal.va_target_level,
+            ..Self::default()
         }
     }
 }
@@ -53,6 +227,22 @@ pub struct Signal {
     pub strategy_tag: String,
 }
 
+/// Entry setup kept for a possible re-entry after a stop-out.
+#[derive(Debug, Clone)]
+struct ReentrySetup {
+    /// Signal of the original entry.
+    signal: Signal,
+    /// Touch price at the original entry (stops/targets are shifted by the
+    /// move from it).
+    entry_ref: f64,
+    /// Price relative to the VA at the original entry.
+    zone: Ordering,
+    /// Re-entries taken so far.
+    count: u32,
+    /// Whether the last exit was a stop that allows a re-entry.
+    armed: bool,
+}
+
 /// Backtest simulator state.
 pub struct BacktestSimulator {
     config: BacktestConfig,
@@ -61,49 +251,422 @@ pub struct BacktestSimulator {
     metrics_calculator: MetricsCalculator,
     /// Current equity.
     equity: f64,
-    /// Last funding timestamp.
+    /// Last funding timestamp (last accrual with `FundingAccrual::Continuous`).
     last_funding_ts: Option<TimestampMs>,
     /// Funding interval in ms (8 hours).
     funding_interval_ms: i64,
+    /// UTC day rollover tracker.
+    daily: DailyRollover,
+    /// Index of the first trade closed in the current day.
+    day_trade_start: usize,
+    /// Summaries of completed days.
+    day_stats: Vec<DayStats>,
+    /// Number of bars replayed so far.
+    bars_seen: usize,
+    /// Latest features, attached to positions as entry context.
+    features: Option<Features1m>,
+    /// Signal awaiting the next bar open (`FillTiming::NextBarOpen`).
+    pending_signal: Option<Signal>,
+    /// Time and side of the last full exit (for the cooldown).
+    last_exit: Option<(TimestampMs, PositionSide)>,
+    /// End boundary of the current session.
+    session_end_ts: Option<TimestampMs>,
+    /// Index of the first trade closed in the current session.
+    session_trade_start: usize,
+    /// Summaries of completed sessions.
+    session_stats: Vec<SessionStats>,
+    /// Close of the last bar checked (reference for mark sanity bounds).
+    last_close: Option<f64>,
+    /// Marks rejected by `max_mark_deviation_pct`.
+    rejected_marks: u64,
+    /// Entry signals rejected by `target_order_policy`.
+    rejected_signals: u64,
+    /// Setup of the current or last position (`max_reentries > 0` only).
+    reentry_setup: Option<ReentrySetup>,
+    /// Peak bar-close marked equity.
+    peak_marked_equity: f64,
+    /// Latest bar-close marked equity.
+    marked_equity: f64,
+    /// Largest drawdown of marked equity from its peak (absolute).
+    max_drawdown: f64,
+    /// Drawdown percentage of the peak at `max_drawdown`.
+    max_drawdown_pct: f64,
+    /// Whether the run was aborted.
+    status: RunStatus,
 }
 
 impl BacktestSimulator {
     /// Create a new backtest simulator.
     pub fn new(config: BacktestConfig) -> Self {
-        let fill_model = FillModel::new(config.fill_model.clone());
+        let fill_model =
+            FillModel::new(config.fill_model.clone()).with_contract(config.contract);
         let metrics_calculator = MetricsCalculator::new(config.initial_capital);
         let equity = config.initial_capital;
+        let position_tracker = PositionTracker::new().with_contract(config.contract);
 
         Self {
             config,
             fill_model,
-            position_tracker: PositionTracker::new(),
+            position_tracker,
             metrics_calculator,
             equity,
             last_funding_ts: None,
             funding_interval_ms: 8 * 60 * 60 * 1000, // 8 hours
+            daily: DailyRollover::new(),
+            day_trade_start: 0,
+            day_stats: Vec::new(),
+            bars_seen: 0,
+            features: None,
+            pending_signal: None,
+            last_exit: None,
+            session_end_ts: None,
+            session_trade_start: 0,
+            session_stats: Vec::new(),
+            last_close: None,
+            rejected_marks: 0,
+            rejected_signals: 0,
+            reentry_setup: None,
+            peak_marked_equity: equity,
+            marked_equity: equity,
+            max_drawdown: 0.0,
+            max_drawdown_pct: 0.0,
+            status: RunStatus::Completed,
+        }
+    }
+
+    /// Record the latest features.
+    ///
+    /// Positions opened afterwards carry a copy as their entry context, which
+    /// is kept on every trade closed from them.
+    pub fn update_features(&mut self, features: &Features1m) {
+        self.features = Some(features.clone());
+    }
+
+    /// Replay a bar.
+    ///
+    /// Counts the bar towards warmup and, once warmup is complete, checks
+    /// stops and targets and the drawdown abort. Returns true if the
+    /// simulator is trading after this bar, i.e. signals for the following
+    /// minute will be acted on.
+    pub fn on_bar(&mut self, bar: &Bar1m, quote: &Quote) -> bool {
+        if self.status == RunStatus::Aborted {
+            return false;
+        }
+        if self.in_warmup() {
+            self.roll_day(bar.ts_min);
+        } else {
+            self.check_stops_targets(bar, quote);
+            self.mark_equity(bar);
+            self.check_drawdown_abort(bar);
+        }
+        self.bars_seen += 1;
+        !self.in_warmup() && self.status == RunStatus::Completed
+    }
+
+    /// Mark equity at the bar close and update the running drawdown.
+    fn mark_equity(&mut self, bar: &Bar1m) {
+        self.marked_equity = self.equity() + self.position_tracker.unrealized_pnl(bar.close);
+        self.peak_marked_equity = self.peak_marked_equity.max(self.marked_equity);
+        let drawdown = self.peak_marked_equity - self.marked_equity;
+        if drawdown > self.max_drawdown {
+            self.max_drawdown = drawdown;
+            self.max_drawdown_pct = self.current_drawdown_pct();
+        }
+    }
+
+    /// Drawdown of the latest bar-close marked equity from its peak, as a
+    /// percentage of the peak.
+    pub fn current_drawdown_pct(&self) -> f64 {
+        if self.peak_marked_equity > 0.0 {
+            (self.peak_marked_equity - self.marked_equity) / self.peak_marked_equity * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Percentage drawdown at the largest bar-close marked drawdown so far,
+    /// as `BacktestMetrics::max_drawdown_pct` reports it.
+    pub fn max_drawdown_pct(&self) -> f64 {
+        self.max_drawdown_pct
+    }
+
+    /// Abort the run if the bar-close marked equity has drawn down from its
+    /// peak by `max_drawdown_abort_pct` of initial capital.
+    ///
+    /// Any open position is closed at the bar close.
+    fn check_drawdown_abort(&mut self, bar: &Bar1m) {
+        let Some(max_dd) = self.config.max_drawdown_abort_pct else {
+            return;
+        };
+        if self.peak_marked_equity - self.marked_equity < max_dd * self.config.initial_capital {
+            return;
+        }
+
+        let close_quote = Quote {
+            ts_ms: bar.ts_min + 59_999,
+            bid_px: bar.close,
+            bid_sz: 0.0,
+            ask_px: bar.close,
+            ask_sz: 0.0,
+        };
+        while self.position_tracker.has_position() {
+            self.close_position(close_quote.ts_ms, &close_quote, ExitReason::Aborted);
+        }
+        self.pending_signal = None;
+        self.status = RunStatus::Aborted;
+    }
+
+    /// Whether the run completed or was aborted.
+    pub fn status(&self) -> RunStatus {
+        self.status
+    }
+
+    /// Check if the simulator is still inside the warmup span.
+    pub fn in_warmup(&self) -> bool {
+        self.bars_seen < self.config.warmup_bars
+    }
+
+    /// Number of bars consumed by warmup so far.
+    pub fn warmup_bars_used(&self) -> usize {
+        self.bars_seen.min(self.config.warmup_bars)
+    }
+
+    /// Advance the UTC day to the one containing `ts_ms`.
+    ///
+    /// On a midnight rollover the per-day state is reset and the summary of
+    /// the day that just ended is recorded and returned.
+    pub fn roll_day(&mut self, ts_ms: TimestampMs) -> Option<DayStats> {
+        let ended = self.daily.advance(ts_ms)?;
+        let trades = &self.position_tracker.trades[self.day_trade_start..];
+        let stats = DayStats::from_trades(ended, trades);
+
+        self.day_trade_start = self.position_tracker.trades.len();
+        self.day_stats.push(stats.clone());
+        Some(stats)
+    }
+
+    /// Summary of the current (incomplete) day so far.
+    pub fn current_day_stats(&self) -> Option<DayStats> {
+        let date = self.daily.current_day()?;
+        let trades = &self.position_tracker.trades[self.day_trade_start..];
+        Some(DayStats::from_trades(date, trades))
+    }
+
+    /// Get summaries of all completed days.
+    pub fn day_stats(&self) -> &[DayStats] {
+        &self.day_stats
+    }
+
+    /// Get summaries of all completed sessions.
+    pub fn session_stats(&self) -> &[SessionStats] {
+        &self.session_stats
+    }
+
+    /// Close the session if `quote` is at or past its end boundary.
+    ///
+    /// Any open position is flattened against `quote` with
+    /// `ExitReason::SessionEnd` and counted in the session that ended.
+    fn roll_session(&mut self, quote: &Quote) -> Option<SessionStats> {
+        let end_time = self.config.session_end_utc?;
+        let ts_ms = quote.ts_ms;
+
+        let ended = match self.session_end_ts {
+            Some(end_ts) if ts_ms >= end_ts => {
+                if self.position_tracker.has_position() {
+                    self.close_position(ts_ms, quote, ExitReason::SessionEnd);
+                }
+                let trades = &self.position_tracker.trades[self.session_trade_start..];
+                let stats = SessionStats::from_trades(end_ts, trades);
+                self.session_trade_start = self.position_tracker.trades.len();
+                self.session_stats.push(stats.clone());
+                Some(stats)
+            }
+            Some(_) => return None,
+            None => None,
+        };
+
+        self.session_end_ts = Some(next_session_end(ts_ms, end_time));
+        ended
+    }
+
+    /// Check if new entries are allowed at `ts_ms` by the session window.
+    fn in_session(&self, ts_ms: TimestampMs) -> bool {
+        let (Some(start), Some(end)) = (self.config.session_start_utc, self.config.session_end_utc) else {
+            return true;
+        };
+        let Some(time) = chrono::DateTime::from_timestamp_millis(ts_ms).map(|dt| dt.time()) else {
+            return true;
+        };
+        if start <= end {
+            start <= time && time < end
+        } else {
+            // Session spans UTC midnight
+            time >= start || time < end
         }
     }
 
     /// Process a signal with the next available quote for fills.
+    ///
+    /// Signals are ignored while the simulator is in warmup. Under
+    /// `FillTiming::NextBarOpen` the signal is held and executed at the open
+    /// of the next bar passed to [`check_stops_targets`](Self::check_stops_targets).
+    ///
+    /// A `Hold` while flat after a stop-out may be replaced by a re-entry of
+    /// the stopped setup; see [`BacktestConfig::max_reentries`].
     pub fn process_signal(&mut self, signal: &Signal, quote: &Quote) {
+        self.roll_day(quote.ts_ms);
+
+        if self.in_warmup() || self.status == RunStatus::Aborted {
+            return;
+        }
+        self.roll_session(quote);
+
+        let reentry = match signal.action {
+            Action::Hold => self.reentry_signal(quote),
+            _ => None,
+        };
+        let signal = reentry.as_ref().unwrap_or(signal);
+
+        if self.config.fill_timing == FillTiming::NextBarOpen {
+            if signal.action != Action::Hold {
+                self.pending_signal = Some(signal.clone());
+            }
+            return;
+        }
+
+        self.execute_signal(signal, quote);
+    }
+
+    /// Get the signal waiting for the next bar open, if any.
+    pub fn pending_signal(&self) -> Option<&Signal> {
+        self.pending_signal.as_ref()
+    }
+
+    /// Build a re-entry of the stopped setup if its conditions still hold.
+    ///
+    /// The price must be on the same side of the VA as at the original entry
+    /// and the minute's order flow must agree with the side. Stops and
+    /// targets keep their distances from the new entry price.
+    fn reentry_signal(&mut self, quote: &Quote) -> Option<Signal> {
+        if self.position_tracker.has_position() {
+            return None;
+        }
+        let setup = self.reentry_setup.as_ref().filter(|s| s.armed)?;
+        let side = match setup.signal.action {
+            Action::EnterLong => PositionSide::Long,
+            _ => PositionSide::Short,
+        };
+        let features = self.features.as_ref()?;
+        if va_zone(features)? != setup.zone
+            || features.order_flow.of_norm_1m * side.sign() <= 0.0
+            || self.in_cooldown(side, quote.ts_ms)
+            || !self.in_session(quote.ts_ms)
+        {
+            return None;
+        }
+
+        let setup = self.reentry_setup.as_mut()?;
+        setup.armed = false;
+        setup.count += 1;
+
+        let shift = entry_ref(side, quote) - setup.entry_ref;
+        let original = &setup.signal;
+        Some(Signal {
+            ts_ms: quote.ts_ms,
+            action: original.action,
+            stop_price: original.stop_price.map(|p| p + shift),
+            tp1_price: original.tp1_price.map(|p| p + shift),
+            tp2_price: original.tp2_price.map(|p| p + shift),
+            size: original.size,
+            strategy_tag: reentry_tag(&original.strategy_tag),
+        })
+    }
+
+    /// Remember the setup of a position just opened by `signal`.
+    ///
+    /// Re-entries keep the original setup (and its re-entry count).
+    fn record_setup(&mut self, signal: &Signal, quote: &Quote) {
+        if self.config.max_reentries == 0 {
+            return;
+        }
+        let Some(side) = self.position_tracker.position.as_ref().map(|p| p.side) else {
+            return;
+        };
+        if self
+            .reentry_setup
+            .as_ref()
+            .is_some_and(|s| reentry_tag(&s.signal.strategy_tag) == signal.strategy_tag)
+        {
+            return;
+        }
+        self.reentry_setup = self.features.as_ref().and_then(va_zone).map(|zone| ReentrySetup {
+            signal: signal.clone(),
+            entry_ref: entry_ref(side, quote),
+            zone,
+            count: 0,
+            armed: false,
+        });
+    }
+
+    /// Check if an entry on `side` at `ts_ms` is blocked by the cooldown.
+    pub fn in_cooldown(&self, side: PositionSide, ts_ms: TimestampMs) -> bool {
+        let Some((exit_ts, exit_side)) = self.last_exit else {
+            return false;
+        };
+        if self.config.cooldown_scope == CooldownScope::PerDirection && exit_side != side {
+            return false;
+        }
+        ts_ms - exit_ts < self.config.cooldown_minutes as i64 * 60_000
+    }
+
+    /// Execute a signal's orders against a quote.
+    ///
+    /// Entries (including flips) blocked by the cooldown, or with targets
+    /// rejected by the target order policy, are ignored.
+    fn execute_signal(&mut self, signal: &Signal, quote: &Quote) {
+        let entry_side = match signal.action {
+            Action::EnterLong => Some(PositionSide::Long),
+            Action::EnterShort => Some(PositionSide::Short),
+            _ => None,
+        };
+        if entry_side.is_some_and(|side| self.in_cooldown(side, quote.ts_ms))
+            || (entry_side.is_some() && !self.in_session(quote.ts_ms))
+        {
+            return;
+        }
+        let ordered;
+        let signal = match entry_side {
+            Some(side) => match self.order_targets(signal, side) {
+                Some(s) => {
+                    ordered = s;
+                    &ordered
+                }
+                None => return,
+            },
+            None => signal,
+        };
+
         match signal.action {
             Action::EnterLong => {
                 if !self.position_tracker.has_position() {
                     self.enter_long(signal, quote);
+                    self.record_setup(signal, quote);
                 } else if self.position_tracker.is_short() {
                     // Flip: close short, enter long
                     self.close_position(quote.ts_ms, quote, ExitReason::SignalFlip);
                     self.enter_long(signal, quote);
+                    self.record_setup(signal, quote);
                 }
             }
             Action::EnterShort => {
                 if !self.position_tracker.has_position() {
                     self.enter_short(signal, quote);
+                    self.record_setup(signal, quote);
                 } else if self.position_tracker.is_long() {
                     // Flip: close long, enter short
                     self.close_position(quote.ts_ms, quote, ExitReason::SignalFlip);
                     self.enter_short(signal, quote);
+                    self.record_setup(signal, quote);
                 }
             }
             Action::Exit => {
@@ -117,9 +680,38 @@ impl BacktestSimulator {
         }
     }
 
-    /// Enter a long position.
+    /// Apply the target order policy to an entry on `side` (None = rejected).
+    fn order_targets(&mut self, signal: &Signal, side: PositionSide) -> Option<Signal> {
+        let (Some(tp1), Some(tp2)) = (signal.tp1_price, signal.tp2_price) else {
+            return Some(signal.clone());
+        };
+        if (tp2 - tp1) * side.sign() > 0.0 {
+            return Some(signal.clone());
+        }
+        match self.config.target_order_policy {
+            TargetOrderPolicy::Reject => {
+                self.rejected_signals += 1;
+                tracing::warn!(
+                    ts_ms = signal.ts_ms,
+                    tp1,
+                    tp2,
+                    "rejected entry with TP2 not beyond TP1"
+                );
+                None
+            }
+            TargetOrderPolicy::Clamp => Some(Signal {
+                tp1_price: Some(tp2),
+                ..signal.clone()
+            }),
+        }
+    }
+
+    /// Enter a long position (skipped if the size rounds to no valid lot).
     fn enter_long(&mut self, signal: &Signal, quote: &Quote) {
-        let size = signal.size.unwrap_or(0.1);
+        let size = self.fill_model.round_qty(signal.size.unwrap_or(0.1));
+        if size <= 0.0 {
+            return;
+        }
         let fill = self.fill_model.market_buy(quote.ts_ms, quote, size);
 
         self.position_tracker.open_position(
@@ -129,11 +721,15 @@ impl BacktestSimulator {
             signal.tp2_price,
             signal.strategy_tag.clone(),
         );
+        self.attach_entry_context();
     }
 
-    /// Enter a short position.
+    /// Enter a short position (skipped if the size rounds to no valid lot).
     fn enter_short(&mut self, signal: &Signal, quote: &Quote) {
-        let size = signal.size.unwrap_or(0.1);
+        let size = self.fill_model.round_qty(signal.size.unwrap_or(0.1));
+        if size <= 0.0 {
+            return;
+        }
         let fill = self.fill_model.market_sell(quote.ts_ms, quote, size);
 
         self.position_tracker.open_position(
@@ -143,62 +739,141 @@ impl BacktestSimulator {
             signal.tp2_price,
             signal.strategy_tag.clone(),
         );
+        self.attach_entry_context();
+    }
+
+    /// Attach the latest features to the newly opened position.
+    fn attach_entry_context(&mut self) {
+        if let Some(pos) = &mut self.position_tracker.position {
+            pos.entry_context = self.features.clone();
+        }
     }
 
     /// Close current position.
     fn close_position(&mut self, ts_ms: TimestampMs, quote: &Quote, reason: ExitReason) {
         if let Some(pos) = &self.position_tracker.position {
             let size = pos.size;
+            let slippage = self.fill_model.exit_slippage_at(quote);
             let exit_price = match pos.side {
-                auction_core::PositionSide::Long => {
-                    quote.bid_px - self.config.fill_model.slippage_ticks_exit as f64
-                        * self.config.fill_model.tick_size
-                }
-                auction_core::PositionSide::Short => {
-                    quote.ask_px + self.config.fill_model.slippage_ticks_exit as f64
-                        * self.config.fill_model.tick_size
-                }
+                auction_core::PositionSide::Long => quote.bid_px - slippage,
+                auction_core::PositionSide::Short => quote.ask_px + slippage,
+            };
+
+            let side = pos.side;
+            let notional = self.config.contract.notional(size, exit_price);
+            let fill = Fill {
+                ts_ms,
+                price: exit_price,
+                size,
+                side: side.opposite(),
+                fee: self.fill_model.calculate_fee(notional, false),
+                slippage,
             };
+            self.position_tracker.close_with_fill(side, &fill, reason);
+            self.record_exit(ts_ms, side);
+        }
+    }
 
-            let fee = self.fill_model.calculate_fee(exit_price * size, false);
-            self.position_tracker.close_position(ts_ms, exit_price, size, fee, reason);
+    /// Start the cooldown if the position is now fully closed.
+    ///
+    /// Any re-entry is disarmed; a stop-out re-arms it afterwards.
+    fn record_exit(&mut self, ts_ms: TimestampMs, side: PositionSide) {
+        if !self.position_tracker.has_position() {
+            self.last_exit = Some((ts_ms, side));
+            if let Some(setup) = self.reentry_setup.as_mut() {
+                setup.armed = false;
+            }
         }
     }
 
     /// Check and process stops/targets for the current bar.
-    pub fn check_stops_targets(&mut self, bar: &Bar1m, quote: &Quote) {
+    ///
+    /// A pending next-bar-open signal is filled at this bar's open first, so
+    /// the new position is exposed to the same bar's range.
+    pub fn check_stops_targets(&mut self, bar: &Bar1m, _quote: &Quote) {
+        self.roll_day(bar.ts_min);
+        self.last_close = Some(bar.close);
+
+        let open_quote = Quote {
+            ts_ms: bar.ts_min,
+            bid_px: bar.open,
+            bid_sz: 0.0,
+            ask_px: bar.open,
+            ask_sz: 0.0,
+        };
+        self.roll_session(&open_quote);
+
+        if let Some(signal) = self.pending_signal.take() {
+            self.execute_signal(&signal, &open_quote);
+        }
+
         let position = match &self.position_tracker.position {
             Some(p) => p.clone(),
             None => return,
         };
 
-        // Check stop (worst case assumption: stop hit first if both triggered)
-        if position.is_stopped(bar.low, bar.high) {
-            let exit_price = position.stop_price;
-            let size = position.size;
-            let fee = self.fill_model.calculate_fee(exit_price * size, false);
-            self.position_tracker.close_position(
-                bar.ts_min + 59_999,
-                exit_price,
-                size,
-                fee,
-                ExitReason::StopLoss,
-            );
+        let held_bars = (bar.ts_min - ts_to_minute(position.entry_ts)) / 60_000;
+        if held_bars < self.config.min_hold_bars as i64 {
+            self.check_min_hold_exits(&position, bar);
+            self.update_trailing_stop(bar);
+            return;
+        }
+
+        // Check stop first unless the intrabar assumption puts a target first
+        let stopped = position.is_stopped(bar.low, bar.high);
+        if stopped && self.stop_first(&position, bar) {
+            self.exit_at_stop(bar.ts_min + 59_999);
             return;
         }
 
+        match self.config.exit_mode {
+            ExitMode::FixedTargets => self.check_fixed_targets(&position, bar),
+            ExitMode::ValueAreaTarget => self.check_value_area_target(&position, bar),
+        }
+
+        // Targets went first: the bar still reaches the stop afterwards
+        if stopped {
+            self.exit_at_stop(bar.ts_min + 59_999);
+        }
+
+        self.update_trailing_stop(bar);
+    }
+
+    /// TP1 (partial) and TP2 (full) exits at the prices set at entry.
+    fn check_fixed_targets(&mut self, position: &crate::position::Position, bar: &Bar1m) {
         // Check TP1 (partial exit)
         if !position.tp1_hit && position.is_tp1_triggered(bar.low, bar.high) {
             if let Some(tp1_price) = position.tp1_price {
-                let partial_size = position.size * self.config.tp1_pct;
-                let fee = self.fill_model.calculate_fee(tp1_price * partial_size, false);
-                self.position_tracker.close_position(
-                    bar.ts_min + 59_999,
-                    tp1_price,
-                    partial_size,
-                    fee,
-                    ExitReason::TakeProfit1,
-                );
+                let raw_partial = position.size * self.config.tp1_pct;
+                let mut partial_size = self.fill_model.round_qty(raw_partial);
+
+                // Neither the partial nor the remainder may be an invalid lot
+                let min_qty = self.config.fill_model.min_qty;
+                let partial_dust = raw_partial > 0.0 && partial_size <= 0.0;
+                let remainder_dust = min_qty > 0.0 && position.size - partial_size < min_qty;
+                let dust = partial_dust || remainder_dust;
+                if dust {
+                    match self.config.tp1_dust_policy {
+                        Tp1DustPolicy::CloseFull => partial_size = position.size,
+                        Tp1DustPolicy::SkipPartial => partial_size = 0.0,
+                    }
+                }
+
+                if partial_size > 0.0 {
+                    let notional = self.config.contract.notional(partial_size, tp1_price);
+                    let fee = self.fill_model.calculate_fee(notional, false);
+                    self.position_tracker.close_position(
+                        bar.ts_min + 59_999,
+                        tp1_price,
+                        partial_size,
+                        fee,
+                        ExitReason::TakeProfit1,
+                    );
+                    self.record_exit(bar.ts_min + 59_999, position.side);
+                }
+                if let Some(pos) = self.position_tracker.position.as_mut() {
+                    pos.tp1_hit = true;
+                }
 
                 // Move stop to breakeven
                 if self.config.move_stop_to_breakeven {
@@ -213,7 +888,8 @@ impl BacktestSimulator {
             if pos.is_tp2_triggered(bar.low, bar.high) {
                 if let Some(tp2_price) = pos.tp2_price {
                     let size = pos.size;
-                    let fee = self.fill_model.calculate_fee(tp2_price * size, false);
+                    let notional = self.config.contract.notional(size, tp2_price);
+                    let fee = self.fill_model.calculate_fee(notional, false);
                     self.position_tracker.close_position(
                         bar.ts_min + 59_999,
                         tp2_price,
@@ -221,34 +897,216 @@ impl BacktestSimulator {
                         fee,
                         ExitReason::TakeProfit2,
                     );
+                    self.record_exit(bar.ts_min + 59_999, position.side);
                 }
             }
         }
     }
 
+    /// Full exit when the bar reaches the configured level of the latest VA.
+    ///
+    /// Only a level on the profitable side of the entry is a target; after
+    /// the VA migrates past the entry the position is left to its stop.
+    fn check_value_area_target(&mut self, position: &crate::position::Position, bar: &Bar1m) {
+        let Some(va) = self.features.as_ref().map(|f| &f.va).filter(|va| va.is_valid) else {
+            return;
+        };
+        let target = match (self.config.va_target_level, position.side) {
+            (VaTargetLevel::Poc, _) => va.poc,
+            (VaTargetLevel::OppositeEdge, PositionSide::Long) => va.vah,
+            (VaTargetLevel::OppositeEdge, PositionSide::Short) => va.val,
+        };
+        let reached = match position.side {
+            PositionSide::Long => target > position.entry_price && bar.high >= target,
+            PositionSide::Short => target < position.entry_price && bar.low <= target,
+        };
+        if reached {
+            let notional = self.config.contract.notional(position.size, target);
+            let fee = self.fill_model.calculate_fee(notional, false);
+            self.position_tracker.close_position(
+                bar.ts_min + 59_999,
+                target,
+                position.size,
+                fee,
+                ExitReason::ValueAreaTarget,
+            );
+            self.record_exit(bar.ts_min + 59_999, position.side);
+        }
+    }
+
+    /// Exits allowed during the minimum hold: the stop (with
+    /// `MinHoldScope::TargetsOnly`), then the hard stop.
+    fn check_min_hold_exits(&mut self, position: &crate::position::Position, bar: &Bar1m) {
+        if self.config.min_hold_scope == MinHoldScope::TargetsOnly && position.is_stopped(bar.low, bar.high) {
+            self.exit_at_stop(bar.ts_min + 59_999);
+            return;
+        }
+
+        if let Some(ticks) = self.config.hard_stop_ticks {
+            let distance = ticks as f64 * self.config.fill_model.tick_size;
+            let hard_stop = match position.side {
+                PositionSide::Long => position.entry_price - distance,
+                PositionSide::Short => position.entry_price + distance,
+            };
+            if is_stop_triggered(position.side, hard_stop, bar.low, bar.high) {
+                self.exit_at(bar.ts_min + 59_999, hard_stop);
+            }
+        }
+    }
+
+    /// Staged stop: evaluated after the bar's exits so the new level only
+    /// applies from the next bar.
+    fn update_trailing_stop(&mut self, bar: &Bar1m) {
+        if let Some(trigger) = self.config.trail_trigger {
+            let tick_size = self.config.fill_model.tick_size;
+            let offset_ticks = self.config.trail_offset_ticks;
+            if let Some(pos) = self.position_tracker.position.as_mut() {
+                let trigger_distance = match trigger {
+                    TrailTrigger::RMultiple { r } => r * pos.initial_risk(),
+                    TrailTrigger::Ticks { ticks } => ticks as f64 * tick_size,
+                };
+                let offset = offset_ticks
+                    .map(|t| t as f64 * tick_size)
+                    .unwrap_or_else(|| pos.initial_risk());
+                pos.update_trailing_stop(bar.low, bar.high, trigger_distance, offset);
+            }
+        }
+    }
+
+    /// Whether the stop is taken before a target hit by the same bar.
+    fn stop_first(&self, position: &crate::position::Position, bar: &Bar1m) -> bool {
+        match self.config.intrabar_assumption {
+            IntrabarAssumption::StopFirst => true,
+            IntrabarAssumption::TargetFirst => false,
+            IntrabarAssumption::NearestFirst => {
+                let target = if position.tp1_hit { position.tp2_price } else { position.tp1_price };
+                target.is_none_or(|tp| (position.stop_price - bar.open).abs() <= (tp - bar.open).abs())
+            }
+        }
+    }
+
+    /// Close the whole position at its stop, arming a re-entry if allowed.
+    fn exit_at_stop(&mut self, ts_ms: TimestampMs) {
+        if let Some(stop_price) = self.position_tracker.position.as_ref().map(|p| p.stop_price) {
+            self.exit_at(ts_ms, stop_price);
+        }
+    }
+
+    /// Close the whole position as stopped out at `exit_price`.
+    fn exit_at(&mut self, ts_ms: TimestampMs, exit_price: f64) {
+        let Some(position) = self.position_tracker.position.as_ref() else {
+            return;
+        };
+        let (side, size) = (position.side, position.size);
+        let notional = self.config.contract.notional(size, exit_price);
+        let fee = self.fill_model.calculate_fee(notional, false);
+        self.position_tracker.close_position(ts_ms, exit_price, size, fee, ExitReason::StopLoss);
+        self.record_exit(ts_ms, side);
+
+        let max_reentries = self.config.max_reentries;
+        if let Some(setup) = self.reentry_setup.as_mut() {
+            setup.armed = setup.count < max_reentries;
+        }
+    }
+
     /// Process funding (call periodically).
+    ///
+    /// With [`FundingAccrual::Continuous`], call once per bar: each call
+    /// charges the interval funding pro-rated by the time since the previous
+    /// call. Time without a position is not charged.
     pub fn process_funding(&mut self, ts_ms: TimestampMs, mark_price: f64) {
-        let should_apply = match self.last_funding_ts {
-            Some(last) => ts_ms - last >= self.funding_interval_ms,
-            None => true,
+        let fraction = match self.config.funding_accrual {
+            FundingAccrual::Discrete => match self.last_funding_ts {
+                Some(last) if ts_ms - last < self.funding_interval_ms => return,
+                _ => 1.0,
+            },
+            FundingAccrual::Continuous => {
+                if !self.position_tracker.has_position() {
+                    self.last_funding_ts = Some(ts_ms);
+                    return;
+                }
+                let elapsed = self.last_funding_ts.map_or(60_000, |last| (ts_ms - last).max(0));
+                elapsed as f64 / self.funding_interval_ms as f64
+            }
         };
 
-        if should_apply && self.position_tracker.has_position() {
-            let pos = self.position_tracker.position.as_ref().unwrap();
-            let notional = mark_price * pos.size;
-            let funding = notional * self.config.funding_rate_8h_bps / 10000.0;
+        if self.position_tracker.has_position() && self.accept_mark(mark_price) {
+            // Each hedge leg pays on its own side
+            for side in [PositionSide::Long, PositionSide::Short] {
+                let Some(pos) = self.position_tracker.position_for(side) else {
+                    continue;
+                };
+                let notional = self.config.contract.notional(pos.size, mark_price);
+                let funding = notional * self.config.funding_rate_8h_bps / 10000.0 * fraction;
 
-            // Longs pay when funding is positive
-            let funding_cost = match pos.side {
-                auction_core::PositionSide::Long => funding,
-                auction_core::PositionSide::Short => -funding,
-            };
+                // Longs pay when funding is positive
+                let funding_cost = match side {
+                    PositionSide::Long => funding,
+                    PositionSide::Short => -funding,
+                };
 
-            self.position_tracker.add_funding(funding_cost);
+                self.position_tracker.add_funding_for(side, funding_cost);
+            }
             self.last_funding_ts = Some(ts_ms);
         }
     }
 
+    /// Mark the open position to `mark_price` and liquidate if configured.
+    ///
+    /// Returns the marked equity (realized equity plus unrealized P&L), or
+    /// None if the mark was rejected by `max_mark_deviation_pct`. Rejected
+    /// marks never trigger liquidation.
+    pub fn mark_to_market(&mut self, ts_ms: TimestampMs, mark_price: f64) -> Option<f64> {
+        if !self.accept_mark(mark_price) {
+            return None;
+        }
+
+        let marked = self.equity()
+            + self
+                .position_tracker
+                .position
+                .as_ref()
+                .map_or(0.0, |p| p.unrealized_pnl(mark_price));
+
+        if let Some(level) = self.config.liquidation_equity {
+            if marked <= level && self.position_tracker.has_position() {
+                let mark_quote = Quote {
+                    ts_ms,
+                    bid_px: mark_price,
+                    bid_sz: 0.0,
+                    ask_px: mark_price,
+                    ask_sz: 0.0,
+                };
+                self.close_position(ts_ms, &mark_quote, ExitReason::Liquidation);
+            }
+        }
+        Some(marked)
+    }
+
+    /// Check a mark against the sanity bounds, counting rejections.
+    fn accept_mark(&mut self, mark_price: f64) -> bool {
+        let ok = match (self.config.max_mark_deviation_pct, self.last_close) {
+            (Some(max_dev), Some(close)) if close > 0.0 => {
+                mark_price.is_finite() && ((mark_price - close) / close).abs() <= max_dev
+            }
+            _ => true,
+        };
+        if !ok {
+            self.rejected_marks += 1;
+        }
+        ok
+    }
+
+    /// Number of marks rejected as bad prints.
+    pub fn rejected_marks(&self) -> u64 {
+        self.rejected_marks
+    }
+
+    /// Number of entry signals rejected for equal or inverted targets.
+    pub fn rejected_signals(&self) -> u64 {
+        self.rejected_signals
+    }
+
     /// Get current position.
     pub fn position(&self) -> Option<&crate::position::Position> {
         self.position_tracker.position.as_ref()
@@ -269,11 +1127,92 @@ impl BacktestSimulator {
         self.metrics_calculator.calculate(&self.position_tracker.trades)
     }
 
+    /// Metrics over the trades with strategy tag `tag` only.
+    pub fn calculate_metrics_for_tag(&self, tag: &str) -> BacktestMetrics {
+        let trades: Vec<ClosedTrade> = self
+            .position_tracker
+            .trades
+            .iter()
+            .filter(|t| t.strategy_tag == tag)
+            .cloned()
+            .collect();
+        self.metrics_calculator.calculate(&trades)
+    }
+
+    /// Metrics per strategy tag.
+    pub fn metrics_by_tag(&self) -> BTreeMap<String, BacktestMetrics> {
+        self.metrics_calculator.calculate_by_tag(&self.position_tracker.trades)
+    }
+
+    /// Realized equity and drawdown after each closed trade.
+    ///
+    /// Starts with the initial capital, so it has `trades().len() + 1` points.
+    pub fn equity_curve(&self) -> Vec<EquityPoint> {
+        self.metrics_calculator.build_equity_curve(&self.position_tracker.trades)
+    }
+
     /// Reset the simulator.
     pub fn reset(&mut self) {
-        self.position_tracker = PositionTracker::new();
+        self.position_tracker = PositionTracker::new().with_contract(self.config.contract);
         self.equity = self.config.initial_capital;
         self.last_funding_ts = None;
+        self.daily.reset();
+        self.day_trade_start = 0;
+        self.day_stats.clear();
+        self.bars_seen = 0;
+        self.features = None;
+        self.pending_signal = None;
+        self.last_exit = None;
+        self.session_end_ts = None;
+        self.session_trade_start = 0;
+        self.session_stats.clear();
+        self.last_close = None;
+        self.rejected_marks = 0;
+        self.rejected_signals = 0;
+        self.reentry_setup = None;
+        self.peak_marked_equity = self.config.initial_capital;
+        self.marked_equity = self.config.initial_capital;
+        self.max_drawdown = 0.0;
+        self.max_drawdown_pct = 0.0;
+        self.status = RunStatus::Completed;
+    }
+}
+
+/// Price relative to the VA: below VAL, inside, or above VAH (None if invalid).
+fn va_zone(features: &Features1m) -> Option<Ordering> {
+    let va = &features.va;
+    if !va.is_valid {
+        return None;
+    }
+    Some(if features.mid_close < va.val {
+        Ordering::Less
+    } else if features.mid_close > va.vah {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    })
+}
+
+/// Touch price an entry on `side` fills against.
+fn entry_ref(side: PositionSide, quote: &Quote) -> f64 {
+    match side {
+        PositionSide::Long => quote.ask_px,
+        PositionSide::Short => quote.bid_px,
+    }
+}
+
+/// Strategy tag for a re-entry of `tag`.
+fn reentry_tag(tag: &str) -> String {
+    format!("{tag}_reentry")
+}
+
+/// First session end boundary strictly after `ts_ms`.
+fn next_session_end(ts_ms: TimestampMs, end: NaiveTime) -> TimestampMs {
+    let boundary = utc_date(ts_ms).and_time(end).and_utc().timestamp_millis();
+    if ts_ms >= boundary {
+        boundary + 24 * 60 * 60 * 1000
+    } else {
+        boundary
     }
 }
 
@@ -308,6 +1247,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_config_from_system_config() {
+        let mut system = Config::default();
+        system.backtest.initial_capital = 25_000.0;
+        system.instrument.tick_size = 0.5;
+        system.instrument.contract_multiplier = 100.0;
+        system.instrument.inverse = true;
+        system.execution.taker_fee_bps = 4.0;
+        system.risk.cooldown_minutes = 7;
+        system.risk.cooldown_scope = CooldownScope::PerDirection;
+        system.risk.trail_trigger = Some(TrailTrigger::RMultiple { r: 1.5 });
+        system.risk.trail_offset_ticks = Some(40);
+        system.risk.max_reentries = 2;
+        system.risk.min_hold_bars = 3;
+        system.risk.min_hold_scope = MinHoldScope::TargetsOnly;
+        system.risk.hard_stop_ticks = Some(500);
+        system.signal.exit_mode = ExitMode::ValueAreaTarget;
+        system.signal.va_target_level = VaTargetLevel::OppositeEdge;
+
+        let config = BacktestConfig::from(&system);
+        assert_eq!(config.initial_capital, 25_000.0);
+        assert_eq!(config.fill_model.tick_size, 0.5);
+        assert_eq!(config.fill_model.taker_fee_bps, 4.0);
+        let contract = ContractSpec { multiplier: 100.0, inverse: true, ..ContractSpec::linear() };
+        assert_eq!(config.contract, contract);
+        assert_eq!(config.cooldown_minutes, 7);
+        assert_eq!(config.cooldown_scope, CooldownScope::PerDirection);
+        assert_eq!(config.trail_trigger, Some(TrailTrigger::RMultiple { r: 1.5 }));
+        assert_eq!(config.trail_offset_ticks, Some(40));
+        assert_eq!(config.max_reentries, 2);
+        assert_eq!(config.min_hold_bars, 3);
+        assert_eq!(config.min_hold_scope, MinHoldScope::TargetsOnly);
+        assert_eq!(config.hard_stop_ticks, Some(500));
+        assert_eq!(config.exit_mode, ExitMode::ValueAreaTarget);
+        assert_eq!(config.va_target_level, VaTargetLevel::OppositeEdge);
+    }
+
     #[test]
     fn test_enter_long() {
         let mut sim = BacktestSimulator::new(BacktestConfig::default());
@@ -329,26 +1305,340 @@ mod tests {
         assert_eq!(sim.position().unwrap().side, auction_core::PositionSide::Long);
     }
 
-    #[test]
-    fn test_stop_loss() {
-        let mut sim = BacktestSimulator::new(BacktestConfig::default());
+    fn cooldown_sim(scope: CooldownScope) -> BacktestSimulator {
+        let mut sim = BacktestSimulator::new(BacktestConfig {
+            cooldown_minutes: 5,
+            cooldown_scope: scope,
+            ..Default::default()
+        });
 
-        // Enter long
+        // Long stopped out in the 60_000 bar (exit at 119_999)
         let signal = Signal {
             ts_ms: 1000,
             action: Action::EnterLong,
             stop_price: Some(49500.0),
-            tp1_price: Some(50500.0),
-            tp2_price: Some(51000.0),
+            tp1_price: None,
+            tp2_price: None,
             size: Some(0.1),
             strategy_tag: "test".to_string(),
         };
+        sim.process_signal(&signal, &make_quote(1000, 50000.0, 50001.0));
+        sim.check_stops_targets(&make_bar(60_000, 49400.0, 50100.0, 49600.0), &make_quote(60_000, 49600.0, 49601.0));
+        assert!(sim.position().is_none());
+        sim
+    }
 
-        let quote = make_quote(1000, 50000.0, 50001.0);
-        sim.process_signal(&signal, &quote);
+    fn reentry_features(ts_min: i64, mid_close: f64, of_norm_1m: f64) -> Features1m {
+        let mut va = auction_core::ValueArea::invalid();
+        va.val = 49900.0;
+        va.vah = 50100.0;
+        va.is_valid = true;
 
-        // Bar that triggers stop
-        let bar = make_bar(60_000, 49400.0, 50100.0, 49600.0);
+        Features1m {
+            ts_min,
+            mid_close,
+            sigma_240: 0.001,
+            bin_width: 1.0,
+            va,
+            order_flow: auction_core::OrderFlowMetrics {
+                of_norm_1m,
+                ..auction_core::OrderFlowMetrics::empty()
+            },
+            spread_avg_60m: 0.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reentry_after_stop_is_capped() {
+        let mut sim = BacktestSimulator::new(BacktestConfig {
+            max_reentries: 1,
+            ..Default::default()
+        });
+        let hold = |ts_ms| Signal {
+            ts_ms,
+            action: Action::Hold,
+            stop_price: None,
+            tp1_price: None,
+            tp2_price: None,
+            size: None,
+            strategy_tag: String::new(),
+        };
+
+        // Long inside value with buying flow, stopped out
+        sim.update_features(&reentry_features(0, 50000.0, 0.3));
+        let signal = Signal {
+            ts_ms: 1000,
+            action: Action::EnterLong,
+            stop_price: Some(49950.0),
+            tp1_price: None,
+            tp2_price: None,
+            size: Some(0.1),
+            strategy_tag: "breakin".to_string(),
+        };
+        sim.process_signal(&signal, &make_quote(1000, 49999.0, 50000.0));
+        sim.check_stops_targets(&make_bar(60_000, 49940.0, 50010.0, 49960.0), &make_quote(60_000, 49960.0, 49961.0));
+        assert!(sim.position().is_none());
+
+        // Setup fails (selling flow): no re-entry, but it stays armed
+        sim.update_features(&reentry_features(120_000, 49960.0, -0.2));
+        sim.process_signal(&hold(121_000), &make_quote(121_000, 49960.0, 49961.0));
+        assert!(sim.position().is_none());
+
+        // Setup holds: one re-entry with the stop shifted by the price move
+        sim.update_features(&reentry_features(180_000, 49960.0, 0.2));
+        sim.process_signal(&hold(181_000), &make_quote(181_000, 49960.0, 49961.0));
+        let pos = sim.position().expect("re-entered");
+        assert_eq!(pos.strategy_tag, "breakin_reentry");
+        assert!((pos.stop_price - 49911.0).abs() < 1e-9);
+
+        // Stopped again: the cap allows no further re-entry
+        sim.check_stops_targets(&make_bar(240_000, 49900.0, 49970.0, 49950.0), &make_quote(240_000, 49950.0, 49951.0));
+        assert!(sim.position().is_none());
+        sim.update_features(&reentry_features(300_000, 49950.0, 0.4));
+        sim.process_signal(&hold(301_000), &make_quote(301_000, 49950.0, 49951.0));
+        assert!(sim.position().is_none());
+        assert_eq!(sim.trades().len(), 2);
+    }
+
+    #[test]
+    fn test_stop_converts_to_trailing_after_1r() {
+        let mut sim = BacktestSimulator::new(BacktestConfig {
+            trail_trigger: Some(TrailTrigger::RMultiple { r: 1.0 }),
+            fill_model: FillModelConfig {
+                slippage_ticks_entry: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        // Long at 50001 (ask), stop 49501: 1R = 500
+        let signal = Signal {
+            stop_price: Some(49501.0),
+            ..entry(1000, Action::EnterLong)
+        };
+        let quote = make_quote(1000, 50000.0, 50001.0);
+        sim.process_signal(&signal, &quote);
+
+        // +0.6R: stop stays fixed
+        sim.check_stops_targets(&make_bar(60_000, 49900.0, 50300.0, 50200.0), &quote);
+        let pos = sim.position().unwrap();
+        assert!(!pos.trailing);
+        assert_eq!(pos.stop_price, 49501.0);
+
+        // +1R reached: trail 1R below the high
+        sim.check_stops_targets(&make_bar(120_000, 50100.0, 50501.0, 50400.0), &quote);
+        let pos = sim.position().unwrap();
+        assert!(pos.trailing);
+        assert_eq!(pos.stop_price, 50001.0);
+
+        // New high ratchets the stop up
+        sim.check_stops_targets(&make_bar(180_000, 50400.0, 50800.0, 50700.0), &quote);
+        assert_eq!(sim.position().unwrap().stop_price, 50300.0);
+
+        // Lower high never loosens it
+        sim.check_stops_targets(&make_bar(240_000, 50400.0, 50700.0, 50500.0), &quote);
+        assert_eq!(sim.position().unwrap().stop_price, 50300.0);
+
+        // Pullback through the trailed stop exits in profit
+        sim.check_stops_targets(&make_bar(300_000, 50200.0, 50500.0, 50250.0), &quote);
+        assert!(sim.position().is_none());
+        let trade = &sim.trades()[0];
+        assert_eq!(trade.exit_reason, ExitReason::StopLoss);
+        assert_eq!(trade.exit_price, 50300.0);
+        assert!(trade.pnl > 0.0);
+    }
+
+    #[test]
+    fn test_outlier_mark_ignored_for_liquidation() {
+        let mut sim = BacktestSimulator::new(BacktestConfig {
+            liquidation_equity: Some(9000.0),
+            max_mark_deviation_pct: Some(0.2),
+            ..Default::default()
+        });
+        let quote = make_quote(1000, 50000.0, 50001.0);
+        sim.process_signal(&Signal { size: Some(0.5), ..entry(1000, Action::EnterLong) }, &quote);
+        sim.check_stops_targets(&make_bar(60_000, 49900.0, 50100.0, 50000.0), &quote);
+
+        // Bad print to zero: rejected, position kept
+        assert!(sim.mark_to_market(120_000, 0.0).is_none());
+        assert_eq!(sim.rejected_marks(), 1);
+        assert!(sim.position().is_some());
+
+        // Normal mark, above the liquidation level
+        let marked = sim.mark_to_market(121_000, 49000.0).unwrap();
+        assert!(marked > 9000.0 && marked < 10000.0);
+        assert!(sim.position().is_some());
+
+        // Genuine move through the liquidation level
+        let marked = sim.mark_to_market(122_000, 47900.0).unwrap();
+        assert!(marked <= 9000.0);
+        assert!(sim.position().is_none());
+        assert_eq!(sim.trades()[0].exit_reason, ExitReason::Liquidation);
+        assert_eq!(sim.rejected_marks(), 1);
+    }
+
+    #[test]
+    fn test_entry_size_rounded_to_lot() {
+        let lot_sim = |min_qty: f64| {
+            BacktestSimulator::new(BacktestConfig {
+                fill_model: FillModelConfig {
+                    min_qty,
+                    qty_step: 0.01,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        };
+        let quote = make_quote(1000, 50000.0, 50001.0);
+        let signal = Signal { size: Some(0.137), ..entry(1000, Action::EnterLong) };
+
+        let mut sim = lot_sim(0.0);
+        sim.process_signal(&signal, &quote);
+        assert!((sim.position().unwrap().size - 0.13).abs() < 1e-12);
+
+        let mut sim = lot_sim(0.5);
+        sim.process_signal(&signal, &quote);
+        assert!(sim.position().is_none());
+    }
+
+    fn entry(ts_ms: i64, action: Action) -> Signal {
+        Signal {
+            ts_ms,
+            action,
+            stop_price: None,
+            tp1_price: None,
+            tp2_price: None,
+            size: Some(0.1),
+            strategy_tag: "test".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cooldown_per_direction() {
+        let mut sim = cooldown_sim(CooldownScope::PerDirection);
+        let quote = make_quote(180_000, 49600.0, 49601.0);
+
+        sim.process_signal(&entry(180_000, Action::EnterLong), &quote);
+        assert!(sim.position().is_none());
+
+        sim.process_signal(&entry(180_000, Action::EnterShort), &quote);
+        assert_eq!(sim.position().unwrap().side, PositionSide::Short);
+    }
+
+    #[test]
+    fn test_cooldown_global() {
+        let mut sim = cooldown_sim(CooldownScope::Global);
+        let quote = make_quote(180_000, 49600.0, 49601.0);
+
+        sim.process_signal(&entry(180_000, Action::EnterLong), &quote);
+        sim.process_signal(&entry(180_000, Action::EnterShort), &quote);
+        assert!(sim.position().is_none());
+
+        // Cooldown expires 5 minutes after the exit
+        let later = make_quote(420_000, 49600.0, 49601.0);
+        sim.process_signal(&entry(420_000, Action::EnterShort), &later);
+        assert_eq!(sim.position().unwrap().side, PositionSide::Short);
+    }
+
+    #[test]
+    fn test_session_end_flattens_position() {
+        // 2024-01-01 00:00 UTC
+        const DAY0: i64 = 1704067200000;
+        const HOUR: i64 = 60 * 60 * 1000;
+
+        let mut sim = BacktestSimulator::new(BacktestConfig {
+            session_end_utc: NaiveTime::from_hms_opt(16, 0, 0),
+            ..Default::default()
+        });
+
+        // Long at 15:00, still open through the 15:30 bar
+        sim.process_signal(&entry(DAY0 + 15 * HOUR, Action::EnterLong), &make_quote(DAY0 + 15 * HOUR, 50000.0, 50001.0));
+        sim.check_stops_targets(&make_bar(DAY0 + 15 * HOUR + HOUR / 2, 49900.0, 50100.0, 50050.0), &make_quote(0, 0.0, 0.0));
+        assert!(sim.position().is_some());
+        assert!(sim.session_stats().is_empty());
+
+        // First bar at the 16:00 boundary flattens at its open
+        sim.check_stops_targets(&make_bar(DAY0 + 16 * HOUR, 50100.0, 50300.0, 50200.0), &make_quote(0, 0.0, 0.0));
+        assert!(sim.position().is_none());
+        assert_eq!(sim.trades().len(), 1);
+        assert_eq!(sim.trades()[0].exit_reason, ExitReason::SessionEnd);
+
+        let sessions = sim.session_stats();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].end_ts, DAY0 + 16 * HOUR);
+        assert_eq!(sessions[0].trades, 1);
+        assert!((sessions[0].pnl - sim.trades()[0].pnl).abs() < 1e-10);
+
+        // Next session ends a day later
+        sim.check_stops_targets(&make_bar(DAY0 + 40 * HOUR, 50100.0, 50300.0, 50200.0), &make_quote(0, 0.0, 0.0));
+        assert_eq!(sim.session_stats().len(), 2);
+        assert_eq!(sim.session_stats()[1].end_ts, DAY0 + 40 * HOUR);
+        assert_eq!(sim.session_stats()[1].trades, 0);
+    }
+
+    #[test]
+    fn test_session_start_blocks_entries() {
+        const DAY0: i64 = 1704067200000;
+        const HOUR: i64 = 60 * 60 * 1000;
+
+        let mut sim = BacktestSimulator::new(BacktestConfig {
+            session_start_utc: NaiveTime::from_hms_opt(8, 0, 0),
+            session_end_utc: NaiveTime::from_hms_opt(16, 0, 0),
+            ..Default::default()
+        });
+
+        sim.process_signal(&entry(DAY0 + 7 * HOUR, Action::EnterLong), &make_quote(DAY0 + 7 * HOUR, 50000.0, 50001.0));
+        assert!(sim.position().is_none());
+
+        sim.process_signal(&entry(DAY0 + 9 * HOUR, Action::EnterLong), &make_quote(DAY0 + 9 * HOUR, 50000.0, 50001.0));
+        assert!(sim.position().is_some());
+    }
+
+    #[test]
+    fn test_equity_curve() {
+        let mut sim = BacktestSimulator::new(BacktestConfig::default());
+        assert_eq!(sim.equity_curve().len(), 1);
+
+        let signal = Signal {
+            ts_ms: 1000,
+            action: Action::EnterLong,
+            stop_price: Some(49500.0),
+            tp1_price: None,
+            tp2_price: None,
+            size: Some(0.1),
+            strategy_tag: "test".to_string(),
+        };
+        sim.process_signal(&signal, &make_quote(1000, 50000.0, 50001.0));
+        sim.check_stops_targets(&make_bar(60_000, 49400.0, 50100.0, 49600.0), &make_quote(60_000, 49600.0, 49601.0));
+
+        let curve = sim.equity_curve();
+        assert_eq!(curve.len(), sim.trades().len() + 1);
+        assert_eq!(curve[1].ts_ms, sim.trades()[0].exit_ts);
+        assert!(curve[1].equity < curve[0].equity);
+        assert!(curve[1].drawdown > 0.0);
+    }
+
+    #[test]
+    fn test_stop_loss() {
+        let mut sim = BacktestSimulator::new(BacktestConfig::default());
+
+        // Enter long
+        let signal = Signal {
+            ts_ms: 1000,
+            action: Action::EnterLong,
+            stop_price: Some(49500.0),
+            tp1_price: Some(50500.0),
+            tp2_price: Some(51000.0),
+            size: Some(0.1),
+            strategy_tag: "test".to_string(),
+        };
+
+        let quote = make_quote(1000, 50000.0, 50001.0);
+        sim.process_signal(&signal, &quote);
+
+        // Bar that triggers stop
+        let bar = make_bar(60_000, 49400.0, 50100.0, 49600.0);
         sim.check_stops_targets(&bar, &quote);
 
         assert!(sim.position().is_none());
@@ -390,6 +1680,217 @@ mod tests {
         assert_eq!(sim.trades()[0].exit_reason, ExitReason::TakeProfit1);
     }
 
+    #[test]
+    fn test_intrabar_stop_vs_tp1() {
+        let run = |assumption: IntrabarAssumption, open: f64| {
+            let mut sim = BacktestSimulator::new(BacktestConfig {
+                intrabar_assumption: assumption,
+                ..Default::default()
+            });
+            let signal = Signal {
+                stop_price: Some(49500.0),
+                tp1_price: Some(50100.0),
+                tp2_price: Some(51000.0),
+                size: Some(1.0),
+                ..entry(1000, Action::EnterLong)
+            };
+            let quote = make_quote(1000, 50000.0, 50000.0);
+            sim.process_signal(&signal, &quote);
+            // Bar spans both the stop and TP1
+            let bar = Bar1m { open, ..make_bar(60_000, 49400.0, 50200.0, 49800.0) };
+            sim.check_stops_targets(&bar, &quote);
+            sim.trades().iter().map(|t| t.exit_reason).collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(IntrabarAssumption::StopFirst, 50000.0), vec![ExitReason::StopLoss]);
+        // TP1 partial, then the remainder stops at breakeven
+        assert_eq!(
+            run(IntrabarAssumption::TargetFirst, 50000.0),
+            vec![ExitReason::TakeProfit1, ExitReason::StopLoss]
+        );
+        // Open near TP1 takes it first; open near the stop stops out
+        assert_eq!(
+            run(IntrabarAssumption::NearestFirst, 50050.0),
+            vec![ExitReason::TakeProfit1, ExitReason::StopLoss]
+        );
+        assert_eq!(run(IntrabarAssumption::NearestFirst, 49600.0), vec![ExitReason::StopLoss]);
+    }
+
+    #[test]
+    fn test_tp1_dust_policy() {
+        let run = |policy: Tp1DustPolicy, size: f64| {
+            let mut sim = BacktestSimulator::new(BacktestConfig {
+                tp1_pct: 0.30,
+                tp1_dust_policy: policy,
+                fill_model: FillModelConfig {
+                    min_qty: 0.01,
+                    qty_step: 0.001,
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            let signal = Signal {
+                stop_price: Some(49500.0),
+                tp1_price: Some(50500.0),
+                tp2_price: Some(51000.0),
+                size: Some(size),
+                ..entry(1000, Action::EnterLong)
+            };
+            let quote = make_quote(1000, 50000.0, 50001.0);
+            sim.process_signal(&signal, &quote);
+            sim.check_stops_targets(&make_bar(60_000, 50000.0, 50600.0, 50550.0), &quote);
+            sim
+        };
+
+        // 30% of 0.012 leaves 0.0084, below the 0.01 minimum
+        let sim = run(Tp1DustPolicy::CloseFull, 0.012);
+        assert!(sim.position().is_none());
+        assert_eq!(sim.trades().len(), 1);
+        assert_eq!(sim.trades()[0].exit_reason, ExitReason::TakeProfit1);
+        assert!((sim.trades()[0].size - 0.012).abs() < 1e-12);
+
+        let sim = run(Tp1DustPolicy::SkipPartial, 0.012);
+        let pos = sim.position().expect("position kept");
+        assert!((pos.size - 0.012).abs() < 1e-12);
+        assert!(pos.tp1_hit);
+        assert!(sim.trades().is_empty());
+
+        // 30% of 0.03 is a 0.009 partial, itself below the 0.01 minimum
+        let sim = run(Tp1DustPolicy::CloseFull, 0.03);
+        assert!(sim.position().is_none());
+        assert!((sim.trades()[0].size - 0.03).abs() < 1e-12);
+
+        let sim = run(Tp1DustPolicy::SkipPartial, 0.03);
+        assert!((sim.position().expect("position kept").size - 0.03).abs() < 1e-12);
+        assert!(sim.trades().is_empty());
+
+        // 30% of 0.045 is 0.0135, rounded down to the 0.001 lot grid
+        let sim = run(Tp1DustPolicy::CloseFull, 0.045);
+        assert!((sim.trades()[0].size - 0.013).abs() < 1e-12);
+        assert!((sim.position().expect("remainder kept").size - 0.032).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_daily_rollover() {
+        const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+        let day1 = 1704067200000i64; // 2024-01-01 00:00:00 UTC
+        let day2 = day1 + DAY_MS;
+        let mut sim = BacktestSimulator::new(BacktestConfig::default());
+
+        let signal = |ts_ms| Signal {
+            ts_ms,
+            action: Action::EnterLong,
+            stop_price: Some(49500.0),
+            tp1_price: None,
+            tp2_price: None,
+            size: Some(0.1),
+            strategy_tag: "test".to_string(),
+        };
+
+        // Day 1: one stopped-out trade
+        sim.process_signal(&signal(day1 + 1000), &make_quote(day1 + 1000, 50000.0, 50001.0));
+        let bar = make_bar(day1 + 60_000, 49400.0, 50100.0, 49600.0);
+        sim.check_stops_targets(&bar, &make_quote(day1 + 60_000, 49600.0, 49601.0));
+        assert!(sim.day_stats().is_empty());
+        assert_eq!(sim.current_day_stats().unwrap().trades, 1);
+
+        // Day 2: first event rolls over and resets the per-day counters
+        sim.process_signal(&signal(day2 + 1000), &make_quote(day2 + 1000, 50000.0, 50001.0));
+        assert_eq!(sim.day_stats().len(), 1);
+        assert_eq!(sim.day_stats()[0].trades, 1);
+        assert!(sim.day_stats()[0].pnl < 0.0);
+        assert_eq!(sim.current_day_stats().unwrap().trades, 0);
+
+        let bar = make_bar(day2 + 60_000, 49400.0, 50100.0, 49600.0);
+        sim.check_stops_targets(&bar, &make_quote(day2 + 60_000, 49600.0, 49601.0));
+
+        // Day 3 event closes out day 2
+        let stats = sim.roll_day(day2 + DAY_MS).unwrap();
+        assert_eq!(stats.date, chrono::NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+        assert_eq!(stats.trades, 1);
+        assert_eq!(sim.day_stats().len(), 2);
+        assert_ne!(sim.day_stats()[0].date, sim.day_stats()[1].date);
+    }
+
+    #[test]
+    fn test_warmup_suppresses_trades() {
+        let config = BacktestConfig {
+            warmup_bars: 3,
+            ..Default::default()
+        };
+        let mut sim = BacktestSimulator::new(config);
+
+        let signal = |ts_ms| Signal {
+            ts_ms,
+            action: Action::EnterLong,
+            stop_price: Some(49500.0),
+            tp1_price: None,
+            tp2_price: None,
+            size: Some(0.1),
+            strategy_tag: "test".to_string(),
+        };
+
+        // An entry fires on every bar, but nothing trades during warmup
+        for i in 0..3 {
+            let ts = i * 60_000;
+            let quote = make_quote(ts, 50000.0, 50001.0);
+            assert!(sim.in_warmup());
+            sim.process_signal(&signal(ts), &quote);
+            assert!(sim.position().is_none());
+
+            let live = sim.on_bar(&make_bar(ts, 49400.0, 50100.0, 50000.0), &quote);
+            assert_eq!(live, i == 2);
+        }
+        assert!(sim.trades().is_empty());
+        assert_eq!(sim.warmup_bars_used(), 3);
+
+        // First signal after warmup is acted on
+        let quote = make_quote(180_000, 50000.0, 50001.0);
+        sim.process_signal(&signal(180_000), &quote);
+        assert!(sim.position().is_some());
+
+        sim.on_bar(&make_bar(180_000, 49400.0, 50100.0, 49600.0), &quote);
+        assert_eq!(sim.trades().len(), 1);
+        assert_eq!(sim.warmup_bars_used(), 3);
+    }
+
+    #[test]
+    fn test_next_bar_open_fill() {
+        let config = BacktestConfig {
+            fill_timing: FillTiming::NextBarOpen,
+            ..Default::default()
+        };
+        let tick = config.fill_model.tick_size;
+        let slippage = config.fill_model.slippage_ticks_entry as f64 * tick;
+        let mut sim = BacktestSimulator::new(config);
+
+        // Entry signal at the close of bar N
+        let bar_n = make_bar(0, 49900.0, 50100.0, 50000.0);
+        sim.check_stops_targets(&bar_n, &make_quote(0, 49999.5, 50000.5));
+        let signal = Signal {
+            ts_ms: 59_999,
+            action: Action::EnterLong,
+            stop_price: Some(49000.0),
+            tp1_price: None,
+            tp2_price: None,
+            size: Some(0.1),
+            strategy_tag: "test".to_string(),
+        };
+        sim.process_signal(&signal, &make_quote(59_999, 49999.5, 50000.5));
+        assert!(sim.position().is_none());
+        assert!(sim.pending_signal().is_some());
+
+        // Bar N+1 opens higher: the entry fills at its open plus slippage
+        let mut bar_n1 = make_bar(60_000, 50150.0, 50300.0, 50250.0);
+        bar_n1.open = 50200.0;
+        sim.check_stops_targets(&bar_n1, &make_quote(60_000, 50199.5, 50200.5));
+
+        let pos = sim.position().expect("filled at next bar open");
+        assert!((pos.entry_price - (50200.0 + slippage)).abs() < 1e-9);
+        assert_eq!(pos.entry_ts, 60_000);
+        assert!(sim.pending_signal().is_none());
+    }
+
     #[test]
     fn test_flip_position() {
         let mut sim = BacktestSimulator::new(BacktestConfig::default());
@@ -428,4 +1929,287 @@ mod tests {
         assert_eq!(sim.trades().len(), 1); // One closed trade from flip
         assert_eq!(sim.trades()[0].exit_reason, ExitReason::SignalFlip);
     }
+
+    #[test]
+    fn test_drawdown_abort() {
+        let run = |step: f64| {
+            let mut sim = BacktestSimulator::new(BacktestConfig {
+                max_drawdown_abort_pct: Some(0.05), // 500 on 10000
+                ..Default::default()
+            });
+            let signal = Signal { stop_price: Some(40000.0), size: Some(1.0), ..entry(1000, Action::EnterLong) };
+            sim.process_signal(&signal, &make_quote(1000, 50000.0, 50001.0));
+            let mut bars = 0;
+            for i in 1..=10 {
+                let close = 50000.0 + step * i as f64;
+                let quote = make_quote(i * 60_000, close, close + 1.0);
+                if !sim.on_bar(&make_bar(i * 60_000, close.min(50000.0) - 1.0, close.max(50000.0) + 1.0, close), &quote) {
+                    break;
+                }
+                bars += 1;
+            }
+            (sim, bars)
+        };
+
+        // Loses 100 per bar: aborted once the drawdown reaches 500
+        let (mut losing, bars) = run(-100.0);
+        assert_eq!(losing.status(), RunStatus::Aborted);
+        assert_eq!(bars, 4);
+        assert!(losing.position().is_none());
+        assert_eq!(losing.trades()[0].exit_reason, ExitReason::Aborted);
+        assert!((losing.trades()[0].exit_price - 49499.9).abs() < 1e-9); // bar close less slippage
+        assert_eq!(losing.calculate_metrics().total_trades, 1);
+        losing.process_signal(&entry(700_000, Action::EnterLong), &make_quote(700_000, 49000.0, 49001.0));
+        assert!(losing.position().is_none());
+
+        let (winning, bars) = run(100.0);
+        assert_eq!(winning.status(), RunStatus::Completed);
+        assert_eq!(bars, 10);
+        assert!(winning.position().is_some());
+    }
+
+    #[test]
+    fn test_min_hold_bars() {
+        let run = |min_hold_scope, hard_stop_ticks| {
+            let mut sim = BacktestSimulator::new(BacktestConfig {
+                min_hold_bars: 2,
+                min_hold_scope,
+                hard_stop_ticks,
+                ..Default::default()
+            });
+            let signal = Signal {
+                stop_price: Some(49950.0),
+                tp1_price: Some(50100.0),
+                tp2_price: Some(50300.0),
+                ..entry(1000, Action::EnterLong)
+            };
+            sim.process_signal(&signal, &make_quote(1000, 50000.0, 50001.0));
+            sim
+        };
+
+        // Entry bar and the next bar breach TP1 (and the stop) without an exit
+        let mut sim = run(MinHoldScope::All, None);
+        let quote = make_quote(0, 50000.0, 50001.0);
+        sim.check_stops_targets(&make_bar(0, 49900.0, 50150.0, 50120.0), &quote);
+        sim.check_stops_targets(&make_bar(60_000, 49990.0, 50150.0, 50120.0), &quote);
+        assert!(sim.trades().is_empty());
+        assert!(!sim.position().unwrap().tp1_hit);
+
+        // Hold satisfied: TP1 partial
+        sim.check_stops_targets(&make_bar(120_000, 50050.0, 50150.0, 50120.0), &quote);
+        assert_eq!(sim.trades().len(), 1);
+        assert_eq!(sim.trades()[0].exit_reason, ExitReason::TakeProfit1);
+        assert!(sim.position().unwrap().tp1_hit);
+
+        // Targets only: the stop still applies during the hold
+        let mut sim = run(MinHoldScope::TargetsOnly, None);
+        sim.check_stops_targets(&make_bar(0, 49900.0, 50150.0, 50120.0), &quote);
+        assert_eq!(sim.trades()[0].exit_reason, ExitReason::StopLoss);
+        assert!((sim.trades()[0].exit_price - 49950.0).abs() < 1e-9);
+
+        // Hard stop (100 ticks = 10.0 below entry) is honored during the hold
+        let mut sim = run(MinHoldScope::All, Some(100));
+        let entry_price = sim.position().unwrap().entry_price;
+        sim.check_stops_targets(&make_bar(0, entry_price - 5.0, 50150.0, 50120.0), &quote);
+        assert!(sim.trades().is_empty());
+        sim.check_stops_targets(&make_bar(60_000, entry_price - 20.0, 50010.0, 50000.0), &quote);
+        assert_eq!(sim.trades()[0].exit_reason, ExitReason::StopLoss);
+        assert!((sim.trades()[0].exit_price - (entry_price - 10.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_target_order_policy() {
+        let run = |target_order_policy| {
+            let mut sim = BacktestSimulator::new(BacktestConfig {
+                target_order_policy,
+                ..Default::default()
+            });
+            // Long with TP2 nearer than TP1
+            let signal = Signal {
+                stop_price: Some(49900.0),
+                tp1_price: Some(50200.0),
+                tp2_price: Some(50100.0),
+                ..entry(1000, Action::EnterLong)
+            };
+            sim.process_signal(&signal, &make_quote(1000, 50000.0, 50001.0));
+            sim
+        };
+
+        let sim = run(TargetOrderPolicy::Reject);
+        assert!(sim.position().is_none());
+        assert_eq!(sim.rejected_signals(), 1);
+
+        // Clamped: both targets at 50100 and the whole position exits there
+        let mut sim = run(TargetOrderPolicy::Clamp);
+        assert_eq!(sim.rejected_signals(), 0);
+        assert_eq!(sim.position().unwrap().tp1_price, Some(50100.0));
+        let quote = make_quote(0, 50000.0, 50001.0);
+        sim.check_stops_targets(&make_bar(60_000, 50050.0, 50150.0, 50120.0), &quote);
+        assert!(sim.position().is_none());
+        let reasons: Vec<_> = sim.trades().iter().map(|t| t.exit_reason).collect();
+        assert_eq!(reasons, [ExitReason::TakeProfit1, ExitReason::TakeProfit2]);
+        assert!(sim.trades().iter().all(|t| (t.exit_price - 50100.0).abs() < 1e-9));
+
+        // Short targets must descend; equal targets are not ordered either
+        let mut sim = BacktestSimulator::new(BacktestConfig {
+            target_order_policy: TargetOrderPolicy::Reject,
+            ..Default::default()
+        });
+        let signal = Signal {
+            stop_price: Some(50100.0),
+            tp1_price: Some(49900.0),
+            tp2_price: Some(49900.0),
+            ..entry(1000, Action::EnterShort)
+        };
+        sim.process_signal(&signal, &make_quote(1000, 50000.0, 50001.0));
+        assert_eq!(sim.rejected_signals(), 1);
+        let signal = Signal {
+            tp2_price: Some(49800.0),
+            ..signal
+        };
+        sim.process_signal(&signal, &make_quote(2000, 50000.0, 50001.0));
+        assert!(sim.position().is_some());
+    }
+
+    #[test]
+    fn test_value_area_target_exit() {
+        let features = |poc: f64| {
+            let mut f = reentry_features(0, 49850.0, 0.0);
+            f.va.poc = poc;
+            f
+        };
+        let mut sim = BacktestSimulator::new(BacktestConfig {
+            exit_mode: ExitMode::ValueAreaTarget,
+            ..Default::default()
+        });
+        sim.update_features(&features(50000.0));
+
+        // Long entered below VAL (49900), no fixed targets
+        let signal = Signal {
+            stop_price: Some(49700.0),
+            ..entry(1000, Action::EnterLong)
+        };
+        let quote = make_quote(1000, 49850.0, 49851.0);
+        sim.process_signal(&signal, &quote);
+
+        // Back inside the VA but short of the POC
+        sim.check_stops_targets(&make_bar(60_000, 49840.0, 49990.0, 49960.0), &quote);
+        assert!(sim.trades().is_empty());
+
+        // The POC migrates down into the bar's reach
+        sim.update_features(&features(49980.0));
+        sim.check_stops_targets(&make_bar(120_000, 49950.0, 49985.0, 49970.0), &quote);
+        assert_eq!(sim.trades().len(), 1);
+        assert_eq!(sim.trades()[0].exit_reason, ExitReason::ValueAreaTarget);
+        assert!((sim.trades()[0].exit_price - 49980.0).abs() < 1e-9);
+        assert!(sim.position().is_none());
+    }
+
+    #[test]
+    fn test_funding_accrual() {
+        let run = |funding_accrual| {
+            let mut sim = BacktestSimulator::new(BacktestConfig { funding_accrual, ..Default::default() });
+            let signal = Signal { stop_price: Some(40000.0), size: Some(1.0), ..entry(1000, Action::EnterLong) };
+            sim.process_signal(&signal, &make_quote(1000, 50000.0, 50001.0));
+
+            // One 8h interval of 1m bars; 1 bps of 50000 notional = 5.0
+            let equity: Vec<f64> = (1..=480)
+                .map(|i| {
+                    sim.process_funding(i * 60_000, 50000.0);
+                    sim.mark_to_market(i * 60_000, 50000.0).unwrap()
+                })
+                .collect();
+            (sim.position().unwrap().funding_paid, equity)
+        };
+
+        let (discrete_total, discrete) = run(FundingAccrual::Discrete);
+        let (continuous_total, continuous) = run(FundingAccrual::Continuous);
+        assert!((discrete_total - 5.0).abs() < 1e-9);
+        assert!((continuous_total - discrete_total).abs() < 1e-9);
+        assert!((discrete.last().unwrap() - continuous.last().unwrap()).abs() < 1e-9);
+
+        // Discrete charges the lump up front; continuous one bar at a time
+        assert!((discrete[0] - discrete[479]).abs() < 1e-9);
+        assert!((continuous[0] - continuous[1] - 5.0 / 480.0).abs() < 1e-9);
+        assert!((continuous[239] - discrete[239] - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hedge_mode_funding() {
+        let mut sim = BacktestSimulator::new(BacktestConfig::default());
+        sim.position_tracker = PositionTracker::new().with_mode(crate::position::PositionMode::Hedge);
+        let fill = |price, size, side| Fill { ts_ms: 1000, price, size, side, fee: 0.0, slippage: 0.0 };
+        let tracker = &mut sim.position_tracker;
+        tracker.open_position(fill(50000.0, 1.0, PositionSide::Long), 49000.0, None, None, "long".to_string());
+        tracker.open_position(fill(50000.0, 0.5, PositionSide::Short), 51000.0, None, None, "short".to_string());
+
+        // 1 bps of each leg's notional: the long pays, the short receives
+        sim.process_funding(60_000, 50000.0);
+        let long = sim.position_tracker.position_for(PositionSide::Long).unwrap();
+        let short = sim.position_tracker.position_for(PositionSide::Short).unwrap();
+        assert!((long.funding_paid - 5.0).abs() < 1e-9);
+        assert!((short.funding_paid + 2.5).abs() < 1e-9);
+        assert!((sim.position_tracker.total_funding - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_running_drawdown() {
+        let mut sim = BacktestSimulator::new(BacktestConfig::default());
+        // Round trips of 1.0 closed within each bar, flat at every close
+        let moves = [200.0, -300.0, 100.0, -400.0, 600.0, -100.0];
+        for (i, step) in moves.iter().enumerate() {
+            let ts = (i as i64 + 1) * 60_000;
+            let signal = Signal { stop_price: Some(40000.0), size: Some(1.0), ..entry(ts, Action::EnterLong) };
+            sim.process_signal(&signal, &make_quote(ts, 49999.0, 50000.0));
+            let exit = 50000.0 + step;
+            sim.process_signal(&entry(ts + 30_000, Action::Exit), &make_quote(ts + 30_000, exit, exit + 1.0));
+            sim.on_bar(&make_bar(ts, 49990.0, 50010.0, 50000.0), &make_quote(ts + 59_000, 49999.0, 50000.0));
+        }
+
+        let metrics = sim.calculate_metrics();
+        assert_eq!(metrics.total_trades as usize, moves.len());
+        assert!(metrics.max_drawdown_pct > 0.0);
+        assert!((sim.max_drawdown_pct() - metrics.max_drawdown_pct).abs() < 1e-9);
+        assert!(sim.current_drawdown_pct() > 0.0);
+        assert!(sim.current_drawdown_pct() < sim.max_drawdown_pct());
+
+        sim.reset();
+        assert_eq!(sim.max_drawdown_pct(), 0.0);
+        assert_eq!(sim.current_drawdown_pct(), 0.0);
+    }
+
+    #[test]
+    fn test_drawdown_across_tp1_partial() {
+        let mut sim = BacktestSimulator::new(BacktestConfig {
+            tp1_pct: 0.5,
+            funding_rate_8h_bps: 0.0,
+            ..Default::default()
+        });
+        let signal = Signal {
+            stop_price: Some(49000.0),
+            tp1_price: Some(50500.0),
+            tp2_price: Some(52000.0),
+            size: Some(1.0),
+            ..entry(1000, Action::EnterLong)
+        };
+        sim.process_signal(&signal, &make_quote(1000, 49999.0, 50000.0));
+        let (entry_price, entry_fee) = {
+            let pos = sim.position().unwrap();
+            (pos.entry_price, pos.fees_paid)
+        };
+
+        // Peak: the full position marked at 50400
+        sim.on_bar(&make_bar(60_000, 49990.0, 50450.0, 50400.0), &make_quote(119_000, 50399.0, 50400.0));
+        let peak = sim.config.initial_capital + (50400.0 - entry_price) - entry_fee;
+        assert!((sim.marked_equity - peak).abs() < 1e-6);
+
+        // TP1 closes half; the remainder carries only its half of the entry fee
+        sim.on_bar(&make_bar(120_000, 50300.0, 50550.0, 50300.0), &make_quote(179_000, 50299.0, 50300.0));
+        assert_eq!(sim.trades().len(), 1);
+        let marked = sim.config.initial_capital + sim.trades()[0].pnl + 0.5 * (50300.0 - entry_price) - 0.5 * entry_fee;
+        assert!((sim.marked_equity - marked).abs() < 1e-6);
+        let expected_pct = (peak - marked) / peak * 100.0;
+        assert!((sim.current_drawdown_pct() - expected_pct).abs() < 1e-9);
+        assert!((sim.max_drawdown_pct() - expected_pct).abs() < 1e-9);
+    }
 }
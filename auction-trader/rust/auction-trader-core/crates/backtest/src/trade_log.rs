@@ -0,0 +1,248 @@
+//! Trade log export.
+//!
+//! Dumps closed trades, including the features at entry, to JSON or CSV for
+//! slicing performance by market state.
+
+use crate::position::{ClosedTrade, ExitReason};
+use auction_core::{Features1m, PositionSide, Result};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// CSV header. Context columns are empty for trades without entry context.
+const CSV_HEADER: &str = "entry_ts,exit_ts,side,entry_price,exit_price,size,pnl,fees,funding,\
+exit_reason,strategy_tag,sigma_240,va_position,of_1m,of_norm_1m,qimb_close";
+
+/// Serialize trades (with full entry context) to pretty-printed JSON.
+pub fn to_json(trades: &[ClosedTrade]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(trades)?)
+}
+
+/// Serialize trades to CSV with a flattened subset of the entry context.
+pub fn to_csv(trades: &[ClosedTrade]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+
+    for t in trades {
+        let side = match t.side {
+            PositionSide::Long => "long",
+            PositionSide::Short => "short",
+        };
+        let _ = write!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            t.entry_ts,
+            t.exit_ts,
+            side,
+            t.entry_price,
+            t.exit_price,
+            t.size,
+            t.pnl,
+            t.fees,
+            t.funding,
+            exit_reason_str(t.exit_reason),
+            csv_escape(&t.strategy_tag),
+        );
+
+        match &t.entry_context {
+            Some(f) => {
+                let va_position = va_position(f).map(|p| p.to_string()).unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    ",{},{},{},{},{}",
+                    f.sigma_240,
+                    va_position,
+                    f.order_flow.of_1m,
+                    f.order_flow.of_norm_1m,
+                    f.qimb_close,
+                );
+            }
+            None => out.push_str(",,,,,\n"),
+        }
+    }
+
+    out
+}
+
+/// Write trades as JSON to a file.
+pub fn write_json<P: AsRef<Path>>(path: P, trades: &[ClosedTrade]) -> Result<()> {
+    std::fs::write(path, to_json(trades)?)?;
+    Ok(())
+}
+
+/// Write trades as CSV to a file.
+pub fn write_csv<P: AsRef<Path>>(path: P, trades: &[ClosedTrade]) -> Result<()> {
+    std::fs::write(path, to_csv(trades))?;
+    Ok(())
+}
+
+/// Position of the close within the Value Area (0 = VAL, 1 = VAH).
+///
+/// Values outside [0, 1] mean the close was outside the VA. `None` if the VA
+/// is invalid or degenerate.
+fn va_position(features: &Features1m) -> Option<f64> {
+    let va = &features.va;
+    let width = va.vah - va.val;
+    if !va.is_valid || width <= 0.0 {
+        return None;
+    }
+    Some((features.mid_close - va.val) / width)
+}
+
+fn exit_reason_str(reason: ExitReason) -> &'static str {
+    match reason {
+        ExitReason::StopLoss => "stop_loss",
+        ExitReason::TakeProfit1 => "take_profit_1",
+        ExitReason::TakeProfit2 => "take_profit_2",
+        ExitReason::TimeStop => "time_stop",
+        ExitReason::SignalFlip => "signal_flip",
+        ExitReason::SessionEnd => "session_end",
+        ExitReason::Liquidation => "liquidation",
+        ExitReason::Aborted => "aborted",
+        ExitReason::ValueAreaTarget => "value_area_target",
+        ExitReason::Manual => "manual",
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::{BacktestConfig, BacktestSimulator, Signal};
+    use auction_core::{Action, Bar1m, OrderFlowMetrics, Quote, ValueArea};
+
+    fn make_features(ts_min: i64) -> Features1m {
+        let mut va = ValueArea::invalid();
+        va.val = 49900.0;
+        va.vah = 50100.0;
+        va.is_valid = true;
+
+        Features1m {
+            ts_min,
+            mid_close: 50050.0,
+            sigma_240: 0.0012,
+            bin_width: 1.0,
+            va,
+            order_flow: OrderFlowMetrics {
+                of_1m: 12.5,
+                of_norm_1m: 0.25,
+                total_volume: 50.0,
+                buy_volume: 30.0,
+                sell_volume: 17.5,
+                ambiguous_volume: 2.5,
+                ambiguous_frac: 0.05,
+                has_volume: true,
+                at_bid_volume: 17.5,
+                at_ask_volume: 30.0,
+                inside_volume: 2.5,
+            },
+            qimb_close: 0.3,
+            qimb_ema: 0.2,
+            spread_avg_60m: 0.5,
+            eff_spread_avg_1m: 0.6,
+            ..Default::default()
+        }
+    }
+
+    fn make_quote(ts_ms: i64, bid: f64, ask: f64) -> Quote {
+        Quote {
+            ts_ms,
+            bid_px: bid,
+            bid_sz: 100.0,
+            ask_px: ask,
+            ask_sz: 100.0,
+        }
+    }
+
+    fn run_stopped_trade(features: Option<&Features1m>) -> Vec<ClosedTrade> {
+        let mut sim = BacktestSimulator::new(BacktestConfig::default());
+        if let Some(f) = features {
+            sim.update_features(f);
+        }
+
+        let signal = Signal {
+            ts_ms: 60_000,
+            action: Action::EnterLong,
+            stop_price: Some(49500.0),
+            tp1_price: None,
+            tp2_price: None,
+            size: Some(0.1),
+            strategy_tag: "breakin".to_string(),
+        };
+        let quote = make_quote(60_000, 50000.0, 50001.0);
+        sim.process_signal(&signal, &quote);
+
+        let bar = Bar1m {
+            ts_min: 120_000,
+            open: 50000.0,
+            high: 50100.0,
+            low: 49400.0,
+            close: 49600.0,
+            volume: 100.0,
+            vwap: Some(49800.0),
+            trade_count: 10,
+            bid_px_close: 49599.5,
+            ask_px_close: 49600.5,
+            bid_sz_close: 100.0,
+            ask_sz_close: 100.0,
+        };
+        sim.check_stops_targets(&bar, &quote);
+        sim.trades().to_vec()
+    }
+
+    #[test]
+    fn test_entry_context_attached() {
+        let features = make_features(0);
+        let trades = run_stopped_trade(Some(&features));
+
+        assert_eq!(trades.len(), 1);
+        let ctx = trades[0].entry_context.as_ref().expect("entry context");
+        assert_eq!(ctx.ts_min, 0);
+        assert!((ctx.sigma_240 - 0.0012).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_json_export() {
+        let trades = run_stopped_trade(Some(&make_features(0)));
+        let json = to_json(&trades).unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let trade = &value[0];
+        assert_eq!(trade["exit_reason"], "StopLoss");
+        assert_eq!(trade["strategy_tag"], "breakin");
+        assert_eq!(trade["entry_context"]["order_flow"]["of_1m"], 12.5);
+        assert_eq!(trade["entry_context"]["qimb_close"], 0.3);
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let mut trades = run_stopped_trade(Some(&make_features(0)));
+        trades.extend(run_stopped_trade(None));
+        let csv = to_csv(&trades);
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+
+        let columns = lines[0].split(',').count();
+        let row: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(row.len(), columns);
+        assert_eq!(row[2], "long");
+        assert_eq!(row[9], "stop_loss");
+        assert_eq!(row[11], "0.0012");
+        assert_eq!(row[12], "0.75"); // (50050 - 49900) / 200
+        assert_eq!(row[13], "12.5");
+        assert_eq!(row[15], "0.3");
+
+        // No context: empty trailing columns
+        let row: Vec<&str> = lines[2].split(',').collect();
+        assert_eq!(row.len(), columns);
+        assert!(row[11..].iter().all(|c| c.is_empty()));
+    }
+}
@@ -0,0 +1,138 @@
+//! Byte-stable JSON serialization.
+//!
+//! Feature snapshots are compared and hashed by their serialized bytes, so
+//! float noise (e.g. `0.30000000000000004`) must not leak into storage. Every
+//! float is rounded to a fixed number of decimals before writing; reloading
+//! and re-serializing then reproduces the same bytes.
+
+use crate::error::Result;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Number, Value};
+
+/// Serialize `value` to JSON with every float rounded to `decimals` places.
+///
+/// Non-finite floats serialize as `null`, as with plain `serde_json`.
+pub fn to_canonical_json<T: Serialize>(value: &T, decimals: u32) -> Result<String> {
+    let mut json = serde_json::to_value(value)?;
+    round_floats(&mut json, decimals);
+    Ok(serde_json::to_string(&json)?)
+}
+
+/// Deserialize JSON written by [`to_canonical_json`].
+pub fn from_canonical_json<T: DeserializeOwned>(json: &str) -> Result<T> {
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Number of decimals needed to represent prices on a tick grid.
+pub fn tick_decimals(tick_size: f64) -> u32 {
+    let mut decimals = 0;
+    let mut scaled = tick_size;
+    while decimals < 12 && (scaled - scaled.round()).abs() > 1e-9 {
+        scaled *= 10.0;
+        decimals += 1;
+    }
+    decimals
+}
+
+/// Round all floats in a JSON tree in place. Integers are left untouched.
+pub fn round_floats(value: &mut Value, decimals: u32) {
+    match value {
+        Value::Number(n) if n.is_f64() => {
+            if let Some(rounded) = n.as_f64().and_then(|x| Number::from_f64(round_to(x, decimals))) {
+                *n = rounded;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| round_floats(v, decimals)),
+        Value::Object(map) => map.values_mut().for_each(|v| round_floats(v, decimals)),
+        _ => {}
+    }
+}
+
+fn round_to(x: f64, decimals: u32) -> f64 {
+    let scale = 10f64.powi(decimals as i32);
+    let rounded = (x * scale).round() / scale;
+    // Avoid emitting "-0.0"
+    if rounded == 0.0 {
+        0.0
+    } else {
+        rounded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AuctionState, EdgeFlow, Features1m, OrderFlowMetrics, SpreadRegime, ValueArea, VwapBandTouches};
+
+    fn make_features() -> Features1m {
+        let mut va = ValueArea::invalid();
+        va.poc = 50000.1 + 0.2;
+        va.vah = 50010.0 / 3.0 * 3.0;
+        va.val = 49990.0 - 1e-12;
+        va.coverage = 0.1 + 0.2;
+        va.is_valid = true;
+
+        Features1m {
+            ts_min: 1704067260000,
+            mid_close: 50000.05,
+            sigma_240: 0.000123456789,
+            sigma_annualized: None,
+            skew_240: None,
+            kurtosis_240: None,
+            vwap_z: None,
+            vwap_band_1sd: VwapBandTouches::default(),
+            vwap_band_2sd: VwapBandTouches::default(),
+            rvol: None,
+            bin_width: 0.1 * 3.0,
+            va,
+            profile_entropy: None,
+            profile_entropy_norm: None,
+            order_flow: OrderFlowMetrics::empty(),
+            of_streak: 0,
+            max_of_streak: 0,
+            qimb_close: -1e-15,
+            qimb_ema: 2.0 / 3.0,
+            qimb_predictive_corr: None,
+            flow_quote_divergence: -2.0 / 3.0,
+            flow_quote_divergence_avg_abs: 2.0 / 3.0,
+            ofi_1m: 0.0,
+            max_mid_jump_1m: 0.0,
+            max_mid_jump_sigma_1m: None,
+            spread_avg_60m: 0.1 + 0.7,
+            spread_regime: SpreadRegime::Normal,
+            eff_spread_avg_1m: 0.0,
+            est_roundtrip_cost: 0.0,
+            va_migration: None,
+            auction_state: AuctionState::Transition,
+            vah_edge_flow: EdgeFlow::default(),
+            val_edge_flow: EdgeFlow { buy_volume: 1.0 / 3.0, sell_volume: 0.0 },
+            poc_tests: 0,
+            vah_tests: 0,
+            val_tests: 0,
+            recent_breakout_success_rate: None,
+        }
+    }
+
+    #[test]
+    fn test_round_trip_is_byte_stable() {
+        let features = make_features();
+        let first = to_canonical_json(&features, 8).unwrap();
+        assert_eq!(first, to_canonical_json(&features, 8).unwrap());
+
+        let reloaded: Features1m = from_canonical_json(&first).unwrap();
+        let second = to_canonical_json(&reloaded, 8).unwrap();
+        assert_eq!(first, second);
+
+        assert!(first.contains("\"coverage\":0.3,"));
+        assert!(first.contains("\"qimb_close\":0.0"));
+        assert_eq!(reloaded.ts_min, features.ts_min);
+    }
+
+    #[test]
+    fn test_tick_decimals() {
+        assert_eq!(tick_decimals(1.0), 0);
+        assert_eq!(tick_decimals(0.1), 1);
+        assert_eq!(tick_decimals(0.25), 2);
+        assert_eq!(tick_decimals(0.0001), 4);
+    }
+}
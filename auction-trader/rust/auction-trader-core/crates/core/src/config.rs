@@ -1,9 +1,13 @@
 //! Configuration structures for the auction-trader system.
 
+use crate::error::{Error, Result};
+use crate::types::{
+    AtMidPolicy, CloseQuotePolicy, MissingQuotePolicy, OutlierFilter, Size, TickRounding,
+};
 use serde::{Deserialize, Serialize};
 
 /// Main configuration for the trading system.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
     /// Instrument configuration.
     pub instrument: InstrumentConfig,
@@ -21,20 +25,90 @@ pub struct Config {
     pub execution: ExecutionConfig,
     /// Backtest configuration.
     pub backtest: BacktestConfig,
+    /// Auction state classification thresholds.
+    #[serde(default)]
+    pub auction_state: AuctionStateConfig,
+    /// Minute bar close snapshot configuration.
+    #[serde(default)]
+    pub bars: BarConfig,
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            instrument: InstrumentConfig::default(),
-            value_area: ValueAreaConfig::default(),
-            order_flow: OrderFlowConfig::default(),
-            signal: SignalConfig::default(),
-            sizing: SizingConfig::default(),
-            risk: RiskConfig::default(),
-            execution: ExecutionConfig::default(),
-            backtest: BacktestConfig::default(),
+impl Config {
+    /// Start a builder seeded with the defaults.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// Check the commonly tuned fields for out-of-range values.
+    pub fn validate(&self) -> Result<()> {
+        if !(self.instrument.tick_size.is_finite() && self.instrument.tick_size > 0.0) {
+            return Err(Error::config(format!(
+                "tick_size must be positive, got {}",
+                self.instrument.tick_size
+            )));
+        }
+        if self.instrument.rolling_window_minutes == 0 {
+            return Err(Error::config("rolling_window_minutes must be at least 1"));
+        }
+        let va_fraction = self.value_area.va_fraction;
+        if !(va_fraction > 0.0 && va_fraction <= 1.0) {
+            return Err(Error::config(format!("va_fraction must be in (0, 1], got {va_fraction}")));
+        }
+        let risk_pct = self.sizing.risk_pct;
+        if !(risk_pct > 0.0 && risk_pct <= 1.0) {
+            return Err(Error::config(format!("risk_pct must be in (0, 1], got {risk_pct}")));
         }
+        if !(self.execution.taker_fee_bps.is_finite() && self.execution.maker_fee_bps.is_finite()) {
+            return Err(Error::config("fees must be finite"));
+        }
+        Ok(())
+    }
+}
+
+/// Fluent builder for [`Config`] over the commonly tuned fields.
+///
+/// Unset fields keep their defaults; [`build`](Self::build) validates.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    /// Set the tick size.
+    pub fn tick_size(mut self, tick_size: f64) -> Self {
+        self.config.instrument.tick_size = tick_size;
+        self
+    }
+
+    /// Set the rolling window in minutes.
+    pub fn rolling_window_minutes(mut self, minutes: u32) -> Self {
+        self.config.instrument.rolling_window_minutes = minutes;
+        self
+    }
+
+    /// Set the Value Area volume fraction.
+    pub fn va_fraction(mut self, va_fraction: f64) -> Self {
+        self.config.value_area.va_fraction = va_fraction;
+        self
+    }
+
+    /// Set taker and maker fees in basis points.
+    pub fn fees(mut self, taker_fee_bps: f64, maker_fee_bps: f64) -> Self {
+        self.config.execution.taker_fee_bps = taker_fee_bps;
+        self.config.execution.maker_fee_bps = maker_fee_bps;
+        self
+    }
+
+    /// Set the risk per trade as a fraction of available margin.
+    pub fn risk_pct(mut self, risk_pct: f64) -> Self {
+        self.config.sizing.risk_pct = risk_pct;
+        self
+    }
+
+    /// Validate and return the config.
+    pub fn build(self) -> Result<Config> {
+        self.config.validate()?;
+        Ok(self.config)
     }
 }
 
@@ -51,6 +125,35 @@ pub struct InstrumentConfig {
     pub tick_size: f64,
     /// Rolling window in minutes.
     pub rolling_window_minutes: u32,
+    /// Bars per year used to annualize sigma on features (None = raw only).
+    #[serde(default)]
+    pub sigma_periods_per_year: Option<f64>,
+    /// Minimum order quantity (0 = no minimum).
+    #[serde(default)]
+    pub min_qty: f64,
+    /// Order quantity step; sizes are rounded down to it (0 = continuous).
+    #[serde(default)]
+    pub qty_step: f64,
+    /// Contract multiplier applied to P&L (1 = one unit per contract).
+    #[serde(default = "default_contract_multiplier")]
+    pub contract_multiplier: f64,
+    /// Inverse contract: P&L is `size * (1/entry - 1/exit)`, in the base asset.
+    #[serde(default)]
+    pub inverse: bool,
+    /// Unit of trade and position sizes.
+    #[serde(default)]
+    pub size_unit: SizeUnit,
+}
+
+impl InstrumentConfig {
+    /// Conversion of this instrument's sizes to the base asset.
+    pub fn size_conversion(&self) -> SizeConversion {
+        SizeConversion {
+            unit: self.size_unit,
+            contract_multiplier: self.contract_multiplier,
+            inverse: self.inverse,
+        }
+    }
 }
 
 impl Default for InstrumentConfig {
@@ -61,6 +164,72 @@ impl Default for InstrumentConfig {
             timeframe: "1m".to_string(),
             tick_size: 0.1,
             rolling_window_minutes: 240,
+            sigma_periods_per_year: None,
+            min_qty: 0.0,
+            qty_step: 0.0,
+            contract_multiplier: default_contract_multiplier(),
+            inverse: false,
+            size_unit: SizeUnit::default(),
+        }
+    }
+}
+
+fn default_contract_multiplier() -> f64 {
+    1.0
+}
+
+/// Unit in which a feed reports trade and position sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeUnit {
+    /// Contracts: `contract_multiplier` base units each, or for inverse
+    /// contracts `contract_multiplier` in quote currency each.
+    #[default]
+    Contracts,
+    /// Base asset (e.g. BTC).
+    Base,
+    /// Quote currency notional (e.g. USDT).
+    Quote,
+}
+
+/// Converts sizes in an instrument's [`SizeUnit`] to base asset quantity.
+///
+/// Volume, VWAP and the histogram are kept in base units so that profiles
+/// are comparable across instruments and price levels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeConversion {
+    pub unit: SizeUnit,
+    pub contract_multiplier: f64,
+    pub inverse: bool,
+}
+
+impl SizeConversion {
+    /// Base asset quantity of `size` at `price`.
+    pub fn to_base(&self, size: Size, price: f64) -> Size {
+        match self.unit {
+            SizeUnit::Contracts if self.inverse => size * self.contract_multiplier / price,
+            SizeUnit::Contracts => size * self.contract_multiplier,
+            SizeUnit::Base => size,
+            SizeUnit::Quote => size / price,
+        }
+    }
+
+    /// Whether sizes are already in base units.
+    pub fn is_identity(&self) -> bool {
+        match self.unit {
+            SizeUnit::Contracts => !self.inverse && self.contract_multiplier == 1.0,
+            SizeUnit::Base => true,
+            SizeUnit::Quote => false,
+        }
+    }
+}
+
+impl Default for SizeConversion {
+    fn default() -> Self {
+        Self {
+            unit: SizeUnit::Base,
+            contract_multiplier: 1.0,
+            inverse: false,
         }
     }
 }
@@ -82,6 +251,133 @@ pub struct ValueAreaConfig {
     pub rebucket_change_pct: f64,
     /// Minimum number of bins for valid VA.
     pub min_va_bins: u32,
+    /// Round POC/VAH/VAL to the instrument tick grid on output.
+    #[serde(default)]
+    pub round_to_tick: bool,
+    /// Bins on each side of the POC used for the smoothed POC (0 = disabled).
+    #[serde(default)]
+    pub poc_smoothing_bins: u32,
+    /// Where in its bin the POC is reported. VAL and VAH are always the
+    /// outer edges of the VA bins.
+    #[serde(default)]
+    pub poc_convention: PocConvention,
+    /// How the histogram bin width is chosen at each rebucket.
+    #[serde(default)]
+    pub bin_width_strategy: BinWidthStrategy,
+    /// POC/VAH/VAL move (ticks) that resets that level's test count.
+    #[serde(default = "default_level_test_reset_ticks")]
+    pub level_test_reset_ticks: u32,
+    /// Price window around the last mid that the VA is computed over.
+    #[serde(default)]
+    pub va_price_window: VaPriceWindow,
+    /// Minutes between VA recomputations in the feature engine (0 or 1 =
+    /// every minute). In between, the last VA is carried forward, so it can
+    /// lag the histogram by up to this many minutes; a rebucket always
+    /// forces a recompute.
+    #[serde(default = "default_va_recompute_interval_minutes")]
+    pub va_recompute_interval_minutes: u32,
+    /// Update the VA incrementally from histogram changes, re-expanding only
+    /// when a change can alter the result. Ignored with a bounded
+    /// `va_price_window` or a non-flat `profile_weighting`.
+    #[serde(default)]
+    pub incremental_va: bool,
+    /// Weighting of minutes by age when aggregating the profile.
+    #[serde(default)]
+    pub profile_weighting: ProfileWeighting,
+}
+
+/// Histogram bin width strategy.
+///
+/// Every strategy's width is rounded to the tick grid and clamped to
+/// `[tick_size, bin_width_max_ticks * tick_size]`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BinWidthStrategy {
+    /// `alpha_bin * mid * sigma_240`.
+    #[default]
+    VolatilityScaled,
+    /// Constant `base_bin_ticks` ticks.
+    Fixed,
+    /// Central `coverage` quantile range of the last `lookback_minutes` bar
+    /// mids divided by `target_bins`, giving a stable bin count over that range.
+    Percentile {
+        /// Bars of mids to use.
+        lookback_minutes: u32,
+        /// Desired number of bins across the range.
+        target_bins: u32,
+        /// Central fraction of mids spanned (1.0 = full min-max range).
+        coverage: f64,
+    },
+}
+
+/// Price window around the last mid that histogram bins must fall in to
+/// count towards the VA. Clipping drops stale volume left far behind by a
+/// trend.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VaPriceWindow {
+    /// Use the full histogram.
+    #[default]
+    Unbounded,
+    /// Within `k * mid * sigma_240` of the mid (unbounded until volatility is ready).
+    Sigma {
+        /// Half-width in sigmas.
+        k: f64,
+    },
+    /// Within `bins` bin widths of the mid.
+    Bins {
+        /// Half-width in bins.
+        bins: u32,
+    },
+}
+
+/// Price reported for the POC within its histogram bin.
+///
+/// VAL is always the lower edge of the lowest VA bin and VAH the upper edge
+/// of the highest, so the VA spans `[VAL, VAH)`; only the POC has a choice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PocConvention {
+    /// Bin mid-point (lower edge + half a bin width).
+    #[default]
+    Midpoint,
+    /// Bin lower edge, the same convention as VAL.
+    LowerEdge,
+}
+
+/// Weight of each minute's volume by its age when the rolling profile is
+/// aggregated. Stored volumes are unchanged; only the profile the VA, POC and
+/// entropy are computed from is weighted.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProfileWeighting {
+    /// Every minute in the window counts fully.
+    #[default]
+    Flat,
+    /// Weight falls linearly from 1 for the newest minute to 0 at the
+    /// window length.
+    Linear,
+    /// Weight halves every `half_life_minutes`.
+    Exponential {
+        /// Age in minutes at which a minute counts half.
+        half_life_minutes: f64,
+    },
+}
+
+impl ProfileWeighting {
+    /// Weight of a minute `age_minutes` older than the newest in a
+    /// `window`-minute profile.
+    pub fn weight(&self, age_minutes: f64, window: usize) -> f64 {
+        let age = age_minutes.max(0.0);
+        match *self {
+            ProfileWeighting::Flat => 1.0,
+            ProfileWeighting::Linear => (1.0 - age / window.max(1) as f64).max(0.0),
+            ProfileWeighting::Exponential { half_life_minutes } if half_life_minutes > 0.0 => {
+                0.5f64.powf(age / half_life_minutes)
+            }
+            ProfileWeighting::Exponential { .. } => 1.0,
+        }
+    }
 }
 
 impl Default for ValueAreaConfig {
@@ -94,10 +390,27 @@ impl Default for ValueAreaConfig {
             rebucket_interval_minutes: 15,
             rebucket_change_pct: 0.25,
             min_va_bins: 20,
+            round_to_tick: false,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
+            bin_width_strategy: BinWidthStrategy::VolatilityScaled,
+            level_test_reset_ticks: 10,
+            va_price_window: VaPriceWindow::Unbounded,
+            va_recompute_interval_minutes: 1,
+            incremental_va: false,
+            profile_weighting: ProfileWeighting::Flat,
         }
     }
 }
 
+fn default_level_test_reset_ticks() -> u32 {
+    10
+}
+
+fn default_va_recompute_interval_minutes() -> u32 {
+    1
+}
+
 /// Order flow configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderFlowConfig {
@@ -107,6 +420,13 @@ pub struct OrderFlowConfig {
     pub ambiguous_trade_frac_max: f64,
     /// Whether to use tick rule fallback for ambiguous trades.
     pub use_tick_rule_fallback: bool,
+    /// Quotes with either side below this size are unreliable: trades are
+    /// classified by tick rule and the imbalance sample is skipped.
+    #[serde(default)]
+    pub min_quote_size: f64,
+    /// Attribution of trades exactly at the quote mid.
+    #[serde(default)]
+    pub at_mid_policy: AtMidPolicy,
     /// Whether to use quote imbalance as a signal filter.
     pub use_qimb: bool,
     /// Minimum qimb for entry signals.
@@ -117,6 +437,38 @@ pub struct OrderFlowConfig {
     pub qimb_fail_max: f64,
     /// Lookback for spread average (minutes).
     pub spread_lookback_minutes: u32,
+    /// Assumed quote updates per second, used to convert the qimb EMA span
+    /// into an update count. `qimb_ema` is sensitive to this; set it to the
+    /// venue's actual quote rate.
+    #[serde(default = "default_qimb_updates_per_second")]
+    pub qimb_updates_per_second: f64,
+    /// Distance from VAH/VAL (ticks) within which trades count as edge flow.
+    #[serde(default = "default_edge_flow_tolerance_ticks")]
+    pub edge_flow_tolerance_ticks: u32,
+    /// Spread history percentile below which the spread regime is `Tight`.
+    #[serde(default = "default_spread_tight_pct")]
+    pub spread_tight_pct: f64,
+    /// Spread history percentile above which the spread regime is `Wide`.
+    #[serde(default = "default_spread_wide_pct")]
+    pub spread_wide_pct: f64,
+    /// Snap sub-tick trade prices to the instrument tick before comparing
+    /// them with bid/ask (None = compare raw prices).
+    #[serde(default)]
+    pub tick_rounding: Option<TickRounding>,
+    /// Window (ms) within which exact resends of a trade are dropped
+    /// (None = no dedup).
+    #[serde(default)]
+    pub dedup_window_ms: Option<i64>,
+    /// Bad print filter (None = accept every print).
+    #[serde(default)]
+    pub outlier_filter: Option<OutlierFilter>,
+    /// Use feed-provided aggressor sides instead of inferring them.
+    #[serde(default)]
+    pub trust_provided_side: bool,
+    /// Seed an empty tick rule from the first quote-classified trade of a
+    /// batch, so leading ambiguous trades are resolved against it.
+    #[serde(default)]
+    pub seed_tick_rule_from_batch: bool,
 }
 
 impl Default for OrderFlowConfig {
@@ -125,15 +477,42 @@ impl Default for OrderFlowConfig {
             max_quote_staleness_ms: 250,
             ambiguous_trade_frac_max: 0.35,
             use_tick_rule_fallback: true,
+            min_quote_size: 0.0,
+            at_mid_policy: AtMidPolicy::Ambiguous,
             use_qimb: true,
             qimb_entry_min: 0.10,
             qimb_breakout_min: 0.10,
             qimb_fail_max: -0.10,
             spread_lookback_minutes: 60,
+            qimb_updates_per_second: 10.0,
+            edge_flow_tolerance_ticks: 2,
+            spread_tight_pct: 0.2,
+            spread_wide_pct: 0.8,
+            tick_rounding: None,
+            dedup_window_ms: None,
+            outlier_filter: None,
+            trust_provided_side: false,
+            seed_tick_rule_from_batch: false,
         }
     }
 }
 
+fn default_qimb_updates_per_second() -> f64 {
+    10.0
+}
+
+fn default_edge_flow_tolerance_ticks() -> u32 {
+    2
+}
+
+fn default_spread_tight_pct() -> f64 {
+    0.2
+}
+
+fn default_spread_wide_pct() -> f64 {
+    0.8
+}
+
 /// Signal detection configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignalConfig {
@@ -155,6 +534,82 @@ pub struct SignalConfig {
     pub enable_retest_mode: bool,
     /// Enable flip-on-signal (reverse without explicit exit).
     pub enable_flip_on_signal: bool,
+    /// How profit targets are derived.
+    #[serde(default)]
+    pub target_mode: TargetMode,
+    /// TP1 distance from entry in VA widths (`TargetMode::VaWidth`).
+    #[serde(default = "default_tp1_va_width_mult")]
+    pub tp1_va_width_mult: f64,
+    /// TP2 distance from entry in VA widths (`TargetMode::VaWidth`).
+    #[serde(default = "default_tp2_va_width_mult")]
+    pub tp2_va_width_mult: f64,
+    /// How a close at VAH/VAL counts towards acceptance.
+    #[serde(default)]
+    pub edge_close_policy: EdgeClosePolicy,
+    /// Acceptance credit for an at-edge close (`EdgeClosePolicy::Fractional`).
+    #[serde(default = "default_edge_close_weight")]
+    pub edge_close_weight: f64,
+    /// Closes within this many ticks of an edge count as at it.
+    #[serde(default)]
+    pub edge_tolerance_ticks: f64,
+    /// Fixed target prices or a target tracking the live VA.
+    #[serde(default)]
+    pub exit_mode: ExitMode,
+    /// VA level targeted under `ExitMode::ValueAreaTarget`.
+    #[serde(default)]
+    pub va_target_level: VaTargetLevel,
+    /// Bars after an accepted VA break within which a close back inside
+    /// counts as a failed breakout.
+    #[serde(default = "default_breakout_outcome_bars")]
+    pub breakout_outcome_bars: u32,
+    /// Recent breakout outcomes the success rate is computed over.
+    #[serde(default = "default_breakout_outcome_window")]
+    pub breakout_outcome_window: u32,
+}
+
+/// Profit target derivation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetMode {
+    /// Structural levels (POC / opposite VA edge, or R multiples for breakouts).
+    #[default]
+    Structure,
+    /// Multiples of the VA width (`vah - val`) beyond entry.
+    VaWidth,
+}
+
+/// How open positions take profit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitMode {
+    /// TP1/TP2 prices fixed at entry.
+    #[default]
+    FixedTargets,
+    /// Exit in full at a level of the current VA, re-read every bar as the
+    /// VA migrates.
+    ValueAreaTarget,
+}
+
+/// VA level a position exits at under `ExitMode::ValueAreaTarget`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaTargetLevel {
+    /// The POC.
+    #[default]
+    Poc,
+    /// The opposite edge: VAH for longs, VAL for shorts.
+    OppositeEdge,
+}
+
+/// Handling of a close at a VA edge (within `edge_tolerance_ticks`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeClosePolicy {
+    /// Conservative: the close is inside the VA and resets acceptance.
+    #[default]
+    Inside,
+    /// The close adds `edge_close_weight` to the acceptance count.
+    Fractional,
 }
 
 impl Default for SignalConfig {
@@ -169,10 +624,40 @@ impl Default for SignalConfig {
             accept_outside_k: 3,
             enable_retest_mode: true,
             enable_flip_on_signal: true,
+            target_mode: TargetMode::Structure,
+            tp1_va_width_mult: 0.5,
+            tp2_va_width_mult: 1.0,
+            edge_close_policy: EdgeClosePolicy::Inside,
+            edge_close_weight: 0.5,
+            edge_tolerance_ticks: 0.0,
+            exit_mode: ExitMode::FixedTargets,
+            va_target_level: VaTargetLevel::Poc,
+            breakout_outcome_bars: 30,
+            breakout_outcome_window: 20,
         }
     }
 }
 
+fn default_tp1_va_width_mult() -> f64 {
+    0.5
+}
+
+fn default_tp2_va_width_mult() -> f64 {
+    1.0
+}
+
+fn default_edge_close_weight() -> f64 {
+    0.5
+}
+
+fn default_breakout_outcome_bars() -> u32 {
+    30
+}
+
+fn default_breakout_outcome_window() -> u32 {
+    20
+}
+
 /// Position sizing configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SizingConfig {
@@ -207,12 +692,40 @@ pub struct RiskConfig {
     pub max_hold_minutes: u32,
     /// Extend hold time if profitable.
     pub extend_if_profitable: bool,
+    /// Minutes added per extension when profitable at the time-stop check.
+    #[serde(default = "default_extension_minutes")]
+    pub extension_minutes: u32,
+    /// Maximum number of extensions before the time stop is enforced.
+    #[serde(default = "default_max_extensions")]
+    pub max_extensions: u32,
     /// Cooldown period after exit (minutes).
     pub cooldown_minutes: u32,
+    /// Which entries a cooldown blocks.
+    #[serde(default)]
+    pub cooldown_scope: CooldownScope,
     /// Stop buffer in ticks.
     pub stop_buffer_ticks: u32,
     /// Maximum daily loss (absolute value).
     pub max_daily_loss: Option<f64>,
+    /// Profit at which the fixed stop converts to a trailing stop (None = never).
+    #[serde(default)]
+    pub trail_trigger: Option<TrailTrigger>,
+    /// Trailing distance in ticks (None = the initial risk distance).
+    #[serde(default)]
+    pub trail_offset_ticks: Option<u32>,
+    /// Re-entries allowed per setup after a stop-out while the setup still holds (0 = disabled).
+    #[serde(default)]
+    pub max_reentries: u32,
+    /// Bars after entry during which exits are suppressed (0 = disabled).
+    #[serde(default)]
+    pub min_hold_bars: u32,
+    /// Which exits the minimum hold suppresses.
+    #[serde(default)]
+    pub min_hold_scope: MinHoldScope,
+    /// Catastrophic stop distance from entry in ticks, honored even during
+    /// the minimum hold (None = no hard stop).
+    #[serde(default)]
+    pub hard_stop_ticks: Option<u32>,
 }
 
 impl Default for RiskConfig {
@@ -220,13 +733,62 @@ impl Default for RiskConfig {
         Self {
             max_hold_minutes: 60,
             extend_if_profitable: true,
+            extension_minutes: default_extension_minutes(),
+            max_extensions: default_max_extensions(),
             cooldown_minutes: 3,
+            cooldown_scope: CooldownScope::Global,
             stop_buffer_ticks: 2,
             max_daily_loss: None,
+            trail_trigger: None,
+            trail_offset_ticks: None,
+            max_reentries: 0,
+            min_hold_bars: 0,
+            min_hold_scope: MinHoldScope::All,
+            hard_stop_ticks: None,
         }
     }
 }
 
+/// Exits suppressed during the minimum holding period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MinHoldScope {
+    /// Neither the stop nor the targets are checked.
+    #[default]
+    All,
+    /// Only the targets are suppressed; the stop still applies.
+    TargetsOnly,
+}
+
+/// Scope of the post-exit cooldown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CooldownScope {
+    /// Any exit blocks all new entries.
+    #[default]
+    Global,
+    /// An exit only blocks new entries on the same side.
+    PerDirection,
+}
+
+/// Open profit at which a fixed stop starts trailing.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TrailTrigger {
+    /// Multiple of the initial risk (entry to initial stop).
+    RMultiple { r: f64 },
+    /// Fixed distance from entry in ticks.
+    Ticks { ticks: u32 },
+}
+
+fn default_extension_minutes() -> u32 {
+    30
+}
+
+fn default_max_extensions() -> u32 {
+    2
+}
+
 /// Execution configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionConfig {
@@ -242,6 +804,17 @@ pub struct ExecutionConfig {
     pub taker_fee_bps: f64,
     /// Maker fee in basis points (negative = rebate).
     pub maker_fee_bps: f64,
+    /// Seed for randomized slippage in the backtest fill model (None = always
+    /// the full slippage).
+    #[serde(default)]
+    pub slippage_seed: Option<u64>,
+    /// Market fills slip at least this multiple of the quoted spread
+    /// (0 = fixed ticks only).
+    #[serde(default)]
+    pub spread_multiple: f64,
+    /// Cap on market fill slippage in ticks (None = uncapped).
+    #[serde(default)]
+    pub max_slippage_ticks: Option<u32>,
 }
 
 impl Default for ExecutionConfig {
@@ -253,6 +826,9 @@ impl Default for ExecutionConfig {
             slippage_ticks_exit: 1,
             taker_fee_bps: 5.0,
             maker_fee_bps: -1.0,
+            slippage_seed: None,
+            spread_multiple: 0.0,
+            max_slippage_ticks: None,
         }
     }
 }
@@ -266,6 +842,9 @@ pub struct BacktestConfig {
     pub initial_capital: f64,
     /// Number of parallel workers (0 = auto).
     pub workers: u32,
+    /// Leading bars used to seed features before signals are acted on.
+    #[serde(default)]
+    pub warmup_bars: usize,
 }
 
 impl Default for BacktestConfig {
@@ -274,6 +853,63 @@ impl Default for BacktestConfig {
             funding_rate_8h_bps: 1.0,
             initial_capital: 10000.0,
             workers: 0,
+            warmup_bars: 0,
+        }
+    }
+}
+
+/// Thresholds for the `AuctionState` feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuctionStateConfig {
+    /// Maximum normalized profile entropy (`[0, 1]`, see
+    /// `Features1m::profile_entropy_norm`) for a balanced auction.
+    pub balance_entropy_max: f64,
+    /// Maximum |POC shift| (in previous VA widths) for a balanced auction.
+    pub balance_poc_shift_max: f64,
+    /// Minimum |POC shift| (in previous VA widths) for a trend.
+    pub trend_poc_shift_min: f64,
+    /// Minimum same-signed order flow streak (minutes) for a trend.
+    pub trend_of_streak_min: u32,
+    /// Consecutive minutes a new state must hold before it is reported
+    /// (`Transition` meanwhile).
+    pub confirm_minutes: u32,
+}
+
+impl Default for AuctionStateConfig {
+    fn default() -> Self {
+        Self {
+            balance_entropy_max: 0.9,
+            balance_poc_shift_max: 0.1,
+            trend_poc_shift_min: 0.25,
+            trend_of_streak_min: 3,
+            confirm_minutes: 1,
+        }
+    }
+}
+
+/// Minute bar close snapshot configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BarConfig {
+    /// Which quote becomes the bar's L1 close snapshot.
+    pub close_quote_policy: CloseQuotePolicy,
+    /// Handling of bars without a usable close quote.
+    pub missing_quote_policy: MissingQuotePolicy,
+    /// Oldest quote (ms before the close) usable as the close snapshot
+    /// (None = any age).
+    pub max_quote_age_ms: Option<i64>,
+    /// Latest quote (ms after the close) usable by `ClosestToClose`.
+    pub close_quote_tolerance_ms: i64,
+}
+
+impl Default for BarConfig {
+    fn default() -> Self {
+        Self {
+            close_quote_policy: CloseQuotePolicy::LastBeforeClose,
+            missing_quote_policy: MissingQuotePolicy::Nan,
+            max_quote_age_ms: None,
+            close_quote_tolerance_ms: 1_000,
         }
     }
 }
@@ -288,5 +924,41 @@ mod tests {
         assert_eq!(config.value_area.va_fraction, 0.70);
         assert_eq!(config.signal.accept_outside_k, 3);
         assert_eq!(config.sizing.risk_pct, 0.02);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_builder() {
+        let config = Config::builder()
+            .tick_size(0.5)
+            .rolling_window_minutes(120)
+            .va_fraction(0.68)
+            .fees(4.0, 0.0)
+            .risk_pct(0.01)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.instrument.tick_size, 0.5);
+        assert_eq!(config.instrument.rolling_window_minutes, 120);
+        assert_eq!(config.value_area.va_fraction, 0.68);
+        assert_eq!(config.execution.taker_fee_bps, 4.0);
+        assert_eq!(config.execution.maker_fee_bps, 0.0);
+        assert_eq!(config.sizing.risk_pct, 0.01);
+
+        // Everything else keeps its default
+        let defaults = Config::default();
+        assert_eq!(config.instrument.symbol, defaults.instrument.symbol);
+        assert_eq!(config.value_area.min_va_bins, defaults.value_area.min_va_bins);
+        assert_eq!(config.signal.accept_outside_k, defaults.signal.accept_outside_k);
+        assert_eq!(config.risk.max_hold_minutes, defaults.risk.max_hold_minutes);
+        assert_eq!(config.execution.slippage_ticks_entry, defaults.execution.slippage_ticks_entry);
+    }
+
+    #[test]
+    fn test_builder_validates() {
+        assert!(Config::builder().tick_size(0.0).build().is_err());
+        assert!(Config::builder().va_fraction(1.5).build().is_err());
+        assert!(Config::builder().risk_pct(0.0).build().is_err());
+        assert!(Config::builder().rolling_window_minutes(0).build().is_err());
     }
 }
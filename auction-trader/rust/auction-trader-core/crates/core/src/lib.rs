@@ -4,11 +4,27 @@
 //! - Market data types (trades, quotes, bars)
 //! - Configuration structures
 //! - Common error types
+//! - UTC day rollover tracking
+//! - Stop/take-profit trigger predicates
+//! - Market order slippage
+//! - Byte-stable JSON serialization
 
+pub mod canonical;
 pub mod config;
 pub mod error;
+pub mod rollover;
+pub mod slippage;
+pub mod triggers;
 pub mod types;
 
-pub use config::Config;
+pub use canonical::{from_canonical_json, to_canonical_json};
+pub use config::{
+    AuctionStateConfig, BarConfig, BinWidthStrategy, Config, ConfigBuilder, CooldownScope,
+    EdgeClosePolicy, ExitMode, MinHoldScope, PocConvention, ProfileWeighting, SizeConversion,
+    SizeUnit, TargetMode, TrailTrigger, VaPriceWindow, VaTargetLevel,
+};
 pub use error::{Error, Result};
+pub use rollover::{DailyRollover, utc_date};
+pub use slippage::market_slippage_ticks;
+pub use triggers::{is_stop_triggered, is_target_triggered};
 pub use types::*;
@@ -0,0 +1,90 @@
+//! UTC day rollover tracking.
+//!
+//! Shared helper for day-scoped accumulators (daily loss limits, fee tiers,
+//! per-day metrics) that need a clean reset at UTC midnight.
+
+use crate::types::TimestampMs;
+use chrono::{DateTime, NaiveDate};
+
+/// Convert a timestamp to its UTC calendar date.
+#[inline]
+pub fn utc_date(ts_ms: TimestampMs) -> NaiveDate {
+    DateTime::from_timestamp_millis(ts_ms)
+        .map(|dt| dt.date_naive())
+        .unwrap_or_default()
+}
+
+/// Tracks the current UTC day from event timestamps and reports rollovers.
+#[derive(Debug, Clone, Default)]
+pub struct DailyRollover {
+    /// Day of the most recent event.
+    current_day: Option<NaiveDate>,
+}
+
+impl DailyRollover {
+    /// Create a new rollover tracker.
+    pub fn new() -> Self {
+        Self { current_day: None }
+    }
+
+    /// Advance to the day containing `ts_ms`.
+    ///
+    /// Returns the day that just ended if this event crossed UTC midnight.
+    /// The first event only establishes the current day and never rolls over.
+    pub fn advance(&mut self, ts_ms: TimestampMs) -> Option<NaiveDate> {
+        let day = utc_date(ts_ms);
+        match self.current_day {
+            Some(current) if day > current => {
+                self.current_day = Some(day);
+                Some(current)
+            }
+            Some(_) => None,
+            None => {
+                self.current_day = Some(day);
+                None
+            }
+        }
+    }
+
+    /// Get the current day, if any event has been seen.
+    pub fn current_day(&self) -> Option<NaiveDate> {
+        self.current_day
+    }
+
+    /// Clear the tracked day.
+    pub fn reset(&mut self) {
+        self.current_day = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+    #[test]
+    fn test_utc_date() {
+        // 2024-01-01 23:59:59.999 and 2024-01-02 00:00:00.000
+        let ts = 1704067200000i64 + DAY_MS - 1;
+        assert_eq!(utc_date(ts), NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(utc_date(ts + 1), NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+    }
+
+    #[test]
+    fn test_rollover_at_midnight() {
+        let mut rollover = DailyRollover::new();
+        let day1 = 1704067200000i64;
+
+        assert_eq!(rollover.advance(day1), None);
+        assert_eq!(rollover.advance(day1 + DAY_MS - 1), None);
+
+        let ended = rollover.advance(day1 + DAY_MS);
+        assert_eq!(ended, NaiveDate::from_ymd_opt(2024, 1, 1));
+        assert_eq!(rollover.current_day(), NaiveDate::from_ymd_opt(2024, 1, 2));
+
+        // Out-of-order event from the previous day does not roll back
+        assert_eq!(rollover.advance(day1 + 1000), None);
+        assert_eq!(rollover.current_day(), NaiveDate::from_ymd_opt(2024, 1, 2));
+    }
+}
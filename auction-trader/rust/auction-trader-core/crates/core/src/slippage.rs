@@ -0,0 +1,43 @@
+//! Market order slippage.
+//!
+//! Shared by the backtest fill model and the feature engine's round-trip
+//! cost estimate so the two cannot drift apart.
+
+/// Maximum slippage in whole ticks for a market order.
+///
+/// At least `ticks`, and at least `spread_multiple` times the quoted spread
+/// (`spread_ticks`, in ticks) when `spread_multiple > 0`. Rounded up to whole
+/// ticks, then capped at `max_ticks`.
+pub fn market_slippage_ticks(
+    ticks: u32,
+    spread_ticks: f64,
+    spread_multiple: f64,
+    max_ticks: Option<u32>,
+) -> u32 {
+    let mut slippage = ticks as f64;
+    if spread_multiple > 0.0 {
+        slippage = slippage.max(spread_multiple * spread_ticks.max(0.0));
+    }
+    // Tolerance keeps float noise in the spread from adding a tick
+    let whole = (slippage - 1e-9).ceil().max(0.0) as u32;
+    max_ticks.map_or(whole, |cap| whole.min(cap))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_market_slippage_ticks() {
+        // Fixed ticks only
+        assert_eq!(market_slippage_ticks(2, 10.0, 0.0, None), 2);
+        // Half of a 5-tick spread rounds up to 3 ticks
+        assert_eq!(market_slippage_ticks(1, 5.0, 0.5, None), 3);
+        // Float noise in the spread does not add a tick
+        let noisy_spread = (50000.6 - 50000.0) / 0.1;
+        assert_eq!(market_slippage_ticks(1, noisy_spread, 0.5, None), 3);
+        // Capped
+        assert_eq!(market_slippage_ticks(1, 40.0, 0.5, Some(8)), 8);
+        assert_eq!(market_slippage_ticks(10, 0.0, 0.0, Some(8)), 8);
+    }
+}
@@ -0,0 +1,56 @@
+//! Stop and take-profit trigger predicates.
+//!
+//! Pure functions shared by the backtest position tracker and live order
+//! management so both evaluate triggers identically.
+
+use crate::types::PositionSide;
+
+/// Check if a stop is triggered by a bar's range.
+///
+/// Longs stop when the low touches the stop, shorts when the high does.
+#[inline]
+pub fn is_stop_triggered(side: PositionSide, stop_price: f64, low: f64, high: f64) -> bool {
+    match side {
+        PositionSide::Long => low <= stop_price,
+        PositionSide::Short => high >= stop_price,
+    }
+}
+
+/// Check if a take-profit target is triggered by a bar's range.
+///
+/// Longs take profit when the high reaches the target, shorts when the low does.
+#[inline]
+pub fn is_target_triggered(side: PositionSide, target_price: f64, low: f64, high: f64) -> bool {
+    match side {
+        PositionSide::Long => high >= target_price,
+        PositionSide::Short => low <= target_price,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_stop() {
+        // Low touches stop
+        assert!(is_stop_triggered(PositionSide::Long, 49500.0, 49400.0, 50200.0));
+        assert!(is_stop_triggered(PositionSide::Long, 49500.0, 49500.0, 50200.0));
+        // Low doesn't touch stop
+        assert!(!is_stop_triggered(PositionSide::Long, 49500.0, 49600.0, 50200.0));
+    }
+
+    #[test]
+    fn test_short_stop() {
+        assert!(is_stop_triggered(PositionSide::Short, 50500.0, 49800.0, 50600.0));
+        assert!(!is_stop_triggered(PositionSide::Short, 50500.0, 49800.0, 50400.0));
+    }
+
+    #[test]
+    fn test_targets() {
+        assert!(is_target_triggered(PositionSide::Long, 50500.0, 50000.0, 50600.0));
+        assert!(!is_target_triggered(PositionSide::Long, 50500.0, 50000.0, 50400.0));
+        assert!(is_target_triggered(PositionSide::Short, 49500.0, 49400.0, 50000.0));
+        assert!(!is_target_triggered(PositionSide::Short, 49500.0, 49600.0, 50000.0));
+    }
+}
@@ -1,9 +1,10 @@
 //! Core data types for the auction-trader system.
 
-use chrono::{DateTime, Utc};
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 
+use crate::canonical::tick_decimals;
+
 /// Timestamp in milliseconds since Unix epoch (UTC).
 pub type TimestampMs = i64;
 
@@ -58,6 +59,12 @@ impl Quote {
         self.ask_px - self.bid_px
     }
 
+    /// Check if a price is exactly at mid (strictly inside a valid spread).
+    #[inline]
+    pub fn is_at_mid(&self, price: f64) -> bool {
+        is_at_mid(price, self.bid_px, self.ask_px)
+    }
+
     /// Calculate quote imbalance: (bid_sz - ask_sz) / (bid_sz + ask_sz).
     #[inline]
     pub fn imbalance(&self) -> f64 {
@@ -68,6 +75,15 @@ impl Quote {
             0.0
         }
     }
+
+    /// Human-readable form with prices shown to the tick size's precision.
+    pub fn display(&self, tick_size: f64) -> String {
+        let dp = tick_decimals(tick_size) as usize;
+        format!(
+            "Quote(ts_ms={}, bid={:.dp$}@{:.4}, ask={:.dp$}@{:.4})",
+            self.ts_ms, self.bid_px, self.bid_sz, self.ask_px, self.ask_sz
+        )
+    }
 }
 
 /// Inferred trade side from bid/ask alignment.
@@ -96,6 +112,97 @@ impl TradeSide {
     }
 }
 
+/// Check if `price` sits at the mid of a valid (non-crossed, non-locked) quote.
+#[inline]
+fn is_at_mid(price: f64, bid_px: f64, ask_px: f64) -> bool {
+    if bid_px <= 0.0 || ask_px <= bid_px {
+        return false;
+    }
+    let mid = (bid_px + ask_px) / 2.0;
+    (price - mid).abs() <= 1e-9 * mid.abs().max(1.0)
+}
+
+/// How trades printing exactly at the quote mid are attributed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AtMidPolicy {
+    /// Treat like any inside-spread trade: ambiguous unless the tick rule
+    /// fallback (if enabled) resolves it.
+    #[default]
+    Ambiguous,
+    /// Always resolve with the tick rule, even if the fallback is disabled.
+    TickRuleOnly,
+    /// Leave ambiguous in classification; order flow counts half the size as
+    /// buy and half as sell volume.
+    SplitHalf,
+}
+
+/// Direction used when snapping sub-tick trade prices to the tick grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TickRounding {
+    /// Round to the nearest tick.
+    Nearest,
+    /// Round toward the quote mid (never moves a price outward onto the touch).
+    ///
+    /// A price is left unsnapped if rounding would reach or cross the mid,
+    /// e.g. an inside print with a one-tick spread, so it keeps its side.
+    /// Inside prints therefore stay ambiguous and prints through the touch
+    /// were already buys or sells: sides match classification without
+    /// rounding. Use it to rule out snapping-induced sides; only `Nearest`
+    /// recovers sub-tick prints next to the touch.
+    TowardMid,
+}
+
+/// Rejection rule for prints far from the market.
+///
+/// Deviation is measured from the mid of the trade's quote, or from the last
+/// accepted trade price when there is no quote yet. Following the quote lets
+/// genuine fast moves through: a real repricing moves the mid with it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum OutlierFilter {
+    /// Reject trades more than `max_pct` (a fraction, e.g. 0.05) from the reference.
+    Pct { max_pct: f64 },
+    /// Reject trades more than `k` times the RMS of the last `lookback`
+    /// accepted trade-to-trade log returns from the reference, but never
+    /// at a tighter distance than `min_pct`.
+    Sigma { k: f64, lookback: usize, min_pct: f64 },
+}
+
+/// What to do with a bar that has no usable quote at its close.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingQuotePolicy {
+    /// Emit the bar with NaN L1 fields (detect with `Bar1m::has_close_quote`).
+    #[default]
+    Nan,
+    /// Drop the bar.
+    Drop,
+    /// Use the last known quote regardless of age (NaN if there is none).
+    CarryForward,
+}
+
+/// Which quote becomes a bar's L1 close snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseQuotePolicy {
+    /// The latest quote at or before the close (`ts_min + 59_999`).
+    #[default]
+    LastBeforeClose,
+    /// Time-weighted average of the quotes in effect during the minute
+    /// (prices and sizes), so a stale or momentary last quote is diluted.
+    TimeWeightedMidOfMinute,
+    /// Whichever of the last quote before the close and the first quote
+    /// after it is nearer in time (ties go to the earlier one).
+    ///
+    /// Only quotes within a tolerance after the close (see
+    /// `BarConfig::close_quote_tolerance_ms`) and already received when the
+    /// bar is finalized are considered, so the snapshot never reaches
+    /// further ahead than that and does not change with later data.
+    ClosestToClose,
+}
+
 /// A trade with inferred side and associated quote data.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClassifiedTrade {
@@ -116,6 +223,12 @@ impl ClassifiedTrade {
     pub fn signed_size(&self) -> f64 {
         self.trade.size * self.side.sign_f64()
     }
+
+    /// Check if the trade printed exactly at the mid of its quote.
+    #[inline]
+    pub fn is_at_mid(&self) -> bool {
+        is_at_mid(self.trade.price, self.quote_bid_px, self.quote_ask_px)
+    }
 }
 
 /// 1-minute OHLCV bar with L1 snapshot at close.
@@ -148,6 +261,13 @@ pub struct Bar1m {
 }
 
 impl Bar1m {
+    /// Check if the close L1 snapshot is usable (bar builders leave it NaN
+    /// when no quote was available).
+    #[inline]
+    pub fn has_close_quote(&self) -> bool {
+        self.bid_px_close.is_finite() && self.ask_px_close.is_finite() && self.bid_px_close > 0.0
+    }
+
     /// Calculate mid price at close.
     #[inline]
     pub fn mid_close(&self) -> f64 {
@@ -170,17 +290,38 @@ impl Bar1m {
             0.0
         }
     }
+
+    /// Human-readable form with prices shown to the tick size's precision.
+    pub fn display(&self, tick_size: f64) -> String {
+        let dp = tick_decimals(tick_size) as usize;
+        format!(
+            "Bar1m(ts_min={}, o={:.dp$}, h={:.dp$}, l={:.dp$}, c={:.dp$}, v={:.4})",
+            self.ts_min, self.open, self.high, self.low, self.close, self.volume
+        )
+    }
 }
 
 /// Value Area output.
+///
+/// `Default` is the invalid (empty) VA.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValueArea {
-    /// Point of Control (price with max volume).
+    /// Point of Control (price with max volume): the mid-point or lower
+    /// edge of its bin, per `PocConvention`.
     pub poc: f64,
-    /// Value Area High.
+    /// Value Area High: upper edge of the highest VA bin.
     pub vah: f64,
-    /// Value Area Low.
+    /// Value Area Low: lower edge of the lowest VA bin.
     pub val: f64,
+    /// POC before any tick rounding.
+    pub poc_raw: f64,
+    /// Volume-weighted POC over the POC bin and its neighbours (equals the
+    /// raw POC when smoothing is disabled).
+    pub poc_smoothed: f64,
+    /// VAH before any tick rounding.
+    pub vah_raw: f64,
+    /// VAL before any tick rounding.
+    pub val_raw: f64,
     /// Actual coverage achieved (e.g., 0.70).
     pub coverage: f64,
     /// Number of bins in the VA.
@@ -191,6 +332,9 @@ pub struct ValueArea {
     pub bin_width: f64,
     /// Whether the VA is valid (enough bins).
     pub is_valid: bool,
+    /// Whether the POC bin alone holds the target coverage, making the VA a
+    /// single bin wide (volume concentrated at one price).
+    pub single_bin_va: bool,
 }
 
 impl ValueArea {
@@ -200,17 +344,133 @@ impl ValueArea {
             poc: 0.0,
             vah: 0.0,
             val: 0.0,
+            poc_raw: 0.0,
+            poc_smoothed: 0.0,
+            vah_raw: 0.0,
+            val_raw: 0.0,
             coverage: 0.0,
             bin_count: 0,
             total_volume: 0.0,
             bin_width: 0.0,
             is_valid: false,
+            single_bin_va: false,
+        }
+    }
+
+    /// Create a valid VA from its levels (raw and smoothed values equal the
+    /// levels; other statistics are zero).
+    pub fn from_levels(poc: f64, vah: f64, val: f64) -> Self {
+        Self {
+            poc,
+            vah,
+            val,
+            poc_raw: poc,
+            poc_smoothed: poc,
+            vah_raw: vah,
+            val_raw: val,
+            is_valid: true,
+            ..Self::invalid()
+        }
+    }
+
+    /// Snap POC/VAH/VAL to the nearest tick, keeping the raw values.
+    pub fn round_to_tick(mut self, tick_size: f64) -> Self {
+        if tick_size > 0.0 {
+            let round = |v: f64| (v / tick_size).round() * tick_size;
+            self.poc = round(self.poc_raw);
+            self.vah = round(self.vah_raw);
+            self.val = round(self.val_raw);
         }
+        self
+    }
+}
+
+impl Default for ValueArea {
+    fn default() -> Self {
+        Self::invalid()
     }
 }
 
+/// Direction of Value Area migration between consecutive computations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VaMigrationDirection {
+    /// Both VAL and VAH moved higher.
+    Up,
+    /// Both VAL and VAH moved lower.
+    Down,
+    /// One VA contains the other (balance).
+    Overlap,
+}
+
+/// Value Area migration relative to the previous VA.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct VaMigration {
+    /// Migration direction.
+    pub direction: VaMigrationDirection,
+    /// POC shift normalized by the previous VA width.
+    pub poc_shift: f64,
+    /// Fraction of the previous VA still covered by the current VA (0-1).
+    pub overlap_frac: f64,
+}
+
+/// High-level auction state from profile shape, VA migration and flow.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuctionState {
+    /// Overlapping value with a concentrated profile and no persistent flow.
+    Balanced,
+    /// Value migrating higher with persistent buying.
+    TrendingUp,
+    /// Value migrating lower with persistent selling.
+    TrendingDown,
+    /// Mixed evidence, a state change awaiting confirmation, or too little data.
+    #[default]
+    Transition,
+}
+
+/// Classified volume printed near one value-area edge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct EdgeFlow {
+    /// Buy-initiated volume at the edge.
+    pub buy_volume: f64,
+    /// Sell-initiated volume at the edge.
+    pub sell_volume: f64,
+}
+
+impl EdgeFlow {
+    /// Net signed flow at the edge (buy - sell).
+    pub fn net(&self) -> f64 {
+        self.buy_volume - self.sell_volume
+    }
+}
+
+/// Touches of a rolling VWAP band pair (VWAP +/- k standard deviations).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VwapBandTouches {
+    /// The bar's high reached the upper band.
+    pub touched_upper_band: bool,
+    /// The bar's low reached the lower band.
+    pub touched_lower_band: bool,
+    /// Bars in the rolling window whose high reached the upper band.
+    pub upper_touches: u32,
+    /// Bars in the rolling window whose low reached the lower band.
+    pub lower_touches: u32,
+}
+
+/// Where the current spread sits in its rolling distribution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpreadRegime {
+    /// Below the tight percentile.
+    Tight,
+    /// Between the tight and wide percentiles (or too little history).
+    #[default]
+    Normal,
+    /// Above the wide percentile.
+    Wide,
+}
+
 /// Order flow metrics for a 1-minute period.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "OrderFlowMetricsRecord")]
 pub struct OrderFlowMetrics {
     /// Net signed order flow (sum of signed sizes).
     pub of_1m: f64,
@@ -226,9 +486,84 @@ pub struct OrderFlowMetrics {
     pub ambiguous_volume: Size,
     /// Fraction of volume that was ambiguous.
     pub ambiguous_frac: f64,
+    /// Whether any volume traded. When false the normalized fields are 0.0
+    /// for lack of data, not because flow was balanced. Records without it
+    /// derive it from `total_volume`.
+    pub has_volume: bool,
+    /// Volume printed at or through the classification quote's bid.
+    #[serde(default)]
+    pub at_bid_volume: Size,
+    /// Volume printed at or through the classification quote's ask.
+    #[serde(default)]
+    pub at_ask_volume: Size,
+    /// Volume printed strictly inside the classification quote.
+    #[serde(default)]
+    pub inside_volume: Size,
+}
+
+/// Serialized form of [`OrderFlowMetrics`], accepting records written before
+/// `has_volume` and the quote-relative volumes existed.
+#[derive(Deserialize)]
+struct OrderFlowMetricsRecord {
+    of_1m: f64,
+    of_norm_1m: f64,
+    total_volume: Size,
+    buy_volume: Size,
+    sell_volume: Size,
+    ambiguous_volume: Size,
+    ambiguous_frac: f64,
+    #[serde(default)]
+    has_volume: Option<bool>,
+    #[serde(default)]
+    at_bid_volume: Size,
+    #[serde(default)]
+    at_ask_volume: Size,
+    #[serde(default)]
+    inside_volume: Size,
+}
+
+impl From<OrderFlowMetricsRecord> for OrderFlowMetrics {
+    fn from(record: OrderFlowMetricsRecord) -> Self {
+        Self {
+            of_1m: record.of_1m,
+            of_norm_1m: record.of_norm_1m,
+            total_volume: record.total_volume,
+            buy_volume: record.buy_volume,
+            sell_volume: record.sell_volume,
+            ambiguous_volume: record.ambiguous_volume,
+            ambiguous_frac: record.ambiguous_frac,
+            has_volume: record.has_volume.unwrap_or(record.total_volume > 0.0),
+            at_bid_volume: record.at_bid_volume,
+            at_ask_volume: record.at_ask_volume,
+            inside_volume: record.inside_volume,
+        }
+    }
+}
+
+impl Default for OrderFlowMetrics {
+    fn default() -> Self {
+        Self::empty()
+    }
 }
 
 impl OrderFlowMetrics {
+    /// Metrics for a minute with no trades.
+    pub fn empty() -> Self {
+        Self {
+            of_1m: 0.0,
+            of_norm_1m: 0.0,
+            total_volume: 0.0,
+            buy_volume: 0.0,
+            sell_volume: 0.0,
+            ambiguous_volume: 0.0,
+            ambiguous_frac: 0.0,
+            has_volume: false,
+            at_bid_volume: 0.0,
+            at_ask_volume: 0.0,
+            inside_volume: 0.0,
+        }
+    }
+
     /// Check if ambiguous fraction is above threshold.
     pub fn is_high_ambiguous(&self, threshold: f64) -> bool {
         self.ambiguous_frac > threshold
@@ -236,7 +571,10 @@ impl OrderFlowMetrics {
 }
 
 /// Complete feature set for a 1-minute period.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// `Default` gives neutral values (zeros, `None`, an invalid VA and empty
+/// order flow), so callers can set only the fields they need.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Features1m {
     /// Minute boundary timestamp.
     pub ts_min: TimestampMs,
@@ -244,18 +582,105 @@ pub struct Features1m {
     pub mid_close: f64,
     /// Rolling 4h volatility (stdev of log returns).
     pub sigma_240: f64,
+    /// `sigma_240` annualized, if `sigma_periods_per_year` is configured.
+    #[serde(default)]
+    pub sigma_annualized: Option<f64>,
+    /// Rolling 4h skewness of log returns (None if undefined).
+    #[serde(default)]
+    pub skew_240: Option<f64>,
+    /// Rolling 4h excess kurtosis of log returns (None if undefined).
+    #[serde(default)]
+    pub kurtosis_240: Option<f64>,
+    /// Distance of `mid_close` from the rolling VWAP in units of
+    /// `sigma_240 * mid_close` (None without volume or volatility).
+    #[serde(default)]
+    pub vwap_z: Option<f64>,
+    /// Touches of the rolling VWAP +/-1 standard deviation bands.
+    #[serde(default)]
+    pub vwap_band_1sd: VwapBandTouches,
+    /// Touches of the rolling VWAP +/-2 standard deviation bands.
+    #[serde(default)]
+    pub vwap_band_2sd: VwapBandTouches,
+    /// Bar volume divided by the average bar volume of the prior window
+    /// (None without prior volume).
+    #[serde(default)]
+    pub rvol: Option<f64>,
     /// Current bin width.
     pub bin_width: f64,
     /// Value Area.
     pub va: ValueArea,
+    /// Shannon entropy of the volume profile at `bin_width` (low =
+    /// concentrated, high = diffuse; None without volume).
+    #[serde(default)]
+    pub profile_entropy: Option<f64>,
+    /// `profile_entropy` divided by `ln` of the number of non-empty bins, in
+    /// `[0, 1]` (comparable across profile widths).
+    #[serde(default)]
+    pub profile_entropy_norm: Option<f64>,
     /// Order flow metrics.
     pub order_flow: OrderFlowMetrics,
+    /// Consecutive minutes of same-signed order flow (positive = buying).
+    #[serde(default)]
+    pub of_streak: i32,
+    /// Longest order flow streak (either sign) within the rolling window.
+    #[serde(default)]
+    pub max_of_streak: u32,
     /// Quote imbalance at close.
     pub qimb_close: f64,
     /// EMA of quote imbalance over the minute.
     pub qimb_ema: f64,
+    /// Rolling correlation of `qimb_ema` with the following minute's mid
+    /// return, over completed pairs (None until defined).
+    #[serde(default)]
+    pub qimb_predictive_corr: Option<f64>,
+    /// Aggressor flow minus quote imbalance (`of_norm_1m - qimb_ema`).
+    #[serde(default)]
+    pub flow_quote_divergence: f64,
+    /// Mean |`flow_quote_divergence`| over the rolling window.
+    #[serde(default)]
+    pub flow_quote_divergence_avg_abs: f64,
+    /// Quote-driven order flow imbalance summed over the minute.
+    #[serde(default)]
+    pub ofi_1m: f64,
+    /// Largest mid jump between consecutive quotes in the minute, in ticks.
+    #[serde(default)]
+    pub max_mid_jump_1m: f64,
+    /// The same jump in units of `mid_close * sigma_240` (None without volatility).
+    #[serde(default)]
+    pub max_mid_jump_sigma_1m: Option<f64>,
     /// Rolling 60-min average spread.
     pub spread_avg_60m: f64,
+    /// Spread regime of the close spread against the rolling spread history.
+    #[serde(default)]
+    pub spread_regime: SpreadRegime,
+    /// Size-weighted realized effective spread (`2 * |price - mid|`) over the minute.
+    pub eff_spread_avg_1m: f64,
+    /// Estimated round-trip cost in price: effective spread, expected
+    /// slippage on both sides and taker fees.
+    #[serde(default)]
+    pub est_roundtrip_cost: f64,
+    /// VA migration relative to the prior minute (None if either VA is invalid).
+    pub va_migration: Option<VaMigration>,
+    /// Auction state from entropy, VA migration and the order flow streak.
+    #[serde(default)]
+    pub auction_state: AuctionState,
+    /// Flow printed near the last-known VAH during the minute.
+    pub vah_edge_flow: EdgeFlow,
+    /// Flow printed near the last-known VAL during the minute.
+    pub val_edge_flow: EdgeFlow,
+    /// Bars in the rolling window whose range touched the POC.
+    #[serde(default)]
+    pub poc_tests: u32,
+    /// Bars in the rolling window whose range touched the VAH.
+    #[serde(default)]
+    pub vah_tests: u32,
+    /// Bars in the rolling window whose range touched the VAL.
+    #[serde(default)]
+    pub val_tests: u32,
+    /// Fraction of recent accepted VA breaks that held for
+    /// `breakout_outcome_bars` (None before any resolved).
+    #[serde(default)]
+    pub recent_breakout_success_rate: Option<f64>,
 }
 
 /// Trading signal type.
@@ -315,6 +740,14 @@ impl PositionSide {
             PositionSide::Short => -1.0,
         }
     }
+
+    /// The other side (the side of a fill that closes this one).
+    pub fn opposite(self) -> Self {
+        match self {
+            PositionSide::Long => PositionSide::Short,
+            PositionSide::Short => PositionSide::Long,
+        }
+    }
 }
 
 /// Action to take.
@@ -384,6 +817,45 @@ mod tests {
         assert!((quote.imbalance() - 0.3333333).abs() < 0.001);
     }
 
+    #[test]
+    fn test_display_precision_follows_tick_size() {
+        let quote = Quote {
+            ts_ms: 0,
+            bid_px: 101.25,
+            bid_sz: 3.0,
+            ask_px: 101.26,
+            ask_sz: 1.5,
+        };
+        assert_eq!(quote.display(0.01), "Quote(ts_ms=0, bid=101.25@3.0000, ask=101.26@1.5000)");
+
+        let quote = Quote {
+            bid_px: 50000.0,
+            ask_px: 50001.0,
+            ..quote
+        };
+        assert_eq!(quote.display(1.0), "Quote(ts_ms=0, bid=50000@3.0000, ask=50001@1.5000)");
+
+        let bar = Bar1m {
+            ts_min: 60_000,
+            open: 50000.0,
+            high: 50010.0,
+            low: 49990.0,
+            close: 50005.0,
+            volume: 12.5,
+            vwap: None,
+            trade_count: 4,
+            bid_px_close: 50004.0,
+            ask_px_close: 50006.0,
+            bid_sz_close: 1.0,
+            ask_sz_close: 1.0,
+        };
+        assert_eq!(
+            bar.display(1.0),
+            "Bar1m(ts_min=60000, o=50000, h=50010, l=49990, c=50005, v=12.5000)"
+        );
+        assert!(bar.display(0.01).contains("o=50000.00,"));
+    }
+
     #[test]
     fn test_trade_side_sign() {
         assert_eq!(TradeSide::Buy.sign(), 1);
@@ -396,4 +868,47 @@ mod tests {
         assert!(SignalType::BreakinLong.priority() < SignalType::FailedBreakoutLong.priority());
         assert!(SignalType::FailedBreakoutShort.priority() < SignalType::BreakoutShort.priority());
     }
+
+    #[test]
+    fn test_neutral_defaults() {
+        let va = ValueArea::default();
+        assert!(!va.is_valid);
+        assert_eq!(va.bin_count, 0);
+
+        let va = ValueArea::from_levels(50000.0, 50100.0, 49900.0);
+        assert!(va.is_valid);
+        assert_eq!((va.poc_raw, va.vah_raw, va.val_raw), (50000.0, 50100.0, 49900.0));
+        assert_eq!(va.poc_smoothed, 50000.0);
+
+        let of = OrderFlowMetrics::default();
+        assert!(!of.has_volume);
+        assert_eq!(of.total_volume, 0.0);
+
+        let features = Features1m { ts_min: 60_000, mid_close: 50000.0, ..Default::default() };
+        assert!(!features.va.is_valid);
+        assert!(!features.order_flow.has_volume);
+        assert_eq!(features.spread_regime, SpreadRegime::Normal);
+        assert!(features.rvol.is_none() && features.va_migration.is_none());
+        assert_eq!(features.of_streak, 0);
+    }
+
+    #[test]
+    fn test_order_flow_has_volume_from_old_records() {
+        let record = |total_volume: f64| {
+            format!(
+                r#"{{"of_1m":0.0,"of_norm_1m":0.0,"total_volume":{total_volume},"buy_volume":0.0,
+                "sell_volume":0.0,"ambiguous_volume":0.0,"ambiguous_frac":0.0}}"#
+            )
+        };
+        let of: OrderFlowMetrics = serde_json::from_str(&record(2.5)).unwrap();
+        assert!(of.has_volume);
+        let of: OrderFlowMetrics = serde_json::from_str(&record(0.0)).unwrap();
+        assert!(!of.has_volume);
+
+        // An explicit flag is kept
+        let flagged = OrderFlowMetrics { total_volume: 1.0, ..Default::default() };
+        let json = serde_json::to_string(&flagged).unwrap();
+        let of: OrderFlowMetrics = serde_json::from_str(&json).unwrap();
+        assert!(!of.has_volume);
+    }
 }
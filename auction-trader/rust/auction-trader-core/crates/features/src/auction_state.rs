@@ -0,0 +1,182 @@
+//! Auction state classification.
+//!
+//! Reduces profile entropy, VA migration and the order flow streak to a
+//! single [`AuctionState`]:
+//!
+//! - `TrendingUp` / `TrendingDown`: VA migrating in one direction (POC
+//!   shift of at least `trend_poc_shift_min`) with a same-signed order flow
+//!   streak of at least `trend_of_streak_min` minutes.
+//! - `Balanced`: overlapping VA with a small POC shift, a concentrated
+//!   profile (normalized entropy at most `balance_entropy_max`, so the
+//!   threshold holds at any profile width) and no trend streak.
+//! - `Transition`: anything else, including missing inputs.
+//!
+//! A new state is only reported after holding `confirm_minutes` consecutive
+//! minutes; until then the classifier reports `Transition`.
+
+use auction_core::{AuctionState, AuctionStateConfig, Features1m, TimestampMs, VaMigrationDirection};
+
+/// Rolling auction state classifier over computed features.
+#[derive(Debug, Clone)]
+pub struct AuctionStateClassifier {
+    config: AuctionStateConfig,
+    /// Last confirmed state.
+    state: AuctionState,
+    /// Unconfirmed candidate state and the minutes it has held.
+    pending: Option<(AuctionState, u32)>,
+    /// Minute of the last update.
+    last_ts: Option<TimestampMs>,
+}
+
+impl AuctionStateClassifier {
+    /// Create a classifier starting in `Transition`.
+    pub fn new(config: AuctionStateConfig) -> Self {
+        Self {
+            config,
+            state: AuctionState::Transition,
+            pending: None,
+            last_ts: None,
+        }
+    }
+
+    /// State implied by one minute's features alone.
+    pub fn classify(&self, features: &Features1m) -> AuctionState {
+        let Some(migration) = features.va_migration else {
+            return AuctionState::Transition;
+        };
+        let streak_min = self.config.trend_of_streak_min.max(1) as i32;
+        let streak = features.of_streak;
+
+        let trend_shift = migration.poc_shift.abs() >= self.config.trend_poc_shift_min;
+        match migration.direction {
+            VaMigrationDirection::Up if trend_shift && streak >= streak_min => return AuctionState::TrendingUp,
+            VaMigrationDirection::Down if trend_shift && streak <= -streak_min => return AuctionState::TrendingDown,
+            _ => {}
+        }
+
+        let concentrated = features
+            .profile_entropy_norm
+            .is_some_and(|entropy| entropy <= self.config.balance_entropy_max);
+        if migration.direction == VaMigrationDirection::Overlap
+            && migration.poc_shift.abs() <= self.config.balance_poc_shift_max
+            && concentrated
+            && streak.abs() < streak_min
+        {
+            AuctionState::Balanced
+        } else {
+            AuctionState::Transition
+        }
+    }
+
+    /// Classify minute `features.ts_min` and return the confirmed state.
+    ///
+    /// Updating the same minute twice has no further effect.
+    pub fn update(&mut self, features: &Features1m) -> AuctionState {
+        if self.last_ts.is_some_and(|ts| ts >= features.ts_min) {
+            return self.current();
+        }
+        self.last_ts = Some(features.ts_min);
+
+        let candidate = self.classify(features);
+        if candidate == self.state {
+            self.pending = None;
+            return self.state;
+        }
+        let held = match self.pending {
+            Some((state, held)) if state == candidate => held + 1,
+            _ => 1,
+        };
+        if held >= self.config.confirm_minutes.max(1) {
+            self.state = candidate;
+            self.pending = None;
+        } else {
+            self.pending = Some((candidate, held));
+        }
+        self.current()
+    }
+
+    /// Reported state: the confirmed one, or `Transition` while a change is pending.
+    pub fn current(&self) -> AuctionState {
+        if self.pending.is_some() {
+            AuctionState::Transition
+        } else {
+            self.state
+        }
+    }
+
+    /// Reset to `Transition`.
+    pub fn clear(&mut self) {
+        self.state = AuctionState::Transition;
+        self.pending = None;
+        self.last_ts = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use auction_core::VaMigration;
+
+    fn features(ts_min: TimestampMs, direction: VaMigrationDirection, poc_shift: f64, of_streak: i32, entropy: f64) -> Features1m {
+        Features1m {
+            ts_min,
+            profile_entropy_norm: Some(entropy),
+            of_streak,
+            va_migration: Some(VaMigration { direction, poc_shift, overlap_frac: 0.8 }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_classify_states() {
+        let classifier = AuctionStateClassifier::new(AuctionStateConfig::default());
+        let classify = |direction, poc_shift, of_streak, entropy| {
+            classifier.classify(&features(60_000, direction, poc_shift, of_streak, entropy))
+        };
+
+        assert_eq!(classify(VaMigrationDirection::Overlap, 0.02, 1, 0.6), AuctionState::Balanced);
+        assert_eq!(classify(VaMigrationDirection::Up, 0.3, 4, 0.95), AuctionState::TrendingUp);
+        assert_eq!(classify(VaMigrationDirection::Down, -0.3, -3, 0.95), AuctionState::TrendingDown);
+
+        // Migration without persistent flow, or flow against it
+        assert_eq!(classify(VaMigrationDirection::Up, 0.3, 1, 0.95), AuctionState::Transition);
+        assert_eq!(classify(VaMigrationDirection::Up, 0.3, -4, 0.95), AuctionState::Transition);
+        // A POC shift too small to count as migration
+        assert_eq!(classify(VaMigrationDirection::Up, 0.1, 4, 0.95), AuctionState::Transition);
+        // Overlapping value but a diffuse profile, a large POC shift or a streak
+        assert_eq!(classify(VaMigrationDirection::Overlap, 0.02, 0, 0.95), AuctionState::Transition);
+        assert_eq!(classify(VaMigrationDirection::Overlap, 0.3, 0, 0.6), AuctionState::Transition);
+        assert_eq!(classify(VaMigrationDirection::Overlap, 0.02, -5, 0.6), AuctionState::Transition);
+
+        // Missing inputs
+        let mut no_migration = features(60_000, VaMigrationDirection::Overlap, 0.0, 0, 0.6);
+        no_migration.va_migration = None;
+        assert_eq!(classifier.classify(&no_migration), AuctionState::Transition);
+        let mut no_entropy = features(60_000, VaMigrationDirection::Overlap, 0.0, 0, 0.6);
+        no_entropy.profile_entropy_norm = None;
+        assert_eq!(classifier.classify(&no_entropy), AuctionState::Transition);
+    }
+
+    #[test]
+    fn test_confirmation() {
+        let config = AuctionStateConfig { confirm_minutes: 2, ..Default::default() };
+        let mut classifier = AuctionStateClassifier::new(config);
+        let balanced = |ts| features(ts, VaMigrationDirection::Overlap, 0.0, 0, 0.6);
+        let trending = |ts| features(ts, VaMigrationDirection::Up, 0.3, 5, 0.95);
+
+        assert_eq!(classifier.update(&balanced(60_000)), AuctionState::Transition);
+        assert_eq!(classifier.update(&balanced(120_000)), AuctionState::Balanced);
+        // Re-updating a minute does not count towards confirmation
+        assert_eq!(classifier.update(&trending(120_000)), AuctionState::Balanced);
+
+        // A one-minute blip is not confirmed
+        assert_eq!(classifier.update(&trending(180_000)), AuctionState::Transition);
+        assert_eq!(classifier.update(&balanced(240_000)), AuctionState::Balanced);
+
+        assert_eq!(classifier.update(&trending(300_000)), AuctionState::Transition);
+        assert_eq!(classifier.update(&trending(360_000)), AuctionState::TrendingUp);
+
+        classifier.clear();
+        assert_eq!(classifier.current(), AuctionState::Transition);
+    }
+}
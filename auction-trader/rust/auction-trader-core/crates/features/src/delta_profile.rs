@@ -0,0 +1,132 @@
+//! Per-side volume profile.
+//!
+//! Keeps buy-initiated and sell-initiated volume-at-price in two parallel
+//! rolling histograms, giving the delta at each price and the POC of each side.
+
+use crate::histogram::RollingHistogram;
+use auction_core::{ts_to_minute, ClassifiedTrade, TradeSide};
+use ordered_float::OrderedFloat;
+use std::collections::BTreeMap;
+
+/// Rolling buy and sell volume profiles.
+///
+/// Ambiguous trades are not attributed to either side.
+pub struct DeltaProfile {
+    /// Buy-initiated volume at price.
+    buy: RollingHistogram,
+    /// Sell-initiated volume at price.
+    sell: RollingHistogram,
+}
+
+impl DeltaProfile {
+    /// Create a new delta profile.
+    pub fn new(base_bin: f64, window: usize) -> Self {
+        Self {
+            buy: RollingHistogram::new(base_bin, window),
+            sell: RollingHistogram::new(base_bin, window),
+        }
+    }
+
+    /// Add a classified trade.
+    pub fn add_trade(&mut self, trade: &ClassifiedTrade) {
+        let ts_min = ts_to_minute(trade.trade.ts_ms);
+        match trade.side {
+            TradeSide::Buy => self.buy.add_trade(ts_min, trade.trade.price, trade.trade.size),
+            TradeSide::Sell => self.sell.add_trade(ts_min, trade.trade.price, trade.trade.size),
+            TradeSide::Ambiguous => {}
+        }
+    }
+
+    /// Finalize the current minute on both sides (call at minute boundary).
+    pub fn flush_current_minute(&mut self) {
+        self.buy.flush_current_minute();
+        self.sell.flush_current_minute();
+    }
+
+    /// Buy-initiated volume profile at `bin_width`.
+    pub fn buy_profile(&self, bin_width: f64) -> BTreeMap<OrderedFloat<f64>, f64> {
+        self.buy.aggregate_to(bin_width)
+    }
+
+    /// Sell-initiated volume profile at `bin_width`.
+    pub fn sell_profile(&self, bin_width: f64) -> BTreeMap<OrderedFloat<f64>, f64> {
+        self.sell.aggregate_to(bin_width)
+    }
+
+    /// Buy minus sell volume per bin at `bin_width`.
+    pub fn delta(&self, bin_width: f64) -> BTreeMap<OrderedFloat<f64>, f64> {
+        let mut delta = self.buy_profile(bin_width);
+        for (key, vol) in self.sell_profile(bin_width) {
+            *delta.entry(key).or_insert(0.0) -= vol;
+        }
+        delta
+    }
+
+    /// Price bin with the most buy volume (None if no buys).
+    pub fn buy_poc(&self, bin_width: f64) -> Option<f64> {
+        poc(&self.buy_profile(bin_width))
+    }
+
+    /// Price bin with the most sell volume (None if no sells).
+    pub fn sell_poc(&self, bin_width: f64) -> Option<f64> {
+        poc(&self.sell_profile(bin_width))
+    }
+
+    /// Clear all data.
+    pub fn clear(&mut self) {
+        self.buy.clear();
+        self.sell.clear();
+    }
+}
+
+/// Bin with the maximum volume (the lowest price on ties).
+fn poc(profile: &BTreeMap<OrderedFloat<f64>, f64>) -> Option<f64> {
+    profile
+        .iter()
+        .fold(None, |best: Option<(f64, f64)>, (&key, &vol)| match best {
+            Some((_, best_vol)) if best_vol >= vol => best,
+            _ => Some((key.0, vol)),
+        })
+        .map(|(price, _)| price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use auction_core::Trade;
+
+    fn make_trade(ts_ms: i64, price: f64, size: f64, side: TradeSide) -> ClassifiedTrade {
+        ClassifiedTrade {
+            trade: Trade { ts_ms, price, size },
+            side,
+            quote_bid_px: price - 0.5,
+            quote_ask_px: price + 0.5,
+            quote_staleness_ms: 10,
+        }
+    }
+
+    #[test]
+    fn test_delta_profile() {
+        let mut profile = DeltaProfile::new(1.0, 5);
+
+        // Buyers lift at the highs, sellers hit at the lows
+        profile.add_trade(&make_trade(1000, 105.2, 4.0, TradeSide::Buy));
+        profile.add_trade(&make_trade(2000, 103.0, 1.0, TradeSide::Buy));
+        profile.add_trade(&make_trade(3000, 100.4, 5.0, TradeSide::Sell));
+        profile.add_trade(&make_trade(4000, 103.5, 2.0, TradeSide::Sell));
+        profile.add_trade(&make_trade(5000, 104.0, 9.0, TradeSide::Ambiguous));
+        profile.flush_current_minute();
+
+        assert_eq!(profile.buy_poc(1.0), Some(105.0));
+        assert_eq!(profile.sell_poc(1.0), Some(100.0));
+
+        let delta = profile.delta(1.0);
+        assert!((delta[&OrderedFloat(105.0)] - 4.0).abs() < 1e-10);
+        assert!((delta[&OrderedFloat(103.0)] + 1.0).abs() < 1e-10); // 1 buy, 2 sell
+        assert!((delta[&OrderedFloat(100.0)] + 5.0).abs() < 1e-10);
+        assert!(!delta.contains_key(&OrderedFloat(104.0)));
+
+        profile.clear();
+        assert_eq!(profile.buy_poc(1.0), None);
+    }
+}
@@ -1,38 +1,110 @@
 //! Feature computation engine.
 //!
 //! Combines all feature components into a unified interface.
+//!
+//! Per minute, feed the minute's quotes and trades, then call
+//! [`FeatureEngine::process_bar`] (or [`FeatureEngine::finalize_minute`]) with
+//! the closed bar. It ingests the bar (volatility, spread, histogram flush,
+//! rebucket) and then computes features, so the features for minute T always
+//! include bar T. Bars fed with [`FeatureEngine::add_bar`] (e.g. warmup)
+//! cannot be finalized again. The older `add_bar` + `compute_features` pair
+//! is deprecated.
+//!
+//! Consumers that want features pushed as minutes finalize can
+//! [`subscribe`](FeatureEngine::subscribe) for a channel instead of polling.
+//!
+//! A [`TradeCap`] set with [`FeatureEngine::set_trade_cap`] guards the
+//! engine against feed spam the same way it guards the bar builder.
 
+use auction_core::config::ExecutionConfig;
 use auction_core::{
-    Bar1m, ClassifiedTrade, Config, Features1m, Quote, TimestampMs, ValueArea,
-    ts_to_minute,
+    market_slippage_ticks, ts_to_minute, AuctionState, Bar1m, BinWidthStrategy, ClassifiedTrade,
+    Config, Error, Features1m, ProfileWeighting, Quote, Result, SizeConversion, SpreadRegime,
+    TimestampMs, VaPriceWindow, ValueArea,
 };
+use auction_ingestion::{TradeCap, TradeCapPolicy};
 use crate::{
+    auction_state::AuctionStateClassifier,
     histogram::RollingHistogram,
-    order_flow::{OrderFlowAggregator, QuoteImbalanceTracker},
-    value_area::{ValueAreaComputer, ValueAreaConfig},
+    order_flow::{
+        EdgeFlowTracker, EffectiveSpreadTracker, FlowQuoteDivergence, MidJumpTracker,
+        OrderFlowAggregator, OrderFlowImbalanceTracker, OrderFlowSeries, OrderFlowStreak,
+        PredictiveCorrelation, QuoteImbalanceTracker,
+    },
+    value_area::{
+        normalized_profile_entropy, profile_entropy, va_migration, BreakoutOutcomeTracker,
+        IncrementalValueArea, LevelTestCounter, ValueAreaComputer, ValueAreaConfig,
+    },
     volatility::RollingVolatility,
+    volume::RelativeVolume,
+    vwap::{vwap_z, RollingVwap, VwapBandTouchCounter},
 };
-use std::collections::VecDeque;
+use ordered_float::OrderedFloat;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::mpsc;
 
 /// Feature computation engine.
 pub struct FeatureEngine {
     /// Rolling volatility calculator.
     volatility: RollingVolatility,
+    /// Rolling VWAP over the same window.
+    vwap: RollingVwap,
+    /// Bars touching the VWAP +/-1 and +/-2 standard deviation bands.
+    vwap_band_1sd: VwapBandTouchCounter,
+    vwap_band_2sd: VwapBandTouchCounter,
+    /// Bar volume relative to the window average.
+    rvol: RelativeVolume,
     /// Rolling volume histogram.
     histogram: RollingHistogram,
     /// Value Area computer.
     va_computer: ValueAreaComputer,
+    /// Incrementally maintained VA (`incremental_va`), fed histogram deltas.
+    incremental_va: Option<IncrementalValueArea>,
     /// Order flow aggregator.
     order_flow: OrderFlowAggregator,
+    /// Consecutive same-signed order flow minutes.
+    of_streak: OrderFlowStreak,
+    /// Correlation of `qimb_ema` with the next minute's mid return.
+    qimb_corr: PredictiveCorrelation,
+    /// Rolling magnitude of order flow vs quote imbalance divergence.
+    flow_quote_divergence: FlowQuoteDivergence,
     /// Quote imbalance tracker.
     qimb_tracker: QuoteImbalanceTracker,
+    /// Quote-driven order flow imbalance tracker.
+    ofi_tracker: OrderFlowImbalanceTracker,
+    /// Largest mid jump between consecutive quotes.
+    mid_jumps: MidJumpTracker,
+    /// Realized effective spread tracker.
+    eff_spread: EffectiveSpreadTracker,
+    /// Flow near the last computed VAH/VAL.
+    edge_flow: EdgeFlowTracker,
+    /// Bars touching the POC/VAH/VAL within the window.
+    poc_tests: LevelTestCounter,
+    vah_tests: LevelTestCounter,
+    val_tests: LevelTestCounter,
+    /// Continuation vs failure of accepted VA breaks.
+    breakouts: BreakoutOutcomeTracker,
+    /// Balance/trend classification of the computed features.
+    auction_state: AuctionStateClassifier,
     /// Rolling spread tracker (for 60-min average).
     spreads: VecDeque<(TimestampMs, f64)>,
     /// Configuration.
     tick_size: f64,
     alpha_bin: f64,
+    base_bin_width: f64,
     bin_width_max: f64,
+    bin_width_strategy: BinWidthStrategy,
+    /// Bars per year for annualized sigma.
+    sigma_periods_per_year: Option<f64>,
+    /// Recent bar mids (for `BinWidthStrategy::Percentile`).
+    recent_mids: VecDeque<f64>,
+    /// Last bar close mid (centre of the VA price window).
+    last_mid: Option<f64>,
+    va_price_window: VaPriceWindow,
     spread_lookback: usize,
+    /// Spread history percentiles for the spread regime.
+    spread_tight_pct: f64,
+    spread_wide_pct: f64,
     rolling_window: usize,
     /// Current bin width.
     current_bin_width: f64,
@@ -40,46 +112,249 @@ pub struct FeatureEngine {
     last_rebucket_min: Option<TimestampMs>,
     rebucket_interval: u32,
     rebucket_change_pct: f64,
+    /// Round VA levels to the tick grid on output.
+    round_va_to_tick: bool,
+    /// Fees and slippage for the round-trip cost estimate.
+    execution: ExecutionConfig,
+    /// Conversion of trade sizes to base units for volume and flow.
+    size_conversion: SizeConversion,
+    /// Most recently computed VA and its minute.
+    last_va: Option<(TimestampMs, ValueArea)>,
+    /// VA of the minute before `last_va` (reference for migration).
+    prior_va: Option<ValueArea>,
+    /// Minutes between VA recomputations in `compute`.
+    va_recompute_interval: u32,
+    /// Last recomputed VA, profile entropy and their minute (reused until
+    /// the next interval).
+    va_cache: Option<(TimestampMs, ValueArea, ProfileEntropy)>,
+    /// Minute of the last bar ingested.
+    last_bar_min: Option<TimestampMs>,
+    /// Channels receiving each finalized minute's features.
+    subscribers: Vec<mpsc::Sender<Features1m>>,
+    /// Per-minute trade cap.
+    trade_cap: Option<TradeCap>,
+    /// Capped state of minutes not yet closed by a bar.
+    capped_minutes: BTreeMap<TimestampMs, CappedMinute>,
+    /// Trades rejected by the trade cap.
+    capped_trades: u64,
+}
+
+/// Trades of a minute counted against the [`TradeCap`].
+#[derive(Debug, Default)]
+struct CappedMinute {
+    trade_count: u32,
+    volume: f64,
+    /// Flagged under `TradeCapPolicy::ExcludeMinute`.
+    corrupt: bool,
+    /// Trades held back until the minute closes (`ExcludeMinute` only).
+    held: Vec<ClassifiedTrade>,
+}
+
+/// Components that must be warm for [`FeatureEngine::is_ready_with`].
+///
+/// The default matches [`FeatureEngine::is_ready`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadinessRequirements {
+    /// Require a full volatility window.
+    pub volatility: bool,
+    /// Require a full histogram window (needed for the VA).
+    pub histogram: bool,
+    /// Minimum minutes of order flow in the rolling window.
+    pub order_flow_minutes: usize,
+    /// Require at least one quote in the imbalance tracker.
+    pub qimb: bool,
+}
+
+impl Default for ReadinessRequirements {
+    fn default() -> Self {
+        Self {
+            volatility: true,
+            histogram: true,
+            order_flow_minutes: 0,
+            qimb: false,
+        }
+    }
 }
 
 impl FeatureEngine {
     /// Create a new feature engine from configuration.
+    ///
+    /// # Panics
+    /// If the configuration is invalid; see [`try_new`](Self::try_new).
     pub fn new(config: &Config) -> Self {
+        Self::try_new(config).expect("invalid feature engine configuration")
+    }
+
+    /// Create a new feature engine, validating the configuration.
+    pub fn try_new(config: &Config) -> Result<Self> {
+        config.validate()?;
         let rolling_window = config.instrument.rolling_window_minutes as usize;
         let tick_size = config.instrument.tick_size;
+        let level_test_reset = config.value_area.level_test_reset_ticks as f64 * tick_size;
+        let va_config = ValueAreaConfig {
+            va_fraction: config.value_area.va_fraction,
+            min_bins: config.value_area.min_va_bins,
+            poc_smoothing_bins: config.value_area.poc_smoothing_bins,
+            poc_convention: config.value_area.poc_convention,
+        };
 
-        Self {
+        let mut histogram = RollingHistogram::new(tick_size, rolling_window);
+        histogram.set_weighting(config.value_area.profile_weighting);
+        let incremental = config.value_area.incremental_va
+            && config.value_area.va_price_window == VaPriceWindow::Unbounded
+            && config.value_area.profile_weighting == ProfileWeighting::Flat;
+        let incremental_va = incremental.then(|| {
+            histogram.track_deltas();
+            IncrementalValueArea::new(va_config.clone(), tick_size)
+        });
+
+        Ok(Self {
             volatility: RollingVolatility::new(rolling_window),
-            histogram: RollingHistogram::new(tick_size, rolling_window),
-            va_computer: ValueAreaComputer::new(ValueAreaConfig {
-                va_fraction: config.value_area.va_fraction,
-                min_bins: config.value_area.min_va_bins,
-            }),
-            order_flow: OrderFlowAggregator::new(rolling_window),
-            qimb_tracker: QuoteImbalanceTracker::new(
+            vwap: RollingVwap::new(rolling_window),
+            vwap_band_1sd: VwapBandTouchCounter::new(rolling_window),
+            vwap_band_2sd: VwapBandTouchCounter::new(rolling_window),
+            rvol: RelativeVolume::new(rolling_window),
+            histogram,
+            va_computer: ValueAreaComputer::new(va_config),
+            incremental_va,
+            order_flow: OrderFlowAggregator::new(rolling_window)
+                .with_at_mid_policy(config.order_flow.at_mid_policy),
+            of_streak: OrderFlowStreak::new(rolling_window),
+            qimb_corr: PredictiveCorrelation::new(rolling_window),
+            flow_quote_divergence: FlowQuoteDivergence::new(rolling_window),
+            qimb_tracker: QuoteImbalanceTracker::with_update_rate(
                 rolling_window * 1000, // ~1000 updates per minute max
                 config.order_flow.spread_lookback_minutes,
+                config.order_flow.qimb_updates_per_second,
+            )?
+            .with_min_quote_size(config.order_flow.min_quote_size),
+            ofi_tracker: OrderFlowImbalanceTracker::new(rolling_window),
+            mid_jumps: MidJumpTracker::new(rolling_window),
+            eff_spread: EffectiveSpreadTracker::new(rolling_window),
+            edge_flow: EdgeFlowTracker::new(
+                rolling_window,
+                config.order_flow.edge_flow_tolerance_ticks as f64 * tick_size,
             ),
+            poc_tests: LevelTestCounter::new(rolling_window, level_test_reset),
+            vah_tests: LevelTestCounter::new(rolling_window, level_test_reset),
+            val_tests: LevelTestCounter::new(rolling_window, level_test_reset),
+            breakouts: BreakoutOutcomeTracker::new(
+                config.signal.accept_outside_k,
+                config.signal.breakout_outcome_bars,
+                config.signal.breakout_outcome_window as usize,
+            ),
+            auction_state: AuctionStateClassifier::new(config.auction_state.clone()),
             spreads: VecDeque::with_capacity(config.order_flow.spread_lookback_minutes as usize),
             tick_size,
             alpha_bin: config.value_area.alpha_bin,
+            base_bin_width: config.value_area.base_bin_ticks as f64 * tick_size,
             bin_width_max: config.value_area.bin_width_max_ticks as f64 * tick_size,
+            bin_width_strategy: config.value_area.bin_width_strategy,
+            sigma_periods_per_year: config.instrument.sigma_periods_per_year,
+            recent_mids: VecDeque::new(),
+            last_mid: None,
+            va_price_window: config.value_area.va_price_window,
             spread_lookback: config.order_flow.spread_lookback_minutes as usize,
+            spread_tight_pct: config.order_flow.spread_tight_pct,
+            spread_wide_pct: config.order_flow.spread_wide_pct,
             rolling_window,
             current_bin_width: tick_size,
             last_rebucket_min: None,
             rebucket_interval: config.value_area.rebucket_interval_minutes,
             rebucket_change_pct: config.value_area.rebucket_change_pct,
-        }
+            round_va_to_tick: config.value_area.round_to_tick,
+            execution: config.execution.clone(),
+            size_conversion: config.instrument.size_conversion(),
+            last_va: None,
+            prior_va: None,
+            va_recompute_interval: config.value_area.va_recompute_interval_minutes,
+            va_cache: None,
+            last_bar_min: None,
+            subscribers: Vec::new(),
+            trade_cap: None,
+            capped_minutes: BTreeMap::new(),
+            capped_trades: 0,
+        })
+    }
+
+    /// Cap the trades ingested per minute (None = uncapped).
+    ///
+    /// Trades beyond the cap are dropped. Under `TradeCapPolicy::ExcludeMinute`
+    /// a minute's trades are held back until its bar is ingested and dropped
+    /// entirely if the minute was flagged, so partial-minute snapshots do not
+    /// include them.
+    pub fn set_trade_cap(&mut self, cap: Option<TradeCap>) {
+        self.trade_cap = cap;
+    }
+
+    /// Number of trades rejected by the trade cap.
+    pub fn capped_trades(&self) -> u64 {
+        self.capped_trades
     }
 
     /// Process a quote update.
     pub fn add_quote(&mut self, quote: &Quote) {
-        self.qimb_tracker.add(quote.ts_ms, quote.imbalance());
+        self.qimb_tracker.add_quote(quote);
+        self.ofi_tracker.add_quote(quote);
+        self.mid_jumps.add_quote(quote);
     }
 
     /// Process a classified trade.
+    ///
+    /// Sizes are converted to base units per the instrument's `size_unit`.
+    /// Trades beyond the trade cap are counted in
+    /// [`capped_trades`](Self::capped_trades) and ignored.
     pub fn add_trade(&mut self, trade: &ClassifiedTrade) {
+        let converted;
+        let trade = if self.size_conversion.is_identity() {
+            trade
+        } else {
+            let mut base = trade.clone();
+            base.trade.size = self.size_conversion.to_base(trade.trade.size, trade.trade.price);
+            converted = base;
+            &converted
+        };
+
+        let Some(cap) = self.trade_cap else {
+            self.ingest_trade(trade);
+            return;
+        };
+        let ts_min = ts_to_minute(trade.trade.ts_ms);
+        let minute = self.capped_minutes.entry(ts_min).or_default();
+        if minute.corrupt {
+            self.capped_trades += 1;
+            return;
+        }
+        if cap.exceeds(minute.trade_count, minute.volume, trade.trade.size) {
+            self.capped_trades += 1;
+            if cap.policy == TradeCapPolicy::ExcludeMinute {
+                minute.corrupt = true;
+                self.capped_trades += minute.held.len() as u64;
+                minute.held.clear();
+            }
+            return;
+        }
+        minute.trade_count += 1;
+        minute.volume += trade.trade.size;
+        if cap.policy == TradeCapPolicy::ExcludeMinute {
+            minute.held.push(trade.clone());
+        } else {
+            self.ingest_trade(trade);
+        }
+    }
+
+    /// Ingest the held trades of capped minutes up to `ts_min` and forget
+    /// their cap state.
+    fn release_capped_minutes(&mut self, ts_min: TimestampMs) {
+        let later = self.capped_minutes.split_off(&(ts_min + 1));
+        let closed = std::mem::replace(&mut self.capped_minutes, later);
+        for trade in closed.into_values().flat_map(|minute| minute.held) {
+            self.ingest_trade(&trade);
+        }
+    }
+
+    /// Feed a base-unit trade to the histogram and flow trackers.
+    fn ingest_trade(&mut self, trade: &ClassifiedTrade) {
         let ts_min = ts_to_minute(trade.trade.ts_ms);
 
         // Add to histogram
@@ -87,6 +362,8 @@ impl FeatureEngine {
 
         // Add to order flow
         self.order_flow.add_trade(trade);
+        self.eff_spread.add_trade(trade);
+        self.edge_flow.add_trade(trade);
     }
 
     /// Process multiple classified trades.
@@ -96,11 +373,30 @@ impl FeatureEngine {
         }
     }
 
-    /// Process a completed 1-minute bar.
+    /// Process a completed 1-minute bar without computing features.
+    ///
+    /// Use for warmup; live and replay loops should call
+    /// [`finalize_minute`](Self::finalize_minute) instead.
+    ///
+    /// A bar without a close quote (see [`Bar1m::has_close_quote`]) still
+    /// contributes its volume, but not a mid or spread.
     pub fn add_bar(&mut self, bar: &Bar1m) {
+        self.release_capped_minutes(bar.ts_min);
+        self.last_bar_min = Some(bar.ts_min);
+        self.vwap.add(bar.vwap.unwrap_or(bar.close), bar.volume);
+        self.rvol.add(bar.volume);
+
+        // Flush histogram for this minute
+        self.histogram.flush_current_minute();
+
+        if !bar.has_close_quote() {
+            return;
+        }
+
         // Add mid price to volatility
         let mid = bar.mid_close();
         self.volatility.add_price(mid);
+        self.last_mid = Some(mid);
 
         // Track spread
         let spread = bar.spread_close();
@@ -109,8 +405,13 @@ impl FeatureEngine {
             self.spreads.pop_front();
         }
 
-        // Flush histogram for this minute
-        self.histogram.flush_current_minute();
+        // Track mids for range-based bin widths
+        if let BinWidthStrategy::Percentile { lookback_minutes, .. } = self.bin_width_strategy {
+            self.recent_mids.push_back(mid);
+            while self.recent_mids.len() > lookback_minutes.max(1) as usize {
+                self.recent_mids.pop_front();
+            }
+        }
 
         // Check if rebucketing needed
         self.maybe_rebucket(bar.ts_min, mid);
@@ -118,10 +419,8 @@ impl FeatureEngine {
 
     /// Check and perform rebucketing if needed.
     fn maybe_rebucket(&mut self, ts_min: TimestampMs, mid_price: f64) {
-        let sigma = self.volatility.volatility().unwrap_or(0.0);
-
         // Calculate new bin width
-        let new_bin_width_raw = self.alpha_bin * mid_price * sigma;
+        let new_bin_width_raw = self.target_bin_width(mid_price);
         let new_bin_width = self.round_to_tick(new_bin_width_raw)
             .max(self.tick_size)
             .min(self.bin_width_max);
@@ -145,6 +444,29 @@ impl FeatureEngine {
             self.current_bin_width = new_bin_width;
             self.last_rebucket_min = Some(ts_min);
             // Histogram rebuild is implicit - we aggregate on demand
+            self.va_cache = None;
+        }
+    }
+
+    /// Unrounded bin width for the configured strategy.
+    fn target_bin_width(&self, mid_price: f64) -> f64 {
+        match self.bin_width_strategy {
+            BinWidthStrategy::VolatilityScaled => {
+                let sigma = self.volatility.volatility().unwrap_or(0.0);
+                self.alpha_bin * mid_price * sigma
+            }
+            BinWidthStrategy::Fixed => self.base_bin_width,
+            BinWidthStrategy::Percentile { target_bins, coverage, .. } => {
+                if self.recent_mids.len() < 2 || target_bins == 0 {
+                    return self.tick_size;
+                }
+                let mut mids: Vec<f64> = self.recent_mids.iter().copied().collect();
+                mids.sort_by(|a, b| a.total_cmp(b));
+
+                let tail = (1.0 - coverage.clamp(0.0, 1.0)) / 2.0;
+                let range = quantile(&mids, 1.0 - tail) - quantile(&mids, tail);
+                range / target_bins as f64
+            }
         }
     }
 
@@ -162,48 +484,351 @@ impl FeatureEngine {
         sum / self.spreads.len() as f64
     }
 
-    /// Compute features for a specific minute.
-    pub fn compute_features(&self, ts_min: TimestampMs, bar: &Bar1m) -> Features1m {
+    /// Estimated round-trip cost in price at `mid` with `quoted_spread` at the close.
+    ///
+    /// `2 * (avg_effective_spread + expected_slippage) + fees`. The effective
+    /// spread is the rolling average of `2 * |price - mid|`, or the 60-min
+    /// quoted spread before any trades. The expected slippage follows the
+    /// backtest fill model: the average of the entry and exit
+    /// [`market_slippage_ticks`]. Taker fees are charged on entry and exit.
+    fn roundtrip_cost(&self, mid: f64, quoted_spread: f64) -> f64 {
+        let eff_spread = self.eff_spread.avg().unwrap_or_else(|| self.avg_spread());
+        let exec = &self.execution;
+        let spread_ticks = quoted_spread / self.tick_size;
+        let side_ticks = |ticks| {
+            let max_ticks = exec.max_slippage_ticks;
+            market_slippage_ticks(ticks, spread_ticks, exec.spread_multiple, max_ticks) as f64
+        };
+        let slippage_ticks =
+            (side_ticks(exec.slippage_ticks_entry) + side_ticks(exec.slippage_ticks_exit)) / 2.0;
+        let fees = 2.0 * mid * exec.taker_fee_bps / 10_000.0;
+        2.0 * (eff_spread + slippage_ticks * self.tick_size) + fees
+    }
+
+    /// Classify the latest spread against the percentiles of the spread history.
+    ///
+    /// Comparisons are strict, so a flat history (e.g. always one tick) stays
+    /// `Normal`.
+    fn spread_regime(&self) -> SpreadRegime {
+        let Some(&(_, current)) = self.spreads.back() else {
+            return SpreadRegime::Normal;
+        };
+        if self.spreads.len() < 5 {
+            return SpreadRegime::Normal;
+        }
+
+        let mut sorted: Vec<f64> = self.spreads.iter().map(|(_, s)| *s).collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let percentile = |p: f64| {
+            let idx = (p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64).round() as usize;
+            sorted[idx]
+        };
+
+        if current > percentile(self.spread_wide_pct) {
+            SpreadRegime::Wide
+        } else if current < percentile(self.spread_tight_pct) {
+            SpreadRegime::Tight
+        } else {
+            SpreadRegime::Normal
+        }
+    }
+
+    /// Ingest the closed bar for `ts_min` and compute that minute's features.
+    ///
+    /// Fails if the bar is not for `ts_min` or if `ts_min` is not after the
+    /// last ingested bar, so a minute can't be ingested twice or out of order.
+    pub fn finalize_minute(&mut self, ts_min: TimestampMs, bar: &Bar1m) -> Result<Features1m> {
+        if bar.ts_min != ts_min {
+            return Err(Error::data(format!(
+                "bar minute {} does not match finalized minute {}",
+                bar.ts_min, ts_min
+            )));
+        }
+        if let Some(last) = self.last_bar_min.filter(|&last| ts_min <= last) {
+            return Err(Error::data(format!(
+                "minute {} already ingested (last bar {})",
+                ts_min, last
+            )));
+        }
+
+        self.add_bar(bar);
+        let features = self.compute(ts_min, bar);
+        self.publish(&features);
+        Ok(features)
+    }
+
+    /// Subscribe to finalized features.
+    ///
+    /// The receiver gets one `Features1m` per minute finalized through
+    /// [`finalize_minute`](Self::finalize_minute) or
+    /// [`process_bar`](Self::process_bar), in order. Dropping it unsubscribes.
+    pub fn subscribe(&mut self) -> mpsc::Receiver<Features1m> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.push(tx);
+        rx
+    }
+
+    /// Number of live subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// Send features to all subscribers, dropping disconnected ones.
+    fn publish(&mut self, features: &Features1m) {
+        if !self.subscribers.is_empty() {
+            self.subscribers.retain(|tx| tx.send(features.clone()).is_ok());
+        }
+    }
+
+    /// Ingest a closed bar and compute its minute's features in one call.
+    ///
+    /// Same as [`finalize_minute`](Self::finalize_minute) with `bar.ts_min`;
+    /// fails if the minute was already ingested.
+    pub fn process_bar(&mut self, bar: &Bar1m) -> Result<Features1m> {
+        self.finalize_minute(bar.ts_min, bar)
+    }
+
+    /// Compute features for a specific minute from the current state.
+    ///
+    /// The result depends on whether `add_bar(bar)` was called first; prefer
+    /// [`process_bar`](Self::process_bar).
+    #[deprecated(note = "use finalize_minute, which ingests the bar before computing")]
+    pub fn compute_features(&mut self, ts_min: TimestampMs, bar: &Bar1m) -> Features1m {
+        self.compute(ts_min, bar)
+    }
+
+    /// Compute features from the current state.
+    ///
+    /// The VA is cached so the next minute can report its migration;
+    /// recomputing the same minute compares against the same prior VA.
+    fn compute(&mut self, ts_min: TimestampMs, bar: &Bar1m) -> Features1m {
         let mid_close = bar.mid_close();
         let sigma = self.volatility.volatility().unwrap_or(0.0);
 
-        // Compute VA from aggregated histogram
-        let agg_hist = self.histogram.aggregate_to(self.current_bin_width);
-        let va = self.va_computer.compute(&agg_hist, self.current_bin_width);
+        // Compute VA and entropy from aggregated histogram (or reuse them within the interval)
+        let (va, (profile_entropy, profile_entropy_norm)) = self.cached_value_area(ts_min);
+
+        // VA migration vs the prior minute
+        match self.last_va.take() {
+            Some((last_ts, last)) if last_ts < ts_min => self.prior_va = Some(last),
+            Some((last_ts, _)) if last_ts > ts_min => self.prior_va = None,
+            _ => {}
+        }
+        let migration = self
+            .prior_va
+            .as_ref()
+            .and_then(|prior| va_migration(prior, &va));
+        self.last_va = Some((ts_min, va.clone()));
+
+        // Edge flow was matched against the previous VA; this VA sets the next edges
+        let (vah_edge_flow, val_edge_flow) = self.edge_flow.for_minute(ts_min);
+        if va.is_valid {
+            self.edge_flow.set_edges(va.vah, va.val);
+        } else {
+            self.edge_flow.clear_edges();
+        }
+
+        // Bars touching the VA levels
+        let (poc_tests, vah_tests, val_tests) = if va.is_valid {
+            (
+                self.poc_tests.update(ts_min, va.poc, bar.low, bar.high),
+                self.vah_tests.update(ts_min, va.vah, bar.low, bar.high),
+                self.val_tests.update(ts_min, va.val, bar.low, bar.high),
+            )
+        } else {
+            (0, 0, 0)
+        };
+        let recent_breakout_success_rate = self.breakouts.update(ts_min, &va, bar.close);
 
         // Get order flow metrics
         let order_flow = self.order_flow
             .get_minute(ts_min)
-            .unwrap_or_else(|| auction_core::OrderFlowMetrics {
-                of_1m: 0.0,
-                of_norm_1m: 0.0,
-                total_volume: 0.0,
-                buy_volume: 0.0,
-                sell_volume: 0.0,
-                ambiguous_volume: 0.0,
-                ambiguous_frac: 0.0,
-            });
+            .unwrap_or_else(auction_core::OrderFlowMetrics::empty);
+        let of_streak = self.of_streak.update(ts_min, order_flow.of_norm_1m);
 
         // Get qimb
         let qimb_close = bar.qimb_close();
         let qimb_ema = self.qimb_tracker.ema_for_minute(ts_min);
+        let qimb_predictive_corr = self.qimb_corr.update(ts_min, qimb_ema, mid_close);
+        let flow_quote_divergence = order_flow.of_norm_1m - qimb_ema;
+        let flow_quote_divergence_avg_abs = self.flow_quote_divergence.update(ts_min, flow_quote_divergence);
+        let mid_jump = self.mid_jumps.max_for_minute(ts_min);
+        let jump_sigma_scale = mid_close * sigma;
 
-        Features1m {
+        // Bar range against the VWAP bands
+        let vwap_band_1sd = self.vwap_band_1sd.update(ts_min, self.vwap.band(1.0), bar.low, bar.high);
+        let vwap_band_2sd = self.vwap_band_2sd.update(ts_min, self.vwap.band(2.0), bar.low, bar.high);
+
+        let mut features = Features1m {
             ts_min,
             mid_close,
             sigma_240: sigma,
+            sigma_annualized: self
+                .sigma_periods_per_year
+                .and_then(|periods| self.volatility.annualized(periods)),
+            skew_240: self.volatility.skewness(),
+            kurtosis_240: self.volatility.kurtosis(),
+            vwap_z: self.vwap.value().and_then(|vwap| vwap_z(mid_close, vwap, sigma)),
+            vwap_band_1sd,
+            vwap_band_2sd,
+            rvol: self.rvol.value(),
             bin_width: self.current_bin_width,
             va,
+            profile_entropy,
+            profile_entropy_norm,
             order_flow,
+            of_streak,
+            max_of_streak: self.of_streak.max_abs(),
             qimb_close,
             qimb_ema,
+            qimb_predictive_corr,
+            flow_quote_divergence,
+            flow_quote_divergence_avg_abs,
+            ofi_1m: self.ofi_tracker.for_minute(ts_min),
+            max_mid_jump_1m: mid_jump / self.tick_size,
+            max_mid_jump_sigma_1m: (jump_sigma_scale > 0.0).then(|| mid_jump / jump_sigma_scale),
             spread_avg_60m: self.avg_spread(),
+            spread_regime: self.spread_regime(),
+            eff_spread_avg_1m: self.eff_spread.avg_for_minute(ts_min).unwrap_or(0.0),
+            est_roundtrip_cost: self.roundtrip_cost(mid_close, bar.ask_px_close - bar.bid_px_close),
+            va_migration: migration,
+            auction_state: AuctionState::Transition,
+            vah_edge_flow,
+            val_edge_flow,
+            poc_tests,
+            vah_tests,
+            val_tests,
+            recent_breakout_success_rate,
+        };
+        features.auction_state = self.auction_state.update(&features);
+        features
+    }
+
+    /// Compute the VA from the current histogram without touching state.
+    ///
+    /// With `include_partial_minute = false` only completed minutes are used,
+    /// so a mid-minute call is not skewed by the minute still accumulating.
+    /// `finalize_minute` flushes the minute first, so its VA always includes it.
+    pub fn value_area_snapshot(&self, include_partial_minute: bool) -> ValueArea {
+        let agg_hist = if include_partial_minute {
+            self.histogram.aggregate_with_current(self.current_bin_width)
+        } else {
+            self.histogram.aggregate_to(self.current_bin_width)
+        };
+        self.value_area_from(agg_hist)
+    }
+
+    /// VA of an aggregated histogram, restricted to the VA price range.
+    fn value_area_from(&self, agg_hist: BTreeMap<OrderedFloat<f64>, f64>) -> ValueArea {
+        let agg_hist = match self.va_price_range() {
+            Some((lo, hi)) => agg_hist.into_iter().filter(|(p, _)| p.0 >= lo && p.0 <= hi).collect(),
+            None => agg_hist,
+        };
+        let va = self.va_computer.compute(&agg_hist, self.current_bin_width);
+        if self.round_va_to_tick && va.is_valid {
+            va.round_to_tick(self.tick_size)
+        } else {
+            va
+        }
+    }
+
+    /// VA and profile entropy for `ts_min`, recomputed only every
+    /// `va_recompute_interval` minutes.
+    ///
+    /// The entropy is taken from the same aggregated profile as the VA (over
+    /// all bins, not only the VA price range).
+    fn cached_value_area(&mut self, ts_min: TimestampMs) -> (ValueArea, ProfileEntropy) {
+        let interval_ms = self.va_recompute_interval.max(1) as i64 * 60_000;
+        match &self.va_cache {
+            Some((cached_min, va, entropy))
+                if ts_min >= *cached_min && ts_min - cached_min < interval_ms =>
+            {
+                (va.clone(), *entropy)
+            }
+            _ => {
+                let (va, entropy) = match self.incremental_value_area() {
+                    Some(result) => result,
+                    None => {
+                        let agg_hist = self.histogram.aggregate_to(self.current_bin_width);
+                        let entropy = entropy_of(&agg_hist);
+                        (self.value_area_from(agg_hist), entropy)
+                    }
+                };
+                self.va_cache = Some((ts_min, va.clone(), entropy));
+                (va, entropy)
+            }
         }
     }
 
+    /// VA and profile entropy over completed minutes from the incremental VA,
+    /// if enabled.
+    ///
+    /// Applies the histogram changes since the last call; a bin width change
+    /// reloads the aggregated histogram. Disabled with a bounded
+    /// `va_price_window`, which moves with the mid.
+    fn incremental_value_area(&mut self) -> Option<(ValueArea, ProfileEntropy)> {
+        let inc = self.incremental_va.as_mut()?;
+        let deltas = self.histogram.take_deltas();
+        if inc.bin_width() != self.current_bin_width {
+            inc.reset(self.histogram.aggregate_to(self.current_bin_width), self.current_bin_width);
+        } else {
+            for (key, volume) in deltas {
+                inc.apply(key.0, volume);
+            }
+        }
+
+        let va = inc.value_area();
+        let entropy = entropy_of(inc.histogram());
+        let va = if self.round_va_to_tick && va.is_valid {
+            va.round_to_tick(self.tick_size)
+        } else {
+            va
+        };
+        Some((va, entropy))
+    }
+
+    /// Price range of bins counted towards the VA (None = unbounded).
+    fn va_price_range(&self) -> Option<(f64, f64)> {
+        let mid = self.last_mid?;
+        let half_width = match self.va_price_window {
+            VaPriceWindow::Unbounded => return None,
+            VaPriceWindow::Sigma { k } => k * mid * self.volatility.volatility()?,
+            VaPriceWindow::Bins { bins } => bins as f64 * self.current_bin_width,
+        };
+        Some((mid - half_width, mid + half_width))
+    }
+
     /// Check if the engine has enough warmup data.
     pub fn is_ready(&self) -> bool {
-        self.volatility.is_ready() && self.histogram.is_ready()
+        self.is_ready_with(ReadinessRequirements::default())
+    }
+
+    /// Check the given subset of components for warmup.
+    pub fn is_ready_with(&self, requirements: ReadinessRequirements) -> bool {
+        (!requirements.volatility || self.volatility_ready())
+            && (!requirements.histogram || self.histogram_ready())
+            && self.order_flow_minutes() >= requirements.order_flow_minutes
+            && (!requirements.qimb || self.qimb_ready())
+    }
+
+    /// Whether the volatility window is full.
+    pub fn volatility_ready(&self) -> bool {
+        self.volatility.is_ready()
+    }
+
+    /// Whether the histogram window is full.
+    pub fn histogram_ready(&self) -> bool {
+        self.histogram.is_ready()
+    }
+
+    /// Minutes with order flow in the rolling window.
+    pub fn order_flow_minutes(&self) -> usize {
+        self.order_flow.minute_count()
+    }
+
+    /// Whether the imbalance tracker has seen a usable quote.
+    pub fn qimb_ready(&self) -> bool {
+        self.qimb_tracker.latest().is_some()
     }
 
     /// Get the current rolling window size.
@@ -211,27 +836,109 @@ impl FeatureEngine {
         self.rolling_window
     }
 
+    /// Get the instrument tick size.
+    pub fn tick_size(&self) -> f64 {
+        self.tick_size
+    }
+
+    /// Number of quotes skipped by the imbalance tracker for being too thin.
+    pub fn rejected_quote_count(&self) -> u64 {
+        self.qimb_tracker.rejected_quotes()
+    }
+
     /// Get the current bin width.
     pub fn current_bin_width(&self) -> f64 {
         self.current_bin_width
     }
 
-    /// Clear all state.
-    pub fn clear(&mut self) {
-        self.volatility.clear();
-        self.histogram.clear();
+    /// Get the per-minute order flow series currently held.
+    pub fn order_flow_series(&self) -> OrderFlowSeries {
+        self.order_flow.series()
+    }
+
+    /// Clear only the order-flow and quote-imbalance state.
+    ///
+    /// These are timestamp-sensitive, so after a feed reconnection they can be
+    /// reset without losing the volatility and histogram warmup.
+    pub fn clear_order_flow(&mut self) {
         self.order_flow.clear();
+        self.of_streak.clear();
+        self.qimb_corr.clear();
+        self.flow_quote_divergence.clear();
         self.qimb_tracker.clear();
+        self.ofi_tracker.clear();
+        self.mid_jumps.clear();
+        self.eff_spread.clear();
+        self.edge_flow.clear();
+    }
+
+    /// Clear only the rolling volume histogram.
+    pub fn clear_histogram(&mut self) {
+        self.histogram.clear();
+        if let Some(inc) = &mut self.incremental_va {
+            inc.clear();
+        }
+        self.last_va = None;
+        self.prior_va = None;
+        self.va_cache = None;
+        self.edge_flow.clear_edges();
+        self.poc_tests.clear();
+        self.vah_tests.clear();
+        self.val_tests.clear();
+        self.breakouts.clear();
+    }
+
+    /// Seed the volatility with the last known mid (e.g. on a live restart),
+    /// so the first bar afterwards contributes a return.
+    pub fn seed_prev_mid(&mut self, mid: f64) {
+        self.volatility.seed_prev_price(mid);
+    }
+
+    /// Clear only the rolling volatility.
+    pub fn clear_volatility(&mut self) {
+        self.volatility.clear();
+    }
+
+    /// Clear all state.
+    pub fn clear(&mut self) {
+        self.clear_volatility();
+        self.vwap.clear();
+        self.vwap_band_1sd.clear();
+        self.vwap_band_2sd.clear();
+        self.rvol.clear();
+        self.clear_histogram();
+        self.clear_order_flow();
         self.spreads.clear();
         self.current_bin_width = self.tick_size;
         self.last_rebucket_min = None;
+        self.last_bar_min = None;
+        self.recent_mids.clear();
+        self.last_mid = None;
+        self.auction_state.clear();
+        self.capped_minutes.clear();
     }
 }
 
+/// Profile entropy in nats and normalized to `[0, 1]`.
+type ProfileEntropy = (Option<f64>, Option<f64>);
+
+/// Both entropies of an aggregated profile.
+fn entropy_of(histogram: &BTreeMap<OrderedFloat<f64>, f64>) -> ProfileEntropy {
+    (profile_entropy(histogram), normalized_profile_entropy(histogram))
+}
+
+/// Linearly interpolated quantile of sorted values.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lo = pos.floor() as usize;
+    let hi = pos.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use auction_core::{Trade, TradeSide};
+    use auction_core::{SizeUnit, Trade, TradeSide};
 
     fn default_config() -> Config {
         let mut config = Config::default();
@@ -279,8 +986,9 @@ mod tests {
         let config = default_config();
         let mut engine = FeatureEngine::new(&config);
 
-        // Add 5 minutes of data
-        for i in 0..5 {
+        // Add 6 minutes of data (the first bar only seeds the previous price,
+        // so a 5-return volatility window needs 6 bars)
+        for i in 0..6 {
             let ts_min = (i + 1) * 60_000;
 
             // Add trades
@@ -296,6 +1004,127 @@ mod tests {
         assert!(engine.is_ready());
     }
 
+    fn warm_up(engine: &mut FeatureEngine, minutes: i64) {
+        for i in 0..minutes {
+            let ts_min = (i + 1) * 60_000;
+
+            engine.add_quote(&Quote {
+                ts_ms: ts_min + 500,
+                bid_px: 49999.5,
+                bid_sz: 2.0,
+                ask_px: 50000.5,
+                ask_sz: 1.0,
+            });
+
+            for j in 0..10 {
+                let price = 50000.0 + j as f64;
+                engine.add_trade(&make_trade(ts_min + j * 1000, price, 1.0, TradeSide::Buy));
+            }
+
+            engine.add_bar(&make_bar(ts_min, 50000.0 + (i % 2) as f64 * 10.0));
+        }
+    }
+
+    #[test]
+    fn test_no_trade_minute_has_no_volume() {
+        let config = default_config();
+        let mut engine = FeatureEngine::new(&config);
+
+        engine.add_trade(&make_trade(60_000 + 1000, 50000.0, 1.0, TradeSide::Buy));
+        engine.add_trade(&make_trade(60_000 + 2000, 50000.0, 1.0, TradeSide::Sell));
+        let balanced = engine.finalize_minute(60_000, &make_bar(60_000, 50000.0)).unwrap();
+        assert!(balanced.order_flow.has_volume);
+        assert_eq!(balanced.order_flow.of_norm_1m, 0.0);
+
+        let empty = engine.finalize_minute(120_000, &make_bar(120_000, 50000.0)).unwrap();
+        assert!(!empty.order_flow.has_volume);
+        assert_eq!(empty.order_flow.of_norm_1m, 0.0);
+    }
+
+    #[test]
+    fn test_component_readiness() {
+        let mut engine = FeatureEngine::new(&default_config());
+        let flow_only = ReadinessRequirements {
+            volatility: false,
+            histogram: false,
+            order_flow_minutes: 3,
+            qimb: true,
+        };
+        assert!(!engine.volatility_ready());
+        assert!(!engine.histogram_ready());
+        assert!(!engine.qimb_ready());
+
+        // Trades only: histogram and order flow fill, volatility does not
+        for minute in 1..=6 {
+            engine.add_trade(&make_trade(minute * 60_000, 50000.0 + minute as f64, 1.0, TradeSide::Buy));
+        }
+        assert!(engine.histogram_ready());
+        assert!(!engine.volatility_ready());
+        assert!(!engine.is_ready());
+        assert_eq!(engine.order_flow_minutes(), 5);
+        assert!(!engine.is_ready_with(flow_only));
+
+        engine.add_quote(&Quote {
+            ts_ms: 6 * 60_000 + 500,
+            bid_px: 50005.5,
+            bid_sz: 2.0,
+            ask_px: 50006.5,
+            ask_sz: 1.0,
+        });
+        assert!(engine.qimb_ready());
+        assert!(engine.is_ready_with(flow_only));
+
+        // Bars fill volatility
+        for minute in 7..=12 {
+            engine.add_bar(&make_bar(minute * 60_000, 50000.0 + (minute % 2) as f64 * 10.0));
+        }
+        assert!(engine.volatility_ready());
+        assert!(engine.is_ready());
+
+        engine.clear_histogram();
+        assert!(!engine.histogram_ready());
+        assert!(engine.volatility_ready());
+        assert!(engine.is_ready_with(ReadinessRequirements {
+            histogram: false,
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn test_selective_clears() {
+        let config = default_config();
+
+        // Order flow only
+        let mut engine = FeatureEngine::new(&config);
+        warm_up(&mut engine, 6);
+        assert!(engine.is_ready());
+        engine.clear_order_flow();
+        assert_eq!(engine.order_flow.minute_count(), 0);
+        assert!(engine.qimb_tracker.latest().is_none());
+        assert!(engine.is_ready());
+        assert_eq!(engine.volatility.count(), 5);
+        assert_eq!(engine.histogram.minute_count(), 5);
+
+        // Histogram only
+        let mut engine = FeatureEngine::new(&config);
+        warm_up(&mut engine, 6);
+        engine.clear_histogram();
+        assert_eq!(engine.histogram.minute_count(), 0);
+        assert!(!engine.is_ready());
+        assert!(engine.volatility.is_ready());
+        assert_eq!(engine.order_flow.minute_count(), 5);
+
+        // Volatility only
+        let mut engine = FeatureEngine::new(&config);
+        warm_up(&mut engine, 6);
+        engine.clear_volatility();
+        assert_eq!(engine.volatility.count(), 0);
+        assert!(engine.vwap.value().is_some());
+        assert!(!engine.is_ready());
+        assert!(engine.histogram.is_ready());
+        assert_eq!(engine.order_flow.minute_count(), 5);
+    }
+
     #[test]
     fn test_compute_features() {
         let config = default_config();
@@ -313,11 +1142,699 @@ mod tests {
             engine.add_bar(&make_bar(ts_min, 50000.0 + i as f64));
         }
 
-        let ts_min = 5 * 60_000;
+        let ts_min = 6 * 60_000;
         let bar = make_bar(ts_min, 50004.0);
-        let features = engine.compute_features(ts_min, &bar);
+        let features = engine.finalize_minute(ts_min, &bar).unwrap();
 
         assert!(features.va.is_valid || !engine.is_ready());
         assert!(features.sigma_240 >= 0.0);
     }
+
+    #[test]
+    fn test_finalize_minute_ordering() {
+        let config = default_config();
+        let mut finalized = FeatureEngine::new(&config);
+        let mut two_call = FeatureEngine::new(&config);
+        warm_up(&mut finalized, 6);
+        warm_up(&mut two_call, 6);
+
+        let ts_min = 7 * 60_000;
+        let bar = make_bar(ts_min, 50020.0);
+        for engine in [&mut finalized, &mut two_call] {
+            engine.add_trade(&make_trade(ts_min + 1000, 50020.0, 1.0, TradeSide::Buy));
+        }
+
+        // Features for minute T include bar T: same as ingest-then-compute
+        let features = finalized.finalize_minute(ts_min, &bar).unwrap();
+        two_call.add_bar(&bar);
+        #[allow(deprecated)]
+        let expected = two_call.compute_features(ts_min, &bar);
+
+        assert!((features.sigma_240 - expected.sigma_240).abs() < 1e-15);
+        assert!((features.va.poc - expected.va.poc).abs() < 1e-10);
+        assert!((features.spread_avg_60m - expected.spread_avg_60m).abs() < 1e-15);
+        assert_eq!(finalized.volatility.count(), two_call.volatility.count());
+
+        // The same minute (or an earlier one) can't be ingested twice
+        assert!(finalized.finalize_minute(ts_min, &bar).is_err());
+        let earlier = make_bar(ts_min - 60_000, 50000.0);
+        assert!(finalized.finalize_minute(ts_min - 60_000, &earlier).is_err());
+
+        // Bar must match the minute being finalized
+        let next = ts_min + 60_000;
+        assert!(finalized.finalize_minute(next, &bar).is_err());
+        assert!(finalized.finalize_minute(next, &make_bar(next, 50020.0)).is_ok());
+    }
+
+    #[test]
+    fn test_quote_denominated_sizes() {
+        let mut config = default_config();
+        config.instrument.size_unit = SizeUnit::Quote;
+        let mut quote_sized = FeatureEngine::new(&config);
+        let mut base_sized = FeatureEngine::new(&default_config());
+
+        // Quote notional of 0.1 BTC at each price, alternating sides
+        for i in 0..8 {
+            let price = 50000.0 + i as f64;
+            let side = if i % 2 == 0 { TradeSide::Buy } else { TradeSide::Sell };
+            quote_sized.add_trade(&make_trade(60_500 + i, price, 0.1 * price, side));
+            base_sized.add_trade(&make_trade(60_500 + i, price, 0.1, side));
+        }
+
+        let quote_va = quote_sized.value_area_snapshot(true);
+        let base_va = base_sized.value_area_snapshot(true);
+        assert!((quote_va.total_volume - 0.8).abs() < 1e-9, "{:?}", quote_va);
+        assert!((quote_va.total_volume - base_va.total_volume).abs() < 1e-9);
+        assert_eq!(quote_va.poc, base_va.poc);
+
+        let bar = make_bar(60_000, 50004.0);
+        let quote_features = quote_sized.process_bar(&bar).unwrap();
+        let base_features = base_sized.process_bar(&bar).unwrap();
+        assert!((quote_features.order_flow.of_1m - base_features.order_flow.of_1m).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_subscribe_receives_finalized_features() {
+        let config = default_config();
+        let mut engine = FeatureEngine::new(&config);
+        let rx = engine.subscribe();
+
+        // Bars fed with add_bar (warmup) are not published
+        warm_up(&mut engine, 6);
+        assert!(rx.try_recv().is_err());
+
+        let mut expected = Vec::new();
+        for i in 7..10 {
+            let bar = make_bar(i * 60_000, 50000.0 + i as f64);
+            expected.push(engine.process_bar(&bar).unwrap());
+        }
+        // A rejected minute publishes nothing
+        assert!(engine.process_bar(&make_bar(9 * 60_000, 50000.0)).is_err());
+
+        let received: Vec<Features1m> = rx.try_iter().collect();
+        assert_eq!(received.len(), 3);
+        for (got, want) in received.iter().zip(&expected) {
+            assert_eq!(got.ts_min, want.ts_min);
+            assert_eq!(serde_json::to_value(got).unwrap(), serde_json::to_value(want).unwrap());
+        }
+
+        // Dropped receivers are pruned on the next publish
+        drop(rx);
+        engine.process_bar(&make_bar(10 * 60_000, 50010.0)).unwrap();
+        assert_eq!(engine.subscriber_count(), 0);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_process_bar_matches_two_step() {
+        let config = default_config();
+        let mut single = FeatureEngine::new(&config);
+        let mut two_step = FeatureEngine::new(&config);
+        warm_up(&mut single, 6);
+        warm_up(&mut two_step, 6);
+
+        for i in 7..10 {
+            let ts_min = i * 60_000;
+            let trade = make_trade(ts_min + 1000, 50000.0 + i as f64, 2.0, TradeSide::Sell);
+            single.add_trade(&trade);
+            two_step.add_trade(&trade);
+
+            let bar = make_bar(ts_min, 50000.0 + i as f64);
+            let processed = single.process_bar(&bar).unwrap();
+            two_step.add_bar(&bar);
+            let manual = two_step.compute_features(ts_min, &bar);
+            assert_eq!(
+                serde_json::to_value(&processed).unwrap(),
+                serde_json::to_value(&manual).unwrap()
+            );
+        }
+
+        // The same bar can't be processed twice
+        assert!(single.process_bar(&make_bar(9 * 60_000, 50000.0)).is_err());
+    }
+
+    #[test]
+    fn test_try_new_rejects_bad_qimb_rate() {
+        let mut config = default_config();
+        config.order_flow.qimb_updates_per_second = 0.0;
+        assert!(FeatureEngine::try_new(&config).is_err());
+
+        let mut config = default_config();
+        config.value_area.va_fraction = 1.5;
+        assert!(FeatureEngine::try_new(&config).is_err());
+    }
+
+    fn bin_width_config(strategy: BinWidthStrategy) -> Config {
+        let mut config = default_config();
+        config.value_area.bin_width_strategy = strategy;
+        config.value_area.base_bin_ticks = 5;
+        config.value_area.rebucket_change_pct = 0.0; // rebucket every bar
+        config
+    }
+
+    #[test]
+    fn test_bin_width_volatility_scaled() {
+        let mut engine = FeatureEngine::new(&bin_width_config(BinWidthStrategy::VolatilityScaled));
+
+        // No volatility yet: floor at one tick
+        engine.add_bar(&make_bar(60_000, 50000.0));
+        assert!((engine.current_bin_width() - 0.1).abs() < 1e-10);
+
+        for (i, close) in [50002.0, 49998.0, 50002.0, 49998.0, 50002.0].iter().enumerate() {
+            engine.add_bar(&make_bar((i as i64 + 2) * 60_000, *close));
+        }
+        let sigma = engine.volatility.volatility().unwrap();
+        let expected = (0.25 * 50002.0 * sigma / 0.1).round() * 0.1;
+        assert!(expected > 0.1 && expected < 20.0);
+        assert!((engine.current_bin_width() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bin_width_fixed() {
+        let mut engine = FeatureEngine::new(&bin_width_config(BinWidthStrategy::Fixed));
+
+        for (i, close) in [50000.0, 51000.0, 49000.0].iter().enumerate() {
+            engine.add_bar(&make_bar((i as i64 + 1) * 60_000, *close));
+            assert!((engine.current_bin_width() - 0.5).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_bin_width_percentile() {
+        let strategy = BinWidthStrategy::Percentile {
+            lookback_minutes: 5,
+            target_bins: 10,
+            coverage: 1.0,
+        };
+        let mut engine = FeatureEngine::new(&bin_width_config(strategy));
+
+        // Mids 50000, 50010, ..., 50060; only the last 5 (50020..50060) count
+        for i in 0..7 {
+            engine.add_bar(&make_bar((i + 1) * 60_000, 50000.0 + i as f64 * 10.0));
+        }
+        // Range 40 / 10 bins
+        assert!((engine.current_bin_width() - 4.0).abs() < 1e-10);
+
+        // Central 50% of the same window: 50030..50050 -> 20 / 10 bins
+        let strategy = BinWidthStrategy::Percentile {
+            lookback_minutes: 5,
+            target_bins: 10,
+            coverage: 0.5,
+        };
+        let mut engine = FeatureEngine::new(&bin_width_config(strategy));
+        for i in 0..7 {
+            engine.add_bar(&make_bar((i + 1) * 60_000, 50000.0 + i as f64 * 10.0));
+        }
+        assert!((engine.current_bin_width() - 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_va_migration_feature() {
+        let config = default_config();
+        let mut engine = FeatureEngine::new(&config);
+        warm_up(&mut engine, 6);
+
+        let ts_min = 7 * 60_000;
+        let first = engine.finalize_minute(ts_min, &make_bar(ts_min, 50000.0)).unwrap();
+        assert!(first.va.is_valid);
+        assert!(first.va_migration.is_none());
+
+        // Next minute compares against the cached VA
+        let next_min = ts_min + 60_000;
+        let next = engine.finalize_minute(next_min, &make_bar(next_min, 50000.0)).unwrap();
+        let migration = next.va_migration.expect("migration vs prior minute");
+        assert_eq!(migration.direction, auction_core::VaMigrationDirection::Overlap);
+        assert!((migration.overlap_frac - 1.0).abs() < 1e-10);
+
+        engine.clear_histogram();
+        let last_min = next_min + 60_000;
+        let cleared = engine.finalize_minute(last_min, &make_bar(last_min, 50000.0)).unwrap();
+        assert!(cleared.va_migration.is_none());
+    }
+
+    #[test]
+    fn test_edge_flow_feature() {
+        let config = default_config();
+        let mut engine = FeatureEngine::new(&config);
+        warm_up(&mut engine, 6);
+
+        let ts_min = 7 * 60_000;
+        let first = engine.finalize_minute(ts_min, &make_bar(ts_min, 50000.0)).unwrap();
+        assert!(first.va.is_valid);
+        let (vah, val) = (first.va.vah, first.va.val);
+        assert!(vah - val > 1.0);
+
+        let next_min = ts_min + 60_000;
+        engine.add_trade(&make_trade(next_min + 1000, vah, 2.0, TradeSide::Buy));
+        engine.add_trade(&make_trade(next_min + 2000, val + 0.1, 1.5, TradeSide::Sell));
+        engine.add_trade(&make_trade(next_min + 3000, (vah + val) / 2.0, 5.0, TradeSide::Buy));
+        engine.add_trade(&make_trade(next_min + 4000, vah + 1.0, 5.0, TradeSide::Sell));
+
+        let next = engine.finalize_minute(next_min, &make_bar(next_min, 50000.0)).unwrap();
+        assert!((next.vah_edge_flow.buy_volume - 2.0).abs() < 1e-10);
+        assert_eq!(next.vah_edge_flow.sell_volume, 0.0);
+        assert!((next.val_edge_flow.net() + 1.5).abs() < 1e-10);
+
+        // Edge flow is reset with the rest of the order flow state
+        engine.clear_order_flow();
+        let last_min = next_min + 60_000;
+        let cleared = engine.finalize_minute(last_min, &make_bar(last_min, 50000.0)).unwrap();
+        assert_eq!(cleared.vah_edge_flow, Default::default());
+    }
+
+    #[test]
+    fn test_level_tests_feature() {
+        let mut config = default_config();
+        // The POC drifts as warmup minutes roll out; only reset on a real move
+        config.value_area.level_test_reset_ticks = 1000;
+        let mut engine = FeatureEngine::new(&config);
+        warm_up(&mut engine, 6);
+
+        // Keep trading around 50000 so the VA stays in place
+        let finalize = |engine: &mut FeatureEngine, ts_min: i64, close: f64| {
+            for j in 0..10 {
+                engine.add_trade(&make_trade(ts_min + j * 1000, 50000.0 + j as f64, 1.0, TradeSide::Buy));
+            }
+            engine.finalize_minute(ts_min, &make_bar(ts_min, close)).unwrap()
+        };
+
+        // make_bar spans close +/- 10, straddling the POC
+        let first = finalize(&mut engine, 7 * 60_000, 50000.0);
+        assert!(first.va.is_valid);
+        assert_eq!(first.poc_tests, 1);
+
+        let next = finalize(&mut engine, 8 * 60_000, 50000.0);
+        assert_eq!(next.poc_tests, 2);
+
+        // A bar away from value does not test it
+        let away = finalize(&mut engine, 9 * 60_000, 50020.0);
+        assert!(away.va.is_valid);
+        assert_eq!(away.poc_tests, 2);
+        assert_eq!(away.vah_tests, next.vah_tests);
+
+        engine.clear_histogram();
+        let cleared = finalize(&mut engine, 10 * 60_000, 50000.0);
+        // Only the current minute is counted after the clear
+        assert_eq!(cleared.poc_tests, 1);
+    }
+
+    #[test]
+    fn test_sigma_annualized_feature() {
+        let mut engine = FeatureEngine::new(&default_config());
+        warm_up(&mut engine, 6);
+        let raw_only = engine.finalize_minute(7 * 60_000, &make_bar(7 * 60_000, 50000.0)).unwrap();
+        assert!(raw_only.sigma_annualized.is_none());
+
+        let mut config = default_config();
+        config.instrument.sigma_periods_per_year = Some(crate::volatility::MINUTES_PER_YEAR);
+        let mut engine = FeatureEngine::new(&config);
+        warm_up(&mut engine, 6);
+        let features = engine.finalize_minute(7 * 60_000, &make_bar(7 * 60_000, 50000.0)).unwrap();
+        let expected = features.sigma_240 * 525_600f64.sqrt();
+        assert!((features.sigma_annualized.unwrap() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_eff_spread_feature() {
+        let config = default_config();
+        let mut engine = FeatureEngine::new(&config);
+
+        // make_trade quotes +/- 0.5 around the trade price, so the
+        // trade prints at mid and has zero effective spread
+        let ts_min = 60_000;
+        engine.add_trade(&make_trade(ts_min + 1000, 50000.0, 1.0, TradeSide::Buy));
+        let mut through = make_trade(ts_min + 2000, 50001.0, 1.0, TradeSide::Buy);
+        through.quote_bid_px = 49999.5;
+        through.quote_ask_px = 50000.5; // 0.5 through the ask, eff spread 2.0
+        engine.add_trade(&through);
+
+        let features = engine.finalize_minute(ts_min, &make_bar(ts_min, 50000.0)).unwrap();
+        assert!((features.eff_spread_avg_1m - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_vwap_z_feature() {
+        let mut engine = FeatureEngine::new(&default_config());
+        warm_up(&mut engine, 6);
+        let ts_min = 7 * 60_000;
+        let features = engine.finalize_minute(ts_min, &make_bar(ts_min, 50000.0)).unwrap();
+        assert!(features.sigma_240 > 0.0);
+
+        let z = features.vwap_z.expect("vwap and sigma available");
+        let vwap = engine.vwap.value().unwrap();
+        let expected = (features.mid_close - vwap) / (features.sigma_240 * features.mid_close);
+        assert!((z - expected).abs() < 1e-9);
+
+        engine.clear_volatility();
+        let next = ts_min + 60_000;
+        let cleared = engine.finalize_minute(next, &make_bar(next, 50000.0)).unwrap();
+        assert!(cleared.vwap_z.is_none());
+    }
+
+    #[test]
+    fn test_vwap_band_touches() {
+        let mut config = default_config();
+        config.instrument.rolling_window_minutes = 4;
+        let mut engine = FeatureEngine::new(&config);
+
+        // Bar VWAPs alternate 49990/50010 (VWAP 50000, sd 10) while the
+        // bars themselves trade in a narrow range around 50000
+        let bar = |minute: i64, high: f64| Bar1m {
+            high,
+            low: 49999.5,
+            vwap: Some(if minute % 2 == 0 { 49990.0 } else { 50010.0 }),
+            ..make_bar(minute * 60_000, 50000.0)
+        };
+        for minute in 1..=4 {
+            let features = engine.process_bar(&bar(minute, 50000.5)).unwrap();
+            assert_eq!(features.vwap_band_1sd.upper_touches, 0);
+        }
+
+        // Pierces +1 sd only
+        let features = engine.process_bar(&bar(5, 50012.0)).unwrap();
+        assert!(features.vwap_band_1sd.touched_upper_band);
+        assert!(!features.vwap_band_1sd.touched_lower_band);
+        assert_eq!(features.vwap_band_1sd.upper_touches, 1);
+        assert!(!features.vwap_band_2sd.touched_upper_band);
+
+        // Pierces +2 sd as well
+        let features = engine.process_bar(&bar(6, 50025.0)).unwrap();
+        assert!(features.vwap_band_2sd.touched_upper_band);
+        assert_eq!(features.vwap_band_2sd.upper_touches, 1);
+        assert_eq!(features.vwap_band_1sd.upper_touches, 2);
+
+        // Quiet bar: flag clears, count persists within the window
+        let features = engine.process_bar(&bar(7, 50000.5)).unwrap();
+        assert!(!features.vwap_band_1sd.touched_upper_band);
+        assert_eq!(features.vwap_band_1sd.upper_touches, 2);
+    }
+
+    #[test]
+    fn test_profile_entropy() {
+        let run = |volume_at: fn(i64) -> f64| {
+            let mut engine = FeatureEngine::new(&default_config());
+            let ts_min = 60_000;
+            for j in 0..10 {
+                let price = 50000.0 + j as f64 * 10.0;
+                engine.add_trade(&make_trade(ts_min + j * 1000, price, volume_at(j), TradeSide::Buy));
+            }
+            engine.finalize_minute(ts_min, &make_bar(ts_min, 50040.0)).unwrap()
+        };
+
+        // Nearly all volume in one bin vs the same bins traded evenly
+        let spiky = run(|j| if j == 4 { 100.0 } else { 0.1 });
+        let flat = run(|_| 1.0);
+
+        assert!(spiky.profile_entropy_norm.unwrap() < 0.1);
+        assert!((flat.profile_entropy_norm.unwrap() - 1.0).abs() < 1e-9);
+        let spiky = spiky.profile_entropy.unwrap();
+        let flat = flat.profile_entropy.unwrap();
+        assert!(spiky < 0.1);
+        assert!((flat - 10f64.ln()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_value_area_excludes_partial_minute() {
+        let mut engine = FeatureEngine::new(&default_config());
+        warm_up(&mut engine, 6);
+        let ts_min = 7 * 60_000;
+        engine.finalize_minute(ts_min, &make_bar(ts_min, 50000.0)).unwrap();
+        let before = engine.value_area_snapshot(false);
+        assert!(before.is_valid);
+
+        // Heavy partial-minute volume far from the POC
+        let live_min = ts_min + 60_000;
+        for i in 0..5 {
+            engine.add_trade(&make_trade(live_min + i * 1000, 50040.0, 50.0, TradeSide::Buy));
+        }
+
+        let excluded = engine.value_area_snapshot(false);
+        assert_eq!(excluded.poc, before.poc);
+        assert_eq!(excluded.vah, before.vah);
+        assert_eq!(excluded.val, before.val);
+        assert_eq!(excluded.total_volume, before.total_volume);
+
+        let included = engine.value_area_snapshot(true);
+        assert!(included.total_volume > before.total_volume);
+        assert_ne!(included.poc, before.poc);
+    }
+
+    #[test]
+    fn test_ofi_feature() {
+        let mut engine = FeatureEngine::new(&default_config());
+        let ts_min = 60_000;
+        for (offset, bid_sz) in [(1000, 2.0), (2000, 5.0), (3000, 4.0)] {
+            engine.add_quote(&Quote {
+                ts_ms: ts_min + offset,
+                bid_px: 49999.5,
+                bid_sz,
+                ask_px: 50000.5,
+                ask_sz: 1.0,
+            });
+        }
+
+        let features = engine.finalize_minute(ts_min, &make_bar(ts_min, 50000.0)).unwrap();
+        assert!((features.ofi_1m - 2.0).abs() < 1e-10); // +3 then -1
+    }
+
+    #[test]
+    fn test_rvol_feature() {
+        let mut engine = FeatureEngine::new(&default_config());
+        warm_up(&mut engine, 6); // 100 per bar
+
+        let loud_min = 7 * 60_000;
+        let loud_bar = Bar1m { volume: 500.0, ..make_bar(loud_min, 50000.0) };
+        let loud = engine.finalize_minute(loud_min, &loud_bar).unwrap();
+        assert!(loud.rvol.unwrap() > 3.0);
+
+        let quiet_min = 8 * 60_000;
+        let quiet_bar = Bar1m { volume: 20.0, ..make_bar(quiet_min, 50000.0) };
+        let quiet = engine.finalize_minute(quiet_min, &quiet_bar).unwrap();
+        assert!(quiet.rvol.unwrap() < 1.0);
+    }
+
+    #[test]
+    fn test_mid_jump_feature() {
+        let mut engine = FeatureEngine::new(&default_config());
+        warm_up(&mut engine, 6);
+
+        // Warmup quotes sit at mid 50000; one flash move of 25 points
+        let ts_min = 7 * 60_000;
+        for (offset, mid) in [(1000, 50000.0), (2000, 50025.0), (3000, 50024.0)] {
+            engine.add_quote(&Quote {
+                ts_ms: ts_min + offset,
+                bid_px: mid - 0.5,
+                bid_sz: 1.0,
+                ask_px: mid + 0.5,
+                ask_sz: 1.0,
+            });
+        }
+
+        let features = engine.finalize_minute(ts_min, &make_bar(ts_min, 50000.0)).unwrap();
+        let tick_size = default_config().instrument.tick_size;
+        assert!((features.max_mid_jump_1m - 25.0 / tick_size).abs() < 1e-6);
+        let sigma_units = features.max_mid_jump_sigma_1m.expect("sigma is warmed up");
+        assert!((sigma_units - 25.0 / (features.mid_close * features.sigma_240)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_spread_regime() {
+        let mut engine = FeatureEngine::new(&default_config());
+        let bar_with_spread = |ts_min: i64, spread: f64| Bar1m {
+            bid_px_close: 50000.0 - spread / 2.0,
+            ask_px_close: 50000.0 + spread / 2.0,
+            ..make_bar(ts_min, 50000.0)
+        };
+
+        // History of spreads 1..=10 (20th percentile 3, 80th percentile 8)
+        let mut ts_min = 0;
+        for spread in 1..=10 {
+            ts_min += 60_000;
+            let features = engine.finalize_minute(ts_min, &bar_with_spread(ts_min, spread as f64)).unwrap();
+            if spread < 5 {
+                assert_eq!(features.spread_regime, SpreadRegime::Normal); // too little history
+            }
+        }
+
+        let mut regime_for = |spread: f64| {
+            ts_min += 60_000;
+            engine.finalize_minute(ts_min, &bar_with_spread(ts_min, spread)).unwrap().spread_regime
+        };
+        assert_eq!(regime_for(5.0), SpreadRegime::Normal);
+        assert_eq!(regime_for(20.0), SpreadRegime::Wide);
+        assert_eq!(regime_for(1.0), SpreadRegime::Tight);
+        assert_eq!(regime_for(3.0), SpreadRegime::Normal);
+    }
+
+    #[test]
+    fn test_va_price_window() {
+        let run = |window: VaPriceWindow| {
+            let mut config = bin_width_config(BinWidthStrategy::Fixed);
+            config.value_area.va_price_window = window;
+            let mut engine = FeatureEngine::new(&config);
+
+            // Heavier volume left behind at 49000, then trend up to 50000
+            let mut features = None;
+            for (i, (base, size)) in [(49000.0, 3.0), (49000.0, 3.0), (50000.0, 2.0), (50000.0, 2.0)].iter().enumerate() {
+                let ts_min = (i as i64 + 1) * 60_000;
+                for j in 0..=10 {
+                    engine.add_trade(&make_trade(ts_min + j * 1000, base + j as f64, *size, TradeSide::Buy));
+                }
+                features = Some(engine.finalize_minute(ts_min, &make_bar(ts_min, base + 5.0)).unwrap());
+            }
+            features.unwrap().va
+        };
+
+        let full = run(VaPriceWindow::Unbounded);
+        assert!(full.is_valid);
+        assert!(full.val < 49010.0 && full.vah >= 50000.0); // straddles the move
+
+        let clipped = run(VaPriceWindow::Bins { bins: 20 }); // +/- 10 around 50005
+        assert!(clipped.is_valid);
+        assert!(clipped.val >= 49995.0 && clipped.vah <= 50015.0);
+        assert!(clipped.vah - clipped.val < full.vah - full.val);
+    }
+
+    #[test]
+    fn test_va_recompute_interval() {
+        let mut config = bin_width_config(BinWidthStrategy::Fixed);
+        config.value_area.va_recompute_interval_minutes = 3;
+        config.value_area.rebucket_change_pct = 1.0;
+        config.value_area.rebucket_interval_minutes = 1000;
+        let mut engine = FeatureEngine::new(&config);
+
+        // Each minute trades 10 points higher, dragging the POC up
+        let poc_at = |engine: &mut FeatureEngine, minute: i64| {
+            let ts_min = minute * 60_000;
+            let base = 50000.0 + minute as f64 * 10.0;
+            for j in 0..5 {
+                engine.add_trade(&make_trade(ts_min + j * 1000, base + j as f64, 10.0, TradeSide::Buy));
+            }
+            engine.finalize_minute(ts_min, &make_bar(ts_min, base)).unwrap().va.poc
+        };
+
+        let first = poc_at(&mut engine, 1);
+        assert_eq!(poc_at(&mut engine, 2), first); // reused
+        assert_eq!(poc_at(&mut engine, 3), first);
+        let refreshed = poc_at(&mut engine, 4); // interval boundary
+        assert!(refreshed > first);
+        assert_eq!(poc_at(&mut engine, 5), refreshed);
+    }
+
+    #[test]
+    fn test_incremental_va_matches_full() {
+        let mut config = default_config();
+        config.value_area.rebucket_interval_minutes = 30;
+        config.value_area.poc_smoothing_bins = 2;
+        let mut full = FeatureEngine::new(&config);
+        config.value_area.incremental_va = true;
+        let mut incremental = FeatureEngine::new(&config);
+
+        let mut state = 7u64;
+        let mut next = move |n: u64| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) % n
+        };
+
+        // Random walk with bin width changes from volatility rebuckets
+        let mut mid = 50000.0;
+        for minute in 1..600 {
+            let ts_min = minute * 60_000;
+            mid += (next(21) as f64 - 10.0) * 0.5;
+            for j in 0..(1 + next(6)) as i64 {
+                let price = mid + (next(41) as f64 - 20.0) * 0.1;
+                let trade = make_trade(ts_min + j * 1000, price, 1.0 + next(4) as f64, TradeSide::Buy);
+                full.add_trade(&trade);
+                incremental.add_trade(&trade);
+            }
+
+            let bar = make_bar(ts_min, mid);
+            let expected = full.finalize_minute(ts_min, &bar).unwrap().va;
+            let actual = incremental.finalize_minute(ts_min, &bar).unwrap().va;
+            assert_eq!(actual.is_valid, expected.is_valid, "minute {}", minute);
+            assert_eq!(actual.bin_width, expected.bin_width, "minute {}", minute);
+            if expected.is_valid {
+                assert_eq!(actual.poc, expected.poc, "minute {}", minute);
+                assert!((actual.poc_smoothed - expected.poc_smoothed).abs() < 1e-6, "minute {}", minute);
+                assert_eq!(actual.val, expected.val, "minute {}", minute);
+                assert_eq!(actual.vah, expected.vah, "minute {}", minute);
+                assert_eq!(actual.bin_count, expected.bin_count, "minute {}", minute);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flow_quote_divergence() {
+        let mut engine = FeatureEngine::new(&default_config());
+        let minute = |engine: &mut FeatureEngine, ts_min: i64, bid_sz: f64, ask_sz: f64| {
+            engine.add_quote(&Quote { ts_ms: ts_min + 1000, bid_px: 49999.5, bid_sz, ask_px: 50000.5, ask_sz });
+            engine.add_trade(&make_trade(ts_min + 2000, 50000.5, 1.0, TradeSide::Buy));
+            engine.finalize_minute(ts_min, &make_bar(ts_min, 50000.0)).unwrap()
+        };
+
+        // Buying into a bid-heavy book: flow and quotes agree
+        let agree = minute(&mut engine, 60_000, 99.0, 1.0);
+        assert!((agree.order_flow.of_norm_1m - 1.0).abs() < 1e-10);
+        assert!(agree.flow_quote_divergence.abs() < 0.05);
+        assert!((agree.flow_quote_divergence_avg_abs - agree.flow_quote_divergence.abs()).abs() < 1e-10);
+
+        // Buying into an ask-heavy book: they oppose
+        let oppose = minute(&mut engine, 120_000, 1.0, 99.0);
+        assert!(oppose.flow_quote_divergence > 1.9);
+        let avg = (agree.flow_quote_divergence.abs() + oppose.flow_quote_divergence.abs()) / 2.0;
+        assert!((oppose.flow_quote_divergence_avg_abs - avg).abs() < 1e-10);
+
+        engine.clear_order_flow();
+        let fresh = minute(&mut engine, 180_000, 99.0, 1.0);
+        assert!((fresh.flow_quote_divergence_avg_abs - fresh.flow_quote_divergence.abs()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_trade_cap() {
+        let volume = |policy, trades: i64| {
+            let cap = TradeCap { max_trades: Some(5), policy, ..Default::default() };
+            let mut engine = FeatureEngine::new(&default_config());
+            engine.set_trade_cap(Some(cap));
+            for i in 0..trades {
+                engine.add_trade(&make_trade(60_000 + i, 50000.0, 1.0, TradeSide::Buy));
+            }
+            // The next minute is unaffected
+            engine.add_trade(&make_trade(120_000, 50000.0, 1.0, TradeSide::Buy));
+            let first = engine.finalize_minute(60_000, &make_bar(60_000, 50000.0)).unwrap();
+            let second = engine.finalize_minute(120_000, &make_bar(120_000, 50000.0)).unwrap();
+            let capped = engine.capped_trades();
+            (first.order_flow.total_volume, second.order_flow.total_volume, capped)
+        };
+
+        // A burst of 20 trades keeps the first 5
+        assert_eq!(volume(TradeCapPolicy::DropExcess, 20), (5.0, 1.0, 15));
+        // Excluding the minute drops the 5 already accepted as well
+        assert_eq!(volume(TradeCapPolicy::ExcludeMinute, 20), (0.0, 1.0, 20));
+        // Within the cap, held trades are ingested when the minute closes
+        assert_eq!(volume(TradeCapPolicy::ExcludeMinute, 5), (5.0, 1.0, 0));
+    }
+
+    #[test]
+    fn test_roundtrip_cost_feature() {
+        let cost = |half_spread: f64| {
+            let mut engine = FeatureEngine::new(&default_config());
+            let ts_min = 60_000;
+            // Buys lifting an ask `2 * half_spread` above the bid
+            let mut trade = make_trade(ts_min + 1000, 50000.0, 1.0, TradeSide::Buy);
+            trade.quote_bid_px = 50000.0 - 2.0 * half_spread;
+            trade.quote_ask_px = 50000.0;
+            engine.add_trade(&trade);
+            let features = engine.finalize_minute(ts_min, &make_bar(ts_min, 50000.0)).unwrap();
+            (features.eff_spread_avg_1m, features.est_roundtrip_cost)
+        };
+
+        let (tight_eff, tight) = cost(0.5);
+        let (wide_eff, wide) = cost(2.0);
+        assert!((tight_eff - 1.0).abs() < 1e-10 && (wide_eff - 4.0).abs() < 1e-10);
+
+        // Default execution: 1 tick slippage each side, 5 bps taker fees
+        let fees = 2.0 * 50000.0 * 5.0 / 10_000.0;
+        assert!((tight - (2.0 * (1.0 + 0.1) + fees)).abs() < 1e-9);
+        assert!((wide - tight - 2.0 * 3.0).abs() < 1e-9);
+    }
 }
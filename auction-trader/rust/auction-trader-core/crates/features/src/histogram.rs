@@ -1,7 +1,9 @@
 //! Rolling volume-at-price histogram.
 //!
 //! Maintains a rolling histogram of volume by price bin over a configurable window.
+//! Aggregated profiles can weight recent minutes more (see [`ProfileWeighting`]).
 
+use auction_core::ProfileWeighting;
 use ordered_float::OrderedFloat;
 use std::collections::{BTreeMap, VecDeque};
 
@@ -28,6 +30,10 @@ pub struct RollingHistogram {
     current_minute: Option<i64>,
     /// Current minute's bins.
     current_bins: BTreeMap<OrderedFloat<f64>, f64>,
+    /// Changes to `aggregated` since last drained (None = not tracked).
+    deltas: Option<Vec<(OrderedFloat<f64>, f64)>>,
+    /// Weighting of minutes by age in aggregated profiles.
+    weighting: ProfileWeighting,
 }
 
 impl RollingHistogram {
@@ -40,9 +46,30 @@ impl RollingHistogram {
             aggregated: BTreeMap::new(),
             current_minute: None,
             current_bins: BTreeMap::new(),
+            deltas: None,
+            weighting: ProfileWeighting::Flat,
         }
     }
 
+    /// Weight minutes by age in [`aggregate_to`](Self::aggregate_to) and
+    /// [`aggregate_with_current`](Self::aggregate_with_current).
+    ///
+    /// `histogram`, `total_volume` and the tracked deltas stay unweighted.
+    pub fn set_weighting(&mut self, weighting: ProfileWeighting) {
+        self.weighting = weighting;
+    }
+
+    /// Record `(base bin, volume change)` for every change to the aggregated
+    /// histogram, drained with [`take_deltas`](Self::take_deltas).
+    pub fn track_deltas(&mut self) {
+        self.deltas.get_or_insert_with(Vec::new);
+    }
+
+    /// Take the changes recorded since the last call.
+    pub fn take_deltas(&mut self) -> Vec<(OrderedFloat<f64>, f64)> {
+        self.deltas.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
     /// Get the bin key for a price.
     fn bin_key(&self, price: f64) -> OrderedFloat<f64> {
         let bin = (price / self.base_bin).floor() * self.base_bin;
@@ -74,6 +101,9 @@ impl RollingHistogram {
         for (&key, &vol) in &self.current_bins {
             *self.aggregated.entry(key).or_insert(0.0) += vol;
         }
+        if let Some(deltas) = &mut self.deltas {
+            deltas.extend(self.current_bins.iter().map(|(&key, &vol)| (key, vol)));
+        }
 
         // Store minute snapshot
         self.minute_volumes.push_back(MinuteVolume {
@@ -85,6 +115,9 @@ impl RollingHistogram {
         while self.minute_volumes.len() > self.window {
             if let Some(old) = self.minute_volumes.pop_front() {
                 // Subtract from aggregated
+                if let Some(deltas) = &mut self.deltas {
+                    deltas.extend(old.bins.iter().map(|(&key, &vol)| (key, -vol)));
+                }
                 for (key, vol) in old.bins {
                     if let Some(agg_vol) = self.aggregated.get_mut(&key) {
                         *agg_vol -= vol;
@@ -111,21 +144,69 @@ impl RollingHistogram {
 
     /// Aggregate to a wider bin width.
     ///
-    /// Returns a new histogram with bins at the specified width.
+    /// Returns a new histogram with bins at the specified width. Only
+    /// completed (flushed) minutes are included; see
+    /// [`aggregate_with_current`](Self::aggregate_with_current).
     pub fn aggregate_to(&self, bin_width: f64) -> BTreeMap<OrderedFloat<f64>, f64> {
         let mut result = BTreeMap::new();
-
-        for (&base_key, &vol) in &self.aggregated {
-            let agg_key = (base_key.0 / bin_width).floor() * bin_width;
-            *result.entry(OrderedFloat(agg_key)).or_insert(0.0) += vol;
+        match self.minute_volumes.back() {
+            Some(newest) if self.weighting != ProfileWeighting::Flat => {
+                self.accumulate_weighted(&mut result, bin_width, newest.ts_min);
+            }
+            _ => Self::accumulate(&mut result, &self.aggregated, bin_width, 1.0),
         }
+        result
+    }
 
+    /// Like [`aggregate_to`](Self::aggregate_to), also including the
+    /// still-accumulating current minute (as the newest).
+    pub fn aggregate_with_current(&self, bin_width: f64) -> BTreeMap<OrderedFloat<f64>, f64> {
+        let now = match self.current_minute {
+            Some(now) if self.weighting != ProfileWeighting::Flat => now,
+            _ => {
+                let mut result = self.aggregate_to(bin_width);
+                Self::accumulate(&mut result, &self.current_bins, bin_width, 1.0);
+                return result;
+            }
+        };
+        let mut result = BTreeMap::new();
+        self.accumulate_weighted(&mut result, bin_width, now);
+        Self::accumulate(&mut result, &self.current_bins, bin_width, 1.0);
         result
     }
 
+    /// Accumulate each stored minute weighted by its age relative to `now_min`.
+    fn accumulate_weighted(&self, result: &mut BTreeMap<OrderedFloat<f64>, f64>, bin_width: f64, now_min: i64) {
+        for minute in &self.minute_volumes {
+            let age = (now_min - minute.ts_min) as f64 / 60_000.0;
+            let weight = self.weighting.weight(age, self.window);
+            if weight > 0.0 {
+                Self::accumulate(result, &minute.bins, bin_width, weight);
+            }
+        }
+    }
+
+    /// Volume in the still-accumulating current minute.
+    pub fn current_minute_volume(&self) -> f64 {
+        self.current_bins.values().sum()
+    }
+
+    fn accumulate(
+        result: &mut BTreeMap<OrderedFloat<f64>, f64>,
+        bins: &BTreeMap<OrderedFloat<f64>, f64>,
+        bin_width: f64,
+        weight: f64,
+    ) {
+        for (&base_key, &vol) in bins {
+            let agg_key = (base_key.0 / bin_width).floor() * bin_width;
+            // Eviction residue can leave a bin a hair below zero
+            *result.entry(OrderedFloat(agg_key)).or_insert(0.0) += vol.max(0.0) * weight;
+        }
+    }
+
     /// Get total volume in the histogram.
     pub fn total_volume(&self) -> f64 {
-        self.aggregated.values().sum()
+        self.aggregated.values().map(|v| v.max(0.0)).sum()
     }
 
     /// Get number of bins with volume.
@@ -149,6 +230,9 @@ impl RollingHistogram {
         self.aggregated.clear();
         self.current_minute = None;
         self.current_bins.clear();
+        if let Some(deltas) = &mut self.deltas {
+            deltas.clear();
+        }
     }
 
     /// Rebuild the histogram from stored minute data.
@@ -241,6 +325,22 @@ mod tests {
         assert!((agg[&OrderedFloat(102.0)] - 70.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_aggregate_with_current() {
+        let mut hist = RollingHistogram::new(1.0, 10);
+        hist.add_trade(0, 100.0, 2.0);
+        hist.flush_current_minute();
+        hist.add_trade(60_000, 103.0, 5.0);
+
+        let completed = hist.aggregate_to(2.0);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(hist.current_minute_volume(), 5.0);
+
+        let live = hist.aggregate_with_current(2.0);
+        assert_eq!(live.get(&OrderedFloat(100.0)), Some(&2.0));
+        assert_eq!(live.get(&OrderedFloat(102.0)), Some(&5.0));
+    }
+
     #[test]
     fn test_is_ready() {
         let mut hist = RollingHistogram::new(1.0, 3);
@@ -254,4 +354,81 @@ mod tests {
 
         assert!(hist.is_ready());
     }
+
+    #[test]
+    fn test_no_negative_volume_after_many_cycles() {
+        use crate::value_area::{IncrementalValueArea, ValueAreaComputer, ValueAreaConfig};
+        use auction_core::PocConvention;
+
+        let config = ValueAreaConfig {
+            va_fraction: 0.70,
+            min_bins: 1,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
+        };
+        let computer = ValueAreaComputer::new(config.clone());
+        let mut incremental = IncrementalValueArea::new(config, 1.0);
+        let mut hist = RollingHistogram::new(1.0, 7);
+        hist.track_deltas();
+
+        // Sizes with inexact binary representations so add/subtract leaves residue
+        let sizes = [0.1, 0.7, 0.3, 1e-3, 2.2e-7, 0.01, 123.456];
+        for minute in 0..3000i64 {
+            for i in 0..3 {
+                let k = (minute as usize * 3 + i) % sizes.len();
+                let price = 100.0 + ((minute + i as i64) % 4) as f64;
+                hist.add_trade(minute * 60_000, price, sizes[k]);
+            }
+            hist.flush_current_minute();
+            for (key, volume) in hist.take_deltas() {
+                incremental.apply(key.0, volume);
+            }
+
+            assert!(hist.histogram().values().all(|&v| v >= 0.0));
+            assert!(hist.total_volume() >= 0.0);
+
+            for va in [computer.compute(&hist.aggregate_to(2.0), 2.0), incremental.value_area()] {
+                assert!(va.total_volume >= 0.0);
+                assert!((0.0..=1.0).contains(&va.coverage), "coverage {}", va.coverage);
+            }
+        }
+    }
+
+    #[test]
+    fn test_recency_weighting() {
+        // 240 minutes of 1 lot at 101, plus a 300-lot spike at 102 either
+        // a minute or 200 minutes before the end
+        let poc = |weighting: ProfileWeighting, spike_age: i64| {
+            let mut hist = RollingHistogram::new(1.0, 240);
+            hist.set_weighting(weighting);
+            for i in 0..240_i64 {
+                hist.add_trade(i * 60_000, 101.5, 1.0);
+                if i == 239 - spike_age {
+                    hist.add_trade(i * 60_000, 102.5, 300.0);
+                }
+            }
+            hist.flush_current_minute();
+            let profile = hist.aggregate_to(1.0);
+            profile.iter().max_by(|a, b| a.1.total_cmp(b.1)).map(|(k, _)| k.0).unwrap()
+        };
+
+        // Flat: the spike is the POC whenever it happened
+        assert_eq!(poc(ProfileWeighting::Flat, 1), 102.0);
+        assert_eq!(poc(ProfileWeighting::Flat, 200), 102.0);
+
+        // Recency weighting: only the recent spike moves the POC
+        for weighting in [ProfileWeighting::Linear, ProfileWeighting::Exponential { half_life_minutes: 60.0 }] {
+            assert_eq!(poc(weighting, 1), 102.0, "{weighting:?}");
+            assert_eq!(poc(weighting, 200), 101.0, "{weighting:?}");
+        }
+
+        // Raw totals are unweighted
+        let mut hist = RollingHistogram::new(1.0, 240);
+        hist.set_weighting(ProfileWeighting::Linear);
+        hist.add_trade(0, 100.5, 5.0);
+        hist.add_trade(60_000, 100.5, 5.0);
+        hist.flush_current_minute();
+        assert!((hist.total_volume() - 10.0).abs() < 1e-9);
+        assert!(hist.aggregate_to(1.0)[&OrderedFloat(100.0)] < 10.0);
+    }
 }
@@ -3,18 +3,38 @@
 //! This crate handles:
 //! - Rolling volatility (sigma_240)
 //! - Rolling volume-at-price histogram
+//! - Per-side (buy/sell) volume and delta profiles
 //! - Value Area computation (POC, VAH, VAL)
 //! - Order flow metrics aggregation
 //! - Quote imbalance computation
+//! - Streaming quantiles (adaptive trade-size thresholds)
+//! - Rolling VWAP and VWAP drift
+//! - Relative volume (RVOL)
+//! - Auction state classification (balance/trend/transition)
 
 pub mod volatility;
 pub mod histogram;
+pub mod delta_profile;
 pub mod value_area;
 pub mod order_flow;
+pub mod quantile;
+pub mod vwap;
+pub mod volume;
+pub mod auction_state;
 pub mod engine;
 
-pub use volatility::RollingVolatility;
+pub use volatility::{RollingVolatility, MINUTES_PER_YEAR};
 pub use histogram::RollingHistogram;
-pub use value_area::ValueAreaComputer;
-pub use order_flow::OrderFlowAggregator;
-pub use engine::FeatureEngine;
+pub use delta_profile::DeltaProfile;
+pub use value_area::{
+    normalized_profile_entropy, profile_entropy, va_migration, BreakoutOutcomeTracker, IncrementalValueArea,
+    LevelTestCounter, ValueAreaComputer,
+};
+pub use order_flow::{
+    LargeTradeThreshold, OrderFlowAggregator, OrderFlowImbalanceTracker, OrderFlowSeries, PredictiveCorrelation,
+};
+pub use quantile::P2Quantile;
+pub use vwap::{vwap_z, RollingVwap, VwapBandTouchCounter};
+pub use volume::RelativeVolume;
+pub use auction_state::AuctionStateClassifier;
+pub use engine::{FeatureEngine, ReadinessRequirements};
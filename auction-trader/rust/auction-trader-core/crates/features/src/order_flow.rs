@@ -2,8 +2,9 @@
 //!
 //! Aggregates classified trades into per-minute order flow metrics.
 
-use auction_core::{ClassifiedTrade, OrderFlowMetrics, TradeSide, TimestampMs, ts_to_minute};
-use std::collections::BTreeMap;
+use auction_core::{AtMidPolicy, ClassifiedTrade, EdgeFlow, Error, OrderFlowMetrics, Quote, Result, TradeSide, TimestampMs, ts_to_minute};
+use crate::quantile::P2Quantile;
+use std::collections::{BTreeMap, VecDeque};
 
 /// Accumulator for order flow within a minute.
 #[derive(Debug, Clone, Default)]
@@ -11,11 +12,33 @@ struct MinuteAccumulator {
     buy_volume: f64,
     sell_volume: f64,
     ambiguous_volume: f64,
+    at_bid_volume: f64,
+    at_ask_volume: f64,
+    inside_volume: f64,
 }
 
 impl MinuteAccumulator {
-    fn add(&mut self, trade: &ClassifiedTrade) {
+    fn add(&mut self, trade: &ClassifiedTrade, split_at_mid: bool) {
         let size = trade.trade.size;
+
+        // Passive side: where the print sat against its quote, independent of
+        // the aggressor classification (skipped without a valid quote)
+        let (bid, ask, price) = (trade.quote_bid_px, trade.quote_ask_px, trade.trade.price);
+        if bid > 0.0 && ask >= bid {
+            if price <= bid {
+                self.at_bid_volume += size;
+            } else if price >= ask {
+                self.at_ask_volume += size;
+            } else {
+                self.inside_volume += size;
+            }
+        }
+
+        if split_at_mid && trade.side == TradeSide::Ambiguous && trade.is_at_mid() {
+            self.buy_volume += size / 2.0;
+            self.sell_volume += size / 2.0;
+            return;
+        }
         match trade.side {
             TradeSide::Buy => self.buy_volume += size,
             TradeSide::Sell => self.sell_volume += size,
@@ -23,6 +46,15 @@ impl MinuteAccumulator {
         }
     }
 
+    fn merge(&mut self, other: &MinuteAccumulator) {
+        self.buy_volume += other.buy_volume;
+        self.sell_volume += other.sell_volume;
+        self.ambiguous_volume += other.ambiguous_volume;
+        self.at_bid_volume += other.at_bid_volume;
+        self.at_ask_volume += other.at_ask_volume;
+        self.inside_volume += other.inside_volume;
+    }
+
     fn to_metrics(&self) -> OrderFlowMetrics {
         let total_volume = self.buy_volume + self.sell_volume + self.ambiguous_volume;
         let of_1m = self.buy_volume - self.sell_volume;
@@ -45,16 +77,99 @@ impl MinuteAccumulator {
             sell_volume: self.sell_volume,
             ambiguous_volume: self.ambiguous_volume,
             ambiguous_frac,
+            has_volume: total_volume > 0.0,
+            at_bid_volume: self.at_bid_volume,
+            at_ask_volume: self.at_ask_volume,
+            inside_volume: self.inside_volume,
         }
     }
 }
 
+/// Per-minute order flow series as parallel columns, ordered by minute.
+#[derive(Debug, Clone, Default)]
+pub struct OrderFlowSeries {
+    /// Minute timestamps.
+    pub ts_min: Vec<TimestampMs>,
+    /// Net signed order flow.
+    pub of_1m: Vec<f64>,
+    /// Normalized order flow.
+    pub of_norm_1m: Vec<f64>,
+    /// Buy volume.
+    pub buy_volume: Vec<f64>,
+    /// Sell volume.
+    pub sell_volume: Vec<f64>,
+    /// Ambiguous volume fraction.
+    pub ambiguous_frac: Vec<f64>,
+}
+
+impl OrderFlowSeries {
+    /// Number of minutes in the series.
+    pub fn len(&self) -> usize {
+        self.ts_min.len()
+    }
+
+    /// Check if the series is empty.
+    pub fn is_empty(&self) -> bool {
+        self.ts_min.is_empty()
+    }
+}
+
+/// Size above which a trade counts as large.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LargeTradeThreshold {
+    /// Fixed size.
+    Fixed(f64),
+    /// Rolling 95th percentile of trade sizes.
+    #[default]
+    P95,
+    /// Rolling 99th percentile of trade sizes.
+    P99,
+}
+
+/// Trade size quantiles for one generation of the rolling estimate.
+#[derive(Debug, Clone)]
+struct SizeQuantiles {
+    /// Start of the window this generation covers.
+    start: Option<TimestampMs>,
+    p95: P2Quantile,
+    p99: P2Quantile,
+}
+
+impl SizeQuantiles {
+    fn new() -> Self {
+        Self {
+            start: None,
+            p95: P2Quantile::new(0.95),
+            p99: P2Quantile::new(0.99),
+        }
+    }
+
+    /// Add a size, restarting the estimators when a later window begins.
+    fn add(&mut self, start: TimestampMs, size: f64) {
+        if !matches!(self.start, Some(current) if start <= current) {
+            self.p95.reset();
+            self.p99.reset();
+            self.start = Some(start);
+        }
+        self.p95.add(size);
+        self.p99.add(size);
+    }
+}
+
 /// Order flow aggregator that tracks per-minute metrics.
 pub struct OrderFlowAggregator {
     /// Accumulators by minute.
     minutes: BTreeMap<TimestampMs, MinuteAccumulator>,
     /// Maximum minutes to keep.
     max_minutes: usize,
+    /// Split ambiguous at-mid trades half buy / half sell.
+    split_at_mid: bool,
+    /// Two size-quantile generations restarted every `max_minutes`, offset
+    /// by half a window; the older one always covers between half and all
+    /// of the latest window.
+    size_quantiles: [SizeQuantiles; 2],
+    /// Large-trade threshold.
+    large_trade_threshold: LargeTradeThreshold,
 }
 
 impl OrderFlowAggregator {
@@ -63,16 +178,32 @@ impl OrderFlowAggregator {
         Self {
             minutes: BTreeMap::new(),
             max_minutes,
+            split_at_mid: false,
+            size_quantiles: [SizeQuantiles::new(), SizeQuantiles::new()],
+            large_trade_threshold: LargeTradeThreshold::default(),
         }
     }
 
+    /// Set the large-trade threshold.
+    pub fn with_large_trade_threshold(mut self, threshold: LargeTradeThreshold) -> Self {
+        self.large_trade_threshold = threshold;
+        self
+    }
+
+    /// Set the at-mid policy; only `AtMidPolicy::SplitHalf` changes aggregation.
+    pub fn with_at_mid_policy(mut self, policy: AtMidPolicy) -> Self {
+        self.split_at_mid = policy == AtMidPolicy::SplitHalf;
+        self
+    }
+
     /// Add a classified trade.
     pub fn add_trade(&mut self, trade: &ClassifiedTrade) {
         let ts_min = ts_to_minute(trade.trade.ts_ms);
         self.minutes
             .entry(ts_min)
             .or_default()
-            .add(trade);
+            .add(trade, self.split_at_mid);
+        self.add_size(ts_min, trade.trade.size);
 
         // Prune old minutes
         while self.minutes.len() > self.max_minutes {
@@ -82,6 +213,23 @@ impl OrderFlowAggregator {
         }
     }
 
+    /// Feed a trade size to both quantile generations, restarting each one
+    /// when its window advances.
+    fn add_size(&mut self, ts_min: TimestampMs, size: f64) {
+        let span = self.max_minutes.max(1) as i64 * 60_000;
+        for (i, quantiles) in self.size_quantiles.iter_mut().enumerate() {
+            let offset = i as i64 * span / 2;
+            let start = (ts_min + offset).div_euclid(span) * span - offset;
+            quantiles.add(start, size);
+        }
+    }
+
+    /// The quantile generation with the longest history.
+    fn sizes(&self) -> &SizeQuantiles {
+        let [a, b] = &self.size_quantiles;
+        if b.start < a.start { b } else { a }
+    }
+
     /// Add multiple trades.
     pub fn add_trades(&mut self, trades: &[ClassifiedTrade]) {
         for trade in trades {
@@ -107,25 +255,596 @@ impl OrderFlowAggregator {
         let mut total = MinuteAccumulator::default();
 
         for acc in self.minutes.values().rev().take(minutes) {
-            total.buy_volume += acc.buy_volume;
-            total.sell_volume += acc.sell_volume;
-            total.ambiguous_volume += acc.ambiguous_volume;
+            total.merge(acc);
         }
 
         total.to_metrics()
     }
 
+    /// Resample the tracked minutes into windows of `window_minutes`.
+    ///
+    /// Windows are aligned to multiples of the window length since the epoch
+    /// and keyed by their start. Volumes are summed and the metrics re-derived,
+    /// so `of_norm_1m` is the window's net flow over its total volume (not an
+    /// average of the minutes' ratios). Windows without tracked minutes are
+    /// omitted; a zero window length yields nothing.
+    pub fn resample(&self, window_minutes: u32) -> Vec<(TimestampMs, OrderFlowMetrics)> {
+        let window_ms = window_minutes as i64 * 60_000;
+        if window_ms == 0 {
+            return Vec::new();
+        }
+
+        let mut windows: BTreeMap<TimestampMs, MinuteAccumulator> = BTreeMap::new();
+        for (ts_min, acc) in &self.minutes {
+            let start = ts_min - ts_min.rem_euclid(window_ms);
+            windows.entry(start).or_default().merge(acc);
+        }
+
+        windows
+            .into_iter()
+            .map(|(start, acc)| (start, acc.to_metrics()))
+            .collect()
+    }
+
+    /// Get all tracked minutes as parallel columns.
+    pub fn series(&self) -> OrderFlowSeries {
+        let n = self.minutes.len();
+        let mut series = OrderFlowSeries {
+            ts_min: Vec::with_capacity(n),
+            of_1m: Vec::with_capacity(n),
+            of_norm_1m: Vec::with_capacity(n),
+            buy_volume: Vec::with_capacity(n),
+            sell_volume: Vec::with_capacity(n),
+            ambiguous_frac: Vec::with_capacity(n),
+        };
+
+        for (ts_min, acc) in &self.minutes {
+            let m = acc.to_metrics();
+            series.ts_min.push(*ts_min);
+            series.of_1m.push(m.of_1m);
+            series.of_norm_1m.push(m.of_norm_1m);
+            series.buy_volume.push(m.buy_volume);
+            series.sell_volume.push(m.sell_volume);
+            series.ambiguous_frac.push(m.ambiguous_frac);
+        }
+
+        series
+    }
+
     /// Get the number of minutes tracked.
     pub fn minute_count(&self) -> usize {
         self.minutes.len()
     }
 
+    /// Rolling 95th percentile of trade sizes over at most the last
+    /// `max_minutes`.
+    pub fn size_p95(&self) -> Option<f64> {
+        self.sizes().p95.value()
+    }
+
+    /// Rolling 99th percentile of trade sizes over at most the last
+    /// `max_minutes`.
+    pub fn size_p99(&self) -> Option<f64> {
+        self.sizes().p99.value()
+    }
+
+    /// Current large-trade size threshold (None until a percentile has data).
+    pub fn large_trade_threshold(&self) -> Option<f64> {
+        match self.large_trade_threshold {
+            LargeTradeThreshold::Fixed(size) => Some(size),
+            LargeTradeThreshold::P95 => self.size_p95(),
+            LargeTradeThreshold::P99 => self.size_p99(),
+        }
+    }
+
+    /// Whether a trade size is at or above the large-trade threshold.
+    pub fn is_large_trade(&self, size: f64) -> bool {
+        self.large_trade_threshold().is_some_and(|threshold| size >= threshold)
+    }
+
+    /// Clear all data.
+    pub fn clear(&mut self) {
+        self.minutes.clear();
+        self.size_quantiles = [SizeQuantiles::new(), SizeQuantiles::new()];
+    }
+}
+
+/// Run length of consecutive same-signed order flow minutes.
+///
+/// The streak is positive for consecutive minutes of net buying and negative
+/// for net selling; a sign flip starts a new run and a minute with zero net
+/// flow (including no volume) resets it to 0.
+pub struct OrderFlowStreak {
+    /// Current signed streak.
+    current: i32,
+    /// Streak after each of the last `window` minutes.
+    history: VecDeque<i32>,
+    /// Maximum minutes to keep.
+    window: usize,
+    /// Last minute recorded.
+    last_min: Option<TimestampMs>,
+}
+
+impl OrderFlowStreak {
+    /// Create a streak tracker whose maximum spans `window` minutes.
+    pub fn new(window: usize) -> Self {
+        Self {
+            current: 0,
+            history: VecDeque::with_capacity(window),
+            window: window.max(1),
+            last_min: None,
+        }
+    }
+
+    /// Record a minute's normalized order flow and return the new streak.
+    ///
+    /// Minutes at or before the last recorded one are ignored, so
+    /// recomputing a minute does not extend the streak.
+    pub fn update(&mut self, ts_min: TimestampMs, of_norm: f64) -> i32 {
+        if self.last_min.is_some_and(|last| ts_min <= last) {
+            return self.current;
+        }
+        self.last_min = Some(ts_min);
+
+        self.current = if of_norm > 0.0 {
+            self.current.max(0) + 1
+        } else if of_norm < 0.0 {
+            self.current.min(0) - 1
+        } else {
+            0
+        };
+
+        self.history.push_back(self.current);
+        while self.history.len() > self.window {
+            self.history.pop_front();
+        }
+        self.current
+    }
+
+    /// Current signed streak.
+    pub fn current(&self) -> i32 {
+        self.current
+    }
+
+    /// Longest run (either sign) reached within the window.
+    pub fn max_abs(&self) -> u32 {
+        self.history.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0)
+    }
+
+    /// Clear all data.
+    pub fn clear(&mut self) {
+        self.current = 0;
+        self.history.clear();
+        self.last_min = None;
+    }
+}
+
+/// Rolling mean magnitude of the per-minute divergence between aggressor
+/// flow and quote imbalance.
+#[derive(Debug, Clone)]
+pub struct FlowQuoteDivergence {
+    /// |divergence| of each of the last `window` minutes.
+    history: VecDeque<f64>,
+    /// Sum of `history`.
+    sum: f64,
+    /// Maximum minutes to keep.
+    window: usize,
+    /// Last minute recorded.
+    last_min: Option<TimestampMs>,
+}
+
+impl FlowQuoteDivergence {
+    /// Create a tracker averaging over `window` minutes.
+    pub fn new(window: usize) -> Self {
+        Self {
+            history: VecDeque::with_capacity(window),
+            sum: 0.0,
+            window: window.max(1),
+            last_min: None,
+        }
+    }
+
+    /// Record a minute's divergence and return the rolling mean magnitude.
+    ///
+    /// Minutes at or before the last recorded one are ignored.
+    pub fn update(&mut self, ts_min: TimestampMs, divergence: f64) -> f64 {
+        if self.last_min.is_some_and(|last| ts_min <= last) || !divergence.is_finite() {
+            return self.mean_abs();
+        }
+        self.last_min = Some(ts_min);
+
+        self.history.push_back(divergence.abs());
+        self.sum += divergence.abs();
+        while self.history.len() > self.window {
+            if let Some(old) = self.history.pop_front() {
+                self.sum -= old;
+            }
+        }
+        self.mean_abs()
+    }
+
+    /// Mean |divergence| over the window (0 when empty).
+    pub fn mean_abs(&self) -> f64 {
+        if self.history.is_empty() {
+            0.0
+        } else {
+            self.sum.max(0.0) / self.history.len() as f64
+        }
+    }
+
+    /// Clear all data.
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.sum = 0.0;
+        self.last_min = None;
+    }
+}
+
+/// Rolling correlation between a per-minute signal and the following
+/// minute's mid log return.
+///
+/// Each update pairs the previous minute's signal with the return from the
+/// previous mid to this one, so the value at a minute only uses returns
+/// already realized. Rolling sums are kept over the last `window` pairs.
+pub struct PredictiveCorrelation {
+    /// (signal, next return) pairs in the window.
+    pairs: VecDeque<(f64, f64)>,
+    /// Maximum pairs to keep.
+    window: usize,
+    /// Sums of x, y, x², y² and xy over the window.
+    sums: [f64; 5],
+    /// Signal and mid of the last minute recorded.
+    prev: Option<(f64, f64)>,
+    /// Last minute recorded.
+    last_min: Option<TimestampMs>,
+}
+
+impl PredictiveCorrelation {
+    /// Create a tracker over the last `window` minutes.
+    pub fn new(window: usize) -> Self {
+        Self {
+            pairs: VecDeque::with_capacity(window),
+            window: window.max(1),
+            sums: [0.0; 5],
+            prev: None,
+            last_min: None,
+        }
+    }
+
+    /// Record a minute's signal and close mid and return the correlation.
+    ///
+    /// Minutes at or before the last recorded one are ignored. A non-finite
+    /// or non-positive input breaks the pairing until the next valid minute.
+    pub fn update(&mut self, ts_min: TimestampMs, signal: f64, mid: f64) -> Option<f64> {
+        if self.last_min.is_some_and(|last| ts_min <= last) {
+            return self.value();
+        }
+        self.last_min = Some(ts_min);
+
+        if !(signal.is_finite() && mid.is_finite() && mid > 0.0) {
+            self.prev = None;
+            return self.value();
+        }
+        if let Some((prev_signal, prev_mid)) = self.prev {
+            self.push(prev_signal, (mid / prev_mid).ln());
+        }
+        self.prev = Some((signal, mid));
+        self.value()
+    }
+
+    fn push(&mut self, x: f64, y: f64) {
+        self.pairs.push_back((x, y));
+        self.add_to_sums(x, y, 1.0);
+        while self.pairs.len() > self.window {
+            if let Some((old_x, old_y)) = self.pairs.pop_front() {
+                self.add_to_sums(old_x, old_y, -1.0);
+            }
+        }
+    }
+
+    fn add_to_sums(&mut self, x: f64, y: f64, sign: f64) {
+        for (sum, term) in self.sums.iter_mut().zip([x, y, x * x, y * y, x * y]) {
+            *sum += sign * term;
+        }
+    }
+
+    /// Pearson correlation over the window (None with fewer than 3 pairs or
+    /// no variance).
+    pub fn value(&self) -> Option<f64> {
+        let n = self.pairs.len() as f64;
+        if self.pairs.len() < 3 {
+            return None;
+        }
+        let [sx, sy, sxx, syy, sxy] = self.sums;
+        let var_x = n * sxx - sx * sx;
+        let var_y = n * syy - sy * sy;
+        if var_x <= 0.0 || var_y <= 0.0 {
+            return None;
+        }
+        Some(((n * sxy - sx * sy) / (var_x * var_y).sqrt()).clamp(-1.0, 1.0))
+    }
+
+    /// Clear all data.
+    pub fn clear(&mut self) {
+        self.pairs.clear();
+        self.sums = [0.0; 5];
+        self.prev = None;
+        self.last_min = None;
+    }
+}
+
+/// Per-minute realized effective spread tracker.
+///
+/// The effective spread of a trade is `2 * |price - mid|` against the quote it
+/// was classified with; each minute reports the size-weighted average.
+pub struct EffectiveSpreadTracker {
+    /// (sum of size * effective spread, sum of size) by minute.
+    minutes: BTreeMap<TimestampMs, (f64, f64)>,
+    /// Maximum minutes to keep.
+    max_minutes: usize,
+}
+
+impl EffectiveSpreadTracker {
+    /// Create a new effective spread tracker.
+    pub fn new(max_minutes: usize) -> Self {
+        Self {
+            minutes: BTreeMap::new(),
+            max_minutes,
+        }
+    }
+
+    /// Add a classified trade. Trades without a valid quote are ignored.
+    pub fn add_trade(&mut self, trade: &ClassifiedTrade) {
+        if trade.quote_bid_px <= 0.0 || trade.quote_ask_px < trade.quote_bid_px {
+            return;
+        }
+
+        let mid = (trade.quote_bid_px + trade.quote_ask_px) / 2.0;
+        let eff_spread = 2.0 * (trade.trade.price - mid).abs();
+        let size = trade.trade.size;
+
+        let entry = self
+            .minutes
+            .entry(ts_to_minute(trade.trade.ts_ms))
+            .or_insert((0.0, 0.0));
+        entry.0 += eff_spread * size;
+        entry.1 += size;
+
+        while self.minutes.len() > self.max_minutes {
+            self.minutes.pop_first();
+        }
+    }
+
+    /// Size-weighted average effective spread over all kept minutes.
+    pub fn avg(&self) -> Option<f64> {
+        let (weighted, size) = self
+            .minutes
+            .values()
+            .fold((0.0, 0.0), |(w, s), (mw, ms)| (w + mw, s + ms));
+        (size > 0.0).then(|| weighted / size)
+    }
+
+    /// Get the size-weighted average effective spread for a minute.
+    pub fn avg_for_minute(&self, ts_min: TimestampMs) -> Option<f64> {
+        self.minutes
+            .get(&ts_min)
+            .filter(|(_, size)| *size > 0.0)
+            .map(|(weighted, size)| weighted / size)
+    }
+
     /// Clear all data.
     pub fn clear(&mut self) {
         self.minutes.clear();
     }
 }
 
+/// Per-minute order flow near the value-area edges.
+///
+/// Trades are matched against the last-known VAH/VAL (set with
+/// [`set_edges`](Self::set_edges), typically from the previous minute's VA),
+/// since the current minute's VA is not known until it closes.
+pub struct EdgeFlowTracker {
+    /// (VAH flow, VAL flow) by minute.
+    minutes: BTreeMap<TimestampMs, (EdgeFlow, EdgeFlow)>,
+    /// Maximum minutes to keep.
+    max_minutes: usize,
+    /// Maximum distance from an edge for a trade to count.
+    tolerance: f64,
+    /// Current (VAH, VAL), if known.
+    edges: Option<(f64, f64)>,
+}
+
+impl EdgeFlowTracker {
+    /// Create a new edge flow tracker with a price tolerance.
+    pub fn new(max_minutes: usize, tolerance: f64) -> Self {
+        Self {
+            minutes: BTreeMap::new(),
+            max_minutes,
+            tolerance,
+            edges: None,
+        }
+    }
+
+    /// Set the edges used for subsequent trades.
+    pub fn set_edges(&mut self, vah: f64, val: f64) {
+        self.edges = Some((vah, val));
+    }
+
+    /// Forget the current edges (trades are ignored until new ones are set).
+    pub fn clear_edges(&mut self) {
+        self.edges = None;
+    }
+
+    /// Add a classified trade. Ambiguous trades and trades away from both
+    /// edges are ignored.
+    pub fn add_trade(&mut self, trade: &ClassifiedTrade) {
+        let Some((vah, val)) = self.edges else {
+            return;
+        };
+        if trade.side == TradeSide::Ambiguous {
+            return;
+        }
+
+        let price = trade.trade.price;
+        let at_vah = (price - vah).abs() <= self.tolerance;
+        let at_val = (price - val).abs() <= self.tolerance;
+        if !at_vah && !at_val {
+            return;
+        }
+
+        let entry = self.minutes.entry(ts_to_minute(trade.trade.ts_ms)).or_default();
+        // A very narrow VA can put a trade near both edges; attribute it to the closer one
+        let flow = if at_vah && (!at_val || (price - vah).abs() <= (price - val).abs()) {
+            &mut entry.0
+        } else {
+            &mut entry.1
+        };
+        match trade.side {
+            TradeSide::Buy => flow.buy_volume += trade.trade.size,
+            TradeSide::Sell => flow.sell_volume += trade.trade.size,
+            TradeSide::Ambiguous => {}
+        }
+
+        while self.minutes.len() > self.max_minutes {
+            self.minutes.pop_first();
+        }
+    }
+
+    /// Get the (VAH, VAL) edge flow for a minute.
+    pub fn for_minute(&self, ts_min: TimestampMs) -> (EdgeFlow, EdgeFlow) {
+        self.minutes.get(&ts_min).copied().unwrap_or_default()
+    }
+
+    /// Clear accumulated flow (the current edges are kept).
+    pub fn clear(&mut self) {
+        self.minutes.clear();
+    }
+}
+
+/// Per-minute order flow imbalance (OFI) from consecutive L1 quotes.
+///
+/// Each quote update contributes the Cont-Kukanov-Stoikov increment
+///
+/// ```text
+/// e = 1{bid >= bid'} * bid_sz - 1{bid <= bid'} * bid_sz'
+///   - 1{ask <= ask'} * ask_sz + 1{ask >= ask'} * ask_sz'
+/// ```
+///
+/// where primes denote the previous quote. Positive OFI means bid-side
+/// liquidity was added or ask-side liquidity removed, whether or not any
+/// trade printed.
+pub struct OrderFlowImbalanceTracker {
+    /// Summed OFI by minute.
+    minutes: BTreeMap<TimestampMs, f64>,
+    /// Maximum minutes to keep.
+    max_minutes: usize,
+    /// Previous quote.
+    prev: Option<Quote>,
+}
+
+impl OrderFlowImbalanceTracker {
+    /// Create a new OFI tracker.
+    pub fn new(max_minutes: usize) -> Self {
+        Self {
+            minutes: BTreeMap::new(),
+            max_minutes,
+            prev: None,
+        }
+    }
+
+    /// Add a quote, returning its OFI increment (None for the first quote).
+    pub fn add_quote(&mut self, quote: &Quote) -> Option<f64> {
+        let prev = self.prev.replace(quote.clone())?;
+        let e = Self::increment(&prev, quote);
+
+        *self.minutes.entry(ts_to_minute(quote.ts_ms)).or_insert(0.0) += e;
+        while self.minutes.len() > self.max_minutes {
+            self.minutes.pop_first();
+        }
+        Some(e)
+    }
+
+    /// OFI increment between two consecutive quotes.
+    pub fn increment(prev: &Quote, quote: &Quote) -> f64 {
+        let mut e = 0.0;
+        if quote.bid_px >= prev.bid_px {
+            e += quote.bid_sz;
+        }
+        if quote.bid_px <= prev.bid_px {
+            e -= prev.bid_sz;
+        }
+        if quote.ask_px <= prev.ask_px {
+            e -= quote.ask_sz;
+        }
+        if quote.ask_px >= prev.ask_px {
+            e += prev.ask_sz;
+        }
+        e
+    }
+
+    /// Get the summed OFI for a minute (0 if no quote changes).
+    pub fn for_minute(&self, ts_min: TimestampMs) -> f64 {
+        self.minutes.get(&ts_min).copied().unwrap_or(0.0)
+    }
+
+    /// Clear all data, including the previous quote.
+    pub fn clear(&mut self) {
+        self.minutes.clear();
+        self.prev = None;
+    }
+}
+
+/// Largest mid-price jump between consecutive quotes, by minute.
+///
+/// A jump is assigned to the minute of the later quote. Crossed or empty
+/// quotes are skipped and do not replace the previous mid.
+pub struct MidJumpTracker {
+    /// Largest absolute jump by minute.
+    minutes: BTreeMap<TimestampMs, f64>,
+    /// Maximum minutes to keep.
+    max_minutes: usize,
+    /// Previous mid.
+    prev_mid: Option<f64>,
+}
+
+impl MidJumpTracker {
+    /// Create a new mid jump tracker.
+    pub fn new(max_minutes: usize) -> Self {
+        Self {
+            minutes: BTreeMap::new(),
+            max_minutes,
+            prev_mid: None,
+        }
+    }
+
+    /// Add a quote, returning its absolute mid jump (None for the first quote).
+    pub fn add_quote(&mut self, quote: &Quote) -> Option<f64> {
+        if quote.bid_px <= 0.0 || quote.ask_px < quote.bid_px {
+            return None;
+        }
+        let mid = quote.mid();
+        let jump = (mid - self.prev_mid.replace(mid)?).abs();
+
+        let max = self.minutes.entry(ts_to_minute(quote.ts_ms)).or_insert(0.0);
+        *max = max.max(jump);
+        while self.minutes.len() > self.max_minutes {
+            self.minutes.pop_first();
+        }
+        Some(jump)
+    }
+
+    /// Get the largest jump in a minute (0 if no consecutive quotes).
+    pub fn max_for_minute(&self, ts_min: TimestampMs) -> f64 {
+        self.minutes.get(&ts_min).copied().unwrap_or(0.0)
+    }
+
+    /// Clear all data, including the previous mid.
+    pub fn clear(&mut self) {
+        self.minutes.clear();
+        self.prev_mid = None;
+    }
+}
+
 /// Quote imbalance tracker.
 pub struct QuoteImbalanceTracker {
     /// Recent qimb values for EMA calculation.
@@ -134,24 +853,70 @@ pub struct QuoteImbalanceTracker {
     max_values: usize,
     /// EMA decay factor.
     ema_alpha: f64,
+    /// Minimum size on each side for a quote's imbalance to be sampled.
+    min_quote_size: f64,
+    /// Quotes skipped for being below `min_quote_size`.
+    rejected_quotes: u64,
 }
 
 impl QuoteImbalanceTracker {
-    /// Create a new quote imbalance tracker.
+    /// Create a new quote imbalance tracker, assuming ~10 quote updates per second.
     ///
     /// # Arguments
     /// * `max_values` - Maximum quote updates to keep
     /// * `ema_span_seconds` - EMA span in seconds (for alpha calculation)
     pub fn new(max_values: usize, ema_span_seconds: u32) -> Self {
-        // Alpha for EMA: 2 / (span + 1)
-        // For span in seconds, assuming ~10 updates per second
-        let ema_alpha = 2.0 / (ema_span_seconds as f64 * 10.0 + 1.0);
+        Self::with_update_rate(max_values, ema_span_seconds, 10.0)
+            .expect("default update rate is positive")
+    }
 
-        Self {
+    /// Create a tracker with an explicit quote update rate.
+    ///
+    /// The EMA is applied per update, so its span in updates is
+    /// `ema_span_seconds * updates_per_second`. A rate far from the venue's
+    /// real one silently shortens or lengthens the effective span of `qimb_ema`.
+    pub fn with_update_rate(
+        max_values: usize,
+        ema_span_seconds: u32,
+        updates_per_second: f64,
+    ) -> Result<Self> {
+        if !(updates_per_second.is_finite() && updates_per_second > 0.0) {
+            return Err(Error::config(format!(
+                "qimb updates_per_second must be positive, got {updates_per_second}"
+            )));
+        }
+
+        // Alpha for EMA: 2 / (span + 1), span in updates
+        let ema_alpha = 2.0 / (ema_span_seconds as f64 * updates_per_second + 1.0);
+
+        Ok(Self {
             values: Vec::with_capacity(max_values),
             max_values,
             ema_alpha,
+            min_quote_size: 0.0,
+            rejected_quotes: 0,
+        })
+    }
+
+    /// Skip quotes with either side below `min_quote_size` in [`add_quote`](Self::add_quote).
+    pub fn with_min_quote_size(mut self, min_quote_size: f64) -> Self {
+        self.min_quote_size = min_quote_size.max(0.0);
+        self
+    }
+
+    /// Sample a quote's imbalance. Returns false if the quote was too thin.
+    pub fn add_quote(&mut self, quote: &Quote) -> bool {
+        if quote.bid_sz < self.min_quote_size || quote.ask_sz < self.min_quote_size {
+            self.rejected_quotes += 1;
+            return false;
         }
+        self.add(quote.ts_ms, quote.imbalance());
+        true
+    }
+
+    /// Number of quotes skipped for being too thin.
+    pub fn rejected_quotes(&self) -> u64 {
+        self.rejected_quotes
     }
 
     /// Add a quote imbalance value.
@@ -215,6 +980,7 @@ impl QuoteImbalanceTracker {
     /// Clear all data.
     pub fn clear(&mut self) {
         self.values.clear();
+        self.rejected_quotes = 0;
     }
 }
 
@@ -223,6 +989,16 @@ mod tests {
     use super::*;
     use auction_core::Trade;
 
+    fn make_quoted(ts_ms: i64, price: f64, size: f64, bid: f64, ask: f64) -> ClassifiedTrade {
+        ClassifiedTrade {
+            trade: Trade { ts_ms, price, size },
+            side: TradeSide::Ambiguous,
+            quote_bid_px: bid,
+            quote_ask_px: ask,
+            quote_staleness_ms: 10,
+        }
+    }
+
     fn make_classified(ts_ms: i64, size: f64, side: TradeSide) -> ClassifiedTrade {
         ClassifiedTrade {
             trade: Trade {
@@ -237,6 +1013,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_predictive_correlation() {
+        let mut corr = PredictiveCorrelation::new(50);
+        let mut mid = 50000.0;
+        let mut value = None;
+        for i in 0..40 {
+            // Imbalance in [-1, 1] that fully determines the next return
+            let qimb = ((i * 7) % 11) as f64 / 5.0 - 1.0;
+            value = corr.update(i * 60_000, qimb, mid);
+            mid *= (0.001 * qimb).exp();
+        }
+        assert!((value.unwrap() - 1.0).abs() < 1e-9);
+
+        // A recomputed minute does not add a pair
+        assert_eq!(corr.update(39 * 60_000, -1.0, 1.0), value);
+
+        corr.clear();
+        assert!(corr.value().is_none());
+    }
+
+    #[test]
+    fn test_passive_side_buckets() {
+        let mut agg = OrderFlowAggregator::new(10);
+
+        agg.add_trade(&make_quoted(60_000, 50000.0, 1.0, 50000.0, 50001.0)); // at bid
+        agg.add_trade(&make_quoted(61_000, 50001.0, 2.0, 50000.0, 50001.0)); // at ask
+        agg.add_trade(&make_quoted(62_000, 50000.5, 4.0, 50000.0, 50001.0)); // inside
+        // Aggressor says buy, but the print hit the bid
+        let mut hit_bid = make_quoted(63_000, 50000.0, 8.0, 50000.0, 50001.0);
+        hit_bid.side = TradeSide::Buy;
+        agg.add_trade(&hit_bid);
+        // No quote: not bucketed
+        agg.add_trade(&make_quoted(64_000, 50000.0, 16.0, 0.0, 0.0));
+
+        let metrics = agg.get_minute(60_000).unwrap();
+        assert!((metrics.at_bid_volume - 9.0).abs() < 1e-10);
+        assert!((metrics.at_ask_volume - 2.0).abs() < 1e-10);
+        assert!((metrics.inside_volume - 4.0).abs() < 1e-10);
+        assert!((metrics.buy_volume - 8.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_single_minute() {
         let mut agg = OrderFlowAggregator::new(10);
@@ -287,6 +1104,56 @@ mod tests {
         assert!((rolling.of_1m - 0.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_split_half_at_mid() {
+        let at_mid = |ts_ms| make_quoted(ts_ms, 100.5, 2.0, 100.0, 101.0);
+        let off_mid = |ts_ms| make_quoted(ts_ms, 100.25, 1.0, 100.0, 101.0);
+
+        let mut split = OrderFlowAggregator::new(10).with_at_mid_policy(AtMidPolicy::SplitHalf);
+        let mut plain = OrderFlowAggregator::new(10);
+        for agg in [&mut split, &mut plain] {
+            agg.add_trade(&at_mid(60_000));
+            agg.add_trade(&at_mid(61_000));
+            agg.add_trade(&off_mid(62_000));
+            agg.add_trade(&make_classified(63_000, 1.0, TradeSide::Buy));
+        }
+
+        // At-mid volume (4.0) split evenly; off-mid stays ambiguous
+        let m = split.get_minute(60_000).unwrap();
+        assert!((m.buy_volume - 3.0).abs() < 1e-10);
+        assert!((m.sell_volume - 2.0).abs() < 1e-10);
+        assert!((m.ambiguous_volume - 1.0).abs() < 1e-10);
+        assert!((m.of_1m - 1.0).abs() < 1e-10);
+
+        let m = plain.get_minute(60_000).unwrap();
+        assert!((m.ambiguous_volume - 5.0).abs() < 1e-10);
+        assert!((m.of_1m - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_series() {
+        let mut agg = OrderFlowAggregator::new(10);
+
+        agg.add_trade(&make_classified(120_000 + 500, 2.0, TradeSide::Sell));
+        agg.add_trade(&make_classified(60_000 + 100, 3.0, TradeSide::Buy));
+        agg.add_trade(&make_classified(60_000 + 200, 1.0, TradeSide::Ambiguous));
+
+        let series = agg.series();
+        assert_eq!(series.len(), 2);
+        assert_eq!(series.of_1m.len(), 2);
+        assert_eq!(series.ambiguous_frac.len(), 2);
+
+        // Ordered by minute regardless of insertion order
+        assert_eq!(series.ts_min, vec![60_000, 120_000]);
+        assert!((series.of_1m[0] - 3.0).abs() < 1e-10);
+        assert!((series.ambiguous_frac[0] - 0.25).abs() < 1e-10);
+        assert!((series.of_1m[1] - (-2.0)).abs() < 1e-10);
+        assert!((series.sell_volume[1] - 2.0).abs() < 1e-10);
+        assert!((series.of_norm_1m[1] - (-1.0)).abs() < 1e-10);
+
+        assert!(OrderFlowAggregator::new(10).series().is_empty());
+    }
+
     #[test]
     fn test_normalized_of() {
         let mut agg = OrderFlowAggregator::new(10);
@@ -304,6 +1171,201 @@ mod tests {
         assert!((metrics2.of_norm_1m - (-1.0)).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_empty_vs_balanced_minute() {
+        let mut agg = OrderFlowAggregator::new(10);
+        agg.add_trade(&make_classified(60_000, 5.0, TradeSide::Buy));
+        agg.add_trade(&make_classified(60_500, 5.0, TradeSide::Sell));
+
+        let balanced = agg.get_minute(60_000).unwrap();
+        assert!(balanced.has_volume);
+        assert_eq!(balanced.of_norm_1m, 0.0);
+
+        let empty = agg.get_rolling(0);
+        assert!(!empty.has_volume);
+        assert_eq!(empty.of_norm_1m, 0.0);
+        assert!(!OrderFlowMetrics::empty().has_volume);
+    }
+
+    #[test]
+    fn test_effective_spread() {
+        let mut tracker = EffectiveSpreadTracker::new(10);
+
+        // Mid = 100.5, quoted spread = 1.0
+        // At the ask: 2 * 0.5 = 1.0
+        tracker.add_trade(&make_quoted(60_000, 101.0, 1.0, 100.0, 101.0));
+        assert!((tracker.avg_for_minute(60_000).unwrap() - 1.0).abs() < 1e-10);
+
+        // Through the quote by 1.0 with 3x size: 2 * 1.5 = 3.0
+        // Size-weighted: (1.0 * 1 + 3.0 * 3) / 4 = 2.5
+        tracker.add_trade(&make_quoted(60_000 + 1000, 99.0, 3.0, 100.0, 101.0));
+        assert!((tracker.avg_for_minute(60_000).unwrap() - 2.5).abs() < 1e-10);
+
+        // At mid: zero effective spread
+        tracker.add_trade(&make_quoted(120_000, 100.5, 1.0, 100.0, 101.0));
+        assert!(tracker.avg_for_minute(120_000).unwrap().abs() < 1e-10);
+
+        // Trades without a quote are skipped
+        tracker.add_trade(&make_quoted(180_000, 100.5, 1.0, 0.0, 0.0));
+        assert!(tracker.avg_for_minute(180_000).is_none());
+    }
+
+    #[test]
+    fn test_qimb_thin_quote_excluded() {
+        let mut tracker = QuoteImbalanceTracker::new(1000, 60).with_min_quote_size(0.01);
+        let quote = |ts_ms, bid_sz, ask_sz| Quote {
+            ts_ms,
+            bid_px: 100.0,
+            bid_sz,
+            ask_px: 101.0,
+            ask_sz,
+        };
+
+        assert!(tracker.add_quote(&quote(60_000, 3.0, 1.0))); // qimb 0.5
+        assert!(!tracker.add_quote(&quote(60_500, 1.0, 0.001))); // thin ask, ~1.0
+        assert_eq!(tracker.rejected_quotes(), 1);
+        assert!((tracker.avg_for_minute(60_000) - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_resample_recomputes_normalized_flow() {
+        let mut agg = OrderFlowAggregator::new(100);
+        // Minutes 0-4 form one window, 5-6 the next
+        let flows = [(1.0, 9.0), (10.0, 0.0), (2.0, 2.0), (0.5, 0.0), (3.0, 1.0), (4.0, 4.0), (0.0, 2.0)];
+        for (i, (buy, sell)) in flows.iter().enumerate() {
+            let ts = i as i64 * 60_000 + 1000;
+            agg.add_trade(&make_classified(ts, *buy, TradeSide::Buy));
+            agg.add_trade(&make_classified(ts, *sell, TradeSide::Sell));
+        }
+        agg.add_trade(&make_classified(2 * 60_000 + 2000, 1.5, TradeSide::Ambiguous));
+
+        let windows = agg.resample(5);
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].0, 0);
+        assert_eq!(windows[1].0, 300_000);
+
+        let first = &windows[0].1;
+        let minutes: Vec<_> = (0..5).map(|i| agg.get_minute(i * 60_000).unwrap()).collect();
+        let buy: f64 = minutes.iter().map(|m| m.buy_volume).sum();
+        let sell: f64 = minutes.iter().map(|m| m.sell_volume).sum();
+        let total: f64 = minutes.iter().map(|m| m.total_volume).sum();
+        assert!((first.of_1m - (buy - sell)).abs() < 1e-10);
+        assert!((first.of_norm_1m - (buy - sell) / total).abs() < 1e-10);
+        assert!((first.ambiguous_frac - 1.5 / total).abs() < 1e-10);
+
+        // Averaging the per-minute ratios would give a different answer
+        let avg_norm: f64 = minutes.iter().map(|m| m.of_norm_1m).sum::<f64>() / 5.0;
+        assert!((first.of_norm_1m - avg_norm).abs() > 0.1);
+
+        let second = &windows[1].1;
+        assert!((second.of_norm_1m - (-2.0 / 10.0)).abs() < 1e-10);
+
+        assert!(agg.resample(0).is_empty());
+        assert_eq!(agg.resample(1).len(), 7);
+    }
+
+    #[test]
+    fn test_of_streak() {
+        let mut streak = OrderFlowStreak::new(10);
+        let flows = [0.2, 0.1, 0.5, -0.3, -0.1, 0.4, 0.0, -0.2];
+        let expected = [1, 2, 3, -1, -2, 1, 0, -1];
+        for (i, (&of, &want)) in flows.iter().zip(&expected).enumerate() {
+            assert_eq!(streak.update(i as i64 * 60_000, of), want, "minute {i}");
+        }
+        assert_eq!(streak.max_abs(), 3);
+
+        // Recomputing a minute does not extend the streak
+        assert_eq!(streak.update(7 * 60_000, -0.5), -1);
+
+        // The max rolls off with the window
+        let mut short = OrderFlowStreak::new(2);
+        for (i, of) in [0.1, 0.1, 0.1, -0.1, -0.1].iter().enumerate() {
+            short.update(i as i64 * 60_000, *of);
+        }
+        assert_eq!(short.current(), -2);
+        assert_eq!(short.max_abs(), 2);
+
+        short.clear();
+        assert_eq!(short.current(), 0);
+        assert_eq!(short.max_abs(), 0);
+    }
+
+    #[test]
+    fn test_mid_jump_tracker() {
+        let quote = |ts_ms, bid_px: f64| Quote { ts_ms, bid_px, bid_sz: 1.0, ask_px: bid_px + 1.0, ask_sz: 1.0 };
+        let mut tracker = MidJumpTracker::new(10);
+
+        assert_eq!(tracker.add_quote(&quote(60_000, 100.0)), None);
+        tracker.add_quote(&quote(61_000, 101.0));
+        tracker.add_quote(&quote(62_000, 96.0)); // flash move down 5
+        tracker.add_quote(&quote(63_000, 96.5));
+        // Crossed quote is skipped
+        assert_eq!(tracker.add_quote(&Quote { ts_ms: 64_000, bid_px: 90.0, bid_sz: 1.0, ask_px: 89.0, ask_sz: 1.0 }), None);
+        tracker.add_quote(&quote(120_000, 96.0));
+
+        assert!((tracker.max_for_minute(60_000) - 5.0).abs() < 1e-10);
+        assert!((tracker.max_for_minute(120_000) - 0.5).abs() < 1e-10);
+        assert_eq!(tracker.max_for_minute(180_000), 0.0);
+
+        tracker.clear();
+        assert_eq!(tracker.add_quote(&quote(180_000, 96.0)), None);
+    }
+
+    #[test]
+    fn test_ofi_canonical_cases() {
+        let quote = |bid: f64, bid_sz: f64, ask: f64, ask_sz: f64| Quote {
+            ts_ms: 1000,
+            bid_px: bid,
+            bid_sz,
+            ask_px: ask,
+            ask_sz,
+        };
+        let base = quote(100.0, 5.0, 101.0, 5.0);
+        let ofi = OrderFlowImbalanceTracker::increment;
+
+        // Bid size up at a stable bid: positive
+        assert_eq!(ofi(&base, &quote(100.0, 8.0, 101.0, 5.0)), 3.0);
+        // Bid size down at a stable bid: negative
+        assert_eq!(ofi(&base, &quote(100.0, 2.0, 101.0, 5.0)), -3.0);
+        // Ask size up at a stable ask: negative
+        assert_eq!(ofi(&base, &quote(100.0, 5.0, 101.0, 9.0)), -4.0);
+        // Bid ticks up: the whole new bid counts
+        assert_eq!(ofi(&base, &quote(100.5, 2.0, 101.0, 5.0)), 2.0);
+        // Bid ticks down: the whole old bid is removed
+        assert_eq!(ofi(&base, &quote(99.5, 7.0, 101.0, 5.0)), -5.0);
+        // Ask ticks up: the old ask was consumed
+        assert_eq!(ofi(&base, &quote(100.0, 5.0, 101.5, 1.0)), 5.0);
+        // Ask ticks down: new ask liquidity
+        assert_eq!(ofi(&base, &quote(100.0, 5.0, 100.5, 3.0)), -3.0);
+        // No change
+        assert_eq!(ofi(&base, &base), 0.0);
+    }
+
+    #[test]
+    fn test_ofi_per_minute() {
+        let mut tracker = OrderFlowImbalanceTracker::new(10);
+        let quote = |ts_ms: i64, bid_sz: f64| Quote {
+            ts_ms,
+            bid_px: 100.0,
+            bid_sz,
+            ask_px: 101.0,
+            ask_sz: 5.0,
+        };
+
+        assert!(tracker.add_quote(&quote(1000, 5.0)).is_none());
+        assert_eq!(tracker.add_quote(&quote(2000, 7.0)), Some(2.0));
+        assert_eq!(tracker.add_quote(&quote(3000, 10.0)), Some(3.0));
+        assert_eq!(tracker.add_quote(&quote(61_000, 4.0)), Some(-6.0));
+
+        assert_eq!(tracker.for_minute(0), 5.0);
+        assert_eq!(tracker.for_minute(60_000), -6.0);
+        assert_eq!(tracker.for_minute(120_000), 0.0);
+
+        tracker.clear();
+        assert!(tracker.add_quote(&quote(121_000, 9.0)).is_none());
+        assert_eq!(tracker.for_minute(60_000), 0.0);
+    }
+
     #[test]
     fn test_qimb_tracker() {
         let mut tracker = QuoteImbalanceTracker::new(1000, 60);
@@ -316,4 +1378,109 @@ mod tests {
         let avg = tracker.avg_for_minute(60_000);
         assert!((avg - 0.2).abs() < 1e-10); // (0.1 + 0.2 + 0.3) / 3 = 0.2
     }
+
+    #[test]
+    fn test_size_quantiles_and_large_threshold() {
+        let mut agg = OrderFlowAggregator::new(10);
+        assert!(agg.large_trade_threshold().is_none());
+        assert!(!agg.is_large_trade(1e9));
+
+        // Sizes 1..=1000 in a scrambled order (383 is coprime with 1000)
+        let sizes: Vec<f64> = (0..1000).map(|i| ((i * 383) % 1000 + 1) as f64).collect();
+        for (i, &size) in sizes.iter().enumerate() {
+            agg.add_trade(&make_classified(60_000 + i as i64, size, TradeSide::Buy));
+        }
+
+        let p95 = agg.size_p95().unwrap();
+        let p99 = agg.size_p99().unwrap();
+        assert!((p95 - 950.0).abs() < 20.0, "p95 = {p95}");
+        assert!((p99 - 990.0).abs() < 10.0, "p99 = {p99}");
+
+        // Default threshold follows the running p95
+        assert_eq!(agg.large_trade_threshold(), Some(p95));
+        assert!(agg.is_large_trade(980.0));
+        assert!(!agg.is_large_trade(500.0));
+
+        let fixed = OrderFlowAggregator::new(10).with_large_trade_threshold(LargeTradeThreshold::Fixed(5.0));
+        assert!(fixed.is_large_trade(5.0));
+
+        agg.clear();
+        assert!(agg.size_p95().is_none());
+    }
+
+    #[test]
+    fn test_large_threshold_follows_size_shift() {
+        let mut agg = OrderFlowAggregator::new(10);
+        // 100 trades a minute, sizes scrambled over 1..=100 times `scale`
+        let feed = |agg: &mut OrderFlowAggregator, minutes: std::ops::Range<i64>, scale: f64| {
+            for m in minutes {
+                for i in 0..100 {
+                    let size = ((i * 37) % 100 + 1) as f64 * scale;
+                    agg.add_trade(&make_classified(m * 60_000 + i, size, TradeSide::Buy));
+                }
+            }
+        };
+
+        // Thirty minutes of large trades, then twenty of small ones
+        feed(&mut agg, 0..30, 10.0);
+        let before = agg.large_trade_threshold().unwrap();
+        assert!((before - 950.0).abs() < 50.0, "p95 = {before}");
+
+        feed(&mut agg, 30..50, 1.0);
+        let after = agg.large_trade_threshold().unwrap();
+        assert!((after - 95.0).abs() < 5.0, "p95 = {after}");
+        assert!(agg.is_large_trade(99.0));
+    }
+
+    #[test]
+    fn test_edge_flow() {
+        let mut tracker = EdgeFlowTracker::new(10, 0.2);
+
+        // No edges yet: ignored
+        tracker.add_trade(&make_classified(60_000, 1.0, TradeSide::Buy));
+        assert_eq!(tracker.for_minute(60_000), Default::default());
+
+        tracker.set_edges(101.0, 99.0);
+        let at = |ts, price, size, side| {
+            let mut t = make_classified(ts, size, side);
+            t.trade.price = price;
+            t
+        };
+        tracker.add_trade(&at(120_000, 101.1, 1.0, TradeSide::Buy)); // VAH
+        tracker.add_trade(&at(120_100, 100.9, 0.5, TradeSide::Sell)); // VAH
+        tracker.add_trade(&at(120_200, 99.0, 2.0, TradeSide::Sell)); // VAL
+        tracker.add_trade(&at(120_300, 100.0, 5.0, TradeSide::Buy)); // Inside, away
+        tracker.add_trade(&at(120_400, 101.5, 5.0, TradeSide::Buy)); // Above, away
+        tracker.add_trade(&at(120_500, 99.0, 5.0, TradeSide::Ambiguous)); // Ambiguous
+
+        let (vah, val) = tracker.for_minute(120_000);
+        assert!((vah.buy_volume - 1.0).abs() < 1e-10);
+        assert!((vah.sell_volume - 0.5).abs() < 1e-10);
+        assert!((vah.net() - 0.5).abs() < 1e-10);
+        assert_eq!(val.buy_volume, 0.0);
+        assert!((val.net() + 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_qimb_update_rate() {
+        let mut slow = QuoteImbalanceTracker::with_update_rate(1000, 5, 1.0).unwrap();
+        let mut fast = QuoteImbalanceTracker::with_update_rate(1000, 5, 10.0).unwrap();
+
+        // 20 updates at -0.5, then 20 at +0.5
+        for i in 0..40 {
+            let v = if i < 20 { -0.5 } else { 0.5 };
+            slow.add(60_000 + i * 1000, v);
+            fast.add(60_000 + i * 1000, v);
+        }
+
+        // Span of 5 updates tracks the flip; span of 50 updates lags well behind
+        let ema_slow_rate = slow.ema_for_minute(60_000);
+        let ema_fast_rate = fast.ema_for_minute(60_000);
+        assert!(ema_slow_rate > 0.45);
+        assert!(ema_fast_rate < 0.2);
+
+        assert!(QuoteImbalanceTracker::with_update_rate(1000, 5, 0.0).is_err());
+        assert!(QuoteImbalanceTracker::with_update_rate(1000, 5, -1.0).is_err());
+        assert!(QuoteImbalanceTracker::with_update_rate(1000, 5, f64::NAN).is_err());
+    }
 }
@@ -0,0 +1,188 @@
+//! Streaming quantile estimation.
+//!
+//! Implements the P² algorithm (Jain & Chlamtac, 1985), which tracks a single
+//! quantile in constant memory using five markers whose heights are adjusted
+//! with piecewise-parabolic interpolation.
+
+/// Streaming estimator for a single quantile.
+#[derive(Debug, Clone)]
+pub struct P2Quantile {
+    /// Target quantile (0-1).
+    p: f64,
+    /// Marker heights.
+    heights: [f64; 5],
+    /// Actual marker positions (1-based).
+    positions: [f64; 5],
+    /// Desired marker positions.
+    desired: [f64; 5],
+    /// Desired position increments per observation.
+    increments: [f64; 5],
+    /// Observations seen.
+    count: usize,
+}
+
+impl P2Quantile {
+    /// Create an estimator for quantile `p` (clamped to 0-1).
+    pub fn new(p: f64) -> Self {
+        let p = p.clamp(0.0, 1.0);
+        Self {
+            p,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+            count: 0,
+        }
+    }
+
+    /// Target quantile.
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+
+    /// Number of observations.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Add an observation. Non-finite values are ignored.
+    pub fn add(&mut self, x: f64) {
+        if !x.is_finite() {
+            return;
+        }
+
+        if self.count < 5 {
+            self.heights[self.count] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(|a, b| a.total_cmp(b));
+            }
+            return;
+        }
+        self.count += 1;
+
+        // Find the cell containing x, extending the extremes if needed
+        let k = if x < self.heights[0] {
+            self.heights[0] = x;
+            0
+        } else if x >= self.heights[4] {
+            self.heights[4] = x;
+            3
+        } else {
+            (1..5).find(|&i| x < self.heights[i]).unwrap_or(4) - 1
+        };
+
+        for pos in &mut self.positions[k + 1..] {
+            *pos += 1.0;
+        }
+        for (desired, inc) in self.desired.iter_mut().zip(&self.increments) {
+            *desired += inc;
+        }
+
+        // Adjust the three middle markers
+        for i in 1..4 {
+            let d = self.desired[i] - self.positions[i];
+            let room_up = self.positions[i + 1] - self.positions[i] > 1.0;
+            let room_down = self.positions[i - 1] - self.positions[i] < -1.0;
+            if (d >= 1.0 && room_up) || (d <= -1.0 && room_down) {
+                let d = d.signum();
+                let candidate = self.parabolic(i, d);
+                self.heights[i] = if self.heights[i - 1] < candidate && candidate < self.heights[i + 1] {
+                    candidate
+                } else {
+                    self.linear(i, d)
+                };
+                self.positions[i] += d;
+            }
+        }
+    }
+
+    /// Current estimate (exact for fewer than five observations).
+    pub fn value(&self) -> Option<f64> {
+        match self.count {
+            0 => None,
+            n if n < 5 => {
+                let mut seen = self.heights[..n].to_vec();
+                seen.sort_by(|a, b| a.total_cmp(b));
+                let idx = (self.p * (n - 1) as f64).round() as usize;
+                Some(seen[idx])
+            }
+            _ => Some(self.heights[2]),
+        }
+    }
+
+    /// Reset to the empty state.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.p);
+    }
+
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let j = if d > 0.0 { i + 1 } else { i - 1 };
+        self.heights[i] + d * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic uniform(0, 1) samples.
+    fn uniform_samples(n: usize) -> Vec<f64> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        (0..n)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_small_sample_exact() {
+        let mut est = P2Quantile::new(0.5);
+        assert!(est.value().is_none());
+
+        for x in [3.0, 1.0, 2.0] {
+            est.add(x);
+        }
+        assert_eq!(est.value(), Some(2.0));
+    }
+
+    #[test]
+    fn test_exponential_quantiles() {
+        // Exponential(1) sizes: true quantile is -ln(1 - p)
+        let sizes: Vec<f64> = uniform_samples(20_000).iter().map(|u| -(1.0 - u).ln()).collect();
+
+        for p in [0.5, 0.95, 0.99] {
+            let mut est = P2Quantile::new(p);
+            for &x in &sizes {
+                est.add(x);
+            }
+            let truth = -(1.0 - p).ln();
+            let got = est.value().unwrap();
+            assert!(
+                (got - truth).abs() / truth < 0.05,
+                "p={p}: estimated {got}, true {truth}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut est = P2Quantile::new(0.95);
+        for x in uniform_samples(100) {
+            est.add(x);
+        }
+        est.reset();
+        assert_eq!(est.count(), 0);
+        assert!(est.value().is_none());
+        assert_eq!(est.p(), 0.95);
+    }
+}
@@ -1,10 +1,16 @@
 //! Value Area computation (POC, VAH, VAL).
 //!
 //! Computes Point of Control and Value Area boundaries from a volume histogram.
+//!
+//! Histograms are keyed by bin lower edge. VAL is the lower edge of the
+//! lowest VA bin and VAH the upper edge of the highest, so the VA covers
+//! `[VAL, VAH)`. The POC is the mid-point of its bin by default, or its
+//! lower edge under [`PocConvention::LowerEdge`].
 
-use auction_core::ValueArea;
+use auction_core::{Error, PocConvention, Result, TimestampMs, ValueArea, VaMigration, VaMigrationDirection};
 use ordered_float::OrderedFloat;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::ops::Bound;
 
 /// Configuration for Value Area computation.
 #[derive(Debug, Clone)]
@@ -13,6 +19,10 @@ pub struct ValueAreaConfig {
     pub va_fraction: f64,
     /// Minimum number of bins for valid VA.
     pub min_bins: u32,
+    /// Bins on each side of the POC included in the smoothed POC (0 = raw POC).
+    pub poc_smoothing_bins: u32,
+    /// Where in its bin the POC (and smoothed POC) is reported.
+    pub poc_convention: PocConvention,
 }
 
 impl Default for ValueAreaConfig {
@@ -20,6 +30,8 @@ impl Default for ValueAreaConfig {
         Self {
             va_fraction: 0.70,
             min_bins: 20,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
         }
     }
 }
@@ -38,14 +50,17 @@ impl ValueAreaComputer {
     /// Compute Value Area from a histogram.
     ///
     /// The histogram should be keyed by bin price (lower edge) with volume values.
+    /// If the POC bin alone reaches the target coverage the VA stays valid but
+    /// collapses to that bin, and `single_bin_va` is set so consumers can
+    /// detect the concentration case.
     pub fn compute(&self, histogram: &BTreeMap<OrderedFloat<f64>, f64>, bin_width: f64) -> ValueArea {
         // Check minimum bins
         if histogram.len() < self.config.min_bins as usize {
             return ValueArea::invalid();
         }
 
-        // Calculate total volume
-        let total_volume: f64 = histogram.values().sum();
+        // Calculate total volume (negative float residue counts as empty)
+        let total_volume: f64 = histogram.values().map(|v| v.max(0.0)).sum();
         if total_volume <= 0.0 {
             return ValueArea::invalid();
         }
@@ -57,64 +72,612 @@ impl ValueAreaComputer {
             .map(|(k, v)| (k.0, *v))
             .unwrap_or((0.0, 0.0));
 
+        self.expand(histogram, poc_bin, poc_volume, total_volume, bin_width).va
+    }
+
+    /// Expand the VA outward from the POC bin.
+    ///
+    /// Walks the histogram from the POC, so the cost scales with the bins in
+    /// the VA rather than the whole histogram.
+    fn expand(
+        &self,
+        histogram: &BTreeMap<OrderedFloat<f64>, f64>,
+        poc_bin: f64,
+        poc_volume: f64,
+        total_volume: f64,
+        bin_width: f64,
+    ) -> Expansion {
         // Target volume for VA
         let target_volume = total_volume * self.config.va_fraction;
 
-        // Get sorted bins for expansion
-        let bins: Vec<(f64, f64)> = histogram
-            .iter()
-            .map(|(k, v)| (k.0, *v))
-            .collect();
-
-        // Find POC index
-        let poc_idx = bins.iter().position(|(p, _)| (*p - poc_bin).abs() < 1e-10).unwrap_or(0);
-
         // Expand outward from POC
         let mut cumulative_volume = poc_volume;
-        let mut low_idx = poc_idx;
-        let mut high_idx = poc_idx;
+        let mut low = poc_bin;
+        let mut high = poc_bin;
         let mut included_bins = 1u32;
+        let mut before_last = 0.0;
 
         while cumulative_volume < target_volume {
             // Look at next candidates
-            let next_low = if low_idx > 0 { Some(low_idx - 1) } else { None };
-            let next_high = if high_idx < bins.len() - 1 { Some(high_idx + 1) } else { None };
+            let next_low = histogram.range(..OrderedFloat(low)).next_back();
+            let next_high = histogram
+                .range((Bound::Excluded(OrderedFloat(high)), Bound::Unbounded))
+                .next();
 
             // Choose the one with higher volume (expand to higher-volume adjacent bin)
             let expand_low = match (next_low, next_high) {
-                (Some(l), Some(h)) => bins[l].1 >= bins[h].1,
+                (Some(l), Some(h)) => l.1 >= h.1,
                 (Some(_), None) => true,
                 (None, Some(_)) => false,
                 (None, None) => break, // Can't expand further
             };
 
+            before_last = cumulative_volume;
+            let (key, volume) = if expand_low { next_low } else { next_high }.unwrap();
             if expand_low {
-                low_idx = next_low.unwrap();
-                cumulative_volume += bins[low_idx].1;
+                low = key.0;
             } else {
-                high_idx = next_high.unwrap();
-                cumulative_volume += bins[high_idx].1;
+                high = key.0;
             }
+            cumulative_volume += volume;
             included_bins += 1;
         }
 
         // VA boundaries
-        let val = bins[low_idx].0;
-        let vah = bins[high_idx].0 + bin_width; // VAH is upper edge of highest bin
+        let val = low;
+        let vah = high + bin_width; // VAH is upper edge of highest bin
 
         // Coverage achieved
-        let coverage = cumulative_volume / total_volume;
+        let coverage = (cumulative_volume / total_volume).clamp(0.0, 1.0);
 
-        ValueArea {
-            poc: poc_bin + bin_width / 2.0, // POC is mid-point of bin
+        let poc = poc_bin + self.poc_offset(bin_width);
+        let poc_smoothed = self.smoothed_poc(histogram, poc_bin, bin_width);
+
+        let va = ValueArea {
+            poc,
             vah,
             val,
+            poc_raw: poc,
+            poc_smoothed,
+            vah_raw: vah,
+            val_raw: val,
             coverage,
             bin_count: included_bins,
             total_volume,
             bin_width,
             is_valid: true,
+            single_bin_va: included_bins == 1,
+        };
+
+        Expansion {
+            va,
+            poc_key: poc_bin,
+            low_key: low,
+            high_key: high,
+            va_volume: cumulative_volume,
+            before_last,
+        }
+    }
+
+    /// Offset of the reported POC from its bin's lower edge.
+    fn poc_offset(&self, bin_width: f64) -> f64 {
+        match self.config.poc_convention {
+            PocConvention::Midpoint => bin_width / 2.0,
+            PocConvention::LowerEdge => 0.0,
+        }
+    }
+
+    /// Volume-weighted bin price (per `poc_convention`) over the POC bin and
+    /// its neighbours within `poc_smoothing_bins` bin widths.
+    fn smoothed_poc(&self, histogram: &BTreeMap<OrderedFloat<f64>, f64>, poc_bin: f64, bin_width: f64) -> f64 {
+        let reach = self.smoothing_reach(bin_width);
+        let offset = self.poc_offset(bin_width);
+
+        let (weighted, volume) = histogram
+            .range(OrderedFloat(poc_bin - reach - bin_width)..=OrderedFloat(poc_bin + reach + bin_width))
+            .filter(|(p, _)| (p.0 - poc_bin).abs() <= reach)
+            .fold((0.0, 0.0), |(w, v), (p, vol)| {
+                (w + (p.0 + offset) * vol, v + vol)
+            });
+
+        if volume > 0.0 {
+            weighted / volume
+        } else {
+            poc_bin + offset
+        }
+    }
+
+    /// Distance from the POC bin covered by the smoothed POC.
+    fn smoothing_reach(&self, bin_width: f64) -> f64 {
+        self.config.poc_smoothing_bins as f64 * bin_width + bin_width * 1e-6
+    }
+
+    /// Compute Value Area from externally computed `(price, volume)` pairs.
+    ///
+    /// Prices are bin lower edges and must be strictly increasing and lie on
+    /// a common grid of `bin_width` (empty bins may be omitted). Keys are
+    /// snapped onto the grid anchored at the first price before computing.
+    pub fn compute_from_pairs(&self, pairs: &[(f64, f64)], bin_width: f64) -> Result<ValueArea> {
+        if !(bin_width.is_finite() && bin_width > 0.0) {
+            return Err(Error::value_area(format!("invalid bin width: {}", bin_width)));
+        }
+
+        let Some(&(origin, _)) = pairs.first() else {
+            return Ok(ValueArea::invalid());
+        };
+
+        let mut histogram = BTreeMap::new();
+        let mut prev_steps: Option<i64> = None;
+
+        for &(price, volume) in pairs {
+            if !price.is_finite() || !volume.is_finite() || volume < 0.0 {
+                return Err(Error::value_area(format!(
+                    "invalid bin ({}, {})",
+                    price, volume
+                )));
+            }
+
+            let steps_f = (price - origin) / bin_width;
+            let steps = steps_f.round();
+            if (steps_f - steps).abs() > 1e-6 {
+                return Err(Error::value_area(format!(
+                    "price {} is not aligned to bin width {} from {}",
+                    price, bin_width, origin
+                )));
+            }
+
+            let steps = steps as i64;
+            if prev_steps.is_some_and(|prev| steps <= prev) {
+                return Err(Error::value_area(format!(
+                    "prices must be strictly increasing (at {})",
+                    price
+                )));
+            }
+            prev_steps = Some(steps);
+
+            histogram.insert(OrderedFloat(origin + steps as f64 * bin_width), volume);
+        }
+
+        Ok(self.compute(&histogram, bin_width))
+    }
+}
+
+/// Result of a VA expansion with the state needed to check whether it still holds.
+#[derive(Debug, Clone)]
+struct Expansion {
+    va: ValueArea,
+    /// POC bin key.
+    poc_key: f64,
+    /// Lowest and highest bin keys inside the VA.
+    low_key: f64,
+    high_key: f64,
+    /// Volume inside the VA.
+    va_volume: f64,
+    /// VA volume before the last expansion step (0 for a single-bin VA).
+    before_last: f64,
+}
+
+/// Value Area maintained from histogram deltas without rescanning the
+/// histogram.
+///
+/// The POC is tracked in a volume-ordered index, and a changed VA is
+/// re-expanded by walking outward from it. The previous expansion is reused
+/// as is when the changes cannot alter it: the POC is unchanged, every
+/// changed bin lies beyond the bins adjacent to the VA and outside the POC
+/// smoothing reach, and the new target volume is still first reached on the
+/// same expansion step. The result matches [`ValueAreaComputer::compute`]
+/// on the same histogram (total volume is kept as a running sum).
+pub struct IncrementalValueArea {
+    computer: ValueAreaComputer,
+    bin_width: f64,
+    /// Volume by bin key.
+    histogram: BTreeMap<OrderedFloat<f64>, f64>,
+    /// `(volume, bin key)` for every bin; the last entry is the POC.
+    by_volume: BTreeSet<(OrderedFloat<f64>, OrderedFloat<f64>)>,
+    total_volume: f64,
+    /// Bin keys changed since the last result.
+    changed: BTreeSet<OrderedFloat<f64>>,
+    /// Last expansion (None if the VA was invalid).
+    last: Option<Expansion>,
+    /// Whether `last` is up to date apart from `changed`.
+    computed: bool,
+    reexpansions: u64,
+    reused: u64,
+}
+
+impl IncrementalValueArea {
+    /// Create an empty incremental VA at `bin_width`.
+    pub fn new(config: ValueAreaConfig, bin_width: f64) -> Self {
+        Self {
+            computer: ValueAreaComputer::new(config),
+            bin_width,
+            histogram: BTreeMap::new(),
+            by_volume: BTreeSet::new(),
+            total_volume: 0.0,
+            changed: BTreeSet::new(),
+            last: None,
+            computed: false,
+            reexpansions: 0,
+            reused: 0,
+        }
+    }
+
+    /// Current bin width.
+    pub fn bin_width(&self) -> f64 {
+        self.bin_width
+    }
+
+    /// Current volume by bin key.
+    pub fn histogram(&self) -> &BTreeMap<OrderedFloat<f64>, f64> {
+        &self.histogram
+    }
+
+    /// Replace the histogram, e.g. after a rebucket.
+    pub fn reset(&mut self, histogram: BTreeMap<OrderedFloat<f64>, f64>, bin_width: f64) {
+        self.by_volume = histogram.iter().map(|(&k, &v)| (OrderedFloat(v), k)).collect();
+        self.total_volume = histogram.values().sum();
+        self.histogram = histogram;
+        self.bin_width = bin_width;
+        self.changed.clear();
+        self.last = None;
+        self.computed = false;
+    }
+
+    /// Add volume at `price` (negative to remove).
+    pub fn apply(&mut self, price: f64, volume: f64) {
+        let key = OrderedFloat((price / self.bin_width).floor() * self.bin_width);
+        let entry = self.histogram.entry(key).or_insert(0.0);
+        self.by_volume.remove(&(OrderedFloat(*entry), key));
+        *entry += volume;
+        self.total_volume += volume;
+        if *entry <= 1e-10 {
+            // Drop the residue from the total too so it cannot drift
+            self.total_volume -= *entry;
+            self.histogram.remove(&key);
+        } else {
+            self.by_volume.insert((OrderedFloat(*entry), key));
+        }
+        self.total_volume = self.total_volume.max(0.0);
+        self.changed.insert(key);
+    }
+
+    /// Current Value Area.
+    pub fn value_area(&mut self) -> ValueArea {
+        if !self.computed || !self.changed.is_empty() {
+            self.update();
+            self.changed.clear();
+            self.computed = true;
+        }
+
+        self.last
+            .as_ref()
+            .map_or_else(ValueArea::invalid, |expansion| expansion.va.clone())
+    }
+
+    /// Bring `last` up to date with the pending changes.
+    fn update(&mut self) {
+        let config = &self.computer.config;
+        if self.histogram.len() < config.min_bins as usize || self.total_volume <= 0.0 {
+            self.last = None;
+            return;
+        }
+
+        // Ties go to the highest-priced bin, as in `compute`
+        let Some(&(poc_volume, poc_key)) = self.by_volume.last() else {
+            self.last = None;
+            return;
+        };
+
+        if self.computed && self.can_reuse(poc_key.0) {
+            if let Some(last) = self.last.as_mut() {
+                last.va.total_volume = self.total_volume;
+                last.va.coverage = (last.va_volume / self.total_volume).clamp(0.0, 1.0);
+            }
+            self.reused += 1;
+        } else {
+            self.last = Some(self.computer.expand(
+                &self.histogram,
+                poc_key.0,
+                poc_volume.0,
+                self.total_volume,
+                self.bin_width,
+            ));
+            self.reexpansions += 1;
+        }
+    }
+
+    /// Whether the last expansion still holds after the pending changes.
+    fn can_reuse(&self, poc_key: f64) -> bool {
+        let Some(last) = &self.last else {
+            return false;
+        };
+        if poc_key != last.poc_key {
+            return false;
+        }
+
+        // Each expansion step only compares the bins adjacent to the range
+        // so far, so changes must lie strictly beyond those neighbours
+        let lower_guard = self
+            .histogram
+            .range(..OrderedFloat(last.low_key))
+            .next_back()
+            .map(|(k, _)| k.0);
+        let upper_guard = self
+            .histogram
+            .range((Bound::Excluded(OrderedFloat(last.high_key)), Bound::Unbounded))
+            .next()
+            .map(|(k, _)| k.0);
+        let reach = self.computer.smoothing_reach(self.bin_width);
+
+        let unaffected = self.changed.iter().all(|key| {
+            let k = key.0;
+            let beyond = lower_guard.is_some_and(|g| k < g) || upper_guard.is_some_and(|g| k > g);
+            beyond && (k - poc_key).abs() > reach
+        });
+        if !unaffected {
+            return false;
+        }
+
+        // The new target must still be first reached on the same step
+        let target = self.total_volume * self.computer.config.va_fraction;
+        last.va_volume >= target && (last.va.bin_count == 1 || last.before_last < target)
+    }
+
+    /// Updates that re-expanded the VA.
+    pub fn reexpansions(&self) -> u64 {
+        self.reexpansions
+    }
+
+    /// Updates that reused the previous expansion.
+    pub fn reused(&self) -> u64 {
+        self.reused
+    }
+
+    /// Clear all data.
+    pub fn clear(&mut self) {
+        self.reset(BTreeMap::new(), self.bin_width);
+    }
+}
+
+/// Compute how the Value Area migrated from `prev` to `curr`.
+///
+/// Returns `None` if either VA is invalid or `prev` has no width.
+pub fn va_migration(prev: &ValueArea, curr: &ValueArea) -> Option<VaMigration> {
+    let prev_width = prev.vah - prev.val;
+    if !prev.is_valid || !curr.is_valid || prev_width <= 0.0 {
+        return None;
+    }
+
+    let direction = if curr.val > prev.val && curr.vah > prev.vah {
+        VaMigrationDirection::Up
+    } else if curr.val < prev.val && curr.vah < prev.vah {
+        VaMigrationDirection::Down
+    } else {
+        VaMigrationDirection::Overlap
+    };
+
+    let overlap = (curr.vah.min(prev.vah) - curr.val.max(prev.val)).max(0.0);
+
+    Some(VaMigration {
+        direction,
+        poc_shift: (curr.poc - prev.poc) / prev_width,
+        overlap_frac: (overlap / prev_width).min(1.0),
+    })
+}
+
+/// Shannon entropy (nats) of the normalized bin volumes.
+///
+/// 0 when all volume sits in one bin, `ln(n)` for a flat profile over `n`
+/// bins. `None` for an empty histogram.
+pub fn profile_entropy(histogram: &BTreeMap<OrderedFloat<f64>, f64>) -> Option<f64> {
+    let total: f64 = histogram.values().map(|v| v.max(0.0)).sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let entropy = histogram
+        .values()
+        .map(|&v| v / total)
+        .filter(|&p| p > 0.0)
+        .map(|p| -p * p.ln())
+        .sum::<f64>();
+    Some(entropy.max(0.0))
+}
+
+/// [`profile_entropy`] divided by `ln(n)` over the `n` non-empty bins.
+///
+/// In `[0, 1]` regardless of how many bins the profile spans: 0 for a single
+/// bin, 1 for a flat profile. `None` for an empty histogram.
+pub fn normalized_profile_entropy(histogram: &BTreeMap<OrderedFloat<f64>, f64>) -> Option<f64> {
+    let entropy = profile_entropy(histogram)?;
+    let bins = histogram.values().filter(|&&v| v > 0.0).count();
+    if bins <= 1 {
+        return Some(0.0);
+    }
+    Some((entropy / (bins as f64).ln()).min(1.0))
+}
+
+/// Counts bars whose range touched a VA level within a rolling window.
+///
+/// The count is reset when the level moves more than `reset_distance`
+/// from the level the current tests were counted against.
+#[derive(Debug, Clone)]
+pub struct LevelTestCounter {
+    /// Level the current tests refer to.
+    anchor: Option<f64>,
+    /// Minutes in which the level was tested.
+    tests: VecDeque<TimestampMs>,
+    /// Window length in ms.
+    window_ms: i64,
+    /// Level move that starts a new count.
+    reset_distance: f64,
+}
+
+impl LevelTestCounter {
+    /// Create a counter over `window_minutes` minutes.
+    pub fn new(window_minutes: usize, reset_distance: f64) -> Self {
+        Self {
+            anchor: None,
+            tests: VecDeque::new(),
+            window_ms: window_minutes as i64 * 60_000,
+            reset_distance,
+        }
+    }
+
+    /// Record the bar `[low, high]` of minute `ts_min` against `level` and
+    /// return the number of tests in the window.
+    ///
+    /// Recording the same minute twice counts at most one test.
+    pub fn update(&mut self, ts_min: TimestampMs, level: f64, low: f64, high: f64) -> u32 {
+        match self.anchor {
+            Some(anchor) if (level - anchor).abs() <= self.reset_distance => {}
+            _ => {
+                self.anchor = Some(level);
+                self.tests.clear();
+            }
+        }
+
+        while self.tests.front().is_some_and(|&ts| ts <= ts_min - self.window_ms) {
+            self.tests.pop_front();
+        }
+
+        if low <= level && level <= high && self.tests.back().is_none_or(|&ts| ts < ts_min) {
+            self.tests.push_back(ts_min);
         }
+        self.count()
+    }
+
+    /// Number of tests in the window.
+    pub fn count(&self) -> u32 {
+        self.tests.len() as u32
+    }
+
+    /// Clear all data.
+    pub fn clear(&mut self) {
+        self.anchor = None;
+        self.tests.clear();
+    }
+}
+
+/// Tracks whether accepted VA breaks continued or failed.
+///
+/// A break is accepted on the `accept_k`-th consecutive close beyond the same
+/// edge. It fails if a close comes back across that edge within
+/// `horizon_bars` bars and succeeds otherwise.
+#[derive(Debug, Clone)]
+pub struct BreakoutOutcomeTracker {
+    /// Consecutive closes for acceptance.
+    accept_k: u32,
+    /// Bars a break must hold to count as a success.
+    horizon_bars: u32,
+    /// Resolved outcomes kept for the rate.
+    window: usize,
+    /// Consecutive closes above VAH / below VAL.
+    closes_above: u32,
+    closes_below: u32,
+    /// Unresolved breaks: (upward, edge, bars since acceptance).
+    pending: Vec<(bool, f64, u32)>,
+    /// Recent outcomes (true = continuation), oldest first.
+    outcomes: VecDeque<bool>,
+    /// Minute of the last update.
+    last_ts: Option<TimestampMs>,
+}
+
+impl BreakoutOutcomeTracker {
+    /// Create a tracker keeping the last `window` outcomes.
+    pub fn new(accept_k: u32, horizon_bars: u32, window: usize) -> Self {
+        Self {
+            accept_k: accept_k.max(1),
+            horizon_bars,
+            window: window.max(1),
+            closes_above: 0,
+            closes_below: 0,
+            pending: Vec::new(),
+            outcomes: VecDeque::new(),
+            last_ts: None,
+        }
+    }
+
+    /// Record the close of minute `ts_min` against `va` and return the
+    /// success rate.
+    ///
+    /// Recording the same minute twice has no further effect.
+    pub fn update(&mut self, ts_min: TimestampMs, va: &ValueArea, close: f64) -> Option<f64> {
+        if self.last_ts.is_some_and(|ts| ts >= ts_min) {
+            return self.success_rate();
+        }
+        self.last_ts = Some(ts_min);
+
+        // Resolve open breaks before counting this close towards a new one
+        let horizon = self.horizon_bars;
+        let mut resolved = Vec::new();
+        self.pending.retain_mut(|(upward, edge, bars)| {
+            *bars += 1;
+            let reverted = if *upward { close < *edge } else { close > *edge };
+            if reverted || *bars >= horizon {
+                resolved.push(!reverted);
+                false
+            } else {
+                true
+            }
+        });
+        for outcome in resolved {
+            self.record(outcome);
+        }
+
+        if !va.is_valid {
+            self.closes_above = 0;
+            self.closes_below = 0;
+            return self.success_rate();
+        }
+
+        if close > va.vah {
+            self.closes_above += 1;
+            self.closes_below = 0;
+            if self.closes_above == self.accept_k {
+                self.pending.push((true, va.vah, 0));
+            }
+        } else if close < va.val {
+            self.closes_below += 1;
+            self.closes_above = 0;
+            if self.closes_below == self.accept_k {
+                self.pending.push((false, va.val, 0));
+            }
+        } else {
+            self.closes_above = 0;
+            self.closes_below = 0;
+        }
+        self.success_rate()
+    }
+
+    fn record(&mut self, success: bool) {
+        self.outcomes.push_back(success);
+        if self.outcomes.len() > self.window {
+            self.outcomes.pop_front();
+        }
+    }
+
+    /// Fraction of recent resolved breaks that continued (None before any).
+    pub fn success_rate(&self) -> Option<f64> {
+        if self.outcomes.is_empty() {
+            return None;
+        }
+        let successes = self.outcomes.iter().filter(|&&s| s).count();
+        Some(successes as f64 / self.outcomes.len() as f64)
+    }
+
+    /// Number of resolved outcomes in the window.
+    pub fn outcome_count(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Clear all data.
+    pub fn clear(&mut self) {
+        self.closes_above = 0;
+        self.closes_below = 0;
+        self.pending.clear();
+        self.outcomes.clear();
+        self.last_ts = None;
     }
 }
 
@@ -131,6 +694,8 @@ mod tests {
         let computer = ValueAreaComputer::new(ValueAreaConfig {
             va_fraction: 0.70,
             min_bins: 3,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
         });
 
         // Symmetric histogram around 100
@@ -149,11 +714,119 @@ mod tests {
         assert!((va.total_volume - 500.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_smoothed_poc_twin_peaks() {
+        let computer = ValueAreaComputer::new(ValueAreaConfig {
+            va_fraction: 0.70,
+            min_bins: 3,
+            poc_smoothing_bins: 1,
+            poc_convention: PocConvention::Midpoint,
+        });
+
+        // Two near-equal peaks at 100 and 101; noise flips which one is max
+        let left = make_histogram(&[
+            (98.0, 40.0),
+            (99.0, 120.0),
+            (100.0, 200.0),
+            (101.0, 195.0),
+            (102.0, 115.0),
+            (103.0, 45.0),
+        ]);
+        let right = make_histogram(&[
+            (98.0, 40.0),
+            (99.0, 120.0),
+            (100.0, 195.0),
+            (101.0, 200.0),
+            (102.0, 115.0),
+            (103.0, 45.0),
+        ]);
+
+        let va_left = computer.compute(&left, 1.0);
+        let va_right = computer.compute(&right, 1.0);
+
+        // Raw POC jumps a full bin
+        assert!((va_left.poc - 100.5).abs() < 1e-10);
+        assert!((va_right.poc - 101.5).abs() < 1e-10);
+
+        // Smoothed POC stays between the peak bins and moves less than a bin
+        for va in [&va_left, &va_right] {
+            assert!(va.poc_smoothed > 100.5 && va.poc_smoothed < 101.5);
+        }
+        let raw_jump = va_right.poc - va_left.poc;
+        let smoothed_jump = va_right.poc_smoothed - va_left.poc_smoothed;
+        assert!(smoothed_jump.abs() < 0.75 * raw_jump.abs());
+    }
+
+    #[test]
+    fn test_smoothing_disabled_matches_raw_poc() {
+        let computer = ValueAreaComputer::new(ValueAreaConfig {
+            va_fraction: 0.70,
+            min_bins: 3,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
+        });
+        let hist = make_histogram(&[(99.0, 50.0), (100.0, 200.0), (101.0, 150.0)]);
+
+        let va = computer.compute(&hist, 1.0);
+        assert!((va.poc_smoothed - va.poc).abs() < 1e-10);
+    }
+
+    fn make_va(val: f64, poc: f64, vah: f64) -> ValueArea {
+        ValueArea {
+            poc,
+            vah,
+            val,
+            is_valid: true,
+            ..ValueArea::invalid()
+        }
+    }
+
+    #[test]
+    fn test_va_migration_up() {
+        let prev = make_va(100.0, 105.0, 110.0);
+        let curr = make_va(106.0, 112.0, 116.0);
+
+        let m = va_migration(&prev, &curr).unwrap();
+        assert_eq!(m.direction, VaMigrationDirection::Up);
+        assert!((m.poc_shift - 0.7).abs() < 1e-10);
+        assert!((m.overlap_frac - 0.4).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_va_migration_down() {
+        let prev = make_va(100.0, 105.0, 110.0);
+        let curr = make_va(85.0, 90.0, 95.0);
+
+        let m = va_migration(&prev, &curr).unwrap();
+        assert_eq!(m.direction, VaMigrationDirection::Down);
+        assert!((m.poc_shift - (-1.5)).abs() < 1e-10);
+        assert!(m.overlap_frac.abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_va_migration_overlap() {
+        let prev = make_va(100.0, 105.0, 110.0);
+
+        let m = va_migration(&prev, &prev).unwrap();
+        assert_eq!(m.direction, VaMigrationDirection::Overlap);
+        assert!(m.poc_shift.abs() < 1e-10);
+        assert!((m.overlap_frac - 1.0).abs() < 1e-10);
+
+        // Wider VA containing the previous one is still balance
+        let m = va_migration(&prev, &make_va(98.0, 105.0, 112.0)).unwrap();
+        assert_eq!(m.direction, VaMigrationDirection::Overlap);
+        assert!((m.overlap_frac - 1.0).abs() < 1e-10);
+
+        assert!(va_migration(&ValueArea::invalid(), &prev).is_none());
+    }
+
     #[test]
     fn test_asymmetric_va() {
         let computer = ValueAreaComputer::new(ValueAreaConfig {
             va_fraction: 0.70,
             min_bins: 3,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
         });
 
         // Asymmetric histogram (more volume above POC)
@@ -172,11 +845,75 @@ mod tests {
         assert!((va.poc - 100.5).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_single_bin_va() {
+        let computer = ValueAreaComputer::new(ValueAreaConfig {
+            va_fraction: 0.70,
+            min_bins: 3,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
+        });
+
+        // Spike profile: POC holds 90% of volume
+        let hist = make_histogram(&[
+            (99.0, 5.0),
+            (100.0, 90.0), // POC
+            (101.0, 5.0),
+        ]);
+
+        let va = computer.compute(&hist, 1.0);
+
+        assert!(va.is_valid);
+        assert!(va.single_bin_va);
+        assert_eq!(va.bin_count, 1);
+        assert!((va.val - 100.0).abs() < 1e-10);
+        assert!((va.vah - 101.0).abs() < 1e-10);
+        assert!((va.coverage - 0.9).abs() < 1e-10);
+
+        // A normal profile is not flagged
+        let hist = make_histogram(&[(99.0, 30.0), (100.0, 40.0), (101.0, 30.0)]);
+        assert!(!computer.compute(&hist, 1.0).single_bin_va);
+    }
+
+    #[test]
+    fn test_round_to_tick() {
+        let computer = ValueAreaComputer::new(ValueAreaConfig {
+            va_fraction: 0.70,
+            min_bins: 3,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
+        });
+        let tick = 0.1;
+        let on_grid = |v: f64| ((v / tick) - (v / tick).round()).abs() < 1e-9;
+
+        // Bin width of 0.25 is not a multiple of the 0.1 tick
+        let hist = make_histogram(&[
+            (100.0, 50.0),
+            (100.25, 100.0),
+            (100.5, 200.0), // POC, mid-point 100.625
+            (100.75, 100.0),
+            (101.0, 50.0),
+        ]);
+
+        let va = computer.compute(&hist, 0.25).round_to_tick(tick);
+
+        assert!((va.poc_raw - 100.625).abs() < 1e-10);
+        assert!(!on_grid(va.poc_raw));
+        assert!(on_grid(va.poc));
+        assert!(on_grid(va.vah));
+        assert!(on_grid(va.val));
+        assert!((va.poc - va.poc_raw).abs() <= tick / 2.0 + 1e-10);
+        assert!((va.vah - va.vah_raw).abs() <= tick / 2.0 + 1e-10);
+        assert!((va.val - va.val_raw).abs() <= tick / 2.0 + 1e-10);
+    }
+
     #[test]
     fn test_insufficient_bins() {
         let computer = ValueAreaComputer::new(ValueAreaConfig {
             va_fraction: 0.70,
             min_bins: 20,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
         });
 
         let hist = make_histogram(&[
@@ -193,6 +930,8 @@ mod tests {
         let computer = ValueAreaComputer::new(ValueAreaConfig {
             va_fraction: 0.70,
             min_bins: 3,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
         });
 
         // POC at lower edge
@@ -215,6 +954,8 @@ mod tests {
         let computer = ValueAreaComputer::new(ValueAreaConfig {
             va_fraction: 0.70,
             min_bins: 3,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
         });
 
         let hist = make_histogram(&[
@@ -232,6 +973,151 @@ mod tests {
         assert!(va.coverage >= 0.70);
     }
 
+    #[test]
+    fn test_compute_from_pairs() {
+        let computer = ValueAreaComputer::new(ValueAreaConfig {
+            va_fraction: 0.70,
+            min_bins: 3,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
+        });
+
+        let pairs = [
+            (98.0, 50.0),
+            (99.0, 100.0),
+            (100.0, 200.0),
+            (101.0, 100.0),
+            (102.0, 50.0),
+        ];
+
+        let va = computer.compute_from_pairs(&pairs, 1.0).unwrap();
+        let expected = computer.compute(&make_histogram(&pairs), 1.0);
+
+        assert!(va.is_valid);
+        assert!((va.poc - expected.poc).abs() < 1e-10);
+        assert!((va.vah - expected.vah).abs() < 1e-10);
+        assert!((va.val - expected.val).abs() < 1e-10);
+
+        // Gaps on the grid are allowed
+        let gapped = [(0.1, 10.0), (0.3, 30.0), (0.4, 20.0)];
+        assert!(computer.compute_from_pairs(&gapped, 0.1).unwrap().is_valid);
+    }
+
+    #[test]
+    fn test_compute_from_pairs_malformed() {
+        let computer = ValueAreaComputer::new(ValueAreaConfig {
+            va_fraction: 0.70,
+            min_bins: 1,
+            poc_smoothing_bins: 0,
+            poc_convention: PocConvention::Midpoint,
+        });
+
+        // Not monotonic
+        assert!(computer.compute_from_pairs(&[(100.0, 1.0), (99.0, 1.0)], 1.0).is_err());
+        // Duplicate price
+        assert!(computer.compute_from_pairs(&[(100.0, 1.0), (100.0, 1.0)], 1.0).is_err());
+        // Off-grid price
+        assert!(computer.compute_from_pairs(&[(100.0, 1.0), (100.5, 1.0)], 1.0).is_err());
+        // Negative volume
+        assert!(computer.compute_from_pairs(&[(100.0, -1.0)], 1.0).is_err());
+        // Invalid bin width
+        assert!(computer.compute_from_pairs(&[(100.0, 1.0)], 0.0).is_err());
+        // Empty input is not an error, just an invalid VA
+        assert!(!computer.compute_from_pairs(&[], 1.0).unwrap().is_valid);
+    }
+
+    #[test]
+    fn test_level_test_counter() {
+        let mut counter = LevelTestCounter::new(3, 1.0);
+        assert_eq!(counter.update(60_000, 100.0, 99.0, 101.0), 1);
+        assert_eq!(counter.update(60_000, 100.0, 99.0, 101.0), 1); // same minute
+        assert_eq!(counter.update(120_000, 100.5, 101.0, 102.0), 1); // miss
+        assert_eq!(counter.update(180_000, 100.5, 100.0, 101.0), 2);
+
+        // The first test rolls out of the 3-minute window
+        assert_eq!(counter.update(240_000, 100.0, 105.0, 106.0), 1);
+
+        // A material level move resets the count
+        assert_eq!(counter.update(300_000, 103.0, 102.0, 104.0), 1);
+        assert_eq!(counter.update(360_000, 103.0, 110.0, 111.0), 1);
+
+        counter.clear();
+        assert_eq!(counter.count(), 0);
+    }
+
+    #[test]
+    fn test_incremental_matches_full_recompute() {
+        let config = ValueAreaConfig {
+            va_fraction: 0.70,
+            min_bins: 5,
+            poc_smoothing_bins: 1,
+            poc_convention: PocConvention::Midpoint,
+        };
+        let computer = ValueAreaComputer::new(config.clone());
+        let mut incremental = IncrementalValueArea::new(config, 1.0);
+        let mut reference: BTreeMap<OrderedFloat<f64>, f64> = BTreeMap::new();
+        let mut window: VecDeque<Vec<(f64, f64)>> = VecDeque::new();
+
+        // Deterministic LCG
+        let mut state = 12345u64;
+        let mut next = move |n: u64| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) % n
+        };
+
+        let apply = |inc: &mut IncrementalValueArea, hist: &mut BTreeMap<OrderedFloat<f64>, f64>, price: f64, volume: f64| {
+            inc.apply(price, volume);
+            let entry = hist.entry(OrderedFloat(price)).or_insert(0.0);
+            *entry += volume;
+            if *entry <= 1e-10 {
+                hist.remove(&OrderedFloat(price));
+            }
+        };
+
+        let mut center = 100.0;
+        for minute in 0..2000 {
+            if minute % 50 == 0 {
+                center += next(7) as f64 - 3.0;
+            }
+
+            // Volume concentrated near the centre with occasional tail prints
+            let trades: Vec<(f64, f64)> = (0..1 + next(4))
+                .map(|_| {
+                    let offset = (next(5) + next(5) + next(5)) as f64 - 6.0;
+                    let offset = if next(4) == 0 { offset * 3.0 } else { offset };
+                    (center + offset, 1.0 + next(5) as f64)
+                })
+                .collect();
+            for &(price, volume) in &trades {
+                apply(&mut incremental, &mut reference, price, volume);
+            }
+            window.push_back(trades);
+            if window.len() > 30 {
+                for (price, volume) in window.pop_front().unwrap() {
+                    apply(&mut incremental, &mut reference, price, -volume);
+                }
+            }
+
+            let expected = computer.compute(&reference, 1.0);
+            let actual = incremental.value_area();
+            assert_eq!(actual.is_valid, expected.is_valid, "minute {}", minute);
+            if !expected.is_valid {
+                continue;
+            }
+            assert_eq!(actual.poc, expected.poc, "minute {}", minute);
+            assert_eq!(actual.poc_smoothed, expected.poc_smoothed, "minute {}", minute);
+            assert_eq!(actual.val, expected.val, "minute {}", minute);
+            assert_eq!(actual.vah, expected.vah, "minute {}", minute);
+            assert_eq!(actual.bin_count, expected.bin_count, "minute {}", minute);
+            assert!((actual.total_volume - expected.total_volume).abs() < 1e-9);
+            assert!((actual.coverage - expected.coverage).abs() < 1e-9);
+        }
+
+        // Both paths were exercised
+        assert!(incremental.reused() > 0);
+        assert!(incremental.reexpansions() > 0);
+    }
+
     #[test]
     fn test_empty_histogram() {
         let computer = ValueAreaComputer::new(ValueAreaConfig::default());
@@ -239,4 +1125,103 @@ mod tests {
         let va = computer.compute(&hist, 1.0);
         assert!(!va.is_valid);
     }
+
+    #[test]
+    fn test_normalized_profile_entropy() {
+        assert_eq!(normalized_profile_entropy(&BTreeMap::new()), None);
+        assert_eq!(normalized_profile_entropy(&make_histogram(&[(100.0, 5.0)])), Some(0.0));
+
+        // Flat profiles are 1 whatever their width
+        let flat = |n: usize| {
+            let bins: Vec<_> = (0..n).map(|i| (100.0 + i as f64, 10.0)).collect();
+            normalized_profile_entropy(&make_histogram(&bins)).unwrap()
+        };
+        assert!((flat(4) - 1.0).abs() < 1e-9);
+        assert!((flat(40) - 1.0).abs() < 1e-9);
+
+        let spiky = normalized_profile_entropy(&make_histogram(&[(100.0, 1.0), (101.0, 97.0), (102.0, 1.0), (103.0, 1.0)]));
+        assert!(spiky.unwrap() < 0.2);
+    }
+
+    #[test]
+    fn test_profile_entropy() {
+        assert_eq!(profile_entropy(&BTreeMap::new()), None);
+        assert_eq!(profile_entropy(&make_histogram(&[(100.0, 5.0)])), Some(0.0));
+
+        let flat = profile_entropy(&make_histogram(&[(100.0, 10.0), (101.0, 10.0), (102.0, 10.0), (103.0, 10.0)]));
+        assert!((flat.unwrap() - 4f64.ln()).abs() < 1e-12);
+
+        let spiky = profile_entropy(&make_histogram(&[(100.0, 1.0), (101.0, 97.0), (102.0, 1.0), (103.0, 1.0)]));
+        assert!(spiky.unwrap() < 0.2);
+    }
+
+    #[test]
+    fn test_breakout_outcomes() {
+        let mut va = ValueArea::invalid();
+        va.val = 100.0;
+        va.vah = 110.0;
+        va.is_valid = true;
+
+        // Accept after 2 closes outside, resolve within 3 bars
+        let mut tracker = BreakoutOutcomeTracker::new(2, 3, 10);
+        let mut ts = 0;
+        let mut feed = |closes: &[f64]| {
+            let mut rate = None;
+            for &close in closes {
+                ts += 60_000;
+                rate = tracker.update(ts, &va, close);
+            }
+            (rate, tracker.outcome_count())
+        };
+
+        // Upside break that holds for 3 bars: success
+        assert_eq!(feed(&[105.0, 111.0, 112.0]), (None, 0));
+        assert_eq!(feed(&[113.0, 112.0, 111.0]), (Some(1.0), 1));
+
+        // Downside break that closes back above VAL on the 2nd bar: failure
+        feed(&[105.0, 99.0, 98.0, 99.0]);
+        assert_eq!(feed(&[101.0]), (Some(0.5), 2));
+
+        // Another failure, then a success
+        feed(&[111.0, 111.0]);
+        assert!((feed(&[109.0]).0.unwrap() - 1.0 / 3.0).abs() < 1e-12);
+        feed(&[95.0, 95.0, 94.0, 93.0]);
+        assert_eq!(feed(&[92.0]), (Some(0.5), 4));
+
+        // A long excursion is a single break
+        assert_eq!(feed(&[90.0; 10]), (Some(0.5), 4));
+
+        // Repeated minute is ignored
+        assert_eq!(tracker.update(ts, &va, 200.0), Some(0.5));
+
+        tracker.clear();
+        assert_eq!(tracker.success_rate(), None);
+    }
+
+    #[test]
+    fn test_poc_convention() {
+        // Bins of width 10 at 100..150, heaviest at 120
+        let histogram: BTreeMap<OrderedFloat<f64>, f64> = [(100.0, 1.0), (110.0, 2.0), (120.0, 9.0), (130.0, 3.0), (140.0, 1.0)]
+            .into_iter()
+            .map(|(p, v)| (OrderedFloat(p), v))
+            .collect();
+        let compute = |poc_convention| {
+            ValueAreaComputer::new(ValueAreaConfig { min_bins: 1, poc_convention, ..Default::default() })
+                .compute(&histogram, 10.0)
+        };
+
+        let mid = compute(PocConvention::Midpoint);
+        assert_eq!(mid.poc, 125.0);
+        assert_eq!(mid.poc_smoothed, 125.0);
+
+        let lower = compute(PocConvention::LowerEdge);
+        assert_eq!(lower.poc, 120.0);
+        assert_eq!(lower.poc_smoothed, 120.0);
+
+        // VAL/VAH are bin edges under either convention: 120 + 130 = 12 of 16
+        for va in [&mid, &lower] {
+            assert_eq!(va.val, 120.0);
+            assert_eq!(va.vah, 140.0);
+        }
+    }
 }
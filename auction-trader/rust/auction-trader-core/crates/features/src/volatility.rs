@@ -1,9 +1,13 @@
 //! Rolling volatility computation.
 //!
-//! Computes standard deviation of log returns over a rolling window.
+//! Computes standard deviation of log returns over a rolling window, along
+//! with the skewness and excess kurtosis of the same returns.
 
 use std::collections::VecDeque;
 
+/// Periods per year for 1-minute bars (365 * 24 * 60).
+pub const MINUTES_PER_YEAR: f64 = 525_600.0;
+
 /// Rolling volatility calculator using log returns.
 pub struct RollingVolatility {
     /// Window size in periods.
@@ -16,6 +20,10 @@ pub struct RollingVolatility {
     sum: f64,
     /// Running sum of squared returns (for variance).
     sum_sq: f64,
+    /// Running sum of cubed returns (for skewness).
+    sum_cube: f64,
+    /// Running sum of fourth-power returns (for kurtosis).
+    sum_quad: f64,
 }
 
 impl RollingVolatility {
@@ -27,12 +35,25 @@ impl RollingVolatility {
             prev_price: None,
             sum: 0.0,
             sum_sq: 0.0,
+            sum_cube: 0.0,
+            sum_quad: 0.0,
         }
     }
 
+    /// Seed the previous price (e.g. the last known close on a restart).
+    ///
+    /// Returns come from consecutive prices, so the first `add_price` after
+    /// `new` or `clear` yields no return and a full window needs `window + 1`
+    /// prices. Seeding lets that first call produce a return immediately.
+    pub fn seed_prev_price(&mut self, price: f64) {
+        self.prev_price = Some(price);
+    }
+
     /// Add a price observation.
     ///
-    /// Returns the current volatility if enough data is available.
+    /// Returns the current volatility if enough data is available. The first
+    /// price after `new`/`clear` only sets the reference for the next return
+    /// (see [`seed_prev_price`](Self::seed_prev_price)).
     pub fn add_price(&mut self, price: f64) -> Option<f64> {
         if let Some(prev) = self.prev_price {
             if prev > 0.0 && price > 0.0 {
@@ -51,6 +72,8 @@ impl RollingVolatility {
             if let Some(old) = self.returns.pop_front() {
                 self.sum -= old;
                 self.sum_sq -= old * old;
+                self.sum_cube -= old.powi(3);
+                self.sum_quad -= old.powi(4);
             }
         }
 
@@ -58,6 +81,8 @@ impl RollingVolatility {
         self.returns.push_back(ret);
         self.sum += ret;
         self.sum_sq += ret * ret;
+        self.sum_cube += ret.powi(3);
+        self.sum_quad += ret.powi(4);
     }
 
     /// Calculate current volatility (standard deviation of returns).
@@ -79,6 +104,51 @@ impl RollingVolatility {
         }
     }
 
+    /// Volatility scaled to a year: `volatility() * sqrt(periods_per_year)`.
+    pub fn annualized(&self, periods_per_year: f64) -> Option<f64> {
+        self.volatility().map(|sigma| sigma * periods_per_year.sqrt())
+    }
+
+    /// Skewness of returns (population, `m3 / m2^1.5`).
+    ///
+    /// None with fewer than 3 returns or zero variance.
+    pub fn skewness(&self) -> Option<f64> {
+        if self.returns.len() < 3 {
+            return None;
+        }
+        let (m2, m3, _) = self.central_moments()?;
+        Some(m3 / m2.powf(1.5))
+    }
+
+    /// Excess kurtosis of returns (population, `m4 / m2^2 - 3`).
+    ///
+    /// None with fewer than 4 returns or zero variance.
+    pub fn kurtosis(&self) -> Option<f64> {
+        if self.returns.len() < 4 {
+            return None;
+        }
+        let (m2, _, m4) = self.central_moments()?;
+        Some(m4 / (m2 * m2) - 3.0)
+    }
+
+    /// Second, third and fourth central moments from the running sums.
+    ///
+    /// None if the variance is zero (relative to the raw second moment, to
+    /// absorb cancellation left over from evicted returns).
+    fn central_moments(&self) -> Option<(f64, f64, f64)> {
+        let n = self.returns.len() as f64;
+        let mean = self.sum / n;
+        let (r2, r3, r4) = (self.sum_sq / n, self.sum_cube / n, self.sum_quad / n);
+
+        let m2 = r2 - mean * mean;
+        if m2 <= 1e-12 * r2 || m2 <= 0.0 {
+            return None;
+        }
+        let m3 = r3 - 3.0 * mean * r2 + 2.0 * mean.powi(3);
+        let m4 = r4 - 4.0 * mean * r3 + 6.0 * mean * mean * r2 - 3.0 * mean.powi(4);
+        Some((m2, m3, m4))
+    }
+
     /// Check if the window is full.
     pub fn is_ready(&self) -> bool {
         self.returns.len() >= self.window
@@ -95,6 +165,8 @@ impl RollingVolatility {
         self.prev_price = None;
         self.sum = 0.0;
         self.sum_sq = 0.0;
+        self.sum_cube = 0.0;
+        self.sum_quad = 0.0;
     }
 }
 
@@ -122,6 +194,65 @@ mod tests {
         assert!((sigma - 0.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_annualized() {
+        let mut vol = RollingVolatility::new(4);
+        assert!(vol.annualized(MINUTES_PER_YEAR).is_none());
+
+        for price in [100.0, 101.0, 100.0, 101.0, 100.0] {
+            vol.add_price(price);
+        }
+
+        let sigma = vol.volatility().unwrap();
+        let annual = vol.annualized(MINUTES_PER_YEAR).unwrap();
+        assert!((annual - sigma * 724.9827584156743).abs() < 1e-12);
+        assert!((vol.annualized(1.0).unwrap() - sigma).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_seed_prev_price() {
+        let mut unseeded = RollingVolatility::new(3);
+        unseeded.add_price(101.0);
+        assert_eq!(unseeded.count(), 0);
+
+        let mut vol = RollingVolatility::new(3);
+        vol.seed_prev_price(100.0);
+        vol.add_price(101.0);
+        assert_eq!(vol.count(), 1);
+        assert!((vol.returns[0] - (1.01f64).ln()).abs() < 1e-15);
+
+        vol.add_price(100.0);
+        assert!(vol.volatility().is_some());
+    }
+
+    #[test]
+    fn test_skewness_kurtosis() {
+        let mut vol = RollingVolatility::new(10);
+        assert!(vol.skewness().is_none());
+        assert!(vol.kurtosis().is_none());
+
+        // Nine small gains and one large loss: mean 0, strongly left-skewed
+        // m2 = 9e-6, m3 = -7.2e-8, m4 = 6.57e-10
+        for _ in 0..9 {
+            vol.add_return(0.001);
+        }
+        vol.add_return(-0.009);
+
+        let skew = vol.skewness().unwrap();
+        assert!(skew < 0.0);
+        assert!((skew - (-8.0 / 3.0)).abs() < 1e-6);
+
+        let kurt = vol.kurtosis().unwrap();
+        assert!((kurt - (657.0 / 81.0 - 3.0)).abs() < 1e-6);
+
+        // Evicting the loss through the window leaves zero variance
+        for _ in 0..10 {
+            vol.add_return(0.001);
+        }
+        assert!(vol.skewness().is_none());
+        assert!(vol.kurtosis().is_none());
+    }
+
     #[test]
     fn test_alternating_price() {
         let mut vol = RollingVolatility::new(4);
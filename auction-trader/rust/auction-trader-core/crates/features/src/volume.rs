@@ -0,0 +1,86 @@
+//! Relative volume (RVOL).
+//!
+//! Compares the latest bar's volume to the average of the bars before it in
+//! a rolling window.
+
+use std::collections::VecDeque;
+
+/// Rolling relative volume over the last `window` bars.
+pub struct RelativeVolume {
+    /// Window size in bars (excluding the latest bar).
+    window: usize,
+    /// Volumes of the window bars followed by the latest bar.
+    volumes: VecDeque<f64>,
+    /// Running sum of `volumes`.
+    sum: f64,
+}
+
+impl RelativeVolume {
+    /// Create a new RVOL tracker averaging over `window` prior bars.
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            window,
+            volumes: VecDeque::with_capacity(window + 1),
+            sum: 0.0,
+        }
+    }
+
+    /// Add a bar's volume.
+    pub fn add(&mut self, volume: f64) {
+        if self.volumes.len() > self.window {
+            if let Some(v) = self.volumes.pop_front() {
+                self.sum -= v;
+            }
+        }
+        self.volumes.push_back(volume);
+        self.sum += volume;
+    }
+
+    /// Latest volume divided by the average of the prior bars in the window.
+    ///
+    /// None until there is a prior bar with volume.
+    pub fn value(&self) -> Option<f64> {
+        let latest = *self.volumes.back()?;
+        let prior = self.volumes.len() - 1;
+        if prior == 0 {
+            return None;
+        }
+        let avg = (self.sum - latest) / prior as f64;
+        if avg > 1e-12 {
+            Some(latest / avg)
+        } else {
+            None
+        }
+    }
+
+    /// Clear all data.
+    pub fn clear(&mut self) {
+        self.volumes.clear();
+        self.sum = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_volume() {
+        let mut rvol = RelativeVolume::new(3);
+        rvol.add(100.0);
+        assert!(rvol.value().is_none());
+
+        rvol.add(100.0);
+        rvol.add(100.0);
+        rvol.add(400.0);
+        assert!((rvol.value().unwrap() - 4.0).abs() < 1e-10);
+
+        // Window is now [100, 100, 400]; quiet bar
+        rvol.add(50.0);
+        assert!((rvol.value().unwrap() - 0.25).abs() < 1e-10);
+
+        rvol.clear();
+        assert!(rvol.value().is_none());
+    }
+}
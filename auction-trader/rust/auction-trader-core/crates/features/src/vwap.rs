@@ -0,0 +1,254 @@
+//! Rolling volume-weighted average price.
+//!
+//! Accumulates per-bar price * volume over a rolling window of bars, with
+//! volume-weighted standard deviation bands around the VWAP. Prices are
+//! summed relative to the first bar's price so the variance does not lose
+//! precision to cancellation at large price levels.
+
+use auction_core::{TimestampMs, VwapBandTouches};
+use std::collections::VecDeque;
+
+/// Rolling VWAP over the last `window` bars.
+pub struct RollingVwap {
+    /// Window size in bars.
+    window: usize,
+    /// (price, volume) per bar.
+    bars: VecDeque<(f64, f64)>,
+    /// Reference price the sums are taken relative to (first bar since clear).
+    shift: Option<f64>,
+    /// Running sum of (price - shift) * volume.
+    sum_pv: f64,
+    /// Running sum of (price - shift)^2 * volume.
+    sum_p2v: f64,
+    /// Running sum of volume.
+    sum_v: f64,
+}
+
+impl RollingVwap {
+    /// Create a new rolling VWAP over `window` bars.
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            bars: VecDeque::with_capacity(window),
+            shift: None,
+            sum_pv: 0.0,
+            sum_p2v: 0.0,
+            sum_v: 0.0,
+        }
+    }
+
+    /// Add a bar's average price and volume.
+    pub fn add(&mut self, price: f64, volume: f64) {
+        let shift = *self.shift.get_or_insert(price);
+        if self.bars.len() >= self.window {
+            if let Some((p, v)) = self.bars.pop_front() {
+                let d = p - shift;
+                self.sum_pv -= d * v;
+                self.sum_p2v -= d * d * v;
+                self.sum_v -= v;
+            }
+        }
+        self.bars.push_back((price, volume));
+        let d = price - shift;
+        self.sum_pv += d * volume;
+        self.sum_p2v += d * d * volume;
+        self.sum_v += volume;
+    }
+
+    /// Current VWAP (None until some volume has traded).
+    pub fn value(&self) -> Option<f64> {
+        if self.sum_v > 1e-12 {
+            Some(self.shift.unwrap_or(0.0) + self.sum_pv / self.sum_v)
+        } else {
+            None
+        }
+    }
+
+    /// Volume-weighted standard deviation of bar prices around the VWAP.
+    pub fn std_dev(&self) -> Option<f64> {
+        self.value()?;
+        let mean = self.sum_pv / self.sum_v;
+        Some((self.sum_p2v / self.sum_v - mean * mean).max(0.0).sqrt())
+    }
+
+    /// `(lower, upper)` band at `k` standard deviations from the VWAP.
+    pub fn band(&self, k: f64) -> Option<(f64, f64)> {
+        let vwap = self.value()?;
+        let offset = k * self.std_dev()?;
+        Some((vwap - offset, vwap + offset))
+    }
+
+    /// Clear all data.
+    pub fn clear(&mut self) {
+        self.bars.clear();
+        self.shift = None;
+        self.sum_pv = 0.0;
+        self.sum_p2v = 0.0;
+        self.sum_v = 0.0;
+    }
+}
+
+/// Counts bars whose range touched or crossed a VWAP band within a rolling
+/// window.
+#[derive(Debug, Clone)]
+pub struct VwapBandTouchCounter {
+    /// Minutes whose high reached the upper band.
+    upper: VecDeque<TimestampMs>,
+    /// Minutes whose low reached the lower band.
+    lower: VecDeque<TimestampMs>,
+    /// Window length in ms.
+    window_ms: i64,
+}
+
+impl VwapBandTouchCounter {
+    /// Create a counter over `window_minutes` minutes.
+    pub fn new(window_minutes: usize) -> Self {
+        Self {
+            upper: VecDeque::new(),
+            lower: VecDeque::new(),
+            window_ms: window_minutes as i64 * 60_000,
+        }
+    }
+
+    /// Record the bar `[low, high]` of minute `ts_min` against the
+    /// `(lower, upper)` band (None before the VWAP is defined).
+    ///
+    /// Recording the same minute twice counts at most one touch per side.
+    pub fn update(
+        &mut self,
+        ts_min: TimestampMs,
+        band: Option<(f64, f64)>,
+        low: f64,
+        high: f64,
+    ) -> VwapBandTouches {
+        let window_start = ts_min - self.window_ms;
+        for touches in [&mut self.upper, &mut self.lower] {
+            while touches.front().is_some_and(|&ts| ts <= window_start) {
+                touches.pop_front();
+            }
+        }
+
+        let (touched_lower_band, touched_upper_band) = match band {
+            Some((lower, upper)) => (low <= lower, high >= upper),
+            None => (false, false),
+        };
+        if touched_upper_band && self.upper.back().is_none_or(|&ts| ts < ts_min) {
+            self.upper.push_back(ts_min);
+        }
+        if touched_lower_band && self.lower.back().is_none_or(|&ts| ts < ts_min) {
+            self.lower.push_back(ts_min);
+        }
+
+        VwapBandTouches {
+            touched_upper_band,
+            touched_lower_band,
+            upper_touches: self.upper.len() as u32,
+            lower_touches: self.lower.len() as u32,
+        }
+    }
+
+    /// Clear all data.
+    pub fn clear(&mut self) {
+        self.upper.clear();
+        self.lower.clear();
+    }
+}
+
+/// Distance of `mid` from `vwap` in units of price volatility:
+/// `(mid - vwap) / (sigma * mid)`.
+///
+/// None if `sigma * mid` is not positive (e.g. before volatility warms up).
+pub fn vwap_z(mid: f64, vwap: f64, sigma: f64) -> Option<f64> {
+    let scale = sigma * mid;
+    if scale > 0.0 && scale.is_finite() {
+        Some((mid - vwap) / scale)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_vwap() {
+        let mut vwap = RollingVwap::new(2);
+        assert!(vwap.value().is_none());
+
+        vwap.add(100.0, 1.0);
+        vwap.add(110.0, 3.0);
+        assert!((vwap.value().unwrap() - 107.5).abs() < 1e-10);
+
+        // Oldest bar drops out
+        vwap.add(90.0, 1.0);
+        assert!((vwap.value().unwrap() - 105.0).abs() < 1e-10);
+
+        vwap.clear();
+        assert!(vwap.value().is_none());
+    }
+
+    #[test]
+    fn test_vwap_bands() {
+        let mut vwap = RollingVwap::new(3);
+        assert!(vwap.band(1.0).is_none());
+
+        vwap.add(100.0, 1.0);
+        vwap.add(104.0, 1.0);
+        assert!((vwap.std_dev().unwrap() - 2.0).abs() < 1e-10);
+        let (lower, upper) = vwap.band(2.0).unwrap();
+        assert!((lower - 98.0).abs() < 1e-10);
+        assert!((upper - 106.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_vwap_bands_at_large_prices() {
+        let mut vwap = RollingVwap::new(3);
+        vwap.add(1e9, 1.0);
+        vwap.add(1e9 + 0.5, 1.0);
+        assert!((vwap.value().unwrap() - (1e9 + 0.25)).abs() < 1e-6);
+        assert!((vwap.std_dev().unwrap() - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_band_touch_counter() {
+        let mut counter = VwapBandTouchCounter::new(3);
+        let band = Some((99.0, 101.0));
+
+        let touches = counter.update(60_000, band, 99.5, 101.2);
+        assert!(touches.touched_upper_band && !touches.touched_lower_band);
+        assert_eq!((touches.upper_touches, touches.lower_touches), (1, 0));
+
+        // Same minute again: no double count
+        assert_eq!(counter.update(60_000, band, 99.5, 101.2).upper_touches, 1);
+
+        let touches = counter.update(120_000, band, 98.0, 101.0);
+        assert!(touches.touched_upper_band && touches.touched_lower_band);
+        assert_eq!((touches.upper_touches, touches.lower_touches), (2, 1));
+
+        // Inside the band; the first touch rolls out of the window
+        let touches = counter.update(240_000, band, 99.5, 100.5);
+        assert!(!touches.touched_upper_band);
+        assert_eq!((touches.upper_touches, touches.lower_touches), (1, 1));
+
+        // No band yet: no touches
+        assert!(!counter.update(300_000, None, 0.0, 1e9).touched_upper_band);
+
+        counter.clear();
+        assert_eq!(counter.update(360_000, band, 100.0, 100.0).upper_touches, 0);
+    }
+
+    #[test]
+    fn test_vwap_z_grows_with_distance() {
+        let sigma = 0.001;
+        let near = vwap_z(100.05, 100.0, sigma).unwrap();
+        let far = vwap_z(100.2, 100.0, sigma).unwrap();
+        let below = vwap_z(99.8, 100.0, sigma).unwrap();
+
+        assert!(near > 0.0 && far > near);
+        assert!((far - 0.2 / (sigma * 100.2)).abs() < 1e-10);
+        assert!(below < 0.0 && below.abs() > near);
+
+        assert!(vwap_z(100.0, 100.0, 0.0).is_none());
+    }
+}
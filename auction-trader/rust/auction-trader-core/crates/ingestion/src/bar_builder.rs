@@ -1,10 +1,68 @@
 //! Minute bar building from trades and quotes.
 //!
-//! Builds 1-minute OHLCV bars with L1 snapshots at close.
+//! Builds 1-minute OHLCV bars with L1 snapshots at close. Volume and VWAP
+//! are in base units; see [`BarBuilder::with_size_conversion`].
 
-use auction_core::{Bar1m, ClassifiedTrade, Quote, TimestampMs, ts_to_minute};
+use auction_core::{
+    Bar1m, ClassifiedTrade, Config, Quote, SizeConversion, TimestampMs, ts_to_minute,
+};
+pub use auction_core::{CloseQuotePolicy, MissingQuotePolicy};
 use std::collections::BTreeMap;
 
+/// What to do once a minute exceeds its [`TradeCap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TradeCapPolicy {
+    /// Keep the bar and drop the trades beyond the cap.
+    #[default]
+    DropExcess,
+    /// Flag the minute as corrupt: drop its remaining trades and its bar.
+    ExcludeMinute,
+}
+
+/// Per-minute trade count and volume limits guarding against feed spam.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TradeCap {
+    /// Maximum trades per minute.
+    pub max_trades: Option<u32>,
+    /// Maximum volume per minute, in base units.
+    pub max_volume: Option<f64>,
+    /// Handling of trades beyond the cap.
+    pub policy: TradeCapPolicy,
+}
+
+impl TradeCap {
+    /// Whether a trade of `size` would exceed the cap for a minute that
+    /// already holds `trade_count` trades and `volume`.
+    pub fn exceeds(&self, trade_count: u32, volume: f64, size: f64) -> bool {
+        self.max_trades.is_some_and(|max| trade_count >= max)
+            || self.max_volume.is_some_and(|max| volume + size > max)
+    }
+
+    /// Whether adding a trade of `size` to `bar` would exceed the cap.
+    fn exceeded_by(&self, bar: &BarInProgress, size: f64) -> bool {
+        self.exceeds(bar.trade_count, bar.volume, size)
+    }
+}
+
+/// A timestamped input to [`BarBuilder::feed`].
+#[derive(Debug, Clone)]
+pub enum BarEvent {
+    /// A quote update.
+    Quote(Quote),
+    /// A classified trade.
+    Trade(ClassifiedTrade),
+}
+
+impl BarEvent {
+    /// Event timestamp in ms.
+    pub fn ts_ms(&self) -> TimestampMs {
+        match self {
+            BarEvent::Quote(q) => q.ts_ms,
+            BarEvent::Trade(t) => t.trade.ts_ms,
+        }
+    }
+}
+
 /// Builder for 1-minute bars from classified trades and quotes.
 pub struct BarBuilder {
     /// Current bars being built, keyed by minute timestamp.
@@ -13,11 +71,29 @@ pub struct BarBuilder {
     quotes: Vec<Quote>,
     /// Maximum quotes to keep.
     max_quotes: usize,
+    /// Which quote is used as the close snapshot.
+    close_quote_policy: CloseQuotePolicy,
+    /// Handling of bars without a usable close quote.
+    missing_quote_policy: MissingQuotePolicy,
+    /// Oldest quote (ms before the close) usable as the close snapshot.
+    max_quote_age_ms: Option<i64>,
+    /// Latest quote (ms after the close) usable by `ClosestToClose`.
+    close_quote_tolerance_ms: i64,
+    /// Bars finalized without a usable close quote.
+    missing_quote_bars: u64,
+    /// Conversion of trade sizes to base units.
+    size_conversion: SizeConversion,
+    /// Per-minute trade limits (None = unlimited).
+    trade_cap: Option<TradeCap>,
+    /// Trades rejected by the trade cap.
+    capped_trades: u64,
+    /// Minutes excluded under `TradeCapPolicy::ExcludeMinute`.
+    corrupt_minutes: u64,
 }
 
 /// A bar that's currently being built.
 #[derive(Debug, Clone)]
-struct BarInProgress {
+pub(crate) struct BarInProgress {
     ts_min: TimestampMs,
     open: Option<f64>,
     high: f64,
@@ -26,10 +102,12 @@ struct BarInProgress {
     volume: f64,
     vwap_numerator: f64,
     trade_count: u32,
+    /// Flagged by the trade cap; the bar is not emitted.
+    corrupt: bool,
 }
 
 impl BarInProgress {
-    fn new(ts_min: TimestampMs) -> Self {
+    pub(crate) fn new(ts_min: TimestampMs) -> Self {
         Self {
             ts_min,
             open: None,
@@ -39,10 +117,11 @@ impl BarInProgress {
             volume: 0.0,
             vwap_numerator: 0.0,
             trade_count: 0,
+            corrupt: false,
         }
     }
 
-    fn add_trade(&mut self, price: f64, size: f64) {
+    pub(crate) fn add_trade(&mut self, price: f64, size: f64) {
         if self.open.is_none() {
             self.open = Some(price);
         }
@@ -62,12 +141,12 @@ impl BarInProgress {
         }
     }
 
-    fn to_bar(&self, quote: Option<&Quote>) -> Option<Bar1m> {
+    pub(crate) fn to_bar(&self, quote: Option<&Quote>) -> Option<Bar1m> {
         let open = self.open?;
 
         let (bid_px, ask_px, bid_sz, ask_sz) = quote
             .map(|q| (q.bid_px, q.ask_px, q.bid_sz, q.ask_sz))
-            .unwrap_or((0.0, 0.0, 0.0, 0.0));
+            .unwrap_or((f64::NAN, f64::NAN, f64::NAN, f64::NAN));
 
         Some(Bar1m {
             ts_min: self.ts_min,
@@ -93,9 +172,82 @@ impl BarBuilder {
             bars: BTreeMap::new(),
             quotes: Vec::with_capacity(10000),
             max_quotes: 100000,
+            close_quote_policy: CloseQuotePolicy::LastBeforeClose,
+            missing_quote_policy: MissingQuotePolicy::Nan,
+            max_quote_age_ms: None,
+            close_quote_tolerance_ms: 1_000,
+            missing_quote_bars: 0,
+            size_conversion: SizeConversion::default(),
+            trade_cap: None,
+            capped_trades: 0,
+            corrupt_minutes: 0,
         }
     }
 
+    /// Limit the trades and volume accepted per minute.
+    pub fn with_trade_cap(mut self, cap: TradeCap) -> Self {
+        self.trade_cap = Some(cap);
+        self
+    }
+
+    /// Number of trades rejected by the trade cap.
+    pub fn capped_trades(&self) -> u64 {
+        self.capped_trades
+    }
+
+    /// Number of minutes excluded as corrupt by the trade cap.
+    pub fn corrupt_minutes(&self) -> u64 {
+        self.corrupt_minutes
+    }
+
+    /// Whether the pending minute `ts_min` has been flagged as corrupt.
+    pub fn is_minute_corrupt(&self, ts_min: TimestampMs) -> bool {
+        self.bars.get(&ts_min).is_some_and(|bar| bar.corrupt)
+    }
+
+    /// Convert trade sizes to base units before aggregating volume and VWAP.
+    pub fn with_size_conversion(mut self, conversion: SizeConversion) -> Self {
+        self.size_conversion = conversion;
+        self
+    }
+
+    /// Set which quote is used as the close snapshot.
+    pub fn with_close_quote_policy(mut self, policy: CloseQuotePolicy) -> Self {
+        self.close_quote_policy = policy;
+        self
+    }
+
+    /// Set the handling of bars without a usable close quote.
+    pub fn with_missing_quote_policy(mut self, policy: MissingQuotePolicy) -> Self {
+        self.missing_quote_policy = policy;
+        self
+    }
+
+    /// Treat quotes older than `max_age_ms` at the bar close as missing.
+    ///
+    /// With `TimeWeightedMidOfMinute` the age is that of the latest quote
+    /// in the average.
+    pub fn with_max_quote_age_ms(mut self, max_age_ms: i64) -> Self {
+        self.max_quote_age_ms = Some(max_age_ms.max(0));
+        self
+    }
+
+    /// Only quotes at most `tolerance_ms` after the close are considered by
+    /// `CloseQuotePolicy::ClosestToClose` (default 1000).
+    ///
+    /// Bars finalized sooner than that after their close see only the quotes
+    /// received so far: the snapshot uses what is available at finalize time
+    /// and is not revised.
+    pub fn with_close_quote_tolerance_ms(mut self, tolerance_ms: i64) -> Self {
+        self.close_quote_tolerance_ms = tolerance_ms.max(0);
+        self
+    }
+
+    /// Number of bars finalized without a usable close quote.
+    pub fn missing_quote_bars(&self) -> u64 {
+        self.missing_quote_bars
+    }
+
     /// Add a quote.
     pub fn add_quote(&mut self, quote: Quote) {
         if self.quotes.len() >= self.max_quotes {
@@ -106,11 +258,28 @@ impl BarBuilder {
     }
 
     /// Add a classified trade.
+    ///
+    /// Trades rejected by the trade cap are counted in `capped_trades`. The
+    /// feature engine keeps its own cap (see `FeatureEngine::set_trade_cap`),
+    /// which also holds back an `ExcludeMinute` minute's trades until the
+    /// minute closes.
     pub fn add_trade(&mut self, trade: &ClassifiedTrade) {
         let ts_min = ts_to_minute(trade.trade.ts_ms);
 
         let bar = self.bars.entry(ts_min).or_insert_with(|| BarInProgress::new(ts_min));
-        bar.add_trade(trade.trade.price, trade.trade.size);
+        let size = self.size_conversion.to_base(trade.trade.size, trade.trade.price);
+        if bar.corrupt {
+            self.capped_trades += 1;
+            return;
+        }
+        if let Some(cap) = self.trade_cap.filter(|cap| cap.exceeded_by(bar, size)) {
+            self.capped_trades += 1;
+            if cap.policy == TradeCapPolicy::ExcludeMinute {
+                bar.corrupt = true;
+            }
+            return;
+        }
+        bar.add_trade(trade.trade.price, size);
     }
 
     /// Add multiple classified trades.
@@ -135,6 +304,97 @@ impl BarBuilder {
         }
     }
 
+    /// First quote strictly after the given timestamp.
+    fn find_quote_after(&self, ts_ms: TimestampMs) -> Option<&Quote> {
+        let i = self.quotes.partition_point(|q| q.ts_ms <= ts_ms);
+        self.quotes.get(i)
+    }
+
+    /// Time-weighted average of the quotes in effect over `[start, end)`.
+    ///
+    /// The result carries the timestamp of the latest quote used. None if no
+    /// quote is in effect during the window.
+    fn time_weighted_quote(&self, start: TimestampMs, end: TimestampMs) -> Option<Quote> {
+        let first = self.quotes.partition_point(|q| q.ts_ms <= start);
+        let mut current = first.checked_sub(1).map(|i| &self.quotes[i]);
+        let mut since = start;
+        let mut sums = [0.0; 4];
+        let mut total = 0.0;
+
+        let mut accumulate = |q: &Quote, duration: f64| {
+            for (sum, value) in sums.iter_mut().zip([q.bid_px, q.ask_px, q.bid_sz, q.ask_sz]) {
+                *sum += value * duration;
+            }
+            total += duration;
+        };
+
+        for quote in self.quotes[first..].iter().take_while(|q| q.ts_ms < end) {
+            if let Some(prev) = current {
+                accumulate(prev, (quote.ts_ms - since) as f64);
+            }
+            current = Some(quote);
+            since = quote.ts_ms;
+        }
+        let last = current?;
+        accumulate(last, (end - since) as f64);
+
+        Some(Quote {
+            ts_ms: last.ts_ms,
+            bid_px: sums[0] / total,
+            ask_px: sums[1] / total,
+            bid_sz: sums[2] / total,
+            ask_sz: sums[3] / total,
+        })
+    }
+
+    /// Close snapshot candidate for a bar under the close quote policy.
+    fn close_quote(&self, bar: &BarInProgress) -> Option<Quote> {
+        let close_ts = bar.ts_min + 59_999;
+        match self.close_quote_policy {
+            CloseQuotePolicy::LastBeforeClose => self.find_quote(close_ts).cloned(),
+            CloseQuotePolicy::TimeWeightedMidOfMinute => {
+                self.time_weighted_quote(bar.ts_min, bar.ts_min + 60_000)
+            }
+            CloseQuotePolicy::ClosestToClose => {
+                let after = self
+                    .find_quote_after(close_ts)
+                    .filter(|q| q.ts_ms - close_ts <= self.close_quote_tolerance_ms);
+                match (self.find_quote(close_ts), after) {
+                    (Some(before), Some(after)) if after.ts_ms - close_ts < close_ts - before.ts_ms => {
+                        Some(after.clone())
+                    }
+                    (Some(before), _) => Some(before.clone()),
+                    (None, after) => after.cloned(),
+                }
+            }
+        }
+    }
+
+    /// Add an event and return the bars it completes.
+    ///
+    /// Events must arrive in timestamp order: an event in a later minute
+    /// completes every earlier bar. The event is added first, so a quote just
+    /// after a close is available to `CloseQuotePolicy::ClosestToClose`.
+    /// Bars still pending at the end of a stream are not emitted until a
+    /// later event or `finalize_before`.
+    pub fn feed(&mut self, event: BarEvent) -> Vec<Bar1m> {
+        let ts_ms = event.ts_ms();
+        match event {
+            BarEvent::Quote(quote) => self.add_quote(quote),
+            BarEvent::Trade(trade) => self.add_trade(&trade),
+        }
+        self.finalize_before(ts_ms)
+    }
+
+    /// Lazily build bars from an ordered event stream (see [`feed`](Self::feed)).
+    pub fn stream<'a, I>(&'a mut self, events: I) -> impl Iterator<Item = Bar1m> + 'a
+    where
+        I: IntoIterator<Item = BarEvent>,
+        I::IntoIter: 'a,
+    {
+        events.into_iter().flat_map(move |event| self.feed(event))
+    }
+
     /// Finalize and return completed bars older than the given timestamp.
     ///
     /// Bars for minutes that are complete (current time > minute end) are returned
@@ -152,11 +412,7 @@ impl BarBuilder {
 
         for ts_min in keys_to_remove {
             if let Some(bar_in_progress) = self.bars.remove(&ts_min) {
-                // Find quote at minute close (ts_min + 59999)
-                let close_ts = ts_min + 59_999;
-                let quote = self.find_quote(close_ts);
-
-                if let Some(bar) = bar_in_progress.to_bar(quote) {
+                if let Some(bar) = self.build_bar(&bar_in_progress) {
                     completed.push(bar);
                 }
             }
@@ -171,9 +427,31 @@ impl BarBuilder {
     /// Force finalize a specific minute, even if not complete.
     pub fn force_finalize(&mut self, ts_min: TimestampMs) -> Option<Bar1m> {
         let bar_in_progress = self.bars.remove(&ts_min)?;
-        let close_ts = ts_min + 59_999;
-        let quote = self.find_quote(close_ts);
-        bar_in_progress.to_bar(quote)
+        self.build_bar(&bar_in_progress)
+    }
+
+    /// Build a bar with the L1 snapshot at its close (ts_min + 59999),
+    /// applying the close quote and missing quote policies.
+    fn build_bar(&mut self, bar: &BarInProgress) -> Option<Bar1m> {
+        if bar.corrupt {
+            self.corrupt_minutes += 1;
+            return None;
+        }
+        let close_ts = bar.ts_min + 59_999;
+        let latest = self.close_quote(bar);
+        let fresh = latest
+            .as_ref()
+            .filter(|q| self.max_quote_age_ms.is_none_or(|age| close_ts - q.ts_ms <= age));
+        if fresh.is_some() {
+            return bar.to_bar(fresh);
+        }
+
+        self.missing_quote_bars += 1;
+        match self.missing_quote_policy {
+            MissingQuotePolicy::Nan => bar.to_bar(None),
+            MissingQuotePolicy::Drop => None,
+            MissingQuotePolicy::CarryForward => bar.to_bar(latest.as_ref()),
+        }
     }
 
     /// Get the number of bars currently being built.
@@ -181,10 +459,13 @@ impl BarBuilder {
         self.bars.len()
     }
 
-    /// Clear all state.
+    /// Clear all state, including the missing-quote and trade cap counters.
     pub fn clear(&mut self) {
         self.bars.clear();
         self.quotes.clear();
+        self.missing_quote_bars = 0;
+        self.capped_trades = 0;
+        self.corrupt_minutes = 0;
     }
 
     /// Prune old quotes to save memory.
@@ -200,10 +481,26 @@ impl Default for BarBuilder {
     }
 }
 
+impl From<&Config> for BarBuilder {
+    /// Bar builder for the instrument sizes and bar settings of `config`.
+    fn from(config: &Config) -> Self {
+        let bars = &config.bars;
+        let mut builder = Self::new()
+            .with_size_conversion(config.instrument.size_conversion())
+            .with_close_quote_policy(bars.close_quote_policy)
+            .with_missing_quote_policy(bars.missing_quote_policy)
+            .with_close_quote_tolerance_ms(bars.close_quote_tolerance_ms);
+        if let Some(max_age_ms) = bars.max_quote_age_ms {
+            builder = builder.with_max_quote_age_ms(max_age_ms);
+        }
+        builder
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use auction_core::{Trade, TradeSide};
+    use auction_core::{BarConfig, SizeUnit, Trade, TradeSide};
 
     fn make_classified_trade(ts_ms: i64, price: f64, size: f64) -> ClassifiedTrade {
         ClassifiedTrade {
@@ -324,4 +621,211 @@ mod tests {
         assert_eq!(bars.len(), 0);
         assert_eq!(builder.pending_bar_count(), 1);
     }
+
+    #[test]
+    fn test_missing_quote_policy() {
+        let make_builder = |policy| {
+            let mut builder = BarBuilder::new()
+                .with_missing_quote_policy(policy)
+                .with_max_quote_age_ms(5_000);
+            // Last quote is well before the second bar's close
+            builder.add_quote(make_quote(60_000 + 59_999, 50000.0, 50002.0));
+            builder.add_trade(&make_classified_trade(60_000 + 30_000, 50001.0, 0.1));
+            builder.add_trade(&make_classified_trade(120_000 + 30_000, 50003.0, 0.1));
+            builder
+        };
+
+        let mut builder = make_builder(MissingQuotePolicy::Nan);
+        let bars = builder.finalize_before(180_000 + 1000);
+        assert_eq!(bars.len(), 2);
+        assert!(bars[0].has_close_quote());
+        assert!(!bars[1].has_close_quote());
+        assert!(bars[1].bid_px_close.is_nan());
+        assert_eq!(builder.missing_quote_bars(), 1);
+
+        let mut builder = make_builder(MissingQuotePolicy::Drop);
+        let bars = builder.finalize_before(180_000 + 1000);
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].ts_min, 60_000);
+        assert_eq!(builder.missing_quote_bars(), 1);
+
+        let mut builder = make_builder(MissingQuotePolicy::CarryForward);
+        let bars = builder.finalize_before(180_000 + 1000);
+        assert_eq!(bars.len(), 2);
+        assert!((bars[1].bid_px_close - 50000.0).abs() < 1e-10);
+        assert_eq!(builder.missing_quote_bars(), 1);
+    }
+
+    #[test]
+    fn test_close_quote_policy() {
+        let make_builder = |policy| {
+            let mut builder = BarBuilder::new().with_close_quote_policy(policy);
+            // Representative book for most of the minute
+            builder.add_quote(make_quote(60_000 + 1_000, 50000.0, 50001.0));
+            // Momentary spike just before the close
+            builder.add_quote(make_quote(60_000 + 59_000, 50100.0, 50101.0));
+            // Book back to normal shortly after the close
+            builder.add_quote(make_quote(120_000 + 500, 50000.0, 50001.0));
+            builder.add_trade(&make_classified_trade(60_000 + 30_000, 50000.5, 0.1));
+            builder
+        };
+
+        let mut builder = make_builder(CloseQuotePolicy::LastBeforeClose);
+        let bars = builder.finalize_before(120_000 + 1000);
+        assert!((bars[0].mid_close() - 50100.5).abs() < 1e-10);
+
+        // 58s at 50000.5 and 1s at 50100.5 over the 59s with a quote in effect
+        let mut builder = make_builder(CloseQuotePolicy::TimeWeightedMidOfMinute);
+        let bars = builder.finalize_before(120_000 + 1000);
+        let expected = (50000.5 * 58.0 + 50100.5) / 59.0;
+        assert!((bars[0].mid_close() - expected).abs() < 1e-6);
+
+        // The quote 501ms after the close is nearer than the one 999ms before
+        let mut builder = make_builder(CloseQuotePolicy::ClosestToClose);
+        let bars = builder.finalize_before(120_000 + 1000);
+        assert!((bars[0].mid_close() - 50000.5).abs() < 1e-10);
+
+        // ...but beyond a 250ms tolerance
+        let mut builder = make_builder(CloseQuotePolicy::ClosestToClose)
+            .with_close_quote_tolerance_ms(250);
+        let bars = builder.finalize_before(120_000 + 1000);
+        assert!((bars[0].mid_close() - 50100.5).abs() < 1e-10);
+
+        // Finalized before the later quote arrives: only quotes already received count
+        let policy = CloseQuotePolicy::ClosestToClose;
+        let mut builder = BarBuilder::new().with_close_quote_policy(policy);
+        builder.add_quote(make_quote(60_000 + 59_000, 50100.0, 50101.0));
+        builder.add_trade(&make_classified_trade(60_000 + 30_000, 50000.5, 0.1));
+        let bars = builder.finalize_before(120_000);
+        assert!((bars[0].mid_close() - 50100.5).abs() < 1e-10);
+        // ...and the emitted bar is not revised or re-emitted
+        builder.add_quote(make_quote(120_000 + 500, 50000.0, 50001.0));
+        assert_eq!(builder.pending_bar_count(), 0);
+        assert!(builder.finalize_before(120_000 + 1000).is_empty());
+    }
+
+    #[test]
+    fn test_feed_events() {
+        let events = vec![
+            BarEvent::Quote(make_quote(60_000 + 100, 50000.0, 50001.0)),
+            BarEvent::Trade(make_classified_trade(60_000 + 1_000, 50000.5, 0.1)),
+            BarEvent::Trade(make_classified_trade(60_000 + 59_000, 50002.0, 0.2)),
+            BarEvent::Quote(make_quote(60_000 + 59_500, 50001.0, 50002.0)),
+            // First event of the next minute closes the first bar
+            BarEvent::Quote(make_quote(120_000 + 10, 50003.0, 50004.0)),
+            BarEvent::Trade(make_classified_trade(120_000 + 5_000, 50003.5, 0.3)),
+            // A gap: the next event completes the second bar only
+            BarEvent::Trade(make_classified_trade(300_000 + 1_000, 50010.0, 0.4)),
+        ];
+
+        let mut builder = BarBuilder::new();
+        let emitted: Vec<usize> = events.iter().map(|e| builder.feed(e.clone()).len()).collect();
+        assert_eq!(emitted, [0, 0, 0, 0, 1, 0, 1]);
+        assert_eq!(builder.pending_bar_count(), 1);
+
+        let mut builder = BarBuilder::new();
+        let bars: Vec<Bar1m> = builder.stream(events).collect();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].ts_min, 60_000);
+        assert_eq!(bars[0].close, 50002.0);
+        assert!((bars[0].bid_px_close - 50001.0).abs() < 1e-10);
+        assert_eq!(bars[1].ts_min, 120_000);
+        assert!((bars[1].volume - 0.3).abs() < 1e-10);
+        assert_eq!(builder.pending_bar_count(), 1);
+    }
+
+    #[test]
+    fn test_quote_denominated_sizes() {
+        let quote_sized = SizeConversion { unit: SizeUnit::Quote, ..Default::default() };
+        let mut builder = BarBuilder::new().with_size_conversion(quote_sized);
+        builder.add_quote(make_quote(60_000 + 59_000, 49999.0, 50001.0));
+
+        // 10000 USDT at 50000 (0.2 BTC) and 30000 USDT at 60000 (0.5 BTC)
+        builder.add_trade(&make_classified_trade(60_000 + 1000, 50000.0, 10000.0));
+        builder.add_trade(&make_classified_trade(60_000 + 2000, 60000.0, 30000.0));
+
+        let bar = builder.force_finalize(60_000).unwrap();
+        assert!((bar.volume - 0.7).abs() < 1e-12);
+        // Base-weighted: (0.2 * 50000 + 0.5 * 60000) / 0.7, i.e. notional / base volume
+        let expected_vwap = 40000.0 / 0.7;
+        assert!((bar.vwap.unwrap() - expected_vwap).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_trade_cap() {
+        let cap = TradeCap { max_trades: Some(100), max_volume: Some(50.0), policy: TradeCapPolicy::DropExcess };
+        let mut builder = BarBuilder::new().with_trade_cap(cap);
+        builder.add_quote(make_quote(60_000 + 59_000, 49999.0, 50001.0));
+        builder.add_quote(make_quote(120_000 + 59_000, 49999.0, 50001.0));
+
+        // A burst of 10000 trades in one minute: only the first 100 are kept
+        for i in 0..10_000 {
+            builder.add_trade(&make_classified_trade(60_000 + i % 60_000, 50000.0, 0.1));
+        }
+        assert_eq!(builder.capped_trades(), 9_900);
+
+        // Volume cap: 40 + 10 fits, the next trade would exceed 50
+        builder.add_trade(&make_classified_trade(120_000, 50000.0, 40.0));
+        builder.add_trade(&make_classified_trade(120_001, 50000.0, 10.0));
+        assert_eq!(builder.capped_trades(), 9_900);
+        builder.add_trade(&make_classified_trade(120_002, 50000.0, 1.0));
+
+        let bars = builder.finalize_before(180_000);
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].trade_count, 100);
+        assert!((bars[1].volume - 50.0).abs() < 1e-12);
+        assert_eq!(builder.capped_trades(), 9_901);
+        assert_eq!(builder.corrupt_minutes(), 0);
+    }
+
+    #[test]
+    fn test_trade_cap_excludes_minute() {
+        let cap = TradeCap { max_trades: Some(100), policy: TradeCapPolicy::ExcludeMinute, ..Default::default() };
+        let mut builder = BarBuilder::new().with_trade_cap(cap);
+        builder.add_quote(make_quote(60_000, 49999.0, 50001.0));
+
+        for i in 0..1000 {
+            builder.add_trade(&make_classified_trade(60_000 + i, 50000.0, 0.1));
+        }
+        assert!(builder.is_minute_corrupt(60_000));
+        assert_eq!(builder.capped_trades(), 900);
+        builder.add_trade(&make_classified_trade(120_000, 50000.0, 0.1));
+
+        // The corrupt minute is dropped, the next one is unaffected
+        let bars = builder.finalize_before(180_000);
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].ts_min, 120_000);
+        assert_eq!(builder.corrupt_minutes(), 1);
+
+        builder.clear();
+        assert_eq!(builder.capped_trades(), 0);
+        assert_eq!(builder.corrupt_minutes(), 0);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut config = Config::default();
+        config.bars.missing_quote_policy = MissingQuotePolicy::Drop;
+        config.bars.max_quote_age_ms = Some(5_000);
+        let mut builder = BarBuilder::from(&config);
+
+        // Quote too old at the close: the bar is dropped
+        builder.add_quote(make_quote(60_000 + 1_000, 50000.0, 50002.0));
+        builder.add_trade(&make_classified_trade(60_000 + 30_000, 50001.0, 0.1));
+        assert!(builder.finalize_before(120_000 + 1000).is_empty());
+        assert_eq!(builder.missing_quote_bars(), 1);
+        builder.clear();
+        assert_eq!(builder.missing_quote_bars(), 0);
+
+        config.bars = BarConfig {
+            close_quote_policy: CloseQuotePolicy::TimeWeightedMidOfMinute,
+            ..Default::default()
+        };
+        let mut builder = BarBuilder::from(&config);
+        builder.add_quote(make_quote(60_000, 50000.0, 50002.0));
+        builder.add_quote(make_quote(60_000 + 30_000, 50010.0, 50012.0));
+        builder.add_trade(&make_classified_trade(60_000 + 45_000, 50011.0, 0.1));
+        let bars = builder.finalize_before(120_000 + 1000);
+        assert!((bars[0].mid_close() - 50006.0).abs() < 1e-6);
+    }
 }
@@ -3,8 +3,9 @@
 //! Classifies trades as buy-initiated, sell-initiated, or ambiguous based on
 //! their price relative to the prevailing bid/ask quote.
 
-use auction_core::{ClassifiedTrade, Quote, Trade, TradeSide};
-use std::collections::VecDeque;
+use auction_core::{AtMidPolicy, ClassifiedTrade, Config, Error, Quote, Result, Trade, TradeSide};
+pub use auction_core::{OutlierFilter, TickRounding};
+use std::collections::{HashSet, VecDeque};
 
 /// Statistics about trade classification quality.
 #[derive(Debug, Clone, Default)]
@@ -29,6 +30,22 @@ pub struct ClassificationStats {
     pub total_staleness_ms: i64,
     /// Trades where quote was stale (> max_staleness).
     pub stale_quote_trades: u64,
+    /// Quotes added with a side below the minimum quote size.
+    pub thin_quotes: u64,
+    /// Trades aligned with a thin quote (classified by tick rule instead).
+    pub thin_quote_trades: u64,
+    /// Exact duplicate trades (feed resends) dropped before classification.
+    pub duplicate_trades: u64,
+    /// Volume of dropped duplicate trades.
+    pub duplicate_volume: f64,
+    /// Trades dropped by the outlier filter (bad prints).
+    pub outlier_trades: u64,
+    /// Volume of dropped outlier trades.
+    pub outlier_volume: f64,
+    /// Predicted vs true sides of trades classified with a label.
+    pub confusion: ConfusionMatrix,
+    /// Trades whose feed-provided side was used without inference.
+    pub provided_side_trades: u64,
 }
 
 impl ClassificationStats {
@@ -56,6 +73,169 @@ impl ClassificationStats {
     }
 }
 
+/// Predicted vs true trade side counts from labeled trades.
+///
+/// Rows and columns are in `SIDES` order (buy, sell, ambiguous).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConfusionMatrix {
+    /// `counts[predicted][truth]`.
+    pub counts: [[u64; 3]; 3],
+}
+
+impl ConfusionMatrix {
+    /// Row/column order of `counts`.
+    pub const SIDES: [TradeSide; 3] = [TradeSide::Buy, TradeSide::Sell, TradeSide::Ambiguous];
+
+    fn index(side: TradeSide) -> usize {
+        match side {
+            TradeSide::Buy => 0,
+            TradeSide::Sell => 1,
+            TradeSide::Ambiguous => 2,
+        }
+    }
+
+    /// Record one labeled classification.
+    pub fn record(&mut self, predicted: TradeSide, truth: TradeSide) {
+        self.counts[Self::index(predicted)][Self::index(truth)] += 1;
+    }
+
+    /// Trades with the given predicted and true sides.
+    pub fn count(&self, predicted: TradeSide, truth: TradeSide) -> u64 {
+        self.counts[Self::index(predicted)][Self::index(truth)]
+    }
+
+    /// Total labeled trades.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// Fraction of labeled trades classified correctly (0 if none).
+    pub fn accuracy(&self) -> f64 {
+        let correct: u64 = (0..3).map(|i| self.counts[i][i]).sum();
+        ratio(correct, self.total())
+    }
+
+    /// Fraction of trades predicted as `side` that truly were (0 if none).
+    pub fn precision(&self, side: TradeSide) -> f64 {
+        let i = Self::index(side);
+        ratio(self.counts[i][i], self.counts[i].iter().sum())
+    }
+
+    /// Fraction of trades truly `side` that were predicted as such (0 if none).
+    pub fn recall(&self, side: TradeSide) -> f64 {
+        let i = Self::index(side);
+        ratio(self.counts[i][i], self.counts.iter().map(|row| row[i]).sum())
+    }
+}
+
+fn ratio(num: u64, den: u64) -> f64 {
+    if den > 0 {
+        num as f64 / den as f64
+    } else {
+        0.0
+    }
+}
+
+/// Trade-quote join diagnostics for a single `classify_batch` call.
+///
+/// Trades with no quote at or before their timestamp are classified without a
+/// quote (zeroed quote prices, `quote_staleness_ms = i64::MAX`). A non-zero
+/// count usually means the quote load started after the trade load.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JoinDiagnostics {
+    /// Input trades with no quote available.
+    pub trades_without_quote: u64,
+    /// Timestamp of the first trade without a quote.
+    pub first_unquoted_ts: Option<i64>,
+    /// Timestamp of the last trade without a quote.
+    pub last_unquoted_ts: Option<i64>,
+}
+
+impl JoinDiagnostics {
+    /// Whether every trade in the batch had a quote.
+    pub fn is_complete(&self) -> bool {
+        self.trades_without_quote == 0
+    }
+
+    /// Time span (ms) covered by trades without a quote.
+    pub fn gap_span_ms(&self) -> i64 {
+        match (self.first_unquoted_ts, self.last_unquoted_ts) {
+            (Some(first), Some(last)) => last - first,
+            _ => 0,
+        }
+    }
+
+    fn record(&mut self, ts_ms: i64, count: usize) {
+        self.trades_without_quote += count as u64;
+        self.first_unquoted_ts.get_or_insert(ts_ms);
+        self.last_unquoted_ts = Some(ts_ms);
+    }
+}
+
+/// Classified trades as parallel columns (one row per classified trade).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClassifiedColumns {
+    /// Trade timestamps.
+    pub ts_ms: Vec<i64>,
+    /// Trade prices (VWAP for aggregated same-timestamp trades).
+    pub price: Vec<f64>,
+    /// Trade sizes.
+    pub size: Vec<f64>,
+    /// Trade side sign: 1 buy, -1 sell, 0 ambiguous.
+    pub side: Vec<i8>,
+    /// Bid price of the quote used.
+    pub quote_bid_px: Vec<f64>,
+    /// Ask price of the quote used.
+    pub quote_ask_px: Vec<f64>,
+    /// Quote staleness (ms); `i64::MAX` if no quote was available.
+    pub quote_staleness_ms: Vec<i64>,
+}
+
+impl ClassifiedColumns {
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.ts_ms.len()
+    }
+
+    /// Check if there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.ts_ms.is_empty()
+    }
+}
+
+impl From<Vec<ClassifiedTrade>> for ClassifiedColumns {
+    fn from(trades: Vec<ClassifiedTrade>) -> Self {
+        let n = trades.len();
+        let mut cols = ClassifiedColumns {
+            ts_ms: Vec::with_capacity(n),
+            price: Vec::with_capacity(n),
+            size: Vec::with_capacity(n),
+            side: Vec::with_capacity(n),
+            quote_bid_px: Vec::with_capacity(n),
+            quote_ask_px: Vec::with_capacity(n),
+            quote_staleness_ms: Vec::with_capacity(n),
+        };
+        for ct in trades {
+            cols.ts_ms.push(ct.trade.ts_ms);
+            cols.price.push(ct.trade.price);
+            cols.size.push(ct.trade.size);
+            cols.side.push(ct.side.sign());
+            cols.quote_bid_px.push(ct.quote_bid_px);
+            cols.quote_ask_px.push(ct.quote_ask_px);
+            cols.quote_staleness_ms.push(ct.quote_staleness_ms);
+        }
+        cols
+    }
+}
+
+/// Check that all columns of a batch have the same length.
+fn check_lengths(name: &str, lens: &[usize]) -> Result<()> {
+    if lens.windows(2).any(|w| w[0] != w[1]) {
+        return Err(Error::data(format!("{name} columns have mismatched lengths {lens:?}")));
+    }
+    Ok(())
+}
+
 /// Trade classifier that aligns trades with quotes and infers trade side.
 pub struct TradeClassifier {
     /// Maximum allowed quote staleness (ms).
@@ -72,6 +252,28 @@ pub struct TradeClassifier {
     last_trade_side: TradeSide,
     /// Classification statistics.
     stats: ClassificationStats,
+    /// Tick size and rounding policy for snapping trade prices before comparison.
+    tick_rounding: Option<(f64, TickRounding)>,
+    /// Attribution of trades exactly at the quote mid.
+    at_mid_policy: AtMidPolicy,
+    /// Minimum size on each side for a quote to be used for classification.
+    min_quote_size: f64,
+    /// How long (ms) trade keys are remembered for dedup (None = disabled).
+    dedup_window_ms: Option<i64>,
+    /// Recent `(ts_ms, price, size)` keys, oldest first (for eviction).
+    recent_trades: VecDeque<(i64, u64, u64)>,
+    /// The keys of `recent_trades`, for lookup.
+    recent_trade_keys: HashSet<(i64, u64, u64)>,
+    /// Bad print filter (None = disabled).
+    outlier_filter: Option<OutlierFilter>,
+    /// Last trade price accepted by the outlier filter.
+    last_accepted_price: Option<f64>,
+    /// Recent log returns between accepted trades (for `OutlierFilter::Sigma`).
+    accepted_returns: VecDeque<f64>,
+    /// Use feed-provided aggressor sides as-is in `classify_with_side`.
+    trust_provided_side: bool,
+    /// Seed an empty tick rule from the first quote-classified trade of a batch.
+    seed_tick_rule_from_batch: bool,
 }
 
 impl TradeClassifier {
@@ -85,11 +287,221 @@ impl TradeClassifier {
             last_trade_price: None,
             last_trade_side: TradeSide::Ambiguous,
             stats: ClassificationStats::default(),
+            tick_rounding: None,
+            at_mid_policy: AtMidPolicy::Ambiguous,
+            min_quote_size: 0.0,
+            dedup_window_ms: None,
+            recent_trades: VecDeque::new(),
+            recent_trade_keys: HashSet::new(),
+            outlier_filter: None,
+            last_accepted_price: None,
+            accepted_returns: VecDeque::new(),
+            trust_provided_side: false,
+            seed_tick_rule_from_batch: false,
+        }
+    }
+
+    /// Maximum allowed quote staleness (ms).
+    pub fn max_staleness_ms(&self) -> i64 {
+        self.max_staleness_ms
+    }
+
+    /// Whether the tick rule resolves ambiguous trades.
+    pub fn uses_tick_rule(&self) -> bool {
+        self.use_tick_rule
+    }
+
+    /// Minimum quote size on each side for bid/ask classification.
+    pub fn min_quote_size(&self) -> f64 {
+        self.min_quote_size
+    }
+
+    /// Drop exact resends of a trade in `classify_filtered`,
+    /// `classify_with_side_filtered` and `classify_batch`.
+    ///
+    /// A trade is a duplicate if a trade with identical `(ts_ms, price, size)`
+    /// was seen within the last `window_ms` (by trade time). Distinct trades
+    /// sharing a timestamp are kept and aggregated as usual.
+    pub fn with_dedup_window(mut self, window_ms: i64) -> Self {
+        self.dedup_window_ms = Some(window_ms.max(0));
+        self
+    }
+
+    /// Check a trade against recently seen trades, remembering it if new.
+    ///
+    /// Always `false` when dedup is disabled.
+    pub fn is_duplicate(&mut self, trade: &Trade) -> bool {
+        let Some(window_ms) = self.dedup_window_ms else {
+            return false;
+        };
+
+        let newest = self.recent_trades.back().map_or(trade.ts_ms, |k| k.0.max(trade.ts_ms));
+        while self.recent_trades.front().is_some_and(|k| k.0 < newest - window_ms) {
+            if let Some(key) = self.recent_trades.pop_front() {
+                self.recent_trade_keys.remove(&key);
+            }
+        }
+
+        let key = (trade.ts_ms, trade.price.to_bits(), trade.size.to_bits());
+        if !self.recent_trade_keys.insert(key) {
+            self.stats.duplicate_trades += 1;
+            self.stats.duplicate_volume += trade.size;
+            return true;
+        }
+        self.recent_trades.push_back(key);
+        false
+    }
+
+    /// Drop prints far from the market in `classify_filtered`,
+    /// `classify_with_side_filtered` and `classify_batch`.
+    pub fn with_outlier_filter(mut self, filter: OutlierFilter) -> Self {
+        self.outlier_filter = Some(filter);
+        self
+    }
+
+    /// Check a trade against the outlier filter, remembering it if accepted.
+    ///
+    /// Non-positive or non-finite prices are always outliers when the filter
+    /// is enabled. Always `false` when it is disabled.
+    pub fn is_outlier(&mut self, trade: &Trade) -> bool {
+        let Some(filter) = self.outlier_filter else {
+            return false;
+        };
+
+        let reference = self
+            .find_quote(trade.ts_ms)
+            .filter(|q| q.bid_px > 0.0 && q.ask_px >= q.bid_px)
+            .map(Quote::mid)
+            .or(self.last_accepted_price);
+
+        let outlier = if !(trade.price.is_finite() && trade.price > 0.0) {
+            true
+        } else if let Some(reference) = reference {
+            let deviation = (trade.price / reference).ln().abs();
+            let limit = match filter {
+                OutlierFilter::Pct { max_pct } => max_pct.ln_1p(),
+                OutlierFilter::Sigma { k, min_pct, .. } => (k * self.accepted_return_rms()).max(min_pct.ln_1p()),
+            };
+            deviation > limit
+        } else {
+            false
+        };
+
+        if outlier {
+            self.stats.outlier_trades += 1;
+            self.stats.outlier_volume += trade.size;
+            return true;
+        }
+
+        if let Some(last) = self.last_accepted_price.replace(trade.price) {
+            if let OutlierFilter::Sigma { lookback, .. } = filter {
+                self.accepted_returns.push_back((trade.price / last).ln());
+                while self.accepted_returns.len() > lookback {
+                    self.accepted_returns.pop_front();
+                }
+            }
+        }
+        false
+    }
+
+    /// Check a trade against dedup and the outlier filter.
+    fn is_dropped(&mut self, trade: &Trade) -> bool {
+        self.is_duplicate(trade) || self.is_outlier(trade)
+    }
+
+    /// Root-mean-square of the recent accepted trade-to-trade log returns.
+    fn accepted_return_rms(&self) -> f64 {
+        if self.accepted_returns.is_empty() {
+            return 0.0;
+        }
+        let sum_sq: f64 = self.accepted_returns.iter().map(|r| r * r).sum();
+        (sum_sq / self.accepted_returns.len() as f64).sqrt()
+    }
+
+    /// Treat quotes with either side below `min_quote_size` as unreliable.
+    ///
+    /// Trades aligned with such a quote are classified by the tick rule
+    /// (ambiguous if it is disabled) instead of by bid/ask.
+    pub fn with_min_quote_size(mut self, min_quote_size: f64) -> Self {
+        self.min_quote_size = min_quote_size.max(0.0);
+        self
+    }
+
+    /// Check if a quote is too thin to classify against.
+    fn is_thin(&self, quote: &Quote) -> bool {
+        quote.bid_sz < self.min_quote_size || quote.ask_sz < self.min_quote_size
+    }
+
+    /// Tick rule side for a price (ambiguous if disabled or no prior trade).
+    fn tick_rule_side(&self, price: f64) -> TradeSide {
+        match self.last_trade_price {
+            Some(last_price) if self.use_tick_rule => {
+                if price > last_price {
+                    TradeSide::Buy
+                } else if price < last_price {
+                    TradeSide::Sell
+                } else {
+                    self.last_trade_side
+                }
+            }
+            _ => TradeSide::Ambiguous,
+        }
+    }
+
+    /// Set the policy for trades exactly at the quote mid.
+    ///
+    /// With `AtMidPolicy::SplitHalf` the trade stays ambiguous here and the
+    /// order flow aggregator (configured with the same policy) splits it.
+    pub fn with_at_mid_policy(mut self, policy: AtMidPolicy) -> Self {
+        self.at_mid_policy = policy;
+        self
+    }
+
+    /// Snap trade prices to the tick grid before comparing them with bid/ask.
+    ///
+    /// Only the comparison uses the snapped price; classified trades keep
+    /// the reported price. `TickRounding::Nearest` turns sub-tick prints
+    /// within half a tick of the touch into buys or sells;
+    /// `TickRounding::TowardMid` never changes a print's side.
+    pub fn with_tick_rounding(mut self, tick_size: f64, rounding: TickRounding) -> Self {
+        if tick_size > 0.0 {
+            self.tick_rounding = Some((tick_size, rounding));
+        }
+        self
+    }
+
+    /// Snap a price to the tick grid according to the rounding policy.
+    fn snap_price(&self, price: f64, quote: &Quote) -> f64 {
+        let Some((tick_size, rounding)) = self.tick_rounding else {
+            return price;
+        };
+
+        let ticks = price / tick_size;
+        let nearest = ticks.round();
+        if (ticks - nearest).abs() < 1e-9 {
+            return nearest * tick_size;
+        }
+
+        let mid = (quote.bid_px + quote.ask_px) / 2.0;
+        let snapped = match rounding {
+            TickRounding::Nearest => return nearest * tick_size,
+            TickRounding::TowardMid if price < mid => ticks.ceil() * tick_size,
+            TickRounding::TowardMid if price > mid => ticks.floor() * tick_size,
+            TickRounding::TowardMid => return price,
+        };
+        // Stay strictly on the print's side of the mid
+        if (price < mid) == (snapped < mid) && (price > mid) == (snapped > mid) {
+            snapped
+        } else {
+            price
         }
     }
 
     /// Add a quote to the classifier.
     pub fn add_quote(&mut self, quote: Quote) {
+        if self.is_thin(&quote) {
+            self.stats.thin_quotes += 1;
+        }
         // Remove quotes older than the new one (quotes should arrive in order)
         while self.quotes.len() >= self.max_quotes {
             self.quotes.pop_front();
@@ -109,24 +521,137 @@ impl TradeClassifier {
 
     /// Classify a single trade.
     pub fn classify(&mut self, trade: Trade) -> ClassifiedTrade {
-        let quote = self.find_quote(trade.ts_ms);
+        let quote = self.find_quote(trade.ts_ms).cloned();
+        self.classify_against(trade, quote)
+    }
+
+    /// Classify a single trade unless it is dropped as a duplicate or an
+    /// outlier (see [`with_dedup_window`](Self::with_dedup_window) and
+    /// [`with_outlier_filter`](Self::with_outlier_filter)), as
+    /// [`classify_batch`](Self::classify_batch) does.
+    pub fn classify_filtered(&mut self, trade: Trade) -> Option<ClassifiedTrade> {
+        if self.is_dropped(&trade) {
+            return None;
+        }
+        Some(self.classify(trade))
+    }
+
+    /// Classify a trade against an explicit quote, bypassing the quote buffer.
+    ///
+    /// Useful when trades are already joined to their quotes. Tick-rule state
+    /// and statistics are updated exactly as in [`classify`](Self::classify).
+    pub fn classify_with_quote(&mut self, trade: Trade, quote: &Quote) -> ClassifiedTrade {
+        self.classify_against(trade, Some(quote.clone()))
+    }
+
+    /// Trust the aggressor side supplied with a trade (see
+    /// [`classify_with_side`](Self::classify_with_side)).
+    pub fn with_trust_provided_side(mut self, trust: bool) -> Self {
+        self.trust_provided_side = trust;
+        self
+    }
+
+    /// Seed the tick rule of a fresh classifier from within each batch.
+    ///
+    /// When `classify_batch` starts with no prior trade, a first pass finds
+    /// the first trade the quotes classify as a buy or sell and seeds the
+    /// tick rule with its price and side, so leading ambiguous trades are
+    /// resolved against it (as the reverse tick rule would) instead of
+    /// staying ambiguous. Has no effect without the tick rule.
+    pub fn with_seed_tick_rule_from_batch(mut self, seed: bool) -> Self {
+        self.seed_tick_rule_from_batch = seed;
+        self
+    }
+
+    /// Seed an empty tick rule from the first quote-classified trade.
+    fn seed_tick_rule(&mut self, trades: &[Trade]) {
+        if !self.use_tick_rule || self.last_trade_price.is_some() {
+            return;
+        }
+        let seed = trades.iter().find_map(|trade| {
+            let q = self.find_quote(trade.ts_ms).filter(|q| !self.is_thin(q))?;
+            match self.quote_side(trade.price, q) {
+                TradeSide::Ambiguous => None,
+                side => Some((trade.price, side)),
+            }
+        });
+        if let Some((price, side)) = seed {
+            self.last_trade_price = Some(price);
+            self.last_trade_side = side;
+        }
+    }
+
+    /// Classify a trade that may come with an exchange-provided side.
+    ///
+    /// With [`with_trust_provided_side`](Self::with_trust_provided_side) a
+    /// provided side is used directly and quote-alignment inference is
+    /// skipped; the prevailing quote is still attached and statistics
+    /// (including stale quotes) and tick-rule state are updated as usual.
+    /// Otherwise, or without a provided side, this is
+    /// [`classify`](Self::classify).
+    pub fn classify_with_side(&mut self, trade: Trade, provided: Option<TradeSide>) -> ClassifiedTrade {
+        match provided {
+            Some(side) if self.trust_provided_side => {
+                let (bid, ask, staleness_ms) = match self.find_quote(trade.ts_ms) {
+                    Some(q) => (q.bid_px, q.ask_px, trade.ts_ms - q.ts_ms),
+                    None => (0.0, 0.0, i64::MAX),
+                };
+                if staleness_ms != i64::MAX && staleness_ms > self.max_staleness_ms {
+                    self.stats.stale_quote_trades += 1;
+                }
+                self.stats.provided_side_trades += 1;
+                self.record(trade, side, bid, ask, staleness_ms)
+            }
+            _ => self.classify(trade),
+        }
+    }
+
+    /// [`classify_with_side`](Self::classify_with_side), dropping duplicates
+    /// and outliers as [`classify_filtered`](Self::classify_filtered) does.
+    pub fn classify_with_side_filtered(
+        &mut self,
+        trade: Trade,
+        provided: Option<TradeSide>,
+    ) -> Option<ClassifiedTrade> {
+        if self.is_dropped(&trade) {
+            return None;
+        }
+        Some(self.classify_with_side(trade, provided))
+    }
+
+    /// Classify a trade whose true side is known, recording the outcome in
+    /// the confusion matrix of the statistics.
+    pub fn classify_labeled(&mut self, trade: Trade, truth: TradeSide) -> ClassifiedTrade {
+        let classified = self.classify(trade);
+        self.stats.confusion.record(classified.side, truth);
+        classified
+    }
 
+    /// Classify a trade against the given quote (if any).
+    fn classify_against(&mut self, trade: Trade, quote: Option<Quote>) -> ClassifiedTrade {
         let (side, quote_bid_px, quote_ask_px, staleness_ms) = match quote {
+            Some(q) if self.is_thin(&q) => {
+                self.stats.thin_quote_trades += 1;
+                (self.tick_rule_side(trade.price), q.bid_px, q.ask_px, trade.ts_ms - q.ts_ms)
+            }
             Some(q) => {
                 let staleness = trade.ts_ms - q.ts_ms;
                 let is_stale = staleness > self.max_staleness_ms;
 
                 // Classify based on price vs bid/ask
-                let mut side = if trade.price >= q.ask_px {
-                    TradeSide::Buy
-                } else if trade.price <= q.bid_px {
-                    TradeSide::Sell
-                } else {
-                    TradeSide::Ambiguous
-                };
+                let mut side = self.quote_side(trade.price, &q);
 
                 // Apply tick rule fallback for ambiguous trades
-                if side == TradeSide::Ambiguous && self.use_tick_rule {
+                let use_tick_rule = if side == TradeSide::Ambiguous && q.is_at_mid(trade.price) {
+                    match self.at_mid_policy {
+                        AtMidPolicy::Ambiguous => self.use_tick_rule,
+                        AtMidPolicy::TickRuleOnly => true,
+                        AtMidPolicy::SplitHalf => false,
+                    }
+                } else {
+                    self.use_tick_rule
+                };
+                if side == TradeSide::Ambiguous && use_tick_rule {
                     if let Some(last_price) = self.last_trade_price {
                         side = if trade.price > last_price {
                             TradeSide::Buy
@@ -148,25 +673,34 @@ impl TradeClassifier {
             }
             None => {
                 // No quote available - use tick rule if enabled
-                let side = if self.use_tick_rule {
-                    if let Some(last_price) = self.last_trade_price {
-                        if trade.price > last_price {
-                            TradeSide::Buy
-                        } else if trade.price < last_price {
-                            TradeSide::Sell
-                        } else {
-                            self.last_trade_side
-                        }
-                    } else {
-                        TradeSide::Ambiguous
-                    }
-                } else {
-                    TradeSide::Ambiguous
-                };
-                (side, 0.0, 0.0, i64::MAX)
+                (self.tick_rule_side(trade.price), 0.0, 0.0, i64::MAX)
             }
         };
 
+        self.record(trade, side, quote_bid_px, quote_ask_px, staleness_ms)
+    }
+
+    /// Side implied by a price against a quote's bid and ask.
+    fn quote_side(&self, price: f64, quote: &Quote) -> TradeSide {
+        let price = self.snap_price(price, quote);
+        if price >= quote.ask_px {
+            TradeSide::Buy
+        } else if price <= quote.bid_px {
+            TradeSide::Sell
+        } else {
+            TradeSide::Ambiguous
+        }
+    }
+
+    /// Update statistics and tick-rule state for a trade of the given side.
+    fn record(
+        &mut self,
+        trade: Trade,
+        side: TradeSide,
+        quote_bid_px: f64,
+        quote_ask_px: f64,
+        staleness_ms: i64,
+    ) -> ClassifiedTrade {
         // Update statistics
         self.stats.total_trades += 1;
         self.stats.total_volume += trade.size;
@@ -204,8 +738,61 @@ impl TradeClassifier {
 
     /// Classify multiple trades, aggregating trades at the same timestamp.
     pub fn classify_batch(&mut self, trades: Vec<Trade>) -> Vec<ClassifiedTrade> {
+        self.classify_batch_with_diagnostics(trades).0
+    }
+
+    /// Add quotes given as parallel columns (in timestamp order).
+    pub fn add_quote_columns(
+        &mut self,
+        ts_ms: &[i64],
+        bid_px: &[f64],
+        bid_sz: &[f64],
+        ask_px: &[f64],
+        ask_sz: &[f64],
+    ) -> Result<()> {
+        check_lengths("quote", &[ts_ms.len(), bid_px.len(), bid_sz.len(), ask_px.len(), ask_sz.len()])?;
+        for i in 0..ts_ms.len() {
+            self.add_quote(Quote {
+                ts_ms: ts_ms[i],
+                bid_px: bid_px[i],
+                bid_sz: bid_sz[i],
+                ask_px: ask_px[i],
+                ask_sz: ask_sz[i],
+            });
+        }
+        Ok(())
+    }
+
+    /// Columnar `classify_batch`: same aggregation and results, without
+    /// per-trade objects on either side.
+    pub fn classify_columns(&mut self, ts_ms: &[i64], price: &[f64], size: &[f64]) -> Result<ClassifiedColumns> {
+        check_lengths("trade", &[ts_ms.len(), price.len(), size.len()])?;
+        let trades = (0..ts_ms.len())
+            .map(|i| Trade {
+                ts_ms: ts_ms[i],
+                price: price[i],
+                size: size[i],
+            })
+            .collect();
+        Ok(self.classify_batch(trades).into())
+    }
+
+    /// Like `classify_batch`, also reporting trades that had no quote to join.
+    pub fn classify_batch_with_diagnostics(
+        &mut self,
+        trades: Vec<Trade>,
+    ) -> (Vec<ClassifiedTrade>, JoinDiagnostics) {
+        let mut diagnostics = JoinDiagnostics::default();
         if trades.is_empty() {
-            return Vec::new();
+            return (Vec::new(), diagnostics);
+        }
+
+        let trades: Vec<Trade> = trades
+            .into_iter()
+            .filter(|trade| !self.is_dropped(trade))
+            .collect();
+        if self.seed_tick_rule_from_batch {
+            self.seed_tick_rule(&trades);
         }
 
         // Group trades by timestamp
@@ -219,7 +806,7 @@ impl TradeClassifier {
             } else {
                 // Process previous group
                 if !current_group.is_empty() {
-                    self.process_trade_group(&mut current_group, &mut result);
+                    self.process_trade_group(&mut current_group, &mut result, &mut diagnostics);
                 }
                 current_ts = Some(trade.ts_ms);
                 current_group.clear();
@@ -229,16 +816,22 @@ impl TradeClassifier {
 
         // Process last group
         if !current_group.is_empty() {
-            self.process_trade_group(&mut current_group, &mut result);
+            self.process_trade_group(&mut current_group, &mut result, &mut diagnostics);
         }
 
-        result
+        (result, diagnostics)
     }
 
     /// Process a group of trades at the same timestamp.
     /// Aggregates them into a single classified trade.
-    fn process_trade_group(&mut self, group: &mut Vec<Trade>, result: &mut Vec<ClassifiedTrade>) {
-        if group.len() == 1 {
+    fn process_trade_group(
+        &mut self,
+        group: &mut Vec<Trade>,
+        result: &mut Vec<ClassifiedTrade>,
+        diagnostics: &mut JoinDiagnostics,
+    ) {
+        let count = group.len();
+        if count == 1 {
             // Single trade - classify normally
             let trade = group.pop().unwrap();
             result.push(self.classify(trade));
@@ -270,6 +863,12 @@ impl TradeClassifier {
 
             result.push(self.classify(aggregated));
         }
+
+        if let Some(classified) = result.last() {
+            if classified.quote_staleness_ms == i64::MAX {
+                diagnostics.record(classified.trade.ts_ms, count);
+            }
+        }
     }
 
     /// Get classification statistics.
@@ -287,10 +886,37 @@ impl TradeClassifier {
         self.quotes.clear();
         self.last_trade_price = None;
         self.last_trade_side = TradeSide::Ambiguous;
+        self.recent_trades.clear();
+        self.recent_trade_keys.clear();
+        self.last_accepted_price = None;
+        self.accepted_returns.clear();
         self.stats.reset();
     }
 }
 
+impl From<&Config> for TradeClassifier {
+    /// Classifier for the instrument tick and order flow settings of `config`.
+    fn from(config: &Config) -> Self {
+        let order_flow = &config.order_flow;
+        let mut classifier =
+            Self::new(order_flow.max_quote_staleness_ms, order_flow.use_tick_rule_fallback)
+                .with_min_quote_size(order_flow.min_quote_size)
+                .with_at_mid_policy(order_flow.at_mid_policy)
+                .with_trust_provided_side(order_flow.trust_provided_side)
+                .with_seed_tick_rule_from_batch(order_flow.seed_tick_rule_from_batch);
+        if let Some(rounding) = order_flow.tick_rounding {
+            classifier = classifier.with_tick_rounding(config.instrument.tick_size, rounding);
+        }
+        if let Some(window_ms) = order_flow.dedup_window_ms {
+            classifier = classifier.with_dedup_window(window_ms);
+        }
+        if let Some(filter) = order_flow.outlier_filter {
+            classifier = classifier.with_outlier_filter(filter);
+        }
+        classifier
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -382,6 +1008,132 @@ mod tests {
         assert_eq!(classified2.side, TradeSide::Buy);
     }
 
+    #[test]
+    fn test_sub_tick_rounding() {
+        let quote = make_quote(1000, 50000.0, 50000.5);
+        let trade = Trade {
+            ts_ms: 1010,
+            price: 50000.49, // reported with sub-tick precision
+            size: 1.0,
+        };
+
+        // Without rounding the price sits strictly inside the spread
+        let mut classifier = TradeClassifier::new(250, false);
+        classifier.add_quote(quote.clone());
+        assert_eq!(classifier.classify(trade.clone()).side, TradeSide::Ambiguous);
+
+        // Nearest snaps onto the ask
+        let mut classifier =
+            TradeClassifier::new(250, false).with_tick_rounding(0.5, TickRounding::Nearest);
+        classifier.add_quote(quote.clone());
+        let classified = classifier.classify(trade.clone());
+        assert_eq!(classified.side, TradeSide::Buy);
+        assert!((classified.trade.price - 50000.49).abs() < 1e-10);
+
+        let sell = Trade { price: 50000.01, ..trade.clone() };
+        assert_eq!(classifier.classify(sell).side, TradeSide::Sell);
+
+        // Toward-mid never snaps onto the touch: the same print stays ambiguous
+        let mut classifier =
+            TradeClassifier::new(250, false).with_tick_rounding(0.5, TickRounding::TowardMid);
+        classifier.add_quote(quote.clone());
+        assert_eq!(classifier.classify(trade.clone()).side, TradeSide::Ambiguous);
+        let sell = Trade { price: 50000.01, ..trade.clone() };
+        assert_eq!(classifier.classify(sell).side, TradeSide::Ambiguous);
+
+        // Toward-mid keeps inside prices inside; prints through the touch stay outside
+        let mut classifier =
+            TradeClassifier::new(250, false).with_tick_rounding(0.5, TickRounding::TowardMid);
+        classifier.add_quote(make_quote(1000, 50000.0, 50002.0));
+        let inside = Trade { price: 50000.4, ..trade.clone() };
+        assert_eq!(classifier.classify(inside).side, TradeSide::Ambiguous);
+        let through = Trade { price: 50002.3, ..trade.clone() };
+        assert_eq!(classifier.classify(through).side, TradeSide::Buy);
+
+        // One-tick spread: snapping toward the mid would land on the ask
+        // (or bid), so inside prints stay unsnapped and keep their side
+        let mut classifier =
+            TradeClassifier::new(250, false).with_tick_rounding(0.5, TickRounding::TowardMid);
+        classifier.add_quote(make_quote(1000, 50000.0, 50000.5));
+        let below_mid = Trade { price: 50000.1, ..trade.clone() };
+        assert_eq!(classifier.classify(below_mid).side, TradeSide::Ambiguous);
+        let above_mid = Trade { price: 50000.4, ..trade.clone() };
+        assert_eq!(classifier.classify(above_mid).side, TradeSide::Ambiguous);
+        // Prints through the touch still snap onto it
+        let under_bid = Trade { price: 49999.7, ..trade };
+        assert_eq!(classifier.classify(under_bid).side, TradeSide::Sell);
+    }
+
+    #[test]
+    fn test_classify_with_quote() {
+        let quote = make_quote(1000, 50000.0, 50001.0);
+        let trades = vec![
+            make_trade(1010, 50001.0, 1.0),
+            make_trade(1020, 50000.5, 2.0),
+            make_trade(1030, 50000.0, 0.5),
+            make_trade(1400, 50000.5, 1.0), // stale relative to the quote
+        ];
+
+        let mut buffered = TradeClassifier::new(250, true);
+        buffered.add_quote(quote.clone());
+        let mut direct = TradeClassifier::new(250, true);
+
+        for trade in trades {
+            let a = buffered.classify(trade.clone());
+            let b = direct.classify_with_quote(trade, &quote);
+            assert_eq!(a.side, b.side);
+            assert_eq!(a.quote_staleness_ms, b.quote_staleness_ms);
+            assert!((a.quote_bid_px - b.quote_bid_px).abs() < 1e-10);
+            assert!((a.quote_ask_px - b.quote_ask_px).abs() < 1e-10);
+        }
+
+        assert_eq!(buffered.stats().total_trades, direct.stats().total_trades);
+        assert_eq!(buffered.stats().buy_trades, direct.stats().buy_trades);
+        assert_eq!(buffered.stats().stale_quote_trades, 1);
+        assert_eq!(direct.stats().stale_quote_trades, 1);
+    }
+
+    #[test]
+    fn test_at_mid_policies() {
+        // Even-tick spread: 50000.5 is a tradable mid
+        let quote = make_quote(1000, 50000.0, 50001.0);
+        let stream = [50000.0, 50000.5, 50000.5, 50001.0, 50000.5];
+
+        let run = |classifier: TradeClassifier| {
+            let mut classifier = classifier;
+            classifier.add_quote(quote.clone());
+            stream
+                .iter()
+                .enumerate()
+                .map(|(i, &p)| classifier.classify(make_trade(1010 + i as i64, p, 1.0)).side)
+                .collect::<Vec<_>>()
+        };
+
+        use TradeSide::{Ambiguous, Buy, Sell};
+
+        // Ambiguous (default): tick rule only if the fallback is enabled
+        assert_eq!(
+            run(TradeClassifier::new(250, false)),
+            vec![Sell, Ambiguous, Ambiguous, Buy, Ambiguous]
+        );
+        assert_eq!(
+            run(TradeClassifier::new(250, true)),
+            vec![Sell, Buy, Buy, Buy, Sell]
+        );
+
+        // TickRuleOnly: at-mid trades use the tick rule even with the fallback off
+        assert_eq!(
+            run(TradeClassifier::new(250, false).with_at_mid_policy(AtMidPolicy::TickRuleOnly)),
+            vec![Sell, Buy, Buy, Buy, Sell]
+        );
+
+        // SplitHalf: at-mid trades stay ambiguous for the aggregator to split
+        assert_eq!(
+            run(TradeClassifier::new(250, true).with_at_mid_policy(AtMidPolicy::SplitHalf)),
+            vec![Sell, Ambiguous, Ambiguous, Buy, Ambiguous]
+        );
+    }
+
     #[test]
     fn test_batch_aggregation() {
         let mut classifier = TradeClassifier::new(250, false);
@@ -397,10 +1149,224 @@ mod tests {
 
         // Should have 2 results: aggregated first two + third
         assert_eq!(classified.len(), 2);
-        assert_eq!(classified[0].trade.size, 0.3); // Aggregated
+        assert!((classified[0].trade.size - 0.3).abs() < 1e-10); // Aggregated
         assert_eq!(classified[1].trade.size, 0.1);
     }
 
+    #[test]
+    fn test_batch_join_diagnostics() {
+        let mut classifier = TradeClassifier::new(250, false);
+        classifier.add_quote(make_quote(5000, 50000.0, 50001.0));
+
+        let trades = vec![
+            make_trade(1000, 50001.0, 0.1), // Before any quote
+            make_trade(2000, 50001.0, 0.1), // Before any quote (aggregated)
+            make_trade(2000, 50000.0, 0.2),
+            make_trade(5100, 50001.0, 0.1), // Quoted
+        ];
+
+        let (classified, diag) = classifier.classify_batch_with_diagnostics(trades);
+        assert_eq!(classified.len(), 3);
+        assert!(!diag.is_complete());
+        assert_eq!(diag.trades_without_quote, 3);
+        assert_eq!(diag.first_unquoted_ts, Some(1000));
+        assert_eq!(diag.last_unquoted_ts, Some(2000));
+        assert_eq!(diag.gap_span_ms(), 1000);
+
+        let (_, diag) =
+            classifier.classify_batch_with_diagnostics(vec![make_trade(6000, 50001.0, 0.1)]);
+        assert!(diag.is_complete());
+        assert_eq!(diag.gap_span_ms(), 0);
+    }
+
+    #[test]
+    fn test_thin_quote_falls_back_to_tick_rule() {
+        let mut classifier = TradeClassifier::new(250, true).with_min_quote_size(0.01);
+        classifier.add_quote(make_quote(1000, 50000.0, 50001.0));
+        classifier.classify(make_trade(1100, 49999.5, 0.1)); // Seeds tick rule
+
+        let mut thin = make_quote(1200, 50000.0, 50001.0);
+        thin.ask_sz = 0.001;
+        classifier.add_quote(thin.clone());
+
+        // Prints at the bid but upticks: tick rule says buy
+        let ct = classifier.classify(make_trade(1300, 50000.0, 0.1));
+        assert_eq!(ct.side, TradeSide::Buy);
+
+        let stats = classifier.stats();
+        assert_eq!(stats.thin_quotes, 1);
+        assert_eq!(stats.thin_quote_trades, 1);
+
+        // Without a minimum the same trade is inside the spread
+        let mut plain = TradeClassifier::new(250, false);
+        plain.add_quote(thin);
+        assert_eq!(plain.classify(make_trade(1300, 50000.0, 0.1)).side, TradeSide::Sell);
+    }
+
+    #[test]
+    fn test_dedup_drops_exact_resends() {
+        let mut classifier = TradeClassifier::new(250, false).with_dedup_window(5_000);
+        classifier.add_quote(make_quote(1000, 50000.0, 50001.0));
+
+        let trades = vec![
+            make_trade(1100, 50001.0, 0.5),
+            make_trade(1100, 50001.0, 0.5), // resend
+            make_trade(1100, 50000.0, 0.5), // different trade, same ms
+            make_trade(1200, 50001.0, 0.2),
+            make_trade(1100, 50001.0, 0.5), // late resend after reconnect
+        ];
+        let classified = classifier.classify_batch(trades);
+
+        assert_eq!(classified.len(), 2);
+        assert!((classified[0].trade.size - 1.0).abs() < 1e-10);
+        assert!((classified[0].trade.price - 50000.5).abs() < 1e-10);
+        assert!((classified[1].trade.size - 0.2).abs() < 1e-10);
+
+        let stats = classifier.stats();
+        assert_eq!(stats.duplicate_trades, 2);
+        assert!((stats.duplicate_volume - 1.0).abs() < 1e-10);
+        assert!((stats.total_volume - 1.2).abs() < 1e-10);
+
+        // Keys outside the window are forgotten
+        assert!(!classifier.is_duplicate(&make_trade(7000, 50001.0, 0.1)));
+        assert!(!classifier.is_duplicate(&make_trade(1100, 50001.0, 0.5)));
+
+        // Disabled by default
+        let mut plain = TradeClassifier::new(250, false);
+        let trade = make_trade(1100, 50001.0, 0.5);
+        assert!(!plain.is_duplicate(&trade));
+        assert!(!plain.is_duplicate(&trade));
+    }
+
+    #[test]
+    fn test_dedup_when_streaming() {
+        let mut classifier = TradeClassifier::new(250, false)
+            .with_dedup_window(5_000)
+            .with_trust_provided_side(true);
+        classifier.add_quote(make_quote(1000, 50000.0, 50001.0));
+
+        assert!(classifier.classify_filtered(make_trade(1100, 50001.0, 0.5)).is_some());
+        assert!(classifier.classify_filtered(make_trade(1100, 50001.0, 0.5)).is_none());
+        // Same ms, different trade
+        assert!(classifier.classify_filtered(make_trade(1100, 50000.0, 0.5)).is_some());
+        // Provided sides are deduplicated too
+        let resend = make_trade(1100, 50000.0, 0.5);
+        assert!(classifier.classify_with_side_filtered(resend, Some(TradeSide::Sell)).is_none());
+
+        let stats = classifier.stats();
+        assert_eq!(stats.duplicate_trades, 2);
+        assert_eq!(stats.total_trades, 2);
+
+        // The unfiltered path classifies every trade
+        classifier.classify(make_trade(1100, 50001.0, 0.5));
+        assert_eq!(classifier.stats().total_trades, 3);
+    }
+
+    #[test]
+    fn test_outlier_filter_drops_bad_print() {
+        let mut classifier = TradeClassifier::new(250, false)
+            .with_outlier_filter(OutlierFilter::Pct { max_pct: 0.05 });
+        classifier.add_quote(make_quote(1000, 50000.0, 50001.0));
+        // A genuine repricing moves the quote with it
+        classifier.add_quote(make_quote(3000, 53000.0, 53001.0));
+
+        let trades = vec![
+            make_trade(1100, 50001.0, 0.5),
+            make_trade(1200, 5000.0, 2.0), // fat finger
+            make_trade(1300, 50000.0, 0.3),
+            make_trade(3100, 53001.0, 0.4),
+        ];
+        let classified = classifier.classify_batch(trades);
+        assert_eq!(classified.len(), 3);
+        assert_eq!(classifier.stats().outlier_trades, 1);
+        assert!((classifier.stats().outlier_volume - 2.0).abs() < 1e-10);
+
+        let mut builder = crate::BarBuilder::new();
+        builder.add_trades(&classified);
+        let bar = builder.force_finalize(0).unwrap();
+        assert!((bar.low - 50000.0).abs() < 1e-10);
+        assert!((bar.high - 53001.0).abs() < 1e-10);
+
+        // Sigma mode: the threshold tracks recent trade-to-trade noise
+        let mut sigma = TradeClassifier::new(250, false).with_outlier_filter(OutlierFilter::Sigma {
+            k: 10.0,
+            lookback: 50,
+            min_pct: 0.001,
+        });
+        for (i, price) in [100.0, 100.1, 100.0, 100.1, 100.0].iter().enumerate() {
+            assert!(!sigma.is_outlier(&make_trade(i as i64, *price, 1.0)));
+        }
+        assert!(!sigma.is_outlier(&make_trade(10, 100.5, 1.0)));
+        assert!(sigma.is_outlier(&make_trade(11, 110.0, 1.0)));
+
+        // Disabled by default
+        let mut plain = TradeClassifier::new(250, false);
+        assert!(!plain.is_outlier(&make_trade(1200, 5000.0, 2.0)));
+    }
+
+    #[test]
+    fn test_outlier_filter_when_streaming() {
+        let mut classifier = TradeClassifier::new(250, false)
+            .with_outlier_filter(OutlierFilter::Pct { max_pct: 0.05 })
+            .with_trust_provided_side(true);
+        classifier.add_quote(make_quote(1000, 50000.0, 50001.0));
+
+        assert!(classifier.classify_filtered(make_trade(1100, 50001.0, 0.5)).is_some());
+        assert!(classifier.classify_filtered(make_trade(1200, 5000.0, 2.0)).is_none());
+        let bad_print = make_trade(1300, 500000.0, 1.0);
+        assert!(classifier.classify_with_side_filtered(bad_print, Some(TradeSide::Buy)).is_none());
+        let trade = make_trade(1400, 50000.0, 0.3);
+        assert!(classifier.classify_with_side_filtered(trade, None).is_some());
+
+        let stats = classifier.stats();
+        assert_eq!(stats.outlier_trades, 2);
+        assert_eq!(stats.total_trades, 2);
+    }
+
+    #[test]
+    fn test_columns_match_object_path() {
+        let quotes = [
+            make_quote(1000, 50000.0, 50001.0),
+            make_quote(2000, 50002.0, 50003.0),
+        ];
+        let trades = vec![
+            make_trade(500, 50000.5, 0.3),
+            make_trade(1100, 50001.0, 0.1),
+            make_trade(1100, 50000.0, 0.2),
+            make_trade(1500, 50000.5, 0.4),
+            make_trade(2100, 50002.0, 0.5),
+        ];
+
+        let mut objects = TradeClassifier::new(250, true);
+        for q in &quotes {
+            objects.add_quote(q.clone());
+        }
+        let expected: ClassifiedColumns = objects.classify_batch(trades.clone()).into();
+
+        let mut columnar = TradeClassifier::new(250, true);
+        columnar
+            .add_quote_columns(
+                &quotes.iter().map(|q| q.ts_ms).collect::<Vec<_>>(),
+                &quotes.iter().map(|q| q.bid_px).collect::<Vec<_>>(),
+                &quotes.iter().map(|q| q.bid_sz).collect::<Vec<_>>(),
+                &quotes.iter().map(|q| q.ask_px).collect::<Vec<_>>(),
+                &quotes.iter().map(|q| q.ask_sz).collect::<Vec<_>>(),
+            )
+            .unwrap();
+        let ts: Vec<i64> = trades.iter().map(|t| t.ts_ms).collect();
+        let px: Vec<f64> = trades.iter().map(|t| t.price).collect();
+        let sz: Vec<f64> = trades.iter().map(|t| t.size).collect();
+        let got = columnar.classify_columns(&ts, &px, &sz).unwrap();
+
+        assert_eq!(got, expected);
+        assert_eq!(got.len(), 4); // same-ms trades aggregated
+        assert_eq!(got.side[3], -1);
+        assert_eq!(got.quote_staleness_ms[0], i64::MAX);
+        assert_eq!(columnar.stats().total_trades, objects.stats().total_trades);
+
+        assert!(columnar.classify_columns(&ts, &px, &sz[..2]).is_err());
+    }
+
     #[test]
     fn test_stats() {
         let mut classifier = TradeClassifier::new(250, false);
@@ -419,4 +1385,141 @@ mod tests {
         assert!((stats.sell_volume - 0.2).abs() < 1e-10);
         assert!((stats.ambiguous_volume - 0.3).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_confusion_matrix() {
+        let mut classifier = TradeClassifier::new(250, false);
+        classifier.add_quote(make_quote(1000, 50000.0, 50001.0));
+
+        classifier.classify_labeled(make_trade(1100, 50001.0, 0.1), TradeSide::Buy);
+        classifier.classify_labeled(make_trade(1200, 50000.0, 0.1), TradeSide::Sell);
+        classifier.classify_labeled(make_trade(1300, 50000.0, 0.1), TradeSide::Buy);
+        classifier.classify_labeled(make_trade(1400, 50000.5, 0.1), TradeSide::Sell);
+        classifier.classify(make_trade(1500, 50001.0, 0.1)); // Unlabeled
+
+        let cm = classifier.stats().confusion;
+        assert_eq!(cm.total(), 4);
+        assert_eq!(cm.count(TradeSide::Buy, TradeSide::Buy), 1);
+        assert_eq!(cm.count(TradeSide::Sell, TradeSide::Sell), 1);
+        assert_eq!(cm.count(TradeSide::Sell, TradeSide::Buy), 1);
+        assert_eq!(cm.count(TradeSide::Ambiguous, TradeSide::Sell), 1);
+        assert!((cm.accuracy() - 0.5).abs() < 1e-12);
+        assert!((cm.precision(TradeSide::Sell) - 0.5).abs() < 1e-12);
+        assert!((cm.recall(TradeSide::Buy) - 0.5).abs() < 1e-12);
+        assert_eq!(cm.precision(TradeSide::Ambiguous), 0.0);
+
+        classifier.reset_stats();
+        assert_eq!(classifier.stats().confusion.total(), 0);
+    }
+
+    #[test]
+    fn test_trusted_provided_side() {
+        let mut classifier = TradeClassifier::new(250, true).with_trust_provided_side(true);
+        classifier.add_quote(make_quote(1000, 50000.0, 50001.0));
+
+        // Provided sides win even against the quote
+        let ct = classifier.classify_with_side(make_trade(1100, 50001.0, 0.1), Some(TradeSide::Sell));
+        assert_eq!(ct.side, TradeSide::Sell);
+        assert_eq!(ct.quote_bid_px, 50000.0);
+        assert_eq!(ct.quote_staleness_ms, 100);
+        let ct = classifier.classify_with_side(make_trade(1200, 50000.5, 0.2), Some(TradeSide::Buy));
+        assert_eq!(ct.side, TradeSide::Buy);
+
+        // No provided side: inferred as usual
+        let ct = classifier.classify_with_side(make_trade(1300, 50000.0, 0.3), None);
+        assert_eq!(ct.side, TradeSide::Sell);
+
+        let stats = classifier.stats();
+        assert_eq!(stats.provided_side_trades, 2);
+        assert_eq!(stats.total_trades, 3);
+        assert_eq!(stats.buy_trades, 1);
+        assert_eq!(stats.sell_trades, 2);
+        assert!((stats.buy_volume - 0.2).abs() < 1e-10);
+        assert_eq!(stats.stale_quote_trades, 1);
+
+        // Stale quotes count on the trusted path as on the inferred one
+        let ct = classifier.classify_with_side(make_trade(1400, 50001.0, 0.1), Some(TradeSide::Buy));
+        assert_eq!(ct.quote_staleness_ms, 400);
+        assert_eq!(classifier.stats().stale_quote_trades, 2);
+
+        // Untrusted: the provided side is ignored
+        let mut classifier = TradeClassifier::new(250, false);
+        classifier.add_quote(make_quote(1000, 50000.0, 50001.0));
+        let ct = classifier.classify_with_side(make_trade(1100, 50001.0, 0.1), Some(TradeSide::Sell));
+        assert_eq!(ct.side, TradeSide::Buy);
+        assert_eq!(classifier.stats().provided_side_trades, 0);
+    }
+
+    #[test]
+    fn test_seed_tick_rule_from_batch() {
+        let run = |seed: bool| {
+            let mut classifier = TradeClassifier::new(5000, true).with_seed_tick_rule_from_batch(seed);
+            classifier.add_quote(make_quote(1000, 100.0, 101.0));
+            let trades = vec![
+                make_trade(1100, 100.5, 1.0),
+                make_trade(1200, 100.5, 1.0),
+                make_trade(1300, 101.0, 1.0),
+                make_trade(1400, 100.5, 1.0),
+            ];
+            classifier.classify_batch(trades).iter().map(|t| t.side).collect::<Vec<_>>()
+        };
+
+        // Unseeded: no prior price, so the leading inside-spread trades stay ambiguous
+        assert_eq!(
+            run(false),
+            [TradeSide::Ambiguous, TradeSide::Ambiguous, TradeSide::Buy, TradeSide::Sell]
+        );
+        // Seeded from the buy at 101: the leading trades sit below it
+        assert_eq!(run(true), [TradeSide::Sell, TradeSide::Sell, TradeSide::Buy, TradeSide::Sell]);
+
+        // No classifiable trade in the batch leaves the classifier unseeded
+        let mut classifier = TradeClassifier::new(5000, true).with_seed_tick_rule_from_batch(true);
+        classifier.add_quote(make_quote(1000, 100.0, 101.0));
+        let out = classifier.classify_batch(vec![make_trade(1100, 100.5, 1.0)]);
+        assert_eq!(out[0].side, TradeSide::Ambiguous);
+
+        // Reachable from the config
+        let mut config = Config::default();
+        config.order_flow.max_quote_staleness_ms = 5000;
+        config.order_flow.seed_tick_rule_from_batch = true;
+        let mut classifier = TradeClassifier::from(&config);
+        classifier.add_quote(make_quote(1000, 100.0, 101.0));
+        let out = classifier.classify_batch(vec![make_trade(1100, 100.5, 1.0), make_trade(1200, 101.0, 1.0)]);
+        assert_eq!(out[0].side, TradeSide::Sell);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut config = Config::default();
+        config.instrument.tick_size = 0.5;
+        config.order_flow.use_tick_rule_fallback = false;
+        config.order_flow.tick_rounding = Some(TickRounding::Nearest);
+        config.order_flow.dedup_window_ms = Some(1_000);
+        config.order_flow.outlier_filter = Some(OutlierFilter::Pct { max_pct: 0.05 });
+        config.order_flow.trust_provided_side = true;
+        let mut classifier = TradeClassifier::from(&config);
+        classifier.add_quote(make_quote(1000, 100.0, 101.0));
+
+        let out = classifier.classify_batch(vec![
+            // Snaps to the ask on the 0.5 tick
+            make_trade(1100, 100.9, 1.0),
+            // Exact resend
+            make_trade(1100, 100.9, 1.0),
+            // Bad print
+            make_trade(1200, 200.0, 1.0),
+        ]);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].side, TradeSide::Buy);
+        assert!((out[0].trade.size - 1.0).abs() < 1e-12);
+
+        let ct = classifier.classify_with_side(make_trade(1300, 101.0, 1.0), Some(TradeSide::Sell));
+        assert_eq!(ct.side, TradeSide::Sell);
+
+        // Defaults leave every option off
+        let mut classifier = TradeClassifier::from(&Config::default());
+        classifier.add_quote(make_quote(1000, 100.0, 101.0));
+        let trades = vec![make_trade(1100, 100.9, 1.0), make_trade(1200, 200.0, 1.0)];
+        let out = classifier.classify_batch(trades);
+        assert_eq!(out.len(), 2);
+    }
 }
@@ -0,0 +1,160 @@
+//! Signed-volume imbalance bars.
+//!
+//! Closes a bar when the running signed volume (buy minus sell) reaches a
+//! threshold, sampling the series by order-flow imbalance instead of time.
+
+use auction_core::{Bar1m, ClassifiedTrade, Quote};
+use crate::bar_builder::BarInProgress;
+
+/// A bar closed on signed-volume imbalance.
+#[derive(Debug, Clone)]
+pub struct ImbalanceBar {
+    /// Sequential bar index (starting at 0).
+    pub index: u64,
+    /// Signed volume accumulated over the bar.
+    pub signed_volume: f64,
+    /// OHLCV bar. `ts_min` holds the timestamp of the bar's first trade and
+    /// the L1 fields come from the closing trade's quote (sizes are zero).
+    pub bar: Bar1m,
+}
+
+/// Builder for imbalance bars from classified trades.
+pub struct ImbalanceBarBuilder {
+    /// Absolute signed volume that closes a bar.
+    threshold: f64,
+    /// Bar currently being built.
+    current: Option<BarInProgress>,
+    /// Running signed volume of the current bar.
+    signed_volume: f64,
+    /// Index of the next bar to emit.
+    next_index: u64,
+}
+
+impl ImbalanceBarBuilder {
+    /// Create a new imbalance bar builder.
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            current: None,
+            signed_volume: 0.0,
+            next_index: 0,
+        }
+    }
+
+    /// Add a classified trade.
+    ///
+    /// Returns a completed bar if `|cumulative signed volume| >= threshold`.
+    pub fn add_trade(&mut self, trade: &ClassifiedTrade) -> Option<ImbalanceBar> {
+        let bar = self
+            .current
+            .get_or_insert_with(|| BarInProgress::new(trade.trade.ts_ms));
+        bar.add_trade(trade.trade.price, trade.trade.size);
+        self.signed_volume += trade.signed_size();
+
+        if self.signed_volume.abs() < self.threshold {
+            return None;
+        }
+
+        let quote = Quote {
+            ts_ms: trade.trade.ts_ms,
+            bid_px: trade.quote_bid_px,
+            bid_sz: 0.0,
+            ask_px: trade.quote_ask_px,
+            ask_sz: 0.0,
+        };
+        let bar = self.current.take()?.to_bar(Some(&quote))?;
+        let completed = ImbalanceBar {
+            index: self.next_index,
+            signed_volume: self.signed_volume,
+            bar,
+        };
+
+        self.next_index += 1;
+        self.signed_volume = 0.0;
+        Some(completed)
+    }
+
+    /// Add multiple classified trades, returning all bars they complete.
+    pub fn add_trades(&mut self, trades: &[ClassifiedTrade]) -> Vec<ImbalanceBar> {
+        trades.iter().filter_map(|t| self.add_trade(t)).collect()
+    }
+
+    /// Get the running signed volume of the bar in progress.
+    pub fn signed_volume(&self) -> f64 {
+        self.signed_volume
+    }
+
+    /// Get the number of bars emitted so far.
+    pub fn bar_count(&self) -> u64 {
+        self.next_index
+    }
+
+    /// Clear all state.
+    pub fn clear(&mut self) {
+        self.current = None;
+        self.signed_volume = 0.0;
+        self.next_index = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use auction_core::{Trade, TradeSide};
+
+    fn make_trade(ts_ms: i64, price: f64, size: f64, side: TradeSide) -> ClassifiedTrade {
+        ClassifiedTrade {
+            trade: Trade { ts_ms, price, size },
+            side,
+            quote_bid_px: price - 0.5,
+            quote_ask_px: price + 0.5,
+            quote_staleness_ms: 10,
+        }
+    }
+
+    #[test]
+    fn test_buy_run_closes_at_threshold() {
+        let mut builder = ImbalanceBarBuilder::new(3.0);
+
+        assert!(builder.add_trade(&make_trade(1000, 100.0, 1.0, TradeSide::Buy)).is_none());
+        assert!(builder.add_trade(&make_trade(2000, 101.0, 1.0, TradeSide::Buy)).is_none());
+        assert!((builder.signed_volume() - 2.0).abs() < 1e-10);
+
+        let bar = builder
+            .add_trade(&make_trade(3000, 102.0, 1.0, TradeSide::Buy))
+            .expect("bar should close at threshold");
+
+        assert_eq!(bar.index, 0);
+        assert!((bar.signed_volume - 3.0).abs() < 1e-10);
+        assert_eq!(bar.bar.ts_min, 1000);
+        assert!((bar.bar.open - 100.0).abs() < 1e-10);
+        assert!((bar.bar.close - 102.0).abs() < 1e-10);
+        assert!((bar.bar.volume - 3.0).abs() < 1e-10);
+        assert_eq!(bar.bar.trade_count, 3);
+        assert!((builder.signed_volume() - 0.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_opposing_flow_delays_bar() {
+        let mut builder = ImbalanceBarBuilder::new(2.0);
+
+        let trades = vec![
+            make_trade(1000, 100.0, 1.5, TradeSide::Buy),
+            make_trade(2000, 100.0, 1.0, TradeSide::Sell),
+            make_trade(3000, 100.0, 0.5, TradeSide::Ambiguous),
+            make_trade(4000, 100.0, 1.0, TradeSide::Buy),
+            make_trade(5000, 100.0, 1.5, TradeSide::Buy),
+            make_trade(6000, 99.0, 2.0, TradeSide::Sell),
+        ];
+
+        let bars = builder.add_trades(&trades);
+
+        // Net +0.5 after three trades, crosses +2.0 on the fifth trade
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].bar.trade_count, 5);
+        assert!((bars[0].signed_volume - 3.0).abs() < 1e-10);
+        assert_eq!(bars[1].index, 1);
+        assert!((bars[1].signed_volume - (-2.0)).abs() < 1e-10);
+        assert_eq!(builder.bar_count(), 2);
+    }
+}
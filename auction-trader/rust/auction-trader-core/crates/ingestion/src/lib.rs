@@ -4,10 +4,15 @@
 //! - Trade-quote alignment
 //! - Trade side inference (bid/ask classification)
 //! - Minute bar building
+//! - Signed-volume imbalance bars
 //! - Trade aggregation (same-timestamp trades)
 
 pub mod classifier;
 pub mod bar_builder;
+pub mod imbalance_bar;
 
-pub use classifier::{TradeClassifier, ClassificationStats};
-pub use bar_builder::BarBuilder;
+pub use classifier::{
+    TradeClassifier, ClassificationStats, ClassifiedColumns, ConfusionMatrix, JoinDiagnostics, OutlierFilter, TickRounding,
+};
+pub use bar_builder::{BarBuilder, BarEvent, CloseQuotePolicy, MissingQuotePolicy, TradeCap, TradeCapPolicy};
+pub use imbalance_bar::{ImbalanceBar, ImbalanceBarBuilder};
@@ -6,9 +6,14 @@
 //! - Feature computation (VA, OF, volatility)
 //! - Backtesting engine
 
+// The #[pymethods] expansion of PyResult-returning methods trips this lint.
+#![allow(clippy::useless_conversion)]
+
+use numpy::{PyArray1, PyReadonlyArray1};
 use pyo3::prelude::*;
 
 use auction_core::{
+    Action as RustAction,
     Trade as RustTrade,
     Quote as RustQuote,
     Bar1m as RustBar1m,
@@ -16,16 +21,34 @@ use auction_core::{
     TradeSide as RustTradeSide,
     ValueArea as RustValueArea,
     OrderFlowMetrics as RustOrderFlowMetrics,
+    VaMigration as RustVaMigration,
+    VaMigrationDirection as RustVaMigrationDirection,
+    EdgeFlow as RustEdgeFlow,
+    VwapBandTouches as RustVwapBandTouches,
+    SpreadRegime as RustSpreadRegime,
+    AuctionState as RustAuctionState,
     Features1m as RustFeatures1m,
     Config as RustConfig,
 };
-use auction_ingestion::{TradeClassifier, BarBuilder};
-use auction_features::FeatureEngine;
+use auction_ingestion::{
+    TradeClassifier, BarBuilder, TradeCap, TradeCapPolicy, ClassifiedColumns as RustClassifiedColumns, ConfusionMatrix as RustConfusionMatrix,
+    CloseQuotePolicy, MissingQuotePolicy, OutlierFilter, TickRounding,
+};
+use auction_features::{FeatureEngine, OrderFlowSeries as RustOrderFlowSeries, ReadinessRequirements};
+use auction_backtest::{
+    simulator::{BacktestConfig, Signal as RustSignal},
+    BacktestMetrics as RustBacktestMetrics, BacktestSimulator, EquityPoint as RustEquityPoint,
+};
+use std::collections::BTreeMap;
 
 // ============================================================================
 // Python-exposed Types
 // ============================================================================
 
+/// Default tick size for display precision (two decimals), for objects
+/// created without one.
+const DEFAULT_TICK_SIZE: f64 = 0.01;
+
 /// A single trade from the exchange.
 #[pyclass]
 #[derive(Clone)]
@@ -84,13 +107,17 @@ pub struct Quote {
     pub ask_px: f64,
     #[pyo3(get, set)]
     pub ask_sz: f64,
+    /// Tick size used for display precision.
+    #[pyo3(get, set)]
+    pub tick_size: f64,
 }
 
 #[pymethods]
 impl Quote {
     #[new]
-    fn new(ts_ms: i64, bid_px: f64, bid_sz: f64, ask_px: f64, ask_sz: f64) -> Self {
-        Quote { ts_ms, bid_px, bid_sz, ask_px, ask_sz }
+    #[pyo3(signature = (ts_ms, bid_px, bid_sz, ask_px, ask_sz, tick_size=DEFAULT_TICK_SIZE))]
+    fn new(ts_ms: i64, bid_px: f64, bid_sz: f64, ask_px: f64, ask_sz: f64, tick_size: f64) -> Self {
+        Quote { ts_ms, bid_px, bid_sz, ask_px, ask_sz, tick_size }
     }
 
     #[getter]
@@ -114,10 +141,7 @@ impl Quote {
     }
 
     fn __repr__(&self) -> String {
-        format!(
-            "Quote(ts_ms={}, bid={:.2}@{:.4}, ask={:.2}@{:.4})",
-            self.ts_ms, self.bid_px, self.bid_sz, self.ask_px, self.ask_sz
-        )
+        RustQuote::from(self.clone()).display(self.tick_size)
     }
 }
 
@@ -141,13 +165,14 @@ impl From<RustQuote> for Quote {
             bid_sz: q.bid_sz,
             ask_px: q.ask_px,
             ask_sz: q.ask_sz,
+            tick_size: DEFAULT_TICK_SIZE,
         }
     }
 }
 
 /// Inferred trade side.
-#[pyclass]
-#[derive(Clone, Copy)]
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum TradeSide {
     Buy = 1,
     Sell = -1,
@@ -166,6 +191,16 @@ impl TradeSide {
     }
 }
 
+impl From<TradeSide> for RustTradeSide {
+    fn from(s: TradeSide) -> Self {
+        match s {
+            TradeSide::Buy => RustTradeSide::Buy,
+            TradeSide::Sell => RustTradeSide::Sell,
+            TradeSide::Ambiguous => RustTradeSide::Ambiguous,
+        }
+    }
+}
+
 impl From<RustTradeSide> for TradeSide {
     fn from(s: RustTradeSide) -> Self {
         match s {
@@ -240,6 +275,9 @@ pub struct Bar1m {
     pub bid_sz_close: f64,
     #[pyo3(get)]
     pub ask_sz_close: f64,
+    /// Tick size used for display precision.
+    #[pyo3(get, set)]
+    pub tick_size: f64,
 }
 
 #[pymethods]
@@ -263,6 +301,10 @@ impl Bar1m {
             0.0
         }
     }
+
+    fn __repr__(&self) -> String {
+        PyFeatureEngine::bar_to_rust(self).display(self.tick_size)
+    }
 }
 
 impl From<RustBar1m> for Bar1m {
@@ -280,6 +322,7 @@ impl From<RustBar1m> for Bar1m {
             ask_px_close: b.ask_px_close,
             bid_sz_close: b.bid_sz_close,
             ask_sz_close: b.ask_sz_close,
+            tick_size: DEFAULT_TICK_SIZE,
         }
     }
 }
@@ -295,6 +338,14 @@ pub struct ValueArea {
     #[pyo3(get)]
     pub val: f64,
     #[pyo3(get)]
+    pub poc_raw: f64,
+    #[pyo3(get)]
+    pub poc_smoothed: f64,
+    #[pyo3(get)]
+    pub vah_raw: f64,
+    #[pyo3(get)]
+    pub val_raw: f64,
+    #[pyo3(get)]
     pub coverage: f64,
     #[pyo3(get)]
     pub bin_count: usize,
@@ -304,6 +355,8 @@ pub struct ValueArea {
     pub bin_width: f64,
     #[pyo3(get)]
     pub is_valid: bool,
+    #[pyo3(get)]
+    pub single_bin_va: bool,
 }
 
 impl From<RustValueArea> for ValueArea {
@@ -312,11 +365,16 @@ impl From<RustValueArea> for ValueArea {
             poc: va.poc,
             vah: va.vah,
             val: va.val,
+            poc_raw: va.poc_raw,
+            poc_smoothed: va.poc_smoothed,
+            vah_raw: va.vah_raw,
+            val_raw: va.val_raw,
             coverage: va.coverage,
             bin_count: va.bin_count as usize,
             total_volume: va.total_volume,
             bin_width: va.bin_width,
             is_valid: va.is_valid,
+            single_bin_va: va.single_bin_va,
         }
     }
 }
@@ -339,6 +397,14 @@ pub struct OrderFlowMetrics {
     pub ambiguous_volume: f64,
     #[pyo3(get)]
     pub ambiguous_frac: f64,
+    #[pyo3(get)]
+    pub has_volume: bool,
+    #[pyo3(get)]
+    pub at_bid_volume: f64,
+    #[pyo3(get)]
+    pub at_ask_volume: f64,
+    #[pyo3(get)]
+    pub inside_volume: f64,
 }
 
 impl From<RustOrderFlowMetrics> for OrderFlowMetrics {
@@ -351,6 +417,91 @@ impl From<RustOrderFlowMetrics> for OrderFlowMetrics {
             sell_volume: of.sell_volume,
             ambiguous_volume: of.ambiguous_volume,
             ambiguous_frac: of.ambiguous_frac,
+            has_volume: of.has_volume,
+            at_bid_volume: of.at_bid_volume,
+            at_ask_volume: of.at_ask_volume,
+            inside_volume: of.inside_volume,
+        }
+    }
+}
+
+/// Value Area migration relative to the prior minute.
+#[pyclass]
+#[derive(Clone)]
+pub struct VaMigration {
+    /// "up", "down" or "overlap".
+    #[pyo3(get)]
+    pub direction: String,
+    #[pyo3(get)]
+    pub poc_shift: f64,
+    #[pyo3(get)]
+    pub overlap_frac: f64,
+}
+
+impl From<RustVaMigration> for VaMigration {
+    fn from(m: RustVaMigration) -> Self {
+        let direction = match m.direction {
+            RustVaMigrationDirection::Up => "up",
+            RustVaMigrationDirection::Down => "down",
+            RustVaMigrationDirection::Overlap => "overlap",
+        };
+        VaMigration {
+            direction: direction.to_string(),
+            poc_shift: m.poc_shift,
+            overlap_frac: m.overlap_frac,
+        }
+    }
+}
+
+/// Classified volume near a value-area edge.
+#[pyclass]
+#[derive(Clone)]
+pub struct EdgeFlow {
+    #[pyo3(get)]
+    pub buy_volume: f64,
+    #[pyo3(get)]
+    pub sell_volume: f64,
+}
+
+#[pymethods]
+impl EdgeFlow {
+    /// Net signed flow (buy - sell).
+    #[getter]
+    fn net(&self) -> f64 {
+        self.buy_volume - self.sell_volume
+    }
+}
+
+impl From<RustEdgeFlow> for EdgeFlow {
+    fn from(e: RustEdgeFlow) -> Self {
+        EdgeFlow {
+            buy_volume: e.buy_volume,
+            sell_volume: e.sell_volume,
+        }
+    }
+}
+
+/// Touches of a rolling VWAP band pair.
+#[pyclass]
+#[derive(Clone)]
+pub struct VwapBandTouches {
+    #[pyo3(get)]
+    pub touched_upper_band: bool,
+    #[pyo3(get)]
+    pub touched_lower_band: bool,
+    #[pyo3(get)]
+    pub upper_touches: u32,
+    #[pyo3(get)]
+    pub lower_touches: u32,
+}
+
+impl From<RustVwapBandTouches> for VwapBandTouches {
+    fn from(t: RustVwapBandTouches) -> Self {
+        VwapBandTouches {
+            touched_upper_band: t.touched_upper_band,
+            touched_lower_band: t.touched_lower_band,
+            upper_touches: t.upper_touches,
+            lower_touches: t.lower_touches,
         }
     }
 }
@@ -366,17 +517,75 @@ pub struct Features1m {
     #[pyo3(get)]
     pub sigma_240: f64,
     #[pyo3(get)]
+    pub sigma_annualized: Option<f64>,
+    #[pyo3(get)]
+    pub skew_240: Option<f64>,
+    #[pyo3(get)]
+    pub kurtosis_240: Option<f64>,
+    #[pyo3(get)]
+    pub vwap_z: Option<f64>,
+    #[pyo3(get)]
+    pub vwap_band_1sd: VwapBandTouches,
+    #[pyo3(get)]
+    pub vwap_band_2sd: VwapBandTouches,
+    #[pyo3(get)]
+    pub rvol: Option<f64>,
+    #[pyo3(get)]
     pub bin_width: f64,
     #[pyo3(get)]
     pub va: ValueArea,
     #[pyo3(get)]
+    pub profile_entropy: Option<f64>,
+    #[pyo3(get)]
+    pub profile_entropy_norm: Option<f64>,
+    #[pyo3(get)]
     pub order_flow: OrderFlowMetrics,
     #[pyo3(get)]
+    pub of_streak: i32,
+    #[pyo3(get)]
+    pub max_of_streak: u32,
+    #[pyo3(get)]
     pub qimb_close: f64,
     #[pyo3(get)]
     pub qimb_ema: f64,
     #[pyo3(get)]
+    pub qimb_predictive_corr: Option<f64>,
+    #[pyo3(get)]
+    pub flow_quote_divergence: f64,
+    #[pyo3(get)]
+    pub flow_quote_divergence_avg_abs: f64,
+    #[pyo3(get)]
+    pub ofi_1m: f64,
+    #[pyo3(get)]
+    pub max_mid_jump_1m: f64,
+    #[pyo3(get)]
+    pub max_mid_jump_sigma_1m: Option<f64>,
+    #[pyo3(get)]
     pub spread_avg_60m: f64,
+    /// "tight", "normal" or "wide".
+    #[pyo3(get)]
+    pub spread_regime: String,
+    #[pyo3(get)]
+    pub eff_spread_avg_1m: f64,
+    #[pyo3(get)]
+    pub est_roundtrip_cost: f64,
+    #[pyo3(get)]
+    pub va_migration: Option<VaMigration>,
+    /// "balanced", "trending_up", "trending_down" or "transition".
+    #[pyo3(get)]
+    pub auction_state: String,
+    #[pyo3(get)]
+    pub vah_edge_flow: EdgeFlow,
+    #[pyo3(get)]
+    pub val_edge_flow: EdgeFlow,
+    #[pyo3(get)]
+    pub poc_tests: u32,
+    #[pyo3(get)]
+    pub vah_tests: u32,
+    #[pyo3(get)]
+    pub val_tests: u32,
+    #[pyo3(get)]
+    pub recent_breakout_success_rate: Option<f64>,
 }
 
 impl From<RustFeatures1m> for Features1m {
@@ -385,16 +594,346 @@ impl From<RustFeatures1m> for Features1m {
             ts_min: f.ts_min,
             mid_close: f.mid_close,
             sigma_240: f.sigma_240,
+            sigma_annualized: f.sigma_annualized,
+            skew_240: f.skew_240,
+            kurtosis_240: f.kurtosis_240,
+            vwap_z: f.vwap_z,
+            vwap_band_1sd: f.vwap_band_1sd.into(),
+            vwap_band_2sd: f.vwap_band_2sd.into(),
+            rvol: f.rvol,
             bin_width: f.bin_width,
             va: f.va.into(),
+            profile_entropy: f.profile_entropy,
+            profile_entropy_norm: f.profile_entropy_norm,
             order_flow: f.order_flow.into(),
+            of_streak: f.of_streak,
+            max_of_streak: f.max_of_streak,
             qimb_close: f.qimb_close,
             qimb_ema: f.qimb_ema,
+            qimb_predictive_corr: f.qimb_predictive_corr,
+            flow_quote_divergence: f.flow_quote_divergence,
+            flow_quote_divergence_avg_abs: f.flow_quote_divergence_avg_abs,
+            ofi_1m: f.ofi_1m,
+            max_mid_jump_1m: f.max_mid_jump_1m,
+            max_mid_jump_sigma_1m: f.max_mid_jump_sigma_1m,
             spread_avg_60m: f.spread_avg_60m,
+            spread_regime: match f.spread_regime {
+                RustSpreadRegime::Tight => "tight",
+                RustSpreadRegime::Normal => "normal",
+                RustSpreadRegime::Wide => "wide",
+            }
+            .to_string(),
+            eff_spread_avg_1m: f.eff_spread_avg_1m,
+            est_roundtrip_cost: f.est_roundtrip_cost,
+            va_migration: f.va_migration.map(Into::into),
+            auction_state: match f.auction_state {
+                RustAuctionState::Balanced => "balanced",
+                RustAuctionState::TrendingUp => "trending_up",
+                RustAuctionState::TrendingDown => "trending_down",
+                RustAuctionState::Transition => "transition",
+            }
+            .to_string(),
+            vah_edge_flow: f.vah_edge_flow.into(),
+            val_edge_flow: f.val_edge_flow.into(),
+            poc_tests: f.poc_tests,
+            vah_tests: f.vah_tests,
+            val_tests: f.val_tests,
+            recent_breakout_success_rate: f.recent_breakout_success_rate,
+        }
+    }
+}
+
+/// Per-minute order flow series as parallel numpy arrays.
+#[pyclass]
+#[derive(Clone)]
+pub struct OrderFlowSeries {
+    pub ts_min: Vec<i64>,
+    pub of_1m: Vec<f64>,
+    pub of_norm_1m: Vec<f64>,
+    pub buy_volume: Vec<f64>,
+    pub sell_volume: Vec<f64>,
+    pub ambiguous_frac: Vec<f64>,
+}
+
+impl From<RustOrderFlowSeries> for OrderFlowSeries {
+    fn from(s: RustOrderFlowSeries) -> Self {
+        OrderFlowSeries {
+            ts_min: s.ts_min,
+            of_1m: s.of_1m,
+            of_norm_1m: s.of_norm_1m,
+            buy_volume: s.buy_volume,
+            sell_volume: s.sell_volume,
+            ambiguous_frac: s.ambiguous_frac,
+        }
+    }
+}
+
+#[pymethods]
+impl OrderFlowSeries {
+    fn __len__(&self) -> usize {
+        self.ts_min.len()
+    }
+
+    #[getter]
+    fn ts_min<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<i64>> {
+        PyArray1::from_vec_bound(py, self.ts_min.clone())
+    }
+
+    #[getter]
+    fn of_1m<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_vec_bound(py, self.of_1m.clone())
+    }
+
+    #[getter]
+    fn of_norm_1m<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_vec_bound(py, self.of_norm_1m.clone())
+    }
+
+    #[getter]
+    fn buy_volume<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_vec_bound(py, self.buy_volume.clone())
+    }
+
+    #[getter]
+    fn sell_volume<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_vec_bound(py, self.sell_volume.clone())
+    }
+
+    #[getter]
+    fn ambiguous_frac<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_vec_bound(py, self.ambiguous_frac.clone())
+    }
+}
+
+/// Classified trades as parallel numpy arrays (`side` as int8: 1 buy,
+/// -1 sell, 0 ambiguous).
+#[pyclass]
+pub struct ClassifiedColumns {
+    pub ts_ms: Py<PyArray1<i64>>,
+    pub price: Py<PyArray1<f64>>,
+    pub size: Py<PyArray1<f64>>,
+    pub side: Py<PyArray1<i8>>,
+    pub quote_bid_px: Py<PyArray1<f64>>,
+    pub quote_ask_px: Py<PyArray1<f64>>,
+    pub quote_staleness_ms: Py<PyArray1<i64>>,
+    len: usize,
+}
+
+impl ClassifiedColumns {
+    /// Move the columns into numpy arrays (one conversion per column).
+    fn from_columns(py: Python<'_>, c: RustClassifiedColumns) -> Self {
+        ClassifiedColumns {
+            len: c.ts_ms.len(),
+            ts_ms: PyArray1::from_vec_bound(py, c.ts_ms).unbind(),
+            price: PyArray1::from_vec_bound(py, c.price).unbind(),
+            size: PyArray1::from_vec_bound(py, c.size).unbind(),
+            side: PyArray1::from_vec_bound(py, c.side).unbind(),
+            quote_bid_px: PyArray1::from_vec_bound(py, c.quote_bid_px).unbind(),
+            quote_ask_px: PyArray1::from_vec_bound(py, c.quote_ask_px).unbind(),
+            quote_staleness_ms: PyArray1::from_vec_bound(py, c.quote_staleness_ms).unbind(),
+        }
+    }
+}
+
+#[pymethods]
+impl ClassifiedColumns {
+    fn __len__(&self) -> usize {
+        self.len
+    }
+
+    #[getter]
+    fn ts_ms(&self, py: Python<'_>) -> Py<PyArray1<i64>> {
+        self.ts_ms.clone_ref(py)
+    }
+
+    #[getter]
+    fn price(&self, py: Python<'_>) -> Py<PyArray1<f64>> {
+        self.price.clone_ref(py)
+    }
+
+    #[getter]
+    fn size(&self, py: Python<'_>) -> Py<PyArray1<f64>> {
+        self.size.clone_ref(py)
+    }
+
+    #[getter]
+    fn side(&self, py: Python<'_>) -> Py<PyArray1<i8>> {
+        self.side.clone_ref(py)
+    }
+
+    #[getter]
+    fn quote_bid_px(&self, py: Python<'_>) -> Py<PyArray1<f64>> {
+        self.quote_bid_px.clone_ref(py)
+    }
+
+    #[getter]
+    fn quote_ask_px(&self, py: Python<'_>) -> Py<PyArray1<f64>> {
+        self.quote_ask_px.clone_ref(py)
+    }
+
+    #[getter]
+    fn quote_staleness_ms(&self, py: Python<'_>) -> Py<PyArray1<i64>> {
+        self.quote_staleness_ms.clone_ref(py)
+    }
+}
+
+/// Predicted vs true side counts of labeled trades.
+///
+/// `counts[predicted][true]`, with `precision` and `recall` per side, all in
+/// buy, sell, ambiguous order.
+#[pyclass]
+#[derive(Clone)]
+pub struct ConfusionMatrix {
+    #[pyo3(get)]
+    pub counts: Vec<Vec<u64>>,
+    #[pyo3(get)]
+    pub total: u64,
+    #[pyo3(get)]
+    pub accuracy: f64,
+    #[pyo3(get)]
+    pub precision: Vec<f64>,
+    #[pyo3(get)]
+    pub recall: Vec<f64>,
+}
+
+impl From<RustConfusionMatrix> for ConfusionMatrix {
+    fn from(m: RustConfusionMatrix) -> Self {
+        ConfusionMatrix {
+            counts: m.counts.iter().map(|row| row.to_vec()).collect(),
+            total: m.total(),
+            accuracy: m.accuracy(),
+            precision: RustConfusionMatrix::SIDES.iter().map(|&s| m.precision(s)).collect(),
+            recall: RustConfusionMatrix::SIDES.iter().map(|&s| m.recall(s)).collect(),
+        }
+    }
+}
+
+/// Equity and drawdown curve as parallel lists (wrap with `numpy.asarray`).
+#[pyclass]
+#[derive(Clone)]
+pub struct EquityCurve {
+    #[pyo3(get)]
+    pub ts_ms: Vec<i64>,
+    #[pyo3(get)]
+    pub equity: Vec<f64>,
+    #[pyo3(get)]
+    pub drawdown: Vec<f64>,
+    #[pyo3(get)]
+    pub drawdown_pct: Vec<f64>,
+}
+
+impl From<Vec<RustEquityPoint>> for EquityCurve {
+    fn from(points: Vec<RustEquityPoint>) -> Self {
+        EquityCurve {
+            ts_ms: points.iter().map(|p| p.ts_ms).collect(),
+            equity: points.iter().map(|p| p.equity).collect(),
+            drawdown: points.iter().map(|p| p.drawdown).collect(),
+            drawdown_pct: points.iter().map(|p| p.drawdown_pct).collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl EquityCurve {
+    fn __len__(&self) -> usize {
+        self.ts_ms.len()
+    }
+}
+
+/// Backtest performance metrics, with a per-strategy-tag breakdown.
+#[pyclass]
+#[derive(Clone)]
+pub struct PyBacktestMetrics {
+    #[pyo3(get)]
+    pub total_trades: u32,
+    #[pyo3(get)]
+    pub winning_trades: u32,
+    #[pyo3(get)]
+    pub losing_trades: u32,
+    #[pyo3(get)]
+    pub win_rate: f64,
+    #[pyo3(get)]
+    pub gross_pnl: f64,
+    #[pyo3(get)]
+    pub net_pnl: f64,
+    #[pyo3(get)]
+    pub total_fees: f64,
+    #[pyo3(get)]
+    pub total_slippage: f64,
+    #[pyo3(get)]
+    pub total_funding: f64,
+    #[pyo3(get)]
+    pub avg_win: f64,
+    #[pyo3(get)]
+    pub avg_loss: f64,
+    #[pyo3(get)]
+    pub profit_factor: f64,
+    #[pyo3(get)]
+    pub max_drawdown: f64,
+    #[pyo3(get)]
+    pub max_drawdown_pct: f64,
+    #[pyo3(get)]
+    pub sharpe_ratio: f64,
+    #[pyo3(get)]
+    pub sortino_ratio: f64,
+    #[pyo3(get)]
+    pub total_return_pct: f64,
+    #[pyo3(get)]
+    pub avg_trade_duration_min: f64,
+    #[pyo3(get)]
+    pub largest_win: f64,
+    #[pyo3(get)]
+    pub largest_loss: f64,
+    #[pyo3(get)]
+    pub max_consecutive_wins: u32,
+    #[pyo3(get)]
+    pub max_consecutive_losses: u32,
+    /// Metrics of each tag among the included trades.
+    tags: BTreeMap<String, RustBacktestMetrics>,
+}
+
+impl PyBacktestMetrics {
+    fn new(m: RustBacktestMetrics, tags: BTreeMap<String, RustBacktestMetrics>) -> Self {
+        PyBacktestMetrics {
+            total_trades: m.total_trades,
+            winning_trades: m.winning_trades,
+            losing_trades: m.losing_trades,
+            win_rate: m.win_rate,
+            gross_pnl: m.gross_pnl,
+            net_pnl: m.net_pnl,
+            total_fees: m.total_fees,
+            total_slippage: m.total_slippage,
+            total_funding: m.total_funding,
+            avg_win: m.avg_win,
+            avg_loss: m.avg_loss,
+            profit_factor: m.profit_factor,
+            max_drawdown: m.max_drawdown,
+            max_drawdown_pct: m.max_drawdown_pct,
+            sharpe_ratio: m.sharpe_ratio,
+            sortino_ratio: m.sortino_ratio,
+            total_return_pct: m.total_return_pct,
+            avg_trade_duration_min: m.avg_trade_duration_min,
+            largest_win: m.largest_win,
+            largest_loss: m.largest_loss,
+            max_consecutive_wins: m.max_consecutive_wins,
+            max_consecutive_losses: m.max_consecutive_losses,
+            tags,
         }
     }
 }
 
+#[pymethods]
+impl PyBacktestMetrics {
+    /// Metrics of each strategy tag among the included trades, as a dict of
+    /// tag to metrics (the entries have an empty breakdown).
+    fn by_tag(&self) -> BTreeMap<String, PyBacktestMetrics> {
+        self.tags
+            .iter()
+            .map(|(tag, m)| (tag.clone(), PyBacktestMetrics::new(m.clone(), BTreeMap::new())))
+            .collect()
+    }
+}
+
 // ============================================================================
 // Python-exposed Engine Classes
 // ============================================================================
@@ -407,11 +946,36 @@ pub struct PyTradeClassifier {
 
 #[pymethods]
 impl PyTradeClassifier {
+    /// `dedup_window_ms` drops exact resends seen within that window.
+    /// `outlier_max_pct` drops prints further than that fraction from the
+    /// market, `outlier_sigma=(k, lookback, min_pct)` prints further than
+    /// `k` times the recent trade-to-trade volatility.
     #[new]
-    fn new(max_quote_staleness_ms: i64, use_tick_rule_fallback: bool) -> Self {
-        PyTradeClassifier {
-            inner: TradeClassifier::new(max_quote_staleness_ms, use_tick_rule_fallback),
+    #[pyo3(signature = (
+        max_quote_staleness_ms,
+        use_tick_rule_fallback,
+        min_quote_size=0.0,
+        dedup_window_ms=None,
+        outlier_max_pct=None,
+        outlier_sigma=None,
+    ))]
+    fn new(
+        max_quote_staleness_ms: i64,
+        use_tick_rule_fallback: bool,
+        min_quote_size: f64,
+        dedup_window_ms: Option<i64>,
+        outlier_max_pct: Option<f64>,
+        outlier_sigma: Option<(f64, usize, f64)>,
+    ) -> PyResult<Self> {
+        let mut inner = TradeClassifier::new(max_quote_staleness_ms, use_tick_rule_fallback)
+            .with_min_quote_size(min_quote_size);
+        if let Some(window_ms) = dedup_window_ms {
+            inner = inner.with_dedup_window(window_ms);
         }
+        if let Some(filter) = outlier_filter(outlier_max_pct, outlier_sigma)? {
+            inner = inner.with_outlier_filter(filter);
+        }
+        Ok(PyTradeClassifier { inner })
     }
 
     /// Add a quote for trade classification.
@@ -424,6 +988,38 @@ impl PyTradeClassifier {
         self.inner.classify(trade.into()).into()
     }
 
+    /// Classify a single trade (None if dropped as a duplicate or outlier).
+    fn classify_filtered(&mut self, trade: Trade) -> Option<ClassifiedTrade> {
+        self.inner.classify_filtered(trade.into()).map(Into::into)
+    }
+
+    /// Classify a trade that may come with an exchange-provided side.
+    ///
+    /// The provided side is used as-is only if the classifier trusts
+    /// provided sides (see `PyConfig(trust_provided_side=...)`).
+    #[pyo3(signature = (trade, side=None))]
+    fn classify_with_side(&mut self, trade: Trade, side: Option<TradeSide>) -> ClassifiedTrade {
+        self.inner.classify_with_side(trade.into(), side.map(Into::into)).into()
+    }
+
+    /// `classify_with_side`, returning None for a duplicate or outlier.
+    #[pyo3(signature = (trade, side=None))]
+    fn classify_with_side_filtered(
+        &mut self,
+        trade: Trade,
+        side: Option<TradeSide>,
+    ) -> Option<ClassifiedTrade> {
+        self.inner
+            .classify_with_side_filtered(trade.into(), side.map(Into::into))
+            .map(Into::into)
+    }
+
+    /// Classify a trade with a known true side, recording it in the
+    /// confusion matrix.
+    fn classify_labeled(&mut self, trade: Trade, true_side: TradeSide) -> ClassifiedTrade {
+        self.inner.classify_labeled(trade.into(), true_side.into()).into()
+    }
+
     /// Classify a batch of trades.
     fn classify_batch(&mut self, trades: Vec<Trade>) -> Vec<ClassifiedTrade> {
         let rust_trades: Vec<RustTrade> = trades.into_iter().map(|t| t.into()).collect();
@@ -434,13 +1030,79 @@ impl PyTradeClassifier {
             .collect()
     }
 
+    /// Add quotes from contiguous 1-D numpy arrays (int64 timestamps,
+    /// float64 prices and sizes), read in place.
+    fn add_quote_columns(
+        &mut self,
+        py: Python<'_>,
+        ts_ms: PyReadonlyArray1<'_, i64>,
+        bid_px: PyReadonlyArray1<'_, f64>,
+        bid_sz: PyReadonlyArray1<'_, f64>,
+        ask_px: PyReadonlyArray1<'_, f64>,
+        ask_sz: PyReadonlyArray1<'_, f64>,
+    ) -> PyResult<()> {
+        let (ts_ms, bid_px, bid_sz) = (ts_ms.as_slice()?, bid_px.as_slice()?, bid_sz.as_slice()?);
+        let (ask_px, ask_sz) = (ask_px.as_slice()?, ask_sz.as_slice()?);
+        let inner = &mut self.inner;
+        py.allow_threads(|| inner.add_quote_columns(ts_ms, bid_px, bid_sz, ask_px, ask_sz))
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Classify trades given as contiguous 1-D numpy arrays (int64
+    /// timestamps, float64 prices and sizes), read in place.
+    ///
+    /// Equivalent to `classify_batch`; the Rust loop runs without the GIL.
+    fn classify_columns(
+        &mut self,
+        py: Python<'_>,
+        ts_ms: PyReadonlyArray1<'_, i64>,
+        price: PyReadonlyArray1<'_, f64>,
+        size: PyReadonlyArray1<'_, f64>,
+    ) -> PyResult<ClassifiedColumns> {
+        let (ts_ms, price, size) = (ts_ms.as_slice()?, price.as_slice()?, size.as_slice()?);
+        let inner = &mut self.inner;
+        py.allow_threads(|| inner.classify_columns(ts_ms, price, size))
+            .map(|columns| ClassifiedColumns::from_columns(py, columns))
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Maximum quote staleness (ms).
+    fn max_quote_staleness_ms(&self) -> i64 {
+        self.inner.max_staleness_ms()
+    }
+
+    /// Whether the tick rule resolves ambiguous trades.
+    fn use_tick_rule_fallback(&self) -> bool {
+        self.inner.uses_tick_rule()
+    }
+
+    /// Minimum quote size for bid/ask classification.
+    fn min_quote_size(&self) -> f64 {
+        self.inner.min_quote_size()
+    }
+
     /// Get classification statistics.
     fn stats(&self) -> (u64, u64, u64, u64) {
         let s = self.inner.stats();
         (s.total_trades, s.buy_trades, s.sell_trades, s.ambiguous_trades)
     }
 
-    /// Reset statistics.
+    /// Number of trades dropped as duplicates.
+    fn duplicate_trades(&self) -> u64 {
+        self.inner.stats().duplicate_trades
+    }
+
+    /// Number of trades dropped by the outlier filter.
+    fn outlier_trades(&self) -> u64 {
+        self.inner.stats().outlier_trades
+    }
+
+    /// Predicted vs true sides of the trades classified with a label.
+    fn confusion_matrix(&self) -> ConfusionMatrix {
+        self.inner.stats().confusion.into()
+    }
+
+    /// Reset statistics (including the confusion matrix).
     fn reset_stats(&mut self) {
         self.inner.reset_stats();
     }
@@ -451,18 +1113,50 @@ impl PyTradeClassifier {
     }
 }
 
+/// Outlier filter from its Python arguments (at most one may be given).
+fn outlier_filter(
+    max_pct: Option<f64>,
+    sigma: Option<(f64, usize, f64)>,
+) -> PyResult<Option<OutlierFilter>> {
+    match (max_pct, sigma) {
+        (None, None) => Ok(None),
+        (Some(max_pct), None) => Ok(Some(OutlierFilter::Pct { max_pct })),
+        (None, Some((k, lookback, min_pct))) => {
+            Ok(Some(OutlierFilter::Sigma { k, lookback, min_pct }))
+        }
+        (Some(_), Some(_)) => Err(pyo3::exceptions::PyValueError::new_err(
+            "outlier_max_pct and outlier_sigma are mutually exclusive",
+        )),
+    }
+}
+
 /// Bar builder for aggregating trades into 1-minute bars.
 #[pyclass]
 pub struct PyBarBuilder {
     inner: BarBuilder,
+    /// Tick size given to emitted bars for display precision.
+    tick_size: f64,
+}
+
+impl PyBarBuilder {
+    /// Python bar carrying this builder's tick size.
+    fn to_py(&self, bar: RustBar1m) -> Bar1m {
+        Bar1m {
+            tick_size: self.tick_size,
+            ..bar.into()
+        }
+    }
 }
 
 #[pymethods]
 impl PyBarBuilder {
+    /// `tick_size` sets the display precision of the emitted bars.
     #[new]
-    fn new() -> Self {
+    #[pyo3(signature = (tick_size=DEFAULT_TICK_SIZE))]
+    fn new(tick_size: f64) -> Self {
         PyBarBuilder {
             inner: BarBuilder::new(),
+            tick_size,
         }
     }
 
@@ -491,18 +1185,37 @@ impl PyBarBuilder {
         self.inner.add_trade(&rust_ct);
     }
 
+    /// Limit the trades and volume accepted per minute.
+    ///
+    /// With `exclude_minute`, a minute exceeding the cap is flagged corrupt
+    /// and its bar dropped; otherwise only the excess trades are dropped.
+    #[pyo3(signature = (max_trades=None, max_volume=None, exclude_minute=false))]
+    fn set_trade_cap(&mut self, max_trades: Option<u32>, max_volume: Option<f64>, exclude_minute: bool) {
+        let policy = if exclude_minute { TradeCapPolicy::ExcludeMinute } else { TradeCapPolicy::DropExcess };
+        let cap = TradeCap { max_trades, max_volume, policy };
+        self.inner = std::mem::take(&mut self.inner).with_trade_cap(cap);
+    }
+
+    /// Number of trades rejected by the trade cap.
+    fn capped_trades(&self) -> u64 {
+        self.inner.capped_trades()
+    }
+
+    /// Number of minutes excluded as corrupt by the trade cap.
+    fn corrupt_minutes(&self) -> u64 {
+        self.inner.corrupt_minutes()
+    }
+
     /// Finalize and emit bars before a timestamp.
     fn finalize_before(&mut self, current_ts_ms: i64) -> Vec<Bar1m> {
-        self.inner
-            .finalize_before(current_ts_ms)
-            .into_iter()
-            .map(|b| b.into())
-            .collect()
+        let bars = self.inner.finalize_before(current_ts_ms);
+        bars.into_iter().map(|b| self.to_py(b)).collect()
     }
 
     /// Force finalize a specific minute.
     fn force_finalize(&mut self, ts_min: i64) -> Option<Bar1m> {
-        self.inner.force_finalize(ts_min).map(|b| b.into())
+        let bar = self.inner.force_finalize(ts_min);
+        bar.map(|b| self.to_py(b))
     }
 
     /// Get number of pending bars.
@@ -520,6 +1233,8 @@ impl PyBarBuilder {
 #[pyclass]
 pub struct PyFeatureEngine {
     inner: FeatureEngine,
+    /// Python callables invoked with each finalized minute's features.
+    callbacks: Vec<PyObject>,
 }
 
 impl PyFeatureEngine {
@@ -539,16 +1254,36 @@ impl PyFeatureEngine {
             ask_sz_close: bar.ask_sz_close,
         }
     }
+
+    /// Create an engine, raising `ValueError` for an invalid config.
+    fn from_rust_config(config: &RustConfig) -> PyResult<Self> {
+        let inner = FeatureEngine::try_new(config)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyFeatureEngine {
+            inner,
+            callbacks: Vec::new(),
+        })
+    }
+
+    /// Pass finalized features to the registered callbacks, in order.
+    ///
+    /// The minute is already ingested, so a raising callback must not lose
+    /// it: the error is reported as unraisable and the features returned.
+    fn publish(&self, py: Python<'_>, features: Features1m) -> Features1m {
+        for callback in &self.callbacks {
+            if let Err(err) = callback.call1(py, (features.clone(),)) {
+                err.write_unraisable_bound(py, Some(callback.bind(py)));
+            }
+        }
+        features
+    }
 }
 
 #[pymethods]
 impl PyFeatureEngine {
     #[new]
-    fn new() -> Self {
-        let config = RustConfig::default();
-        PyFeatureEngine {
-            inner: FeatureEngine::new(&config),
-        }
+    fn new() -> PyResult<Self> {
+        Self::from_rust_config(&RustConfig::default())
     }
 
     /// Create from a custom config.
@@ -560,7 +1295,7 @@ impl PyFeatureEngine {
         alpha_bin: f64,
         bin_width_max_ticks: u32,
         min_va_bins: u32,
-    ) -> Self {
+    ) -> PyResult<Self> {
         let mut config = RustConfig::default();
         config.instrument.rolling_window_minutes = rolling_window_minutes;
         config.value_area.va_fraction = va_fraction;
@@ -568,9 +1303,7 @@ impl PyFeatureEngine {
         config.value_area.alpha_bin = alpha_bin;
         config.value_area.bin_width_max_ticks = bin_width_max_ticks;
         config.value_area.min_va_bins = min_va_bins;
-        PyFeatureEngine {
-            inner: FeatureEngine::new(&config),
-        }
+        Self::from_rust_config(&config)
     }
 
     /// Add a quote to the engine.
@@ -603,8 +1336,64 @@ impl PyFeatureEngine {
         self.inner.add_bar(&Self::bar_to_rust(bar));
     }
 
+    /// Ingest the closed bar for `ts_min` and compute that minute's features.
+    fn finalize_minute(&mut self, py: Python<'_>, ts_min: i64, bar: &Bar1m) -> PyResult<Features1m> {
+        let features = self
+            .inner
+            .finalize_minute(ts_min, &Self::bar_to_rust(bar))
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(self.publish(py, features.into()))
+    }
+
+    /// Ingest a closed bar and compute its minute's features in one call.
+    fn process_bar(&mut self, py: Python<'_>, bar: &Bar1m) -> PyResult<Features1m> {
+        let features = self
+            .inner
+            .process_bar(&Self::bar_to_rust(bar))
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(self.publish(py, features.into()))
+    }
+
+    /// Register a callable invoked with each finalized minute's features.
+    ///
+    /// Called from `finalize_minute`/`process_bar` in registration order.
+    /// An exception raised by a callback is reported through
+    /// `sys.unraisablehook`; the remaining callbacks still run and the
+    /// features are still returned.
+    fn on_features(&mut self, py: Python<'_>, callback: PyObject) -> PyResult<()> {
+        if !callback.bind(py).is_callable() {
+            return Err(pyo3::exceptions::PyTypeError::new_err("callback must be callable"));
+        }
+        self.callbacks.push(callback);
+        Ok(())
+    }
+
+    /// Remove all registered feature callbacks.
+    fn clear_callbacks(&mut self) {
+        self.callbacks.clear();
+    }
+
+    /// Limit the trades and volume ingested per minute.
+    ///
+    /// With `exclude_minute`, a minute exceeding the cap contributes no
+    /// trades at all; otherwise only the excess trades are dropped.
+    #[pyo3(signature = (max_trades=None, max_volume=None, exclude_minute=false))]
+    fn set_trade_cap(&mut self, max_trades: Option<u32>, max_volume: Option<f64>, exclude_minute: bool) {
+        let policy = if exclude_minute { TradeCapPolicy::ExcludeMinute } else { TradeCapPolicy::DropExcess };
+        self.inner.set_trade_cap(Some(TradeCap { max_trades, max_volume, policy }));
+    }
+
+    /// Number of trades rejected by the trade cap.
+    fn capped_trades(&self) -> u64 {
+        self.inner.capped_trades()
+    }
+
     /// Compute features for the current state.
-    fn compute_features(&self, ts_min: i64, bar: &Bar1m) -> Features1m {
+    ///
+    /// Deprecated: the result depends on whether `add_bar` was called first;
+    /// use `process_bar` or `finalize_minute`.
+    #[allow(deprecated)]
+    fn compute_features(&mut self, ts_min: i64, bar: &Bar1m) -> Features1m {
         self.inner.compute_features(ts_min, &Self::bar_to_rust(bar)).into()
     }
 
@@ -613,17 +1402,346 @@ impl PyFeatureEngine {
         self.inner.is_ready()
     }
 
+    /// Check the given subset of components for warmup.
+    #[pyo3(signature = (volatility=true, histogram=true, order_flow_minutes=0, qimb=false))]
+    fn is_ready_with(&self, volatility: bool, histogram: bool, order_flow_minutes: usize, qimb: bool) -> bool {
+        self.inner.is_ready_with(ReadinessRequirements {
+            volatility,
+            histogram,
+            order_flow_minutes,
+            qimb,
+        })
+    }
+
+    /// Whether the volatility window is full.
+    fn volatility_ready(&self) -> bool {
+        self.inner.volatility_ready()
+    }
+
+    /// Whether the histogram window is full.
+    fn histogram_ready(&self) -> bool {
+        self.inner.histogram_ready()
+    }
+
+    /// Minutes with order flow in the rolling window.
+    fn order_flow_minutes(&self) -> usize {
+        self.inner.order_flow_minutes()
+    }
+
+    /// Whether the imbalance tracker has seen a usable quote.
+    fn qimb_ready(&self) -> bool {
+        self.inner.qimb_ready()
+    }
+
     /// Get the current bin width.
     fn current_bin_width(&self) -> f64 {
         self.inner.current_bin_width()
     }
 
+    /// Get the instrument tick size.
+    fn tick_size(&self) -> f64 {
+        self.inner.tick_size()
+    }
+
+    /// Get the rolling window in minutes.
+    fn window_size(&self) -> usize {
+        self.inner.window_size()
+    }
+
+    /// Clear only the order-flow and quote-imbalance state.
+    fn clear_order_flow(&mut self) {
+        self.inner.clear_order_flow();
+    }
+
+    /// Get the per-minute order flow series as parallel lists.
+    fn order_flow_series(&self) -> OrderFlowSeries {
+        self.inner.order_flow_series().into()
+    }
+
+    /// Clear only the rolling volume histogram.
+    fn clear_histogram(&mut self) {
+        self.inner.clear_histogram();
+    }
+
+    /// Clear only the rolling volatility.
+    fn clear_volatility(&mut self) {
+        self.inner.clear_volatility();
+    }
+
     /// Clear all state.
     fn clear(&mut self) {
         self.inner.clear();
     }
 }
 
+/// Backtest simulator.
+#[pyclass]
+pub struct PyBacktestSimulator {
+    inner: BacktestSimulator,
+}
+
+#[pymethods]
+impl PyBacktestSimulator {
+    #[new]
+    #[pyo3(signature = (initial_capital=10000.0, warmup_bars=0))]
+    fn new(initial_capital: f64, warmup_bars: usize) -> Self {
+        let config = BacktestConfig {
+            initial_capital,
+            warmup_bars,
+            ..Default::default()
+        };
+        PyBacktestSimulator {
+            inner: BacktestSimulator::new(config),
+        }
+    }
+
+    /// Create from the system config (fill model, sizing, risk and exit settings).
+    #[staticmethod]
+    fn from_config(config: &PyConfig) -> Self {
+        PyBacktestSimulator {
+            inner: BacktestSimulator::new(BacktestConfig::from(&config.inner)),
+        }
+    }
+
+    /// Replay a bar. Returns true once warmup is complete.
+    fn on_bar(&mut self, bar: &Bar1m, quote: &Quote) -> bool {
+        self.inner
+            .on_bar(&PyFeatureEngine::bar_to_rust(bar), &quote.clone().into())
+    }
+
+    /// Process a signal (`"enter_long"`, `"enter_short"`, `"exit"` or `"hold"`).
+    #[pyo3(signature = (ts_ms, action, quote, stop_price=None, tp1_price=None, tp2_price=None, size=None, strategy_tag=String::new()))]
+    #[allow(clippy::too_many_arguments)]
+    fn process_signal(
+        &mut self,
+        ts_ms: i64,
+        action: &str,
+        quote: &Quote,
+        stop_price: Option<f64>,
+        tp1_price: Option<f64>,
+        tp2_price: Option<f64>,
+        size: Option<f64>,
+        strategy_tag: String,
+    ) -> PyResult<()> {
+        let action = match action {
+            "enter_long" => RustAction::EnterLong,
+            "enter_short" => RustAction::EnterShort,
+            "exit" => RustAction::Exit,
+            "hold" => RustAction::Hold,
+            other => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "unknown action: {other}"
+                )))
+            }
+        };
+        let signal = RustSignal {
+            ts_ms,
+            action,
+            stop_price,
+            tp1_price,
+            tp2_price,
+            size,
+            strategy_tag,
+        };
+        self.inner.process_signal(&signal, &quote.clone().into());
+        Ok(())
+    }
+
+    /// Current realized equity.
+    fn equity(&self) -> f64 {
+        self.inner.equity()
+    }
+
+    /// Number of closed trades.
+    fn trade_count(&self) -> usize {
+        self.inner.trades().len()
+    }
+
+    /// Performance metrics, over all trades or only those tagged `strategy_tag`.
+    #[pyo3(signature = (strategy_tag=None))]
+    fn calculate_metrics(&self, strategy_tag: Option<&str>) -> PyBacktestMetrics {
+        match strategy_tag {
+            Some(tag) => {
+                let metrics = self.inner.calculate_metrics_for_tag(tag);
+                let tags = if metrics.total_trades > 0 {
+                    BTreeMap::from([(tag.to_string(), metrics.clone())])
+                } else {
+                    BTreeMap::new()
+                };
+                PyBacktestMetrics::new(metrics, tags)
+            }
+            None => PyBacktestMetrics::new(self.inner.calculate_metrics(), self.inner.metrics_by_tag()),
+        }
+    }
+
+    /// Realized equity and drawdown after each closed trade (`trade_count() + 1` points).
+    fn equity_curve(&self) -> EquityCurve {
+        self.inner.equity_curve().into()
+    }
+
+    /// Reset the simulator.
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+/// System configuration over the commonly tuned fields (defaults elsewhere).
+#[pyclass]
+#[derive(Clone)]
+pub struct PyConfig {
+    inner: RustConfig,
+}
+
+#[pymethods]
+impl PyConfig {
+    /// Policies are given by their config names, e.g. `tick_rounding="toward_mid"`,
+    /// `close_quote_policy="closest_to_close"`, `missing_quote_policy="carry_forward"`.
+    /// `outlier_max_pct` enables the percentage outlier filter and
+    /// `outlier_sigma=(k, lookback, min_pct)` the volatility-scaled one.
+    #[new]
+    #[pyo3(signature = (
+        tick_size=0.1,
+        rolling_window_minutes=240,
+        va_fraction=0.70,
+        max_quote_staleness_ms=250,
+        use_tick_rule_fallback=true,
+        min_quote_size=0.0,
+        tick_rounding=None,
+        dedup_window_ms=None,
+        outlier_max_pct=None,
+        outlier_sigma=None,
+        trust_provided_side=false,
+        seed_tick_rule_from_batch=false,
+        close_quote_policy="last_before_close",
+        missing_quote_policy="nan",
+        max_quote_age_ms=None,
+        close_quote_tolerance_ms=1000,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        tick_size: f64,
+        rolling_window_minutes: u32,
+        va_fraction: f64,
+        max_quote_staleness_ms: i64,
+        use_tick_rule_fallback: bool,
+        min_quote_size: f64,
+        tick_rounding: Option<&str>,
+        dedup_window_ms: Option<i64>,
+        outlier_max_pct: Option<f64>,
+        outlier_sigma: Option<(f64, usize, f64)>,
+        trust_provided_side: bool,
+        seed_tick_rule_from_batch: bool,
+        close_quote_policy: &str,
+        missing_quote_policy: &str,
+        max_quote_age_ms: Option<i64>,
+        close_quote_tolerance_ms: i64,
+    ) -> PyResult<Self> {
+        let mut inner = RustConfig::builder()
+            .tick_size(tick_size)
+            .rolling_window_minutes(rolling_window_minutes)
+            .va_fraction(va_fraction)
+            .build()
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        let order_flow = &mut inner.order_flow;
+        order_flow.max_quote_staleness_ms = max_quote_staleness_ms;
+        order_flow.use_tick_rule_fallback = use_tick_rule_fallback;
+        order_flow.min_quote_size = min_quote_size;
+        order_flow.tick_rounding = match tick_rounding {
+            None => None,
+            Some("nearest") => Some(TickRounding::Nearest),
+            Some("toward_mid") => Some(TickRounding::TowardMid),
+            Some(other) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "unknown tick_rounding: {other}"
+                )))
+            }
+        };
+        order_flow.dedup_window_ms = dedup_window_ms;
+        order_flow.outlier_filter = outlier_filter(outlier_max_pct, outlier_sigma)?;
+        order_flow.trust_provided_side = trust_provided_side;
+        order_flow.seed_tick_rule_from_batch = seed_tick_rule_from_batch;
+
+        let bars = &mut inner.bars;
+        bars.close_quote_policy = match close_quote_policy {
+            "last_before_close" => CloseQuotePolicy::LastBeforeClose,
+            "time_weighted_mid_of_minute" => CloseQuotePolicy::TimeWeightedMidOfMinute,
+            "closest_to_close" => CloseQuotePolicy::ClosestToClose,
+            other => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "unknown close_quote_policy: {other}"
+                )))
+            }
+        };
+        bars.missing_quote_policy = match missing_quote_policy {
+            "nan" => MissingQuotePolicy::Nan,
+            "drop" => MissingQuotePolicy::Drop,
+            "carry_forward" => MissingQuotePolicy::CarryForward,
+            other => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "unknown missing_quote_policy: {other}"
+                )))
+            }
+        };
+        bars.max_quote_age_ms = max_quote_age_ms;
+        bars.close_quote_tolerance_ms = close_quote_tolerance_ms;
+        Ok(PyConfig { inner })
+    }
+
+    #[getter]
+    fn tick_size(&self) -> f64 {
+        self.inner.instrument.tick_size
+    }
+
+    #[getter]
+    fn rolling_window_minutes(&self) -> u32 {
+        self.inner.instrument.rolling_window_minutes
+    }
+
+    #[getter]
+    fn va_fraction(&self) -> f64 {
+        self.inner.value_area.va_fraction
+    }
+
+    #[getter]
+    fn max_quote_staleness_ms(&self) -> i64 {
+        self.inner.order_flow.max_quote_staleness_ms
+    }
+
+    #[getter]
+    fn use_tick_rule_fallback(&self) -> bool {
+        self.inner.order_flow.use_tick_rule_fallback
+    }
+
+    #[getter]
+    fn min_quote_size(&self) -> f64 {
+        self.inner.order_flow.min_quote_size
+    }
+}
+
+/// Build a classifier, bar builder and feature engine from one config.
+///
+/// The classifier takes the instrument tick and the order flow section, the
+/// bar builder the instrument sizes and the bars section; the engine takes
+/// everything else.
+#[pyfunction]
+fn build_pipeline(config: &PyConfig) -> PyResult<(PyTradeClassifier, PyBarBuilder, PyFeatureEngine)> {
+    let engine = FeatureEngine::try_new(&config.inner)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    Ok((
+        PyTradeClassifier {
+            inner: TradeClassifier::from(&config.inner),
+        },
+        PyBarBuilder {
+            inner: BarBuilder::from(&config.inner),
+            tick_size: config.inner.instrument.tick_size,
+        },
+        PyFeatureEngine {
+            inner: engine,
+            callbacks: Vec::new(),
+        },
+    ))
+}
+
 // ============================================================================
 // Module Definition
 // ============================================================================
@@ -639,12 +1757,25 @@ fn auction_trader_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Bar1m>()?;
     m.add_class::<ValueArea>()?;
     m.add_class::<OrderFlowMetrics>()?;
+    m.add_class::<VaMigration>()?;
+    m.add_class::<EdgeFlow>()?;
+    m.add_class::<VwapBandTouches>()?;
+    m.add_class::<OrderFlowSeries>()?;
     m.add_class::<Features1m>()?;
+    m.add_class::<EquityCurve>()?;
+    m.add_class::<PyBacktestMetrics>()?;
+    m.add_class::<ClassifiedColumns>()?;
+    m.add_class::<ConfusionMatrix>()?;
 
     // Engine classes
     m.add_class::<PyTradeClassifier>()?;
     m.add_class::<PyBarBuilder>()?;
     m.add_class::<PyFeatureEngine>()?;
+    m.add_class::<PyBacktestSimulator>()?;
+    m.add_class::<PyConfig>()?;
+
+    // Factories
+    m.add_function(wrap_pyfunction!(build_pipeline, m)?)?;
 
     Ok(())
 }
@@ -118,6 +118,51 @@ def sample_features(sample_value_area, sample_order_flow) -> Features1m:
     )
 
 
+@pytest.fixture
+def make_features():
+    """Factory for signal engine features around a 41800-42200 VA.
+
+    Order flow is 100 volume with of_norm_1m of net buying; an ambiguous
+    fraction is taken evenly from both sides. qimb_close equals qimb_ema.
+    """
+    default_va = ValueArea(
+        poc=42000.0, vah=42200.0, val=41800.0,
+        coverage=0.7, bin_count=20, total_volume=1000.0,
+        bin_width=10.0, is_valid=True,
+    )
+
+    def factory(
+        ts_min: int,
+        mid_close: float,
+        of_norm_1m: float = 0.0,
+        ambiguous_frac: float = 0.0,
+        qimb_ema: float = 0.0,
+        va: ValueArea = default_va,
+    ) -> Features1m:
+        ambiguous_volume = 100.0 * ambiguous_frac
+        return Features1m(
+            ts_min=ts_min,
+            mid_close=mid_close,
+            sigma_240=0.015,
+            bin_width=10.0,
+            va=va,
+            order_flow=OrderFlowMetrics(
+                of_1m=100.0 * of_norm_1m,
+                of_norm_1m=of_norm_1m,
+                total_volume=100.0,
+                buy_volume=50.0 + 50.0 * of_norm_1m - ambiguous_volume / 2,
+                sell_volume=50.0 - 50.0 * of_norm_1m - ambiguous_volume / 2,
+                ambiguous_volume=ambiguous_volume,
+                ambiguous_frac=ambiguous_frac,
+            ),
+            qimb_close=qimb_ema,
+            qimb_ema=qimb_ema,
+            spread_avg_60m=1.0,
+        )
+
+    return factory
+
+
 @pytest.fixture
 def bar_history() -> list[Bar1m]:
     """Create a list of bars for testing rolling calculations."""
@@ -155,3 +155,54 @@ class TestConfigValidation:
     def test_va_fraction_range(self):
         config = ValueAreaConfig()
         assert 0 < config.va_fraction < 1
+
+    def test_unknown_target_mode(self):
+        with pytest.raises(ValueError):
+            SignalConfig(target_mode="va")
+        with pytest.raises(ValueError):
+            Config.from_dict({"signal": {"target_mode": "width"}})
+        config = Config.from_dict({"signal": {"target_mode": "va_width"}})
+        assert config.signal.target_mode == "va_width"
+
+    def test_unknown_edge_close_policy(self):
+        with pytest.raises(ValueError):
+            SignalConfig(edge_close_policy="outside")
+        with pytest.raises(ValueError):
+            Config.from_dict({"signal": {"edge_close_policy": "partial"}})
+        config = Config.from_dict({"signal": {"edge_close_policy": "fractional"}})
+        assert config.signal.edge_close_policy == "fractional"
+
+    def test_unknown_exit_mode(self):
+        with pytest.raises(ValueError):
+            SignalConfig(exit_mode="value_area")
+        with pytest.raises(ValueError):
+            Config.from_dict({"signal": {"exit_mode": "va_target"}})
+        config = Config.from_dict({"signal": {"exit_mode": "value_area_target"}})
+        assert config.signal.exit_mode == "value_area_target"
+
+    def test_unknown_va_target_level(self):
+        with pytest.raises(ValueError):
+            SignalConfig(va_target_level="vah")
+        with pytest.raises(ValueError):
+            Config.from_dict({"signal": {"va_target_level": "opposite"}})
+        config = Config.from_dict({"signal": {"va_target_level": "opposite_edge"}})
+        assert config.signal.va_target_level == "opposite_edge"
+
+    def test_unknown_ambiguous_gate_mode(self):
+        with pytest.raises(ValueError):
+            OrderFlowConfig(ambiguous_gate_mode="block")
+        with pytest.raises(ValueError):
+            Config.from_dict({"order_flow": {"ambiguous_gate_mode": "confrim"}})
+        config = Config.from_dict({"order_flow": {"ambiguous_gate_mode": "suppress"}})
+        assert config.order_flow.ambiguous_gate_mode == "suppress"
+
+    def test_unknown_risk_scopes(self):
+        with pytest.raises(ValueError):
+            RiskConfig(cooldown_scope="per_side")
+        with pytest.raises(ValueError):
+            Config.from_dict({"risk": {"min_hold_scope": "stops_only"}})
+        config = Config.from_dict(
+            {"risk": {"cooldown_scope": "per_direction", "min_hold_scope": "targets_only"}}
+        )
+        assert config.risk.cooldown_scope == "per_direction"
+        assert config.risk.min_hold_scope == "targets_only"
@@ -173,6 +173,57 @@ class TestTimeStop:
         current_price = 42500.0  # Profitable
         assert pm._check_time_stop(current_price, current_ts) is False
 
+    def test_time_stop_extension_cap(self):
+        config = Config()
+        config.risk.extend_if_profitable = True
+        config.risk.extension_minutes = 15
+        config.risk.max_extensions = 2
+        pm = PositionManager(config, initial_capital=10000.0)
+        pm.position = Position(
+            entry_ts=0,
+            side=PositionSide.LONG,
+            entry_price=42000.0,
+            size=0.1,
+            original_size=0.1,
+            stop_price=41800.0,
+            tp1_price=42100.0,
+            tp2_price=42200.0,
+        )
+        base_ms = config.risk.max_hold_minutes * 60_000
+        ext_ms = config.risk.extension_minutes * 60_000
+        profitable = 42500.0
+
+        # Base hold reached: first extension
+        assert pm._check_time_stop(profitable, base_ms) is False
+        assert pm.position.hold_extensions == 1
+        # Inside the extended hold: no re-evaluation
+        assert pm._check_time_stop(profitable, base_ms + ext_ms - 1) is False
+        assert pm.position.hold_extensions == 1
+        # Second extension
+        assert pm._check_time_stop(profitable, base_ms + ext_ms) is False
+        assert pm.position.hold_extensions == 2
+        # Cap reached: timed out despite profit
+        assert pm._check_time_stop(profitable, base_ms + 2 * ext_ms) is True
+
+    def test_time_stop_unprofitable_cut_at_base(self):
+        config = Config()
+        config.risk.extend_if_profitable = True
+        pm = PositionManager(config, initial_capital=10000.0)
+        pm.position = Position(
+            entry_ts=0,
+            side=PositionSide.SHORT,
+            entry_price=42000.0,
+            size=0.1,
+            original_size=0.1,
+            stop_price=42200.0,
+            tp1_price=41900.0,
+            tp2_price=41800.0,
+        )
+        base_ms = config.risk.max_hold_minutes * 60_000
+        assert pm._check_time_stop(42100.0, base_ms - 1) is False
+        assert pm._check_time_stop(42100.0, base_ms) is True
+        assert pm.position.hold_extensions == 0
+
 
 class TestFeeCalculation:
     """Tests for trading fee calculation."""
@@ -0,0 +1,390 @@
+"""Tests for the Rust extension bindings (skipped when it is not built)."""
+
+import sys
+
+import numpy as np
+import pytest
+
+core = pytest.importorskip("auction_trader_core")
+
+
+def _round_trip(sim, ts_ms, tag, exit_bid):
+    """Open a long tagged `tag` and exit it one minute later."""
+    sim.process_signal(
+        ts_ms,
+        "enter_long",
+        core.Quote(ts_ms, 50000.0, 1.0, 50001.0, 1.0),
+        stop_price=49000.0,
+        size=0.1,
+        strategy_tag=tag,
+    )
+    exit_ts = ts_ms + 60_000
+    sim.process_signal(exit_ts, "exit", core.Quote(exit_ts, exit_bid, 1.0, exit_bid + 1.0, 1.0))
+
+
+class TestBacktestMetrics:
+    """Tests for per-tag backtest metrics."""
+
+    def test_by_tag(self):
+        sim = core.PyBacktestSimulator()
+        _round_trip(sim, 60_000, "breakin", 50100.0)
+        _round_trip(sim, 180_000, "breakout", 49900.0)
+        _round_trip(sim, 300_000, "breakin", 50200.0)
+
+        metrics = sim.calculate_metrics()
+        assert metrics.total_trades == 3
+
+        by_tag = metrics.by_tag()
+        assert set(by_tag) == {"breakin", "breakout"}
+        assert by_tag["breakin"].total_trades == 2
+        assert by_tag["breakout"].total_trades == 1
+        assert by_tag["breakin"].net_pnl + by_tag["breakout"].net_pnl == pytest.approx(metrics.net_pnl)
+
+    def test_tag_filter(self):
+        sim = core.PyBacktestSimulator()
+        _round_trip(sim, 60_000, "breakin", 50100.0)
+        _round_trip(sim, 180_000, "breakout", 49900.0)
+
+        breakout = sim.calculate_metrics("breakout")
+        assert breakout.total_trades == 1
+        assert breakout.net_pnl < 0
+        assert set(breakout.by_tag()) == {"breakout"}
+        assert sim.calculate_metrics("missing").total_trades == 0
+
+    def test_equity_curve(self):
+        sim = core.PyBacktestSimulator()
+        assert len(sim.equity_curve()) == 1
+
+        _round_trip(sim, 60_000, "breakin", 50100.0)
+        _round_trip(sim, 180_000, "breakout", 49900.0)
+
+        metrics = sim.calculate_metrics()
+        curve = sim.equity_curve()
+        # The initial capital plus one point per closed trade
+        assert len(curve) == metrics.total_trades + 1 == 3
+        assert len(curve.equity) == len(curve.drawdown) == len(curve.drawdown_pct) == 3
+        assert curve.equity[-1] == pytest.approx(curve.equity[0] + metrics.net_pnl)
+        assert curve.drawdown[1] == 0.0
+        assert curve.drawdown[2] > 0.0
+
+
+class TestTradeClassifier:
+    """Tests for classification against labeled trades."""
+
+    def test_confusion_matrix(self):
+        classifier = core.PyTradeClassifier(250, False)
+        classifier.add_quote(core.Quote(1000, 50000.0, 1.0, 50001.0, 1.0))
+
+        classifier.classify_labeled(core.Trade(1100, 50001.0, 0.1), core.TradeSide.Buy)
+        classifier.classify_labeled(core.Trade(1200, 50000.0, 0.1), core.TradeSide.Sell)
+        classifier.classify_labeled(core.Trade(1300, 50000.0, 0.1), core.TradeSide.Buy)
+        classifier.classify_labeled(core.Trade(1400, 50000.5, 0.1), core.TradeSide.Sell)
+
+        cm = classifier.confusion_matrix()
+        # Rows are predicted, columns true, in buy/sell/ambiguous order
+        assert cm.counts == [[1, 0, 0], [1, 1, 0], [0, 1, 0]]
+        assert cm.total == 4
+        assert cm.accuracy == pytest.approx(0.5)
+        assert cm.precision == pytest.approx([1.0, 0.5, 0.0])
+        assert cm.recall == pytest.approx([0.5, 0.5, 0.0])
+
+        classifier.reset_stats()
+        assert classifier.confusion_matrix().total == 0
+
+    def test_streaming_dedup(self):
+        classifier = core.PyTradeClassifier(250, False, dedup_window_ms=5000)
+        classifier.add_quote(core.Quote(1000, 50000.0, 1.0, 50001.0, 1.0))
+
+        assert classifier.classify_filtered(core.Trade(1100, 50001.0, 0.5)) is not None
+        assert classifier.classify_filtered(core.Trade(1100, 50001.0, 0.5)) is None
+        # A different trade in the same ms is kept
+        assert classifier.classify_filtered(core.Trade(1100, 50000.0, 0.5)) is not None
+        assert classifier.duplicate_trades() == 1
+        assert classifier.stats()[0] == 2
+
+    def test_streaming_outlier_filter(self):
+        classifier = core.PyTradeClassifier(250, False, outlier_max_pct=0.05)
+        classifier.add_quote(core.Quote(1000, 50000.0, 1.0, 50001.0, 1.0))
+
+        assert classifier.classify_filtered(core.Trade(1100, 50001.0, 0.5)) is not None
+        assert classifier.classify_filtered(core.Trade(1200, 5000.0, 2.0)) is None
+        assert classifier.classify_with_side_filtered(core.Trade(1300, 500000.0, 1.0)) is None
+        assert classifier.outlier_trades() == 2
+
+        with pytest.raises(ValueError):
+            core.PyTradeClassifier(250, False, outlier_max_pct=0.05, outlier_sigma=(3.0, 100, 0.01))
+
+
+class TestClassifyColumns:
+    """Tests for the columnar classification path."""
+
+    def test_matches_object_path(self):
+        quotes = [(1000, 50000.0, 50001.0), (1500, 50002.0, 50003.0), (2500, 49999.0, 50000.0)]
+        trades = [
+            (1100, 50001.0, 0.1),
+            (1200, 50000.5, 0.2),
+            (1600, 50002.0, 0.3),
+            # Same timestamp: aggregated on both paths
+            (1700, 50003.0, 0.1),
+            (1700, 50003.0, 0.4),
+            (2600, 49999.5, 0.2),
+            # Stale quote
+            (3000, 50001.0, 0.5),
+        ]
+
+        def make_classifier():
+            classifier = core.PyTradeClassifier(250, True)
+            for ts, bid, ask in quotes:
+                classifier.add_quote(core.Quote(ts, bid, 1.0, ask, 1.0))
+            return classifier
+
+        objects = make_classifier().classify_batch([core.Trade(*t) for t in trades])
+        ts_ms, price, size = zip(*trades)
+        columns = make_classifier().classify_columns(
+            np.array(ts_ms, dtype=np.int64), np.array(price), np.array(size)
+        )
+
+        assert len(columns) == len(objects) == 6
+        assert columns.ts_ms.tolist() == [ct.trade.ts_ms for ct in objects]
+        assert columns.price.tolist() == [ct.trade.price for ct in objects]
+        assert columns.size.tolist() == [ct.trade.size for ct in objects]
+        assert columns.side.tolist() == [int(ct.side) for ct in objects]
+        assert columns.quote_bid_px.tolist() == [ct.quote_bid_px for ct in objects]
+        assert columns.quote_ask_px.tolist() == [ct.quote_ask_px for ct in objects]
+        assert columns.quote_staleness_ms.tolist() == [ct.quote_staleness_ms for ct in objects]
+
+    def test_numpy_columns(self):
+        classifier = core.PyTradeClassifier(250, True)
+        classifier.add_quote_columns(
+            np.array([1000], dtype=np.int64),
+            np.array([50000.0]),
+            np.array([1.0]),
+            np.array([50001.0]),
+            np.array([1.0]),
+        )
+        columns = classifier.classify_columns(
+            np.array([1100, 1200], dtype=np.int64), np.array([50001.0, 50000.0]), np.array([0.1, 0.2])
+        )
+        for name in ("ts_ms", "price", "size", "side", "quote_bid_px", "quote_ask_px", "quote_staleness_ms"):
+            assert isinstance(getattr(columns, name), np.ndarray)
+        assert columns.side.dtype == np.int8
+        assert columns.side.tolist() == [1, -1]
+
+    def test_mismatched_lengths(self):
+        classifier = core.PyTradeClassifier(250, True)
+        with pytest.raises(ValueError):
+            classifier.classify_columns(
+                np.array([1000, 2000], dtype=np.int64), np.array([50000.0]), np.array([0.1, 0.2])
+            )
+
+
+class TestOrderFlowSeries:
+    """Tests for the columnar order flow export."""
+
+    def test_series_matches_minutes(self):
+        classifier = core.PyTradeClassifier(250, False)
+        builder = core.PyBarBuilder()
+        engine = core.PyFeatureEngine()
+        for minute in range(1, 4):
+            ts = minute * 60_000
+            quote = core.Quote(ts + 100, 50000.0, 1.0, 50001.0, 1.0)
+            classifier.add_quote(quote)
+            builder.add_quote(quote)
+            for trade in (core.Trade(ts + 200, 50001.0, 0.5), core.Trade(ts + 300, 50000.0, 0.2)):
+                classified = classifier.classify(trade)
+                builder.add_trade(classified)
+                engine.add_trade(classified)
+        for bar in builder.finalize_before(4 * 60_000):
+            engine.process_bar(bar)
+
+        series = engine.order_flow_series()
+        assert len(series) == 3
+        assert isinstance(series.of_1m, np.ndarray)
+        assert series.ts_min.tolist() == [60_000, 120_000, 180_000]
+        assert series.buy_volume == pytest.approx([0.5] * 3)
+        assert series.sell_volume == pytest.approx([0.2] * 3)
+        assert series.of_1m == pytest.approx([0.3] * 3)
+        assert series.ambiguous_frac == pytest.approx([0.0] * 3)
+        assert len(series.of_norm_1m) == 3
+
+
+class TestBuildPipeline:
+    """Tests for config-driven pipeline construction."""
+
+    def test_components_share_config(self):
+        config = core.PyConfig(
+            tick_size=0.5,
+            use_tick_rule_fallback=False,
+            tick_rounding="nearest",
+            dedup_window_ms=1000,
+            outlier_max_pct=0.05,
+            trust_provided_side=True,
+            missing_quote_policy="drop",
+            max_quote_age_ms=5000,
+        )
+        classifier, builder, _engine = core.build_pipeline(config)
+
+        classifier.add_quote(core.Quote(1000, 100.0, 1.0, 101.0, 1.0))
+        trades = [
+            core.Trade(1100, 100.9, 1.0),  # snaps to the ask on the 0.5 tick
+            core.Trade(1100, 100.9, 1.0),  # exact resend
+            core.Trade(1200, 200.0, 1.0),  # bad print
+        ]
+        classified = classifier.classify_batch(trades)
+        assert len(classified) == 1
+        assert classified[0].side == core.TradeSide.Buy
+        assert classified[0].trade.size == 1.0
+
+        provided = classifier.classify_with_side(core.Trade(1300, 101.0, 1.0), core.TradeSide.Sell)
+        assert provided.side == core.TradeSide.Sell
+
+        # The only quote is too old at the close, so the bar is dropped
+        builder.add_quote(core.Quote(60_000 + 1_000, 100.0, 1.0, 101.0, 1.0))
+        builder.add_trade(classifier.classify(core.Trade(60_000 + 30_000, 101.0, 1.0)))
+        assert builder.finalize_before(121_000) == []
+
+    def test_seed_tick_rule_from_batch(self):
+        config = core.PyConfig(max_quote_staleness_ms=5000, seed_tick_rule_from_batch=True)
+        classifier, _builder, _engine = core.build_pipeline(config)
+        classifier.add_quote(core.Quote(1000, 100.0, 1.0, 101.0, 1.0))
+        trades = [core.Trade(1100, 100.5, 1.0), core.Trade(1200, 101.0, 1.0)]
+        sides = [t.side for t in classifier.classify_batch(trades)]
+        assert sides == [core.TradeSide.Sell, core.TradeSide.Buy]
+
+    def test_invalid_policy(self):
+        with pytest.raises(ValueError):
+            core.PyConfig(close_quote_policy="latest")
+        with pytest.raises(ValueError):
+            core.PyConfig(outlier_max_pct=0.05, outlier_sigma=(3.0, 100, 0.01))
+
+    def test_invalid_config(self):
+        with pytest.raises(ValueError):
+            core.PyConfig(tick_size=0.0)
+
+    def test_invalid_engine_config(self):
+        with pytest.raises(ValueError):
+            core.PyFeatureEngine.with_config(240, 1.5, 0.1, 0.1, 20, 10)
+
+
+class TestReprPrecision:
+    """Tests for tick-size display precision."""
+
+    def _bar(self, builder):
+        quote = core.Quote(60_100, 50000.0, 1.0, 50001.0, 1.0)
+        classifier = core.PyTradeClassifier(250, False)
+        classifier.add_quote(quote)
+        builder.add_quote(quote)
+        builder.add_trade(classifier.classify(core.Trade(60_200, 50001.0, 0.5)))
+        (bar,) = builder.finalize_before(120_000)
+        return bar
+
+    def test_bars_carry_builder_tick_size(self):
+        assert "o=50001.00," in repr(self._bar(core.PyBarBuilder()))
+        assert "o=50001," in repr(self._bar(core.PyBarBuilder(tick_size=1.0)))
+
+        _, builder, _ = core.build_pipeline(core.PyConfig(tick_size=0.5))
+        bar = self._bar(builder)
+        assert bar.tick_size == 0.5
+        assert "o=50001.0," in repr(bar)
+
+    def test_quote_tick_size(self):
+        assert "50000.00" in repr(core.Quote(1000, 50000.0, 1.0, 50001.0, 1.0))
+        assert "50000.00" not in repr(core.Quote(1000, 50000.0, 1.0, 50001.0, 1.0, tick_size=1.0))
+
+
+class TestFeatureCallbacks:
+    """Tests for pushing finalized features to Python callbacks."""
+
+    def test_receives_each_finalized_bar(self):
+        classifier = core.PyTradeClassifier(250, False)
+        builder = core.PyBarBuilder()
+        for minute in range(1, 5):
+            ts = minute * 60_000
+            quote = core.Quote(ts + 100, 50000.0 + minute, 1.0, 50001.0 + minute, 1.0)
+            classifier.add_quote(quote)
+            builder.add_quote(quote)
+            builder.add_trade(classifier.classify(core.Trade(ts + 200, 50001.0 + minute, 0.5)))
+        bars = builder.finalize_before(5 * 60_000)
+        assert len(bars) == 4
+
+        engine = core.PyFeatureEngine()
+        received = []
+        engine.on_features(received.append)
+        returned = [engine.process_bar(bar) for bar in bars]
+
+        assert [f.ts_min for f in received] == [f.ts_min for f in returned] == [b.ts_min for b in bars]
+
+        # A rejected minute publishes nothing
+        with pytest.raises(ValueError):
+            engine.process_bar(bars[-1])
+        assert len(received) == 4
+
+        engine.clear_callbacks()
+        with pytest.raises(TypeError):
+            engine.on_features(42)
+
+    def test_raising_callback_keeps_minute(self, monkeypatch):
+        classifier = core.PyTradeClassifier(250, False)
+        builder = core.PyBarBuilder()
+        quote = core.Quote(60_100, 50000.0, 1.0, 50001.0, 1.0)
+        classifier.add_quote(quote)
+        builder.add_quote(quote)
+        builder.add_trade(classifier.classify(core.Trade(60_200, 50001.0, 0.5)))
+        (bar,) = builder.finalize_before(120_000)
+
+        unraisable = []
+        monkeypatch.setattr(sys, "unraisablehook", unraisable.append)
+        received = []
+
+        def failing(features):
+            raise RuntimeError("subscriber bug")
+
+        engine = core.PyFeatureEngine()
+        engine.on_features(failing)
+        engine.on_features(received.append)
+        features = engine.process_bar(bar)
+
+        # The error is reported, later callbacks still run and the minute is kept
+        assert features.ts_min == bar.ts_min
+        assert [f.ts_min for f in received] == [bar.ts_min]
+        assert len(unraisable) == 1
+        assert isinstance(unraisable[0].exc_value, RuntimeError)
+
+
+class TestTradeCap:
+    """Tests for the per-minute trade cap."""
+
+    def test_burst_is_capped(self):
+        classifier = core.PyTradeClassifier(250, False)
+        builder = core.PyBarBuilder()
+        builder.set_trade_cap(max_trades=50)
+        quote = core.Quote(60_000, 50000.0, 1.0, 50001.0, 1.0)
+        classifier.add_quote(quote)
+        builder.add_quote(quote)
+
+        trades = [classifier.classify(core.Trade(60_000 + i, 50001.0, 0.01)) for i in range(500)]
+        for trade in trades:
+            builder.add_trade(trade)
+        assert builder.capped_trades() == 450
+
+        (bar,) = builder.finalize_before(120_000)
+        assert bar.trade_count == 50
+        assert builder.corrupt_minutes() == 0
+
+    def test_engine_excludes_minute(self):
+        classifier = core.PyTradeClassifier(250, False)
+        builder = core.PyBarBuilder()
+        engine = core.PyFeatureEngine()
+        engine.set_trade_cap(max_trades=50, exclude_minute=True)
+        quote = core.Quote(60_000, 50000.0, 1.0, 50001.0, 1.0)
+        classifier.add_quote(quote)
+        builder.add_quote(quote)
+
+        for i in range(500):
+            trade = classifier.classify(core.Trade(60_000 + i, 50001.0, 0.01))
+            builder.add_trade(trade)
+            engine.add_trade(trade)
+        assert engine.capped_trades() == 500
+
+        (bar,) = builder.finalize_before(120_000)
+        assert engine.process_bar(bar).order_flow.total_volume == 0.0
@@ -10,7 +10,7 @@ from auction_trader.models.types import (
     ValueArea,
     OrderFlowMetrics,
 )
-from auction_trader.services.signal_engine import SignalEngine, PriceZone
+from auction_trader.services.signal_engine import SignalEngine, PriceZone, SignalEvaluation
 
 
 class TestPriceZone:
@@ -186,3 +186,184 @@ class TestCooldown:
     def test_no_cooldown_initially(self, sample_config):
         engine = SignalEngine(sample_config)
         assert engine._in_cooldown(1000) is False
+
+
+class TestAmbiguousGate:
+    """Tests for the ambiguous-fraction circuit breaker."""
+
+    def _breakin(self, make_features, engine, ambiguous_frac, qimb_ema=0.0):
+        """Drop below VAL, then return inside with buying flow."""
+        engine.process(make_features(0, 41700.0, 0.2, ambiguous_frac, qimb_ema))
+        return engine.process(make_features(60_000, 42000.0, 0.2, ambiguous_frac, qimb_ema))
+
+    def test_low_ambiguous_allows_entry(self, make_features):
+        config = Config()
+        config.order_flow.use_qimb = False
+        signal = self._breakin(make_features, SignalEngine(config), ambiguous_frac=0.10)
+        assert signal.action == Action.ENTER_LONG
+        assert signal.signal_type == SignalType.BREAKIN_LONG
+
+    def test_high_ambiguous_requires_qimb_confirmation(self, make_features):
+        config = Config()
+        config.order_flow.use_qimb = False
+        signal = self._breakin(make_features, SignalEngine(config), ambiguous_frac=0.50)
+        assert signal.action == Action.HOLD
+
+        # Confirming qimb restores the entry
+        signal = self._breakin(
+            make_features, SignalEngine(config), ambiguous_frac=0.50, qimb_ema=0.2
+        )
+        assert signal.action == Action.ENTER_LONG
+
+    def test_high_ambiguous_suppresses_entry(self, make_features):
+        config = Config()
+        config.order_flow.ambiguous_gate_mode = "suppress"
+        signal = self._breakin(
+            make_features, SignalEngine(config), ambiguous_frac=0.50, qimb_ema=0.2
+        )
+        assert signal.action == Action.HOLD
+
+        signal = self._breakin(
+            make_features, SignalEngine(config), ambiguous_frac=0.10, qimb_ema=0.2
+        )
+        assert signal.action == Action.ENTER_LONG
+
+
+class TestVaWidthTargets:
+    """Tests for targets sized by value-area width."""
+
+    def _va(self, vah):
+        return ValueArea(
+            poc=42000.0, vah=vah, val=41800.0,
+            coverage=0.7, bin_count=20, total_volume=1000.0,
+            bin_width=10.0, is_valid=True,
+        )
+
+    def _breakin(self, make_features, config, vah):
+        engine = SignalEngine(config)
+        va = self._va(vah)
+        engine.process(make_features(0, 41700.0, 0.2, qimb_ema=0.2, va=va))
+        return engine.process(make_features(60_000, 42000.0, 0.2, qimb_ema=0.2, va=va))
+
+    def test_targets_scale_with_va_width(self, make_features):
+        config = Config()
+        config.signal.target_mode = "va_width"
+        config.signal.tp1_va_width_mult = 0.5
+        config.signal.tp2_va_width_mult = 1.0
+
+        narrow = self._breakin(make_features, config, vah=42200.0)  # width 400
+        wide = self._breakin(make_features, config, vah=42600.0)  # width 800
+        assert narrow.action == Action.ENTER_LONG
+        assert wide.action == Action.ENTER_LONG
+        assert narrow.stop_price == wide.stop_price
+
+        assert narrow.tp1_price == pytest.approx(42200.0)
+        assert narrow.tp2_price == pytest.approx(42400.0)
+        assert (wide.tp1_price - 42000.0) == pytest.approx(2 * (narrow.tp1_price - 42000.0))
+        assert (wide.tp2_price - 42000.0) == pytest.approx(2 * (narrow.tp2_price - 42000.0))
+
+    def test_structure_targets_by_default(self, make_features):
+        signal = self._breakin(make_features, Config(), vah=42600.0)
+        assert signal.tp1_price == pytest.approx(42000.0)  # POC
+        assert signal.tp2_price == pytest.approx(42600.0)  # VAH
+
+    def test_rejects_unknown_target_mode(self):
+        config = Config()
+        config.signal.target_mode = "width"
+        with pytest.raises(ValueError):
+            SignalEngine(config)
+
+
+class TestEdgeClosePolicy:
+    """Tests for closes exactly at a VA edge."""
+
+    def _run(self, make_features, config, closes):
+        engine = SignalEngine(config)
+        for i, close in enumerate(closes):
+            engine.process(make_features(i * 60_000, close))
+        return engine
+
+    def test_inside_policy_resets_at_vah(self, make_features):
+        engine = self._run(make_features, Config(), [42300.0, 42200.0])
+        assert engine.acceptance.consecutive_above_vah == 0
+        # Equal up to float rounding is still at the edge
+        engine = self._run(make_features, Config(), [42300.0, 42200.0 + 1e-9])
+        assert engine.acceptance.consecutive_above_vah == 0
+
+    def test_fractional_policy_counts_partial_acceptance(self, make_features):
+        config = Config()
+        config.signal.edge_close_policy = "fractional"
+        config.signal.edge_close_weight = 0.5
+
+        engine = self._run(make_features, config, [42300.0, 42200.0])
+        assert engine.acceptance.consecutive_above_vah == pytest.approx(1.5)
+        assert engine.prev_zone == PriceZone.ABOVE_VAH
+        assert engine._get_price_zone(42200.0, engine.prev_features.va) == PriceZone.AT_VAH
+
+        # Back inside afterwards resets the sequence
+        engine.process(make_features(120_000, 42000.0))
+        assert engine.acceptance.consecutive_above_vah == 0
+
+    def test_edge_tolerance_ticks(self, make_features):
+        config = Config()
+        config.signal.edge_tolerance_ticks = 2.0
+        tick = config.instrument.tick_size
+        engine = self._run(make_features, config, [42300.0, 42200.0 + 1.5 * tick])
+        assert engine.acceptance.consecutive_above_vah == 0
+
+    def test_rejects_unknown_policy(self):
+        config = Config()
+        config.signal.edge_close_policy = "outside"
+        with pytest.raises(ValueError):
+            SignalEngine(config)
+
+
+class TestEvaluateVerbose:
+    """Tests for the dry-run gate report."""
+
+    def _engine(self):
+        config = Config()
+        config.order_flow.use_qimb = False
+        return SignalEngine(config)
+
+    def test_reports_of_gate_on_near_miss(self, make_features):
+        engine = self._engine()
+        engine.process(make_features(0, 41700.0, 0.0))
+
+        # Returns into the VA, but flow falls just short of the entry threshold
+        of_min = engine.signal_config.of_entry_min_norm
+        near_miss = make_features(60_000, 42000.0, of_min * 0.9)
+        near_miss.order_flow.of_1m = -1.0
+        evaluation = engine.evaluate_verbose(near_miss)
+
+        assert isinstance(evaluation, SignalEvaluation)
+        assert evaluation.signal.action == Action.HOLD
+        assert evaluation.setup == "breakin_long"
+        assert evaluation.blocked_by == "of_threshold"
+        assert evaluation.gate("va_break").passed
+        assert evaluation.gate("qimb").passed
+        assert evaluation.gate("of_threshold").value[1] == pytest.approx(of_min * 0.9)
+
+        # Dry run: engine state is untouched, so the real minute still fires
+        signal = engine.process(make_features(60_000, 42000.0, 1.0))
+        assert signal.action == Action.ENTER_LONG
+
+    def test_reports_acceptance_gate(self, make_features):
+        engine = self._engine()
+        k = engine.signal_config.accept_outside_k
+        for i in range(k - 2):
+            engine.process(make_features(i * 60_000, 42300.0, 1.0))
+
+        evaluation = engine.evaluate_verbose(make_features((k - 1) * 60_000, 42300.0, 1.0))
+        assert evaluation.setup == "breakout_long"
+        assert evaluation.blocked_by == "acceptance"
+        assert evaluation.gate("acceptance").value == k - 1
+        assert evaluation.gate("acceptance").threshold == k
+        assert engine.acceptance.consecutive_above_vah == k - 2
+
+    def test_reports_cooldown(self, make_features):
+        engine = self._engine()
+        engine.last_signal_ts = 0
+        evaluation = engine.evaluate_verbose(make_features(60_000, 42000.0, 1.0))
+        assert evaluation.blocked_by == "cooldown"
+        assert "cooldown" in evaluation.signal.reason
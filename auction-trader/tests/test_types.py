@@ -106,6 +106,17 @@ class TestValueArea:
         assert expected_width == 400.0
 
 
+class TestOrderFlowMetrics:
+    """Tests for OrderFlowMetrics type."""
+
+    def test_empty_vs_balanced_minute(self):
+        empty = OrderFlowMetrics(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0)
+        balanced = OrderFlowMetrics(0.0, 0.0, 10.0, 5.0, 5.0, 0.0, 0.0)
+        assert empty.of_norm_1m == balanced.of_norm_1m
+        assert empty.has_volume is False
+        assert balanced.has_volume is True
+
+
 class TestSignalType:
     """Tests for SignalType enum."""
 